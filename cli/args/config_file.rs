@@ -658,7 +658,7 @@ pub enum LockConfig {
   PathBuf(PathBuf),
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct ConfigFileJson {
   pub compiler_options: Option<Value>,
@@ -825,6 +825,19 @@ impl ConfigFile {
     Ok(Self { specifier, json })
   }
 
+  /// Returns true if `other` is a version of this configuration file which
+  /// only differs in its `compilerOptions`.
+  pub fn is_compiler_options_change(&self, other: &ConfigFile) -> bool {
+    let without_compiler_options = |json: &ConfigFileJson| ConfigFileJson {
+      compiler_options: None,
+      ..json.clone()
+    };
+    self.specifier == other.specifier
+      && self.json.compiler_options != other.json.compiler_options
+      && without_compiler_options(&self.json)
+        == without_compiler_options(&other.json)
+  }
+
   /// Returns true if the configuration indicates that JavaScript should be
   /// type checked, otherwise false.
   pub fn get_check_js(&self) -> bool {
@@ -1576,6 +1589,29 @@ mod tests {
     assert!(options_value.is_object());
   }
 
+  #[test]
+  fn test_is_compiler_options_change() {
+    let config_specifier =
+      ModuleSpecifier::parse("file:///deno/deno.json").unwrap();
+    let config_file =
+      |text: &str| ConfigFile::new(text, config_specifier.clone()).unwrap();
+    let original = config_file(
+      r#"{ "compilerOptions": { "strict": false }, "fmt": { "semiColons": false } }"#,
+    );
+    assert!(original.is_compiler_options_change(&config_file(
+      r#"{ "compilerOptions": { "strict": true }, "fmt": { "semiColons": false } }"#
+    )));
+    assert!(original.is_compiler_options_change(&config_file(
+      r#"{ "fmt": { "semiColons": false } }"#
+    )));
+    assert!(!original.is_compiler_options_change(&config_file(
+      r#"{ "compilerOptions": { "strict": true } }"#
+    )));
+    assert!(!original.is_compiler_options_change(&config_file(
+      r#"{ "compilerOptions": { "strict": false }, "fmt": { "semiColons": false } }"#
+    )));
+  }
+
   #[test]
   fn test_parse_config_with_global_files() {
     let config_text = r#"{
@@ -746,8 +746,13 @@ static ENV_VARIABLES_HELP: &str = r#"ENVIRONMENT VARIABLES:
                          Defaults to "mozilla".
     DENO_CERT            Load certificate authority from PEM encoded file
     DENO_DIR             Set the cache directory
+    DENO_FIPS            Set to restrict Web Crypto to FIPS approved
+                         algorithms. Requires a build with the "fips"
+                         feature and DENO_CRYPTO_BACKEND=aws-lc-rs.
     DENO_INSTALL_ROOT    Set deno install's output directory
                          (defaults to $HOME/.deno/bin)
+    DENO_KEYGEN_TASKS    Maximum number of Web Crypto RSA keys generated
+                         concurrently. Defaults to number of available CPUs.
     DENO_REPL_HISTORY    Set REPL history file path
                          History file is disabled when the value is empty
                          (defaults to $DENO_DIR/deno_history.txt)
@@ -46,6 +46,8 @@ use deno_core::parking_lot::Mutex;
 use deno_core::serde_json;
 use deno_core::url::Url;
 use deno_runtime::colors;
+use deno_runtime::deno_crypto::check_fips_backend;
+use deno_runtime::deno_crypto::CryptoBackend;
 use deno_runtime::deno_node::PackageJson;
 use deno_runtime::deno_tls::rustls;
 use deno_runtime::deno_tls::rustls::RootCertStore;
@@ -1130,6 +1132,21 @@ impl CliOptions {
     self.flags.seed
   }
 
+  /// Restricts Web Crypto to FIPS approved algorithms.
+  pub fn fips(&self) -> Result<bool, AnyError> {
+    fips_from_env()
+  }
+
+  /// Library used by Web Crypto for RSA private key operations.
+  pub fn crypto_backend(&self) -> Result<CryptoBackend, AnyError> {
+    crypto_backend_from_env()
+  }
+
+  /// Maximum number of RSA keys Web Crypto generates concurrently.
+  pub fn max_keygen_tasks(&self) -> Result<Option<usize>, AnyError> {
+    max_keygen_tasks_from_env()
+  }
+
   pub fn sub_command(&self) -> &DenoSubcommand {
     &self.flags.subcommand
   }
@@ -1380,6 +1397,40 @@ pub fn has_flag_env_var(name: &str) -> bool {
   matches!(value.as_ref().map(|s| s.as_str()), Ok("1"))
 }
 
+/// Resolves the Web Crypto backend from `DENO_CRYPTO_BACKEND`.
+pub fn crypto_backend_from_env() -> Result<CryptoBackend, AnyError> {
+  match env::var("DENO_CRYPTO_BACKEND") {
+    Ok(value) => value
+      .parse()
+      .context("Invalid DENO_CRYPTO_BACKEND environment variable"),
+    Err(_) => Ok(CryptoBackend::default()),
+  }
+}
+
+/// Resolves FIPS mode from `DENO_FIPS`, which fails unless the Web Crypto
+/// backend can run in FIPS mode.
+pub fn fips_from_env() -> Result<bool, AnyError> {
+  if !has_flag_env_var("DENO_FIPS") {
+    return Ok(false);
+  }
+  check_fips_backend(crypto_backend_from_env()?)?;
+  Ok(true)
+}
+
+/// Resolves the RSA key generation concurrency from `DENO_KEYGEN_TASKS`.
+pub fn max_keygen_tasks_from_env() -> Result<Option<usize>, AnyError> {
+  match env::var("DENO_KEYGEN_TASKS") {
+    Ok(value) => match value.parse::<NonZeroUsize>() {
+      Ok(tasks) => Ok(Some(tasks.get())),
+      Err(_) => bail!(
+        "Invalid DENO_KEYGEN_TASKS environment variable, expected a positive integer: {}",
+        value
+      ),
+    },
+    Err(_) => Ok(None),
+  }
+}
+
 pub fn npm_pkg_req_ref_to_binary_command(
   req_ref: &NpmPackageReqReference,
 ) -> String {
@@ -335,7 +335,12 @@ fn create_cli_snapshot(snapshot_path: PathBuf) -> CreateSnapshotOutput {
       None,
     ),
     deno_webstorage::deno_webstorage::init_ops(None),
-    deno_crypto::deno_crypto::init_ops(None),
+    deno_crypto::deno_crypto::init_ops::<PermissionsContainer>(
+      None,
+      false,
+      Default::default(),
+      None, // Default key generation concurrency.
+    ),
     deno_broadcast_channel::deno_broadcast_channel::init_ops(
       deno_broadcast_channel::InMemoryBroadcastChannel::default(),
       false, // No --unstable.
@@ -679,6 +679,9 @@ impl CliFactory {
       },
       origin_data_folder_path: Some(self.deno_dir()?.origin_data_folder_path()),
       seed: self.options.seed(),
+      fips: self.options.fips()?,
+      crypto_backend: self.options.crypto_backend()?,
+      max_keygen_tasks: self.options.max_keygen_tasks()?,
       unsafely_ignore_certificate_errors: self
         .options
         .unsafely_ignore_certificate_errors()
@@ -9,6 +9,7 @@ use super::tsc;
 use crate::npm::CliNpmResolver;
 use crate::npm::NpmResolution;
 use crate::tools::lint::create_linter;
+use crate::util::path::relative_specifier;
 
 use deno_ast::SourceRange;
 use deno_ast::SourceRangedForSpanned;
@@ -16,6 +17,7 @@ use deno_ast::SourceTextInfo;
 use deno_core::anyhow::anyhow;
 use deno_core::error::custom_error;
 use deno_core::error::AnyError;
+use deno_core::resolve_url;
 use deno_core::serde::Deserialize;
 use deno_core::serde_json;
 use deno_core::serde_json::json;
@@ -368,6 +370,27 @@ fn try_reverse_map_package_json_exports(
   }
 }
 
+/// Rewrite the import specifiers in the text of a tsc change, which are
+/// assumed to be one per line, with `fix_specifier`.
+fn fix_ts_import_text(
+  new_text: &str,
+  fix_specifier: impl Fn(&str) -> Option<String>,
+) -> String {
+  new_text
+    .split('\n')
+    .map(|line| {
+      if let Some(captures) = IMPORT_SPECIFIER_RE.captures(line) {
+        let specifier = captures.get(1).unwrap().as_str();
+        if let Some(new_specifier) = fix_specifier(specifier) {
+          return line.replace(specifier, &new_specifier);
+        }
+      }
+      line.to_string()
+    })
+    .collect::<Vec<_>>()
+    .join("\n")
+}
+
 /// For a set of tsc changes, can them for any that contain something that looks
 /// like an import and rewrite the import specifier to include the extension
 pub fn fix_ts_import_changes(
@@ -377,33 +400,63 @@ pub fn fix_ts_import_changes(
 ) -> Result<Vec<tsc::FileTextChanges>, AnyError> {
   let mut r = Vec::new();
   for change in changes {
-    let mut text_changes = Vec::new();
-    for text_change in &change.text_changes {
-      let lines = text_change.new_text.split('\n');
-
-      let new_lines: Vec<String> = lines
-        .map(|line| {
-          // This assumes that there's only one import per line.
-          if let Some(captures) = IMPORT_SPECIFIER_RE.captures(line) {
-            let specifier = captures.get(1).unwrap().as_str();
-            if let Some(new_specifier) =
-              import_mapper.check_unresolved_specifier(specifier, referrer)
-            {
-              line.replace(specifier, &new_specifier)
-            } else {
-              line.to_string()
-            }
-          } else {
-            line.to_string()
-          }
-        })
-        .collect();
+    let text_changes = change
+      .text_changes
+      .iter()
+      .map(|text_change| tsc::TextChange {
+        span: text_change.span.clone(),
+        new_text: fix_ts_import_text(&text_change.new_text, |specifier| {
+          import_mapper.check_unresolved_specifier(specifier, referrer)
+        }),
+      })
+      .collect();
+    r.push(tsc::FileTextChanges {
+      file_name: change.file_name.clone(),
+      text_changes,
+      is_new_file: change.is_new_file,
+    });
+  }
+  Ok(r)
+}
 
-      text_changes.push(tsc::TextChange {
+/// Fix the import specifiers in the changes of a refactor, which can span
+/// several files. Refactors like "Move to a new file" import the files they
+/// create without the right extension, and as those files don't exist yet
+/// they are matched against the new files of the changes.
+pub fn fix_ts_refactor_import_changes(
+  changes: &[tsc::FileTextChanges],
+  import_mapper: &TsResponseImportMapper,
+) -> Result<Vec<tsc::FileTextChanges>, AnyError> {
+  let new_files = changes
+    .iter()
+    .filter(|c| c.is_new_file.unwrap_or(false))
+    .map(|c| ModuleSpecifier::parse(&c.file_name))
+    .collect::<Result<Vec<_>, _>>()?;
+  let mut r = Vec::new();
+  for change in changes {
+    let referrer = ModuleSpecifier::parse(&change.file_name)?;
+    let fix_specifier = |specifier: &str| {
+      if let Some(new_specifier) =
+        import_mapper.check_unresolved_specifier(specifier, &referrer)
+      {
+        return Some(new_specifier);
+      }
+      // tsc can also import a new TypeScript file with a `.js` extension
+      let base = specifier.strip_suffix(".js").unwrap_or(specifier);
+      SUPPORTED_EXTENSIONS.iter().find_map(|ext| {
+        let specifier_with_ext = format!("{base}{ext}");
+        let resolved = referrer.join(&specifier_with_ext).ok()?;
+        new_files.contains(&resolved).then_some(specifier_with_ext)
+      })
+    };
+    let text_changes = change
+      .text_changes
+      .iter()
+      .map(|text_change| tsc::TextChange {
         span: text_change.span.clone(),
-        new_text: new_lines.join("\n").to_string(),
-      });
-    }
+        new_text: fix_ts_import_text(&text_change.new_text, fix_specifier),
+      })
+      .collect();
     r.push(tsc::FileTextChanges {
       file_name: change.file_name.clone(),
       text_changes,
@@ -413,6 +466,69 @@ pub fn fix_ts_import_changes(
   Ok(r)
 }
 
+/// Returns where `specifier` is after renaming the module or directory
+/// `old_specifier` to `new_specifier`, or `None` if it is not affected.
+fn get_renamed_specifier(
+  specifier: &ModuleSpecifier,
+  old_specifier: &ModuleSpecifier,
+  new_specifier: &ModuleSpecifier,
+) -> Option<ModuleSpecifier> {
+  if specifier == old_specifier {
+    return Some(new_specifier.clone());
+  }
+  let old_dir = format!("{}/", old_specifier.as_str().trim_end_matches('/'));
+  let sub_path = specifier.as_str().strip_prefix(&old_dir)?;
+  let new_dir = new_specifier.as_str().trim_end_matches('/');
+  ModuleSpecifier::parse(&format!("{new_dir}/{sub_path}")).ok()
+}
+
+/// The module specifiers tsc writes when it updates imports for a renamed file
+/// may drop the extension or use `.js`, so they are replaced with relative
+/// specifiers to the new location which include the extension, as Deno
+/// requires.
+pub fn fix_ts_file_rename_changes(
+  changes: Vec<tsc::FileTextChanges>,
+  old_specifier: &ModuleSpecifier,
+  new_specifier: &ModuleSpecifier,
+  documents: &Documents,
+) -> Result<Vec<tsc::FileTextChanges>, AnyError> {
+  let mut r = Vec::with_capacity(changes.len());
+  for mut change in changes {
+    let referrer = resolve_url(&change.file_name)?;
+    let Some(document) = documents.get(&referrer) else {
+      r.push(change);
+      continue;
+    };
+    let new_referrer =
+      get_renamed_specifier(&referrer, old_specifier, new_specifier)
+        .unwrap_or_else(|| referrer.clone());
+    let text = document.content();
+    let line_index = document.line_index();
+    for text_change in &mut change.text_changes {
+      let start = line_index
+        .offset(line_index.position_tsc(text_change.span.start.into()))?;
+      let end = line_index.offset(line_index.position_tsc(
+        (text_change.span.start + text_change.span.length).into(),
+      ))?;
+      let old_text = &text[usize::from(start)..usize::from(end)];
+      if !old_text.starts_with("./") && !old_text.starts_with("../") {
+        continue;
+      }
+      let Ok(target) = referrer.join(old_text) else {
+        continue;
+      };
+      let new_target =
+        get_renamed_specifier(&target, old_specifier, new_specifier)
+          .unwrap_or(target);
+      if let Some(new_text) = relative_specifier(&new_referrer, &new_target) {
+        text_change.new_text = new_text;
+      }
+    }
+    r.push(change);
+  }
+  Ok(r)
+}
+
 /// Fix tsc import code actions so that the module specifier is correct for
 /// resolution by Deno (includes the extension).
 fn fix_ts_import_action(
@@ -538,6 +654,81 @@ pub fn ts_changes_to_edit(
   }))
 }
 
+pub struct OrganizeImportsCodeActionKind {
+  pub kind: lsp::CodeActionKind,
+  pub title: &'static str,
+  pub mode: tsc::OrganizeImportsMode,
+}
+
+/// The source actions backed by tsc's `organizeImports`, so that editors can
+/// run each of them on save.
+pub const ORGANIZE_IMPORTS_ACTION_KINDS: &[OrganizeImportsCodeActionKind] = &[
+  OrganizeImportsCodeActionKind {
+    kind: lsp::CodeActionKind::SOURCE_ORGANIZE_IMPORTS,
+    title: "Organize imports",
+    mode: tsc::OrganizeImportsMode::All,
+  },
+  OrganizeImportsCodeActionKind {
+    kind: lsp::CodeActionKind::new("source.sortImports"),
+    title: "Sort imports",
+    mode: tsc::OrganizeImportsMode::SortAndCombine,
+  },
+  OrganizeImportsCodeActionKind {
+    kind: lsp::CodeActionKind::new("source.removeUnusedImports"),
+    title: "Remove unused imports",
+    mode: tsc::OrganizeImportsMode::RemoveUnused,
+  },
+];
+
+/// The source action which applies the fixes of all the fixable diagnostics
+/// in a file.
+pub const SOURCE_FIX_ALL_DENO: lsp::CodeActionKind =
+  lsp::CodeActionKind::new("source.fixAll.deno");
+
+/// Combines the changes of several combined code fixes. A fix which changes
+/// text that an earlier fix already changes is left out entirely, as applying
+/// only some of its changes could leave the code broken.
+pub fn merge_fix_all_changes(
+  fixes: Vec<Vec<tsc::FileTextChanges>>,
+) -> Vec<tsc::FileTextChanges> {
+  fn overlaps(a: &tsc::TextSpan, b: &tsc::TextSpan) -> bool {
+    a.start == b.start
+      || (a.start < b.start + b.length && b.start < a.start + a.length)
+  }
+
+  let mut merged: Vec<tsc::FileTextChanges> = Vec::new();
+  for fix in fixes {
+    let conflicts = fix.iter().any(|file_changes| {
+      merged
+        .iter()
+        .filter(|m| m.file_name == file_changes.file_name)
+        .flat_map(|m| &m.text_changes)
+        .any(|merged_change| {
+          file_changes
+            .text_changes
+            .iter()
+            .any(|change| overlaps(&change.span, &merged_change.span))
+        })
+    });
+    if conflicts {
+      continue;
+    }
+    for file_changes in fix {
+      match merged
+        .iter_mut()
+        .find(|m| m.file_name == file_changes.file_name)
+      {
+        Some(m) => m.text_changes.extend(file_changes.text_changes),
+        None => merged.push(file_changes),
+      }
+    }
+  }
+  for file_changes in &mut merged {
+    file_changes.text_changes.sort_by_key(|c| c.span.start);
+  }
+  merged
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CodeActionData {
@@ -1085,4 +1276,69 @@ mod tests {
       "utils/sub_utils"
     );
   }
+
+  #[test]
+  fn test_get_renamed_specifier() {
+    let old = ModuleSpecifier::parse("file:///a/b").unwrap();
+    let new = ModuleSpecifier::parse("file:///a/c").unwrap();
+    let cases = [
+      ("file:///a/b", Some("file:///a/c")),
+      ("file:///a/b/mod.ts", Some("file:///a/c/mod.ts")),
+      ("file:///a/b/d/mod.ts", Some("file:///a/c/d/mod.ts")),
+      ("file:///a/bc/mod.ts", None),
+      ("file:///a/mod.ts", None),
+    ];
+    for (specifier, expected) in cases {
+      let specifier = ModuleSpecifier::parse(specifier).unwrap();
+      assert_eq!(
+        get_renamed_specifier(&specifier, &old, &new)
+          .as_ref()
+          .map(|s| s.as_str()),
+        expected,
+      );
+    }
+  }
+
+  #[test]
+  fn test_merge_fix_all_changes() {
+    fn changes(
+      file_name: &str,
+      spans: &[(u32, u32, &str)],
+    ) -> tsc::FileTextChanges {
+      tsc::FileTextChanges {
+        file_name: file_name.to_string(),
+        text_changes: spans
+          .iter()
+          .map(|(start, length, new_text)| tsc::TextChange {
+            span: tsc::TextSpan {
+              start: *start,
+              length: *length,
+            },
+            new_text: new_text.to_string(),
+          })
+          .collect(),
+        is_new_file: None,
+      }
+    }
+
+    let merged = merge_fix_all_changes(vec![
+      vec![changes("file:///a.ts", &[(10, 5, "a")])],
+      // overlaps with the first fix, so it is left out entirely
+      vec![
+        changes("file:///a.ts", &[(12, 1, "b")]),
+        changes("file:///b.ts", &[(0, 0, "b")]),
+      ],
+      vec![
+        changes("file:///a.ts", &[(0, 0, "c"), (15, 0, "c")]),
+        changes("file:///c.ts", &[(0, 1, "c")]),
+      ],
+    ]);
+    assert_eq!(
+      merged,
+      vec![
+        changes("file:///a.ts", &[(0, 0, "c"), (10, 5, "a"), (15, 0, "c")]),
+        changes("file:///c.ts", &[(0, 1, "c")]),
+      ]
+    );
+  }
 }
@@ -8,6 +8,9 @@
 use deno_core::serde_json::json;
 use tower_lsp::lsp_types::*;
 
+use super::analysis::ORGANIZE_IMPORTS_ACTION_KINDS;
+use super::analysis::SOURCE_FIX_ALL_DENO;
+use super::lsp_custom;
 use super::refactor::ALL_KNOWN_REFACTOR_ACTION_KINDS;
 use super::semantic_tokens::get_legend;
 
@@ -22,6 +25,12 @@ fn code_action_capabilities(
     .map(|_| {
       let mut code_action_kinds =
         vec![CodeActionKind::QUICKFIX, CodeActionKind::REFACTOR];
+      code_action_kinds.extend(
+        ORGANIZE_IMPORTS_ACTION_KINDS
+          .iter()
+          .map(|action| action.kind.clone()),
+      );
+      code_action_kinds.push(SOURCE_FIX_ALL_DENO);
       code_action_kinds.extend(
         ALL_KNOWN_REFACTOR_ACTION_KINDS
           .iter()
@@ -117,7 +126,10 @@ pub fn server_capabilities(
     rename_provider: Some(OneOf::Left(true)),
     document_link_provider: None,
     color_provider: None,
-    execute_command_provider: None,
+    execute_command_provider: Some(ExecuteCommandOptions {
+      commands: vec![lsp_custom::RESTART_TS_SERVER_COMMAND.to_string()],
+      ..Default::default()
+    }),
     call_hierarchy_provider: Some(CallHierarchyServerCapability::Simple(true)),
     semantic_tokens_provider: Some(
       SemanticTokensServerCapabilities::SemanticTokensOptions(
@@ -134,15 +146,54 @@ pub fn server_capabilities(
         supported: Some(true),
         change_notifications: Some(OneOf::Left(true)),
       }),
-      file_operations: None,
+      file_operations: Some(WorkspaceFileOperationsServerCapabilities {
+        will_rename: Some(FileOperationRegistrationOptions {
+          filters: vec![
+            FileOperationFilter {
+              scheme: Some("file".to_string()),
+              pattern: FileOperationPattern {
+                glob: "**/*.{ts,tsx,js,jsx,mts,cts,mjs,cjs}".to_string(),
+                matches: Some(FileOperationPatternKind::File),
+                options: None,
+              },
+            },
+            FileOperationFilter {
+              scheme: Some("file".to_string()),
+              pattern: FileOperationPattern {
+                glob: "**".to_string(),
+                matches: Some(FileOperationPatternKind::Folder),
+                options: None,
+              },
+            },
+          ],
+        }),
+        ..Default::default()
+      }),
     }),
     linked_editing_range_provider: None,
     moniker_provider: None,
+    // `inlineValueProvider` and `diagnosticProvider` are part of LSP 3.17,
+    // which lsp_types 0.93 doesn't cover, and the `ServerCapabilities` it
+    // serializes can't carry other top level fields. They are advertised as
+    // custom capabilities instead, which clients have to opt in to by reading
+    // them from `experimental`. Diagnostics are still pushed for clients that
+    // don't pull them.
     experimental: Some(json!({
       "denoConfigTasks": true,
       "testingApi":true,
+      "multiDocumentHighlightProvider": true,
+      "inlineValueProvider": true,
+      "diagnosticProvider": {
+        "interFileDependencies": true,
+        "workspaceDiagnostics": true,
+      },
     })),
-    inlay_hint_provider: Some(OneOf::Left(true)),
+    inlay_hint_provider: Some(OneOf::Right(
+      InlayHintServerCapabilities::Options(InlayHintOptions {
+        resolve_provider: Some(true),
+        work_done_progress_options: Default::default(),
+      }),
+    )),
     position_encoding: None,
   }
 }
@@ -152,6 +152,18 @@ impl OutsideLockClient {
     self.0.workspace_configuration().await
   }
 
+  /// Awaited so that all partial results are sent before the response to
+  /// the request.
+  pub async fn send_workspace_diagnostic_partial_result(
+    &self,
+    params: lsp_custom::WorkspaceDiagnosticPartialResultParams,
+  ) {
+    self
+      .0
+      .send_workspace_diagnostic_partial_result(params)
+      .await;
+  }
+
   pub async fn publish_diagnostics(
     &self,
     uri: LspClientUrl,
@@ -182,6 +194,10 @@ trait ClientTrait: Send + Sync {
     params: lsp_custom::DiagnosticBatchNotificationParams,
   );
   async fn send_test_notification(&self, params: TestingNotification);
+  async fn send_workspace_diagnostic_partial_result(
+    &self,
+    params: lsp_custom::WorkspaceDiagnosticPartialResultParams,
+  );
   async fn specifier_configurations(
     &self,
     uris: Vec<lsp::Url>,
@@ -255,6 +271,16 @@ impl ClientTrait for TowerClient {
     }
   }
 
+  async fn send_workspace_diagnostic_partial_result(
+    &self,
+    params: lsp_custom::WorkspaceDiagnosticPartialResultParams,
+  ) {
+    self
+      .0
+      .send_notification::<lsp_custom::WorkspaceDiagnosticPartialResult>(params)
+      .await
+  }
+
   async fn specifier_configurations(
     &self,
     uris: Vec<lsp::Url>,
@@ -350,6 +376,12 @@ impl ClientTrait for ReplClient {
 
   async fn send_test_notification(&self, _params: TestingNotification) {}
 
+  async fn send_workspace_diagnostic_partial_result(
+    &self,
+    _params: lsp_custom::WorkspaceDiagnosticPartialResultParams,
+  ) {
+  }
+
   async fn specifier_configurations(
     &self,
     uris: Vec<lsp::Url>,
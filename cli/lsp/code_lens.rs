@@ -15,6 +15,7 @@ use deno_ast::ParsedSource;
 use deno_ast::SourceRange;
 use deno_ast::SourceRangedForSpanned;
 use deno_core::error::AnyError;
+use deno_core::parking_lot::Mutex;
 use deno_core::resolve_url;
 use deno_core::serde::Deserialize;
 use deno_core::serde::Serialize;
@@ -25,7 +26,8 @@ use lazy_regex::lazy_regex;
 use once_cell::sync::Lazy;
 use regex::Regex;
 use std::cell::RefCell;
-use std::collections::HashSet;
+use std::collections::HashMap;
+use std::collections::VecDeque;
 use std::rc::Rc;
 use std::sync::Arc;
 use tower_lsp::lsp_types as lsp;
@@ -34,8 +36,13 @@ static ABSTRACT_MODIFIER: Lazy<Regex> = lazy_regex!(r"\babstract\b");
 
 static EXPORT_MODIFIER: Lazy<Regex> = lazy_regex!(r"\bexport\b");
 
+/// The maximum number of resolved references code lenses that are cached.
+const REFERENCES_CACHE_CAPACITY: usize = 200;
+
 #[derive(Debug, Deserialize, Serialize)]
 pub enum CodeLensSource {
+  #[serde(rename = "fileReferences")]
+  FileReferences,
   #[serde(rename = "implementations")]
   Implementations,
   #[serde(rename = "references")]
@@ -49,11 +56,80 @@ pub struct CodeLensData {
   pub specifier: ModuleSpecifier,
 }
 
+/// The kind of registration a `Deno.*` call makes, which determines the
+/// command the code lenses invoke.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DenoTestKind {
+  Test,
+  Bench,
+}
+
+impl DenoTestKind {
+  fn from_prop(prop: &str) -> Option<Self> {
+    match prop {
+      "test" => Some(Self::Test),
+      "bench" => Some(Self::Bench),
+      _ => None,
+    }
+  }
+
+  fn command(&self) -> &'static str {
+    match self {
+      Self::Test => "deno.test",
+      Self::Bench => "deno.bench",
+    }
+  }
+
+  fn run_title(&self) -> &'static str {
+    match self {
+      Self::Test => "▶\u{fe0e} Run Test",
+      Self::Bench => "▶\u{fe0e} Run Bench",
+    }
+  }
+}
+
+type ReferencesCacheKey = (ModuleSpecifier, lsp::Position, Option<String>);
+
+/// A least recently used cache of the locations resolved for references code
+/// lenses, keyed by the specifier, position and version of the document, so
+/// that re-resolving the lenses of a large file doesn't issue a
+/// `findReferences` request for every lens again.
+#[derive(Debug, Default)]
+pub struct ReferencesCodeLensCache(
+  Mutex<VecDeque<(ReferencesCacheKey, Vec<lsp::Location>)>>,
+);
+
+impl ReferencesCodeLensCache {
+  fn get(&self, key: &ReferencesCacheKey) -> Option<Vec<lsp::Location>> {
+    let mut entries = self.0.lock();
+    let index = entries.iter().position(|(k, _)| k == key)?;
+    let entry = entries.remove(index)?;
+    let locations = entry.1.clone();
+    entries.push_back(entry);
+    Some(locations)
+  }
+
+  fn insert(&self, key: ReferencesCacheKey, locations: Vec<lsp::Location>) {
+    let mut entries = self.0.lock();
+    entries.retain(|(k, _)| k != &key);
+    entries.push_back((key, locations));
+    while entries.len() > REFERENCES_CACHE_CAPACITY {
+      entries.pop_front();
+    }
+  }
+
+  /// Clear the cache, which is needed when any module changes, as references
+  /// can come from any module in the project.
+  pub fn clear(&self) {
+    self.0.lock().clear();
+  }
+}
+
 struct DenoTestCollector {
   code_lenses: Vec<lsp::CodeLens>,
   parsed_source: ParsedSource,
   specifier: ModuleSpecifier,
-  test_vars: HashSet<String>,
+  test_vars: HashMap<String, DenoTestKind>,
 }
 
 impl DenoTestCollector {
@@ -62,15 +138,20 @@ impl DenoTestCollector {
       code_lenses: Vec::new(),
       parsed_source,
       specifier,
-      test_vars: HashSet::new(),
+      test_vars: HashMap::new(),
     }
   }
 
-  fn add_code_lenses<N: AsRef<str>>(&mut self, name: N, range: &SourceRange) {
+  fn add_code_lenses<N: AsRef<str>>(
+    &mut self,
+    name: N,
+    range: &SourceRange,
+    kind: DenoTestKind,
+  ) {
     let range =
       source_range_to_lsp_range(range, self.parsed_source.text_info());
-    self.add_code_lens(&name, range, "▶\u{fe0e} Run Test", false);
-    self.add_code_lens(&name, range, "Debug", true);
+    self.add_code_lens(&name, range, kind.run_title(), kind, false);
+    self.add_code_lens(&name, range, "Debug", kind, true);
   }
 
   fn add_code_lens<N: AsRef<str>>(
@@ -78,6 +159,7 @@ impl DenoTestCollector {
     name: &N,
     range: lsp::Range,
     title: &str,
+    kind: DenoTestKind,
     inspect: bool,
   ) {
     let options = json!({
@@ -87,7 +169,7 @@ impl DenoTestCollector {
       range,
       command: Some(lsp::Command {
         title: title.to_string(),
-        command: "deno.test".to_string(),
+        command: kind.command().to_string(),
         arguments: Some(vec![
           json!(self.specifier),
           json!(name.as_ref()),
@@ -98,7 +180,12 @@ impl DenoTestCollector {
     });
   }
 
-  fn check_call_expr(&mut self, node: &ast::CallExpr, range: &SourceRange) {
+  fn check_call_expr(
+    &mut self,
+    node: &ast::CallExpr,
+    range: &SourceRange,
+    kind: DenoTestKind,
+  ) {
     if let Some(expr) = node.args.get(0).map(|es| es.expr.as_ref()) {
       match expr {
         ast::Expr::Object(obj_lit) => {
@@ -113,7 +200,7 @@ impl DenoTestCollector {
                       key_value_prop.value.as_ref()
                     {
                       let name = lit_str.value.to_string();
-                      self.add_code_lenses(name, range);
+                      self.add_code_lenses(name, range, kind);
                     }
                   }
                 }
@@ -124,12 +211,12 @@ impl DenoTestCollector {
         ast::Expr::Fn(fn_expr) => {
           if let Some(ast::Ident { sym, .. }) = fn_expr.ident.as_ref() {
             let name = sym.to_string();
-            self.add_code_lenses(name, range);
+            self.add_code_lenses(name, range, kind);
           }
         }
         ast::Expr::Lit(ast::Lit::Str(lit_str)) => {
           let name = lit_str.value.to_string();
-          self.add_code_lenses(name, range);
+          self.add_code_lenses(name, range, kind);
         }
         _ => (),
       }
@@ -147,16 +234,16 @@ impl Visit for DenoTestCollector {
     if let ast::Callee::Expr(callee_expr) = &node.callee {
       match callee_expr.as_ref() {
         ast::Expr::Ident(ident) => {
-          if self.test_vars.contains(&ident.sym.to_string()) {
-            self.check_call_expr(node, &ident.range());
+          if let Some(kind) = self.test_vars.get(&ident.sym.to_string()) {
+            self.check_call_expr(node, &ident.range(), *kind);
           }
         }
         ast::Expr::Member(member_expr) => {
           if let ast::MemberProp::Ident(ns_prop_ident) = &member_expr.prop {
-            if ns_prop_ident.sym.to_string() == "test" {
+            if let Some(kind) = DenoTestKind::from_prop(&ns_prop_ident.sym) {
               if let ast::Expr::Ident(ident) = member_expr.obj.as_ref() {
                 if ident.sym.to_string() == "Deno" {
-                  self.check_call_expr(node, &ns_prop_ident.range());
+                  self.check_call_expr(node, &ns_prop_ident.range(), kind);
                 }
               }
             }
@@ -171,7 +258,8 @@ impl Visit for DenoTestCollector {
     for decl in &node.decls {
       if let Some(init) = &decl.init {
         match init.as_ref() {
-          // Identify destructured assignments of `test` from `Deno`
+          // Identify destructured assignments of `test` or `bench` from
+          // `Deno`
           ast::Expr::Ident(ident) => {
             if ident.sym.to_string() == "Deno" {
               if let ast::Pat::Object(object_pat) = &decl.name {
@@ -179,19 +267,21 @@ impl Visit for DenoTestCollector {
                   match prop {
                     ast::ObjectPatProp::Assign(prop) => {
                       let name = prop.key.sym.to_string();
-                      if name == "test" {
-                        self.test_vars.insert(name);
+                      if let Some(kind) = DenoTestKind::from_prop(&name) {
+                        self.test_vars.insert(name, kind);
                       }
                     }
                     ast::ObjectPatProp::KeyValue(prop) => {
                       if let ast::PropName::Ident(key_ident) = &prop.key {
-                        if key_ident.sym.to_string() == "test" {
+                        if let Some(kind) =
+                          DenoTestKind::from_prop(&key_ident.sym)
+                        {
                           if let ast::Pat::Ident(value_ident) =
                             &prop.value.as_ref()
                           {
                             self
                               .test_vars
-                              .insert(value_ident.id.sym.to_string());
+                              .insert(value_ident.id.sym.to_string(), kind);
                           }
                         }
                       }
@@ -202,14 +292,17 @@ impl Visit for DenoTestCollector {
               }
             }
           }
-          // Identify variable assignments where the init is `Deno.test`
+          // Identify variable assignments where the init is `Deno.test` or
+          // `Deno.bench`
           ast::Expr::Member(member_expr) => {
             if let ast::Expr::Ident(obj_ident) = member_expr.obj.as_ref() {
               if obj_ident.sym.to_string() == "Deno" {
                 if let ast::MemberProp::Ident(prop_ident) = &member_expr.prop {
-                  if prop_ident.sym.to_string() == "test" {
+                  if let Some(kind) = DenoTestKind::from_prop(&prop_ident.sym) {
                     if let ast::Pat::Ident(binding_ident) = &decl.name {
-                      self.test_vars.insert(binding_ident.id.sym.to_string());
+                      self
+                        .test_vars
+                        .insert(binding_ident.id.sym.to_string(), kind);
                     }
                   }
                 }
@@ -223,6 +316,53 @@ impl Visit for DenoTestCollector {
   }
 }
 
+async fn resolve_file_references_code_lens(
+  code_lens: lsp::CodeLens,
+  data: CodeLensData,
+  language_server: &language_server::Inner,
+) -> Result<lsp::CodeLens, AnyError> {
+  let entries = language_server
+    .ts_server
+    .get_file_references(language_server.snapshot(), data.specifier.clone())
+    .await?;
+  let mut locations = Vec::new();
+  for entry in entries {
+    let entry_specifier = resolve_url(&entry.document_span.file_name)?;
+    let asset_or_doc =
+      language_server.get_asset_or_document(&entry_specifier)?;
+    locations.push(
+      entry.to_location(asset_or_doc.line_index(), &language_server.url_map),
+    );
+  }
+  let title = if locations.len() == 1 {
+    "1 module reference".to_string()
+  } else {
+    format!("{} module references", locations.len())
+  };
+  let command = if locations.is_empty() {
+    lsp::Command {
+      title,
+      command: String::new(),
+      arguments: None,
+    }
+  } else {
+    lsp::Command {
+      title,
+      command: "deno.showReferences".to_string(),
+      arguments: Some(vec![
+        json!(data.specifier),
+        json!(code_lens.range.start),
+        json!(locations),
+      ]),
+    }
+  };
+  Ok(lsp::CodeLens {
+    range: code_lens.range,
+    command: Some(command),
+    data: None,
+  })
+}
+
 async fn resolve_implementation_code_lens(
   code_lens: lsp::CodeLens,
   data: CodeLensData,
@@ -326,16 +466,32 @@ async fn resolve_references_code_lens(
   let asset_or_document =
     language_server.get_asset_or_document(&data.specifier)?;
   let line_index = asset_or_document.line_index();
+  let cache_key = (
+    data.specifier.clone(),
+    code_lens.range.start,
+    asset_or_document.document().map(|d| d.script_version()),
+  );
 
-  let maybe_referenced_symbols = language_server
-    .ts_server
-    .find_references(
-      language_server.snapshot(),
-      data.specifier.clone(),
-      line_index.offset_tsc(code_lens.range.start)?,
-    )
-    .await?;
-  let locations = get_locations(maybe_referenced_symbols, language_server)?;
+  let locations =
+    match language_server.references_code_lens_cache.get(&cache_key) {
+      Some(locations) => locations,
+      None => {
+        let maybe_referenced_symbols = language_server
+          .ts_server
+          .find_references(
+            language_server.snapshot(),
+            data.specifier.clone(),
+            line_index.offset_tsc(code_lens.range.start)?,
+          )
+          .await?;
+        let locations =
+          get_locations(maybe_referenced_symbols, language_server)?;
+        language_server
+          .references_code_lens_cache
+          .insert(cache_key, locations.clone());
+        locations
+      }
+    };
   let title = if locations.len() == 1 {
     "1 reference".to_string()
   } else {
@@ -372,6 +528,9 @@ pub async fn resolve_code_lens(
   let data: CodeLensData =
     serde_json::from_value(code_lens.data.clone().unwrap())?;
   match data.source {
+    CodeLensSource::FileReferences => {
+      resolve_file_references_code_lens(code_lens, data, language_server).await
+    }
     CodeLensSource::Implementations => {
       resolve_implementation_code_lens(code_lens, data, language_server).await
     }
@@ -389,6 +548,18 @@ pub async fn collect(
   navigation_tree: &NavigationTree,
 ) -> Result<Vec<lsp::CodeLens>, AnyError> {
   let mut code_lenses = collect_test(specifier, parsed_source, config)?;
+  if config.workspace_settings().code_lens.file_references {
+    // The lens is shown at the top of the module, as it relates to the
+    // module as a whole.
+    code_lenses.push(lsp::CodeLens {
+      range: lsp::Range::default(),
+      command: None,
+      data: Some(json!({
+        "specifier": specifier,
+        "source": CodeLensSource::FileReferences,
+      })),
+    });
+  }
   code_lenses.extend(
     collect_tsc(
       specifier,
@@ -719,4 +890,112 @@ mod tests {
       ]
     );
   }
+
+  #[test]
+  fn test_references_code_lens_cache() {
+    let specifier = resolve_url("file:///a/mod.ts").unwrap();
+    let location = lsp::Location {
+      uri: specifier.clone(),
+      range: lsp::Range::default(),
+    };
+    let key = |line: u32| {
+      (
+        specifier.clone(),
+        lsp::Position { line, character: 0 },
+        Some("1".to_string()),
+      )
+    };
+    let cache = ReferencesCodeLensCache::default();
+    for line in 0..REFERENCES_CACHE_CAPACITY as u32 {
+      cache.insert(key(line), vec![location.clone()]);
+    }
+    // accessing the oldest entry makes it the most recently used one
+    assert_eq!(cache.get(&key(0)), Some(vec![location.clone()]));
+    cache.insert(key(REFERENCES_CACHE_CAPACITY as u32), vec![]);
+    assert_eq!(cache.get(&key(0)), Some(vec![location]));
+    assert_eq!(cache.get(&key(1)), None);
+    assert_eq!(
+      cache.get(&(specifier.clone(), lsp::Position::default(), None)),
+      None
+    );
+    cache.clear();
+    assert_eq!(cache.get(&key(0)), None);
+  }
+
+  #[test]
+  fn test_deno_bench_collector() {
+    let specifier = resolve_url("https://deno.land/x/mod.ts").unwrap();
+    let source = r#"
+      Deno.bench("bench a", function () {});
+
+      const { bench } = Deno;
+      bench("bench b", () => {});
+    "#;
+    let parsed_module = deno_ast::parse_module(deno_ast::ParseParams {
+      specifier: specifier.to_string(),
+      text_info: SourceTextInfo::new(source.into()),
+      media_type: MediaType::TypeScript,
+      capture_tokens: true,
+      scope_analysis: true,
+      maybe_syntax: None,
+    })
+    .unwrap();
+    let mut collector =
+      DenoTestCollector::new(specifier, parsed_module.clone());
+    parsed_module.module().visit_with(&mut collector);
+    let actual = collector
+      .take()
+      .into_iter()
+      .map(|code_lens| {
+        let command = code_lens.command.unwrap();
+        (
+          code_lens.range.start,
+          command.title,
+          command.command,
+          command.arguments.unwrap()[1].clone(),
+        )
+      })
+      .collect::<Vec<_>>();
+    assert_eq!(
+      actual,
+      vec![
+        (
+          lsp::Position {
+            line: 1,
+            character: 11,
+          },
+          "▶\u{fe0e} Run Bench".to_string(),
+          "deno.bench".to_string(),
+          json!("bench a"),
+        ),
+        (
+          lsp::Position {
+            line: 1,
+            character: 11,
+          },
+          "Debug".to_string(),
+          "deno.bench".to_string(),
+          json!("bench a"),
+        ),
+        (
+          lsp::Position {
+            line: 4,
+            character: 6,
+          },
+          "▶\u{fe0e} Run Bench".to_string(),
+          "deno.bench".to_string(),
+          json!("bench b"),
+        ),
+        (
+          lsp::Position {
+            line: 4,
+            character: 6,
+          },
+          "Debug".to_string(),
+          "deno.bench".to_string(),
+          json!("bench b"),
+        ),
+      ]
+    );
+  }
 }
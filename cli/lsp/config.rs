@@ -25,6 +25,9 @@ pub const SETTINGS_SECTION: &str = "deno";
 #[derive(Debug, Clone, Default)]
 pub struct ClientCapabilities {
   pub code_action_disabled_support: bool,
+  /// The client can resolve the edits of code actions lazily with a
+  /// `codeAction/resolve` request.
+  pub code_action_resolve_support: bool,
   pub line_folding_only: bool,
   pub snippet_support: bool,
   pub status_notification: bool,
@@ -46,6 +49,10 @@ pub struct CodeLensSettings {
   /// Flag for providing implementation code lenses.
   #[serde(default)]
   pub implementations: bool,
+  /// Flag for providing a code lens at the top of a module which lists the
+  /// modules that import it.
+  #[serde(default)]
+  pub file_references: bool,
   /// Flag for providing reference code lenses.
   #[serde(default)]
   pub references: bool,
@@ -63,6 +70,7 @@ impl Default for CodeLensSettings {
   fn default() -> Self {
     Self {
       implementations: false,
+      file_references: false,
       references: false,
       references_all_functions: false,
       test: true,
@@ -94,10 +102,12 @@ pub struct CompletionSettings {
   pub names: bool,
   #[serde(default = "is_true")]
   pub paths: bool,
-  #[serde(default = "is_true")]
-  pub auto_imports: bool,
+  #[serde(default)]
+  pub auto_imports: AutoImportSettings,
   #[serde(default)]
   pub imports: ImportCompletionSettings,
+  #[serde(default)]
+  pub commit_characters: CommitCharactersSettings,
 }
 
 impl Default for CompletionSettings {
@@ -106,8 +116,85 @@ impl Default for CompletionSettings {
       complete_function_calls: false,
       names: true,
       paths: true,
-      auto_imports: true,
+      auto_imports: AutoImportSettings::default(),
       imports: ImportCompletionSettings::default(),
+      commit_characters: CommitCharactersSettings::default(),
+    }
+  }
+}
+
+/// The settings for auto-import completions. For backwards compatibility these
+/// can also be given as a boolean, which only sets `enabled`.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", from = "AutoImportSettingsValue")]
+pub struct AutoImportSettings {
+  /// A flag that indicates if completions should include symbols from modules
+  /// which are not imported yet.
+  pub enabled: bool,
+  /// Glob patterns of files whose exports should not be suggested for
+  /// auto-imports. Relative patterns are resolved against the workspace root.
+  pub exclude_patterns: Vec<String>,
+}
+
+impl Default for AutoImportSettings {
+  fn default() -> Self {
+    Self {
+      enabled: true,
+      exclude_patterns: Vec::new(),
+    }
+  }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum AutoImportSettingsValue {
+  Enabled(bool),
+  #[serde(rename_all = "camelCase")]
+  Settings {
+    #[serde(default = "is_true")]
+    enabled: bool,
+    #[serde(default)]
+    exclude_patterns: Vec<String>,
+  },
+}
+
+impl From<AutoImportSettingsValue> for AutoImportSettings {
+  fn from(value: AutoImportSettingsValue) -> Self {
+    match value {
+      AutoImportSettingsValue::Enabled(enabled) => Self {
+        enabled,
+        exclude_patterns: Vec::new(),
+      },
+      AutoImportSettingsValue::Settings {
+        enabled,
+        exclude_patterns,
+      } => Self {
+        enabled,
+        exclude_patterns,
+      },
+    }
+  }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitCharactersSettings {
+  /// A flag that indicates if completion items should be accepted by typing
+  /// a commit character, like `.` or `(`.
+  #[serde(default = "is_true")]
+  pub enabled: bool,
+  /// A map of TypeScript element kinds, like `"function"` or `"interface"`,
+  /// to the commit characters used for completion items of that kind instead
+  /// of the default ones.
+  #[serde(default)]
+  pub kinds: HashMap<String, Vec<String>>,
+}
+
+impl Default for CommitCharactersSettings {
+  fn default() -> Self {
+    Self {
+      enabled: true,
+      kinds: HashMap::default(),
     }
   }
 }
@@ -207,6 +294,57 @@ pub struct InlayHintsEnumMemberValuesOptions {
   pub enabled: bool,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PreferencesSettings {
+  /// How the module specifiers of generated imports should end.
+  #[serde(default)]
+  pub import_module_specifier_ending: ImportModuleSpecifierEnding,
+  /// A flag that indicates if renames should introduce aliases where needed
+  /// to keep the external name, like for shorthand properties and named
+  /// imports and exports.
+  #[serde(default = "is_true")]
+  pub use_aliases_for_renames: bool,
+  /// A flag that indicates if renames should also update matching text in
+  /// string literals.
+  #[serde(default)]
+  pub rename_in_strings: bool,
+  /// A flag that indicates if renames should also update matching text in
+  /// comments, like JSDoc references.
+  #[serde(default)]
+  pub rename_in_comments: bool,
+}
+
+impl Default for PreferencesSettings {
+  fn default() -> Self {
+    Self {
+      import_module_specifier_ending: Default::default(),
+      use_aliases_for_renames: true,
+      rename_in_strings: false,
+      rename_in_comments: false,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportModuleSpecifierEnding {
+  /// Let TypeScript pick an ending based on the existing imports.
+  Auto,
+  /// Leave out the extension and `/index` where possible.
+  Minimal,
+  /// Leave out the extension, but keep `/index`.
+  Index,
+  /// Always end with the file extension.
+  Js,
+}
+
+impl Default for ImportModuleSpecifierEnding {
+  fn default() -> Self {
+    Self::Index
+  }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct ImportCompletionSettings {
@@ -267,6 +405,92 @@ impl Default for TestingSettings {
   }
 }
 
+/// Settings which control the behavior of the language server itself.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct LspSettings {
+  /// Settings for tracing the performance of the language server.
+  #[serde(default)]
+  pub trace: TraceSettings,
+
+  /// Settings for the tsc runtimes which serve the TypeScript language
+  /// service.
+  #[serde(default)]
+  pub tsc: TscSettings,
+
+  /// The number of documents whose diagnostics are computed together, and
+  /// sent as one partial result, for a `workspace/diagnostic` request.
+  #[serde(default = "default_workspace_diagnostic_batch_size")]
+  pub workspace_diagnostic_batch_size: usize,
+}
+
+impl Default for LspSettings {
+  fn default() -> Self {
+    Self {
+      trace: Default::default(),
+      tsc: Default::default(),
+      workspace_diagnostic_batch_size: default_workspace_diagnostic_batch_size(
+      ),
+    }
+  }
+}
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceSettings {
+  /// An option that points to a path string of a file which the language
+  /// server writes Chrome trace format events of its requests to.
+  #[serde(default, deserialize_with = "empty_string_none")]
+  pub file: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TscSettings {
+  /// The number of tsc runtimes. Each additional runtime keeps its own
+  /// program in memory, in exchange for diagnostics which no longer block
+  /// interactive requests.
+  #[serde(default = "default_tsc_pool_size")]
+  pub pool_size: usize,
+
+  /// The number of seconds a tsc runtime may work on a request without
+  /// checking back with the language server before it is considered to hang
+  /// and is restarted. `0` turns this off.
+  #[serde(default = "default_tsc_request_timeout")]
+  pub request_timeout: u64,
+
+  /// The heap size of a tsc runtime in megabytes above which it is restarted
+  /// once it is idle. `0` turns this off.
+  #[serde(default = "default_tsc_max_heap_size")]
+  pub max_heap_size: usize,
+}
+
+impl Default for TscSettings {
+  fn default() -> Self {
+    Self {
+      pool_size: default_tsc_pool_size(),
+      request_timeout: default_tsc_request_timeout(),
+      max_heap_size: default_tsc_max_heap_size(),
+    }
+  }
+}
+
+fn default_tsc_pool_size() -> usize {
+  1
+}
+
+fn default_tsc_request_timeout() -> u64 {
+  120
+}
+
+fn default_tsc_max_heap_size() -> usize {
+  3072
+}
+
+fn default_workspace_diagnostic_batch_size() -> usize {
+  50
+}
+
 fn default_to_true() -> bool {
   true
 }
@@ -332,11 +556,26 @@ pub struct WorkspaceSettings {
   #[serde(default = "default_document_preload_limit")]
   pub document_preload_limit: usize,
 
+  /// Settings which control the behavior of the language server itself.
+  #[serde(default)]
+  pub lsp: LspSettings,
+
+  /// Preferences for code which is generated by the language server, like
+  /// the import statements of auto-imports.
+  #[serde(default)]
+  pub preferences: PreferencesSettings,
+
   /// A flag that indicates if Dene should validate code against the unstable
   /// APIs for the workspace.
   #[serde(default)]
   pub suggest: CompletionSettings,
 
+  /// A flag that indicates if tsc's suggestion diagnostics, like "This may be
+  /// converted to an async function", should be published as hints. Those
+  /// which mark code as unused or deprecated are always published.
+  #[serde(default = "is_true")]
+  pub suggestion_diagnostics: bool,
+
   /// Testing settings for the workspace.
   #[serde(default)]
   pub testing: TestingSettings,
@@ -353,6 +592,12 @@ pub struct WorkspaceSettings {
 
   #[serde(default)]
   pub unstable: bool,
+
+  /// An option that points to a path string of the directory untitled
+  /// documents are placed in, which their relative imports are resolved
+  /// against. Defaults to the root of the workspace.
+  #[serde(default, deserialize_with = "empty_string_none")]
+  pub untitled_base: Option<String>,
 }
 
 impl Default for WorkspaceSettings {
@@ -369,11 +614,15 @@ impl Default for WorkspaceSettings {
       internal_debug: false,
       lint: true,
       document_preload_limit: default_document_preload_limit(),
+      lsp: Default::default(),
+      preferences: Default::default(),
       suggest: Default::default(),
+      suggestion_diagnostics: true,
       testing: Default::default(),
       tls_certificate: None,
       unsafely_ignore_certificate_errors: None,
       unstable: false,
+      untitled_base: None,
     }
   }
 }
@@ -382,7 +631,9 @@ impl WorkspaceSettings {
   /// Determine if any code lenses are enabled at all.  This allows short
   /// circuiting when there are no code lenses enabled.
   pub fn enabled_code_lens(&self) -> bool {
-    self.code_lens.implementations || self.code_lens.references
+    self.code_lens.implementations
+      || self.code_lens.file_references
+      || self.code_lens.references
   }
 
   /// Determine if any inlay hints are enabled. This allows short circuiting
@@ -558,6 +809,27 @@ impl Config {
     });
   }
 
+  /// Replace the config file with a version of it which only changes its
+  /// compiler options, keeping the lockfile and the other state resolved from
+  /// the rest of it. Returns `false` if the config file has to be reloaded.
+  pub fn update_config_file_compiler_options(
+    &mut self,
+    config_file: ConfigFile,
+  ) -> bool {
+    let Some(info) = &mut self.maybe_config_file_info else {
+      return false;
+    };
+    if !info
+      .config_file
+      .file
+      .is_compiler_options_change(&config_file)
+    {
+      return false;
+    }
+    info.config_file.file = config_file;
+    true
+  }
+
   pub fn workspace_settings(&self) -> &WorkspaceSettings {
     &self.settings.workspace
   }
@@ -677,6 +949,12 @@ impl Config {
         .as_ref()
         .and_then(|it| it.disabled_support)
         .unwrap_or(false);
+      self.client_capabilities.code_action_resolve_support = text_document
+        .code_action
+        .as_ref()
+        .and_then(|it| it.resolve_support.as_ref())
+        .map(|it| it.properties.iter().any(|p| p == "edit"))
+        .unwrap_or(false);
       self.client_capabilities.snippet_support =
         if let Some(completion) = &text_document.completion {
           completion
@@ -917,6 +1195,7 @@ mod tests {
         import_map: None,
         code_lens: CodeLensSettings {
           implementations: false,
+          file_references: false,
           references: false,
           references_all_functions: false,
           test: true,
@@ -944,16 +1223,39 @@ mod tests {
         internal_debug: false,
         lint: true,
         document_preload_limit: 1_000,
+        lsp: LspSettings {
+          trace: TraceSettings { file: None },
+          tsc: TscSettings {
+            pool_size: 1,
+            request_timeout: 120,
+            max_heap_size: 3072,
+          },
+          workspace_diagnostic_batch_size: 50,
+        },
+        preferences: PreferencesSettings {
+          import_module_specifier_ending: ImportModuleSpecifierEnding::Index,
+          use_aliases_for_renames: true,
+          rename_in_strings: false,
+          rename_in_comments: false,
+        },
         suggest: CompletionSettings {
           complete_function_calls: false,
           names: true,
           paths: true,
-          auto_imports: true,
+          auto_imports: AutoImportSettings {
+            enabled: true,
+            exclude_patterns: vec![],
+          },
           imports: ImportCompletionSettings {
             auto_discover: true,
             hosts: HashMap::new(),
-          }
+          },
+          commit_characters: CommitCharactersSettings {
+            enabled: true,
+            kinds: HashMap::new(),
+          },
         },
+        suggestion_diagnostics: true,
         testing: TestingSettings {
           args: vec!["--allow-all".to_string(), "--no-check".to_string()],
           enable: true
@@ -961,6 +1263,7 @@ mod tests {
         tls_certificate: None,
         unsafely_ignore_certificate_errors: None,
         unstable: false,
+        untitled_base: None,
       }
     );
   }
@@ -1013,6 +1316,95 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_auto_import_settings() {
+    let mut config = Config::new();
+    config
+      .set_workspace_settings(json!({ "suggest": { "autoImports": false } }))
+      .expect("could not update");
+    assert_eq!(
+      config.workspace_settings().suggest.auto_imports,
+      AutoImportSettings {
+        enabled: false,
+        exclude_patterns: vec![],
+      }
+    );
+    config
+      .set_workspace_settings(json!({
+        "suggest": {
+          "autoImports": { "excludePatterns": ["vendor/**"] }
+        }
+      }))
+      .expect("could not update");
+    assert_eq!(
+      config.workspace_settings().suggest.auto_imports,
+      AutoImportSettings {
+        enabled: true,
+        exclude_patterns: vec!["vendor/**".to_string()],
+      }
+    );
+  }
+
+  #[test]
+  fn test_tsc_settings() {
+    let mut config = Config::new();
+    config
+      .set_workspace_settings(json!({
+        "lsp": {
+          "tsc": { "poolSize": 3, "requestTimeout": 0 }
+        }
+      }))
+      .expect("could not update");
+    assert_eq!(
+      config.workspace_settings().lsp.tsc,
+      TscSettings {
+        pool_size: 3,
+        request_timeout: 0,
+        max_heap_size: 3072,
+      }
+    );
+    let options = crate::lsp::tsc::TsServerOptions::from(
+      &config.workspace_settings().lsp.tsc,
+    );
+    assert_eq!(options.pool_size, 3);
+    assert_eq!(options.request_timeout, None);
+    assert_eq!(options.max_heap_size, Some(3072 * 1024 * 1024));
+  }
+
+  #[test]
+  fn test_workspace_diagnostic_batch_size() {
+    let mut config = Config::new();
+    config
+      .set_workspace_settings(json!({
+        "lsp": { "workspaceDiagnosticBatchSize": 10 }
+      }))
+      .expect("could not update");
+    assert_eq!(
+      config
+        .workspace_settings()
+        .lsp
+        .workspace_diagnostic_batch_size,
+      10
+    );
+  }
+
+  #[test]
+  fn test_import_module_specifier_ending_preference() {
+    let mut config = Config::new();
+    config
+      .set_workspace_settings(json!({
+        "preferences": { "importModuleSpecifierEnding": "js" }
+      }))
+      .expect("could not update");
+    assert_eq!(
+      config
+        .workspace_settings()
+        .preferences
+        .import_module_specifier_ending,
+      ImportModuleSpecifierEnding::Js
+    );
+  }
+
   #[test]
   fn config_enabled_urls() {
     let mut config = Config::new();
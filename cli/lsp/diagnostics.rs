@@ -16,9 +16,11 @@ use super::urls::LspClientUrl;
 use super::urls::LspUrlMap;
 
 use crate::args::LintOptions;
+use crate::cache::FastInsecureHasher;
 use crate::graph_util;
 use crate::graph_util::enhanced_resolution_error_message;
 use crate::lsp::lsp_custom::DiagnosticBatchNotificationParams;
+use crate::lsp::lsp_custom::DocumentDiagnosticReport;
 use crate::tools::lint::get_configured_rules;
 
 use deno_ast::MediaType;
@@ -135,11 +137,12 @@ impl DiagnosticsPublisher {
       // DO NOT filter these by version. We want to display even out
       // of date diagnostics in order to prevent flickering. The user's
       // lsp client will eventually catch up.
-      let all_specifier_diagnostics = diagnostics_by_source
+      let mut all_specifier_diagnostics = diagnostics_by_source
         .values()
         .flat_map(|d| &d.diagnostics)
         .cloned()
         .collect::<Vec<_>>();
+      normalize_related_information(&mut all_specifier_diagnostics, url_map);
 
       self
         .client
@@ -645,10 +648,10 @@ fn to_lsp_related_information(
     related
       .iter()
       .filter_map(|ri| {
-        if let (Some(source), Some(start), Some(end)) =
-          (&ri.source, &ri.start, &ri.end)
+        if let (Some(file_name), Some(start), Some(end)) =
+          (&ri.file_name, &ri.start, &ri.end)
         {
-          let uri = lsp::Url::parse(source).unwrap();
+          let uri = lsp::Url::parse(file_name).ok()?;
           Some(lsp::DiagnosticRelatedInformation {
             location: lsp::Location {
               uri,
@@ -664,11 +667,58 @@ fn to_lsp_related_information(
   })
 }
 
+/// The related information of tsc diagnostics is located by the specifiers of
+/// the modules, which have to be mapped to the URLs that the client knows the
+/// modules by, like `deno:` URLs for remote modules.
+pub fn normalize_related_information(
+  diagnostics: &mut [lsp::Diagnostic],
+  url_map: &LspUrlMap,
+) {
+  for related in diagnostics
+    .iter_mut()
+    .filter_map(|d| d.related_information.as_mut())
+    .flatten()
+  {
+    if let Ok(uri) = url_map.normalize_specifier(&related.location.uri) {
+      related.location.uri = uri.into_url();
+    }
+  }
+}
+
+/// Tag the diagnostics which tsc reports for unused or deprecated code, so
+/// the client can fade them out or strike them through.
+fn to_lsp_tags(
+  diagnostic: &crate::tsc::Diagnostic,
+) -> Option<Vec<lsp::DiagnosticTag>> {
+  let mut tags = Vec::new();
+  if diagnostic.reports_unnecessary == Some(true) {
+    tags.push(lsp::DiagnosticTag::UNNECESSARY);
+  }
+  if diagnostic.reports_deprecated == Some(true) {
+    tags.push(lsp::DiagnosticTag::DEPRECATED);
+  }
+  if tags.is_empty() {
+    None
+  } else {
+    Some(tags)
+  }
+}
+
+/// Suggestions which don't mark code as unused or deprecated are only
+/// published when the suggestion diagnostics are enabled.
+fn is_plain_suggestion(diagnostic: &crate::tsc::Diagnostic) -> bool {
+  diagnostic.category == crate::tsc::DiagnosticCategory::Suggestion
+    && diagnostic.reports_unnecessary != Some(true)
+    && diagnostic.reports_deprecated != Some(true)
+}
+
 fn ts_json_to_diagnostics(
   diagnostics: Vec<crate::tsc::Diagnostic>,
+  include_suggestions: bool,
 ) -> Vec<lsp::Diagnostic> {
   diagnostics
     .iter()
+    .filter(|d| include_suggestions || !is_plain_suggestion(d))
     .filter_map(|d| {
       if let (Some(start), Some(end)) = (&d.start, &d.end) {
         Some(lsp::Diagnostic {
@@ -681,14 +731,7 @@ fn ts_json_to_diagnostics(
           related_information: to_lsp_related_information(
             &d.related_information,
           ),
-          tags: match d.code {
-            // These are codes that indicate the variable is unused.
-            2695 | 6133 | 6138 | 6192 | 6196 | 6198 | 6199 | 6205 | 7027
-            | 7028 => Some(vec![lsp::DiagnosticTag::UNNECESSARY]),
-            // These are codes that indicated the variable is deprecated.
-            2789 | 6385 | 6387 => Some(vec![lsp::DiagnosticTag::DEPRECATED]),
-            _ => None,
-          },
+          tags: to_lsp_tags(d),
           data: None,
         })
       } else {
@@ -806,7 +849,10 @@ async fn generate_ts_diagnostics(
     // check if the specifier is enabled again just in case TS returns us
     // diagnostics for a disabled specifier
     let ts_diagnostics = if config.specifier_enabled(&specifier) {
-      ts_json_to_diagnostics(ts_json_diagnostics)
+      ts_json_to_diagnostics(
+        ts_json_diagnostics,
+        config.settings.workspace.suggestion_diagnostics,
+      )
     } else {
       Vec::new()
     };
@@ -1302,24 +1348,13 @@ fn generate_deno_diagnostics(
     if token.is_cancelled() {
       break;
     }
-    let mut diagnostics = Vec::new();
-    let specifier = document.specifier();
-    if config.specifier_enabled(specifier) {
-      for (dependency_key, dependency) in document.dependencies() {
-        diagnose_dependency(
-          &mut diagnostics,
-          snapshot,
-          specifier,
-          dependency_key,
-          dependency,
-        );
-      }
-    }
     diagnostics_vec.push(DiagnosticRecord {
-      specifier: specifier.clone(),
+      specifier: document.specifier().clone(),
       versioned: VersionedDiagnostics {
         version: document.maybe_lsp_version(),
-        diagnostics,
+        diagnostics: generate_document_deno_diagnostics(
+          snapshot, config, &document,
+        ),
       },
     });
   }
@@ -1327,6 +1362,146 @@ fn generate_deno_diagnostics(
   diagnostics_vec
 }
 
+fn generate_document_deno_diagnostics(
+  snapshot: &language_server::StateSnapshot,
+  config: &ConfigSnapshot,
+  document: &Document,
+) -> Vec<lsp::Diagnostic> {
+  let mut diagnostics = Vec::new();
+  let specifier = document.specifier();
+  if config.specifier_enabled(specifier) {
+    for (dependency_key, dependency) in document.dependencies() {
+      diagnose_dependency(
+        &mut diagnostics,
+        snapshot,
+        specifier,
+        dependency_key,
+        dependency,
+      );
+    }
+  }
+  diagnostics
+}
+
+/// Generates the diagnostics of all sources for a single document. This is
+/// used when the client pulls diagnostics via `textDocument/diagnostic`
+/// instead of waiting for them to be published.
+pub async fn generate_document_diagnostics(
+  snapshot: Arc<StateSnapshot>,
+  config: &ConfigSnapshot,
+  lint_options: &LintOptions,
+  ts_server: &TsServer,
+  specifier: &ModuleSpecifier,
+  token: CancellationToken,
+) -> Result<Vec<lsp::Diagnostic>, AnyError> {
+  let diagnostics = generate_documents_diagnostics(
+    snapshot,
+    config,
+    lint_options,
+    ts_server,
+    vec![specifier.clone()],
+    token,
+  )
+  .await?;
+  Ok(
+    diagnostics
+      .into_iter()
+      .next()
+      .map(|(_, diagnostics)| diagnostics)
+      .unwrap_or_default(),
+  )
+}
+
+/// Generates the diagnostics of all sources for each of the documents, with
+/// a single request to tsc. Specifiers without a document are skipped.
+pub async fn generate_documents_diagnostics(
+  snapshot: Arc<StateSnapshot>,
+  config: &ConfigSnapshot,
+  lint_options: &LintOptions,
+  ts_server: &TsServer,
+  specifiers: Vec<ModuleSpecifier>,
+  token: CancellationToken,
+) -> Result<Vec<(ModuleSpecifier, Vec<lsp::Diagnostic>)>, AnyError> {
+  let documents = specifiers
+    .iter()
+    .filter_map(|s| snapshot.documents.get(s))
+    .collect::<Vec<_>>();
+  let enabled_specifiers = documents
+    .iter()
+    .map(|d| d.specifier())
+    .filter(|s| config.specifier_enabled(s))
+    .cloned()
+    .collect::<Vec<_>>();
+  let mut ts_diagnostics_map = if !enabled_specifiers.is_empty() {
+    ts_server
+      .get_diagnostics(snapshot.clone(), enabled_specifiers, token)
+      .await?
+  } else {
+    Default::default()
+  };
+  let lint_rules = get_configured_rules(lint_options.rules.clone());
+
+  let mut diagnostics_vec = Vec::with_capacity(documents.len());
+  for document in documents {
+    let specifier = document.specifier();
+    if !config.specifier_enabled(specifier) {
+      diagnostics_vec.push((specifier.clone(), Vec::new()));
+      continue;
+    }
+    let mut diagnostics = ts_diagnostics_map
+      .remove(specifier.as_str())
+      .map(|d| {
+        ts_json_to_diagnostics(
+          d,
+          config.settings.workspace.suggestion_diagnostics,
+        )
+      })
+      .unwrap_or_default();
+    diagnostics.extend(generate_document_deno_diagnostics(
+      &snapshot, config, &document,
+    ));
+    let in_npm_package = snapshot
+      .maybe_node_resolver
+      .as_ref()
+      .map(|r| r.in_npm_package(specifier))
+      .unwrap_or(false);
+    if config.settings.workspace.lint && !in_npm_package {
+      diagnostics.extend(generate_document_lint_diagnostics(
+        config,
+        lint_options,
+        lint_rules.clone(),
+        &document,
+      ));
+    }
+    diagnostics_vec.push((specifier.clone(), diagnostics));
+  }
+  Ok(diagnostics_vec)
+}
+
+/// Identifies a set of diagnostics, so that a client pulling diagnostics can
+/// be told that they are unchanged since its previous request.
+fn get_diagnostics_result_id(diagnostics: &[lsp::Diagnostic]) -> String {
+  let json = serde_json::to_string(diagnostics).unwrap();
+  FastInsecureHasher::hash(json).to_string()
+}
+
+/// Creates the report for pulled diagnostics, which is `unchanged` if they
+/// match the report the client already has.
+pub fn to_document_diagnostic_report(
+  diagnostics: Vec<lsp::Diagnostic>,
+  previous_result_id: Option<&String>,
+) -> DocumentDiagnosticReport {
+  let result_id = get_diagnostics_result_id(&diagnostics);
+  if previous_result_id == Some(&result_id) {
+    DocumentDiagnosticReport::Unchanged { result_id }
+  } else {
+    DocumentDiagnosticReport::Full {
+      result_id: Some(result_id),
+      items: diagnostics,
+    }
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -1339,6 +1514,7 @@ mod tests {
   use crate::lsp::documents::Documents;
   use crate::lsp::documents::LanguageId;
   use crate::lsp::language_server::StateSnapshot;
+  use crate::lsp::tsc::TsServerOptions;
   use pretty_assertions::assert_eq;
   use std::path::Path;
   use std::path::PathBuf;
@@ -1430,7 +1606,15 @@ let c: number = "a";
     let snapshot = Arc::new(snapshot);
     let cache =
       Arc::new(GlobalHttpCache::new(cache_location, RealDenoCacheEnv));
-    let ts_server = TsServer::new(Default::default(), cache);
+    let ts_server = TsServer::new(
+      Default::default(),
+      cache,
+      TsServerOptions {
+        pool_size: 1,
+        request_timeout: None,
+        max_heap_size: None,
+      },
+    );
 
     // test enabled
     {
@@ -1524,7 +1708,15 @@ let c: number = "a";
     let snapshot = Arc::new(snapshot);
     let cache =
       Arc::new(GlobalHttpCache::new(cache_location, RealDenoCacheEnv));
-    let ts_server = TsServer::new(Default::default(), cache);
+    let ts_server = TsServer::new(
+      Default::default(),
+      cache,
+      TsServerOptions {
+        pool_size: 1,
+        request_timeout: None,
+        max_heap_size: None,
+      },
+    );
 
     let config = mock_config();
     let token = CancellationToken::new();
@@ -1537,6 +1729,49 @@ let c: number = "a";
     assert_eq!(diagnostics.len(), 0);
   }
 
+  #[tokio::test]
+  async fn test_ts_diagnostics_with_tsc_pool() {
+    let temp_dir = TempDir::new();
+    let (snapshot, cache_location) = setup(
+      &temp_dir,
+      &[(
+        "file:///a.ts",
+        r#"export let a: string = 5;"#,
+        1,
+        LanguageId::TypeScript,
+      )],
+      None,
+    );
+    let snapshot = Arc::new(snapshot);
+    let cache =
+      Arc::new(GlobalHttpCache::new(cache_location, RealDenoCacheEnv));
+    let ts_server = TsServer::new(
+      Default::default(),
+      cache,
+      TsServerOptions {
+        pool_size: 3,
+        request_timeout: None,
+        max_heap_size: None,
+      },
+    );
+
+    let config = mock_config();
+    for _ in 0..3 {
+      let diagnostics = generate_ts_diagnostics(
+        snapshot.clone(),
+        &config,
+        &ts_server,
+        CancellationToken::new(),
+      )
+      .await
+      .unwrap();
+      let diagnostics = get_diagnostics_for_single(diagnostics);
+      assert!(diagnostics
+        .iter()
+        .any(|d| d.code == Some(lsp::NumberOrString::Number(2322))));
+    }
+  }
+
   #[tokio::test]
   async fn test_deno_diagnostics_with_import_map() {
     let temp_dir = TempDir::new();
@@ -1591,6 +1826,69 @@ let c: number = "a";
     }
   }
 
+  #[test]
+  fn test_ts_json_to_diagnostics() {
+    let diagnostics: Vec<crate::tsc::Diagnostic> =
+      serde_json::from_value(json!([
+        {
+          "category": 2,
+          "code": 6133,
+          "start": { "line": 0, "character": 6 },
+          "end": { "line": 0, "character": 7 },
+          "messageText": "'a' is declared but its value is never read.",
+          "reportsUnnecessary": true,
+        },
+        {
+          "category": 2,
+          "code": 6385,
+          "start": { "line": 1, "character": 0 },
+          "end": { "line": 1, "character": 1 },
+          "messageText": "'b' is deprecated.",
+          "reportsDeprecated": true,
+        },
+        {
+          "category": 1,
+          "code": 2322,
+          "start": { "line": 2, "character": 0 },
+          "end": { "line": 2, "character": 1 },
+          "messageText": "Type 'number' is not assignable to type 'string'.",
+        },
+        {
+          "category": 2,
+          "code": 80006,
+          "start": { "line": 3, "character": 9 },
+          "end": { "line": 3, "character": 10 },
+          "messageText": "This may be converted to an async function.",
+        },
+      ]))
+      .unwrap();
+    let tags = ts_json_to_diagnostics(diagnostics.clone(), true)
+      .into_iter()
+      .map(|d| d.tags)
+      .collect::<Vec<_>>();
+    assert_eq!(
+      tags,
+      vec![
+        Some(vec![lsp::DiagnosticTag::UNNECESSARY]),
+        Some(vec![lsp::DiagnosticTag::DEPRECATED]),
+        None,
+        None,
+      ]
+    );
+    let codes = ts_json_to_diagnostics(diagnostics, false)
+      .into_iter()
+      .map(|d| d.code)
+      .collect::<Vec<_>>();
+    assert_eq!(
+      codes,
+      vec![
+        Some(lsp::NumberOrString::Number(6133)),
+        Some(lsp::NumberOrString::Number(6385)),
+        Some(lsp::NumberOrString::Number(2322)),
+      ]
+    );
+  }
+
   #[test]
   fn test_get_code_action_import_map_remap() {
     let specifier = ModuleSpecifier::parse("file:///a/file.ts").unwrap();
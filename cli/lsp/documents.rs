@@ -55,7 +55,6 @@ use std::collections::HashSet;
 use std::collections::VecDeque;
 use std::fs;
 use std::fs::ReadDir;
-use std::ops::Range;
 use std::path::Path;
 use std::path::PathBuf;
 use std::str::FromStr;
@@ -165,21 +164,6 @@ impl FromStr for LanguageId {
   }
 }
 
-#[derive(Debug, PartialEq, Eq)]
-enum IndexValid {
-  All,
-  UpTo(u32),
-}
-
-impl IndexValid {
-  fn covers(&self, line: u32) -> bool {
-    match *self {
-      IndexValid::UpTo(to) => to > line,
-      IndexValid::All => true,
-    }
-  }
-}
-
 #[derive(Debug, Clone)]
 pub enum AssetOrDocument {
   Document(Document),
@@ -289,14 +273,18 @@ struct DocumentInner {
   maybe_language_id: Option<LanguageId>,
   maybe_lsp_version: Option<i32>,
   maybe_module: Option<ModuleResult>,
-  // this is a lazily constructed value based on the state of the document,
-  // so having a mutex to hold it is ok
+  // these are lazily constructed values based on the state of the document,
+  // so having a mutex to hold them is ok
+  call_hierarchy_items: Mutex<HashMap<u32, Arc<CallHierarchyItems>>>,
   maybe_navigation_tree: Mutex<Option<Arc<tsc::NavigationTree>>>,
   maybe_parsed_source: Option<ParsedSourceResult>,
   specifier: ModuleSpecifier,
   text_info: SourceTextInfo,
 }
 
+/// The result of preparing a call hierarchy at a position of a document.
+pub type CallHierarchyItems = tsc::OneOrMany<tsc::CallHierarchyItem>;
+
 #[derive(Debug, Clone)]
 pub struct Document(Arc<DocumentInner>);
 
@@ -328,6 +316,7 @@ impl Document {
       maybe_language_id: None,
       maybe_lsp_version: None,
       maybe_module,
+      call_hierarchy_items: Default::default(),
       maybe_navigation_tree: Mutex::new(None),
       maybe_parsed_source,
       text_info,
@@ -355,6 +344,7 @@ impl Document {
       // updated properties
       dependencies,
       maybe_module,
+      call_hierarchy_items: Default::default(),
       maybe_navigation_tree: Mutex::new(None),
       maybe_parsed_source: Some(parsed_source_result),
       // maintain - this should all be copies/clones
@@ -398,6 +388,7 @@ impl Document {
       maybe_lsp_version: Some(version),
       maybe_headers: maybe_headers.map(ToOwned::to_owned),
       maybe_module,
+      call_hierarchy_items: Default::default(),
       maybe_navigation_tree: Mutex::new(None),
       maybe_parsed_source,
       text_info,
@@ -405,43 +396,53 @@ impl Document {
     }))
   }
 
+  /// Apply the changes to the document. If a change can't be applied, the
+  /// unchanged document is handed back along with the error.
   fn with_change(
-    &self,
+    self,
     version: i32,
     changes: Vec<lsp::TextDocumentContentChangeEvent>,
     resolver: &dyn deno_graph::source::Resolver,
-  ) -> Result<Document, AnyError> {
-    let mut content = self.0.text_info.text_str().to_string();
-    let mut line_index = self.0.line_index.clone();
-    let mut index_valid = IndexValid::All;
+  ) -> Result<Document, (Document, AnyError)> {
+    let mut inner = self.0;
+    let mut content = inner.text_info.text_str().to_string();
+    // the line index is taken out of the document and updated in place, it
+    // is only copied when something, like the snapshot of a pending request,
+    // still holds on to this version of the document
+    let mut line_index = match Arc::get_mut(&mut inner) {
+      Some(inner) => Arc::try_unwrap(std::mem::take(&mut inner.line_index))
+        .unwrap_or_else(|line_index| (*line_index).clone()),
+      None => (*inner.line_index).clone(),
+    };
     for change in changes {
-      if let Some(range) = change.range {
-        if !index_valid.covers(range.start.line) {
-          line_index = Arc::new(LineIndex::new(&content));
-        }
-        index_valid = IndexValid::UpTo(range.start.line);
-        let range = line_index.get_text_range(range)?;
-        content.replace_range(Range::<usize>::from(range), &change.text);
+      let result = if let Some(range) = change.range {
+        line_index.apply_change(&mut content, range, &change.text)
       } else {
         content = change.text;
-        index_valid = IndexValid::UpTo(0);
+        line_index = LineIndex::new(&content);
+        Ok(())
+      };
+      if let Err(err) = result {
+        if let Some(inner) = Arc::get_mut(&mut inner) {
+          inner.line_index =
+            Arc::new(LineIndex::new(inner.text_info.text_str()));
+        }
+        return Err((Document(inner), err));
       }
     }
     let text_info = SourceTextInfo::from_string(content);
-    let (maybe_parsed_source, maybe_module) = if self
-      .0
+    let (maybe_parsed_source, maybe_module) = if inner
       .maybe_language_id
       .as_ref()
       .map(|li| li.is_diagnosable())
       .unwrap_or(false)
     {
-      let maybe_headers = self
-        .0
+      let maybe_headers = inner
         .maybe_language_id
         .as_ref()
         .and_then(|li| li.as_headers());
       parse_and_analyze_module(
-        &self.0.specifier,
+        &inner.specifier,
         text_info.clone(),
         maybe_headers,
         resolver,
@@ -452,24 +453,21 @@ impl Document {
     let dependencies = if let Some(Ok(module)) = &maybe_module {
       Arc::new(DocumentDependencies::from_module(module))
     } else {
-      self.0.dependencies.clone() // use the last known good
-    };
-    let line_index = if index_valid == IndexValid::All {
-      line_index
-    } else {
-      Arc::new(LineIndex::new(text_info.text_str()))
+      inner.dependencies.clone() // use the last known good
     };
+    let line_index = Arc::new(line_index);
     Ok(Document(Arc::new(DocumentInner {
-      specifier: self.0.specifier.clone(),
-      fs_version: self.0.fs_version.clone(),
-      maybe_language_id: self.0.maybe_language_id,
+      specifier: inner.specifier.clone(),
+      fs_version: inner.fs_version.clone(),
+      maybe_language_id: inner.maybe_language_id,
       dependencies,
       text_info,
       line_index,
-      maybe_headers: self.0.maybe_headers.clone(),
+      maybe_headers: inner.maybe_headers.clone(),
       maybe_module,
       maybe_parsed_source,
       maybe_lsp_version: Some(version),
+      call_hierarchy_items: Default::default(),
       maybe_navigation_tree: Mutex::new(None),
     })))
   }
@@ -570,6 +568,24 @@ impl Document {
     self.0.maybe_navigation_tree.lock().clone()
   }
 
+  pub fn maybe_call_hierarchy_items(
+    &self,
+    position: u32,
+  ) -> Option<Arc<CallHierarchyItems>> {
+    self.0.call_hierarchy_items.lock().get(&position).cloned()
+  }
+
+  pub fn update_call_hierarchy_items_if_version(
+    &self,
+    position: u32,
+    items: Arc<CallHierarchyItems>,
+    script_version: &str,
+  ) {
+    if self.script_version() == script_version {
+      self.0.call_hierarchy_items.lock().insert(position, items);
+    }
+  }
+
   pub fn update_navigation_tree_if_version(
     &self,
     tree: Arc<tsc::NavigationTree>,
@@ -913,10 +929,11 @@ impl Documents {
     version: i32,
     changes: Vec<lsp::TextDocumentContentChangeEvent>,
   ) -> Result<Document, AnyError> {
-    let doc = self
-      .open_docs
-      .get(specifier)
-      .cloned()
+    // the document is taken out of the open documents, so that its line
+    // index can be updated in place
+    let maybe_open_doc = self.open_docs.remove(specifier);
+    let is_open = maybe_open_doc.is_some();
+    let doc = maybe_open_doc
       .or_else(|| {
         let mut file_system_docs = self.file_system_docs.lock();
         file_system_docs.docs.remove(specifier)
@@ -929,9 +946,18 @@ impl Documents {
         ))
       })?;
     self.dirty = true;
-    let doc = doc.with_change(version, changes, self.get_resolver())?;
-    self.open_docs.insert(doc.specifier().clone(), doc.clone());
-    Ok(doc)
+    match doc.with_change(version, changes, self.get_resolver()) {
+      Ok(doc) => {
+        self.open_docs.insert(doc.specifier().clone(), doc.clone());
+        Ok(doc)
+      }
+      Err((doc, err)) => {
+        if is_open {
+          self.open_docs.insert(doc.specifier().clone(), doc);
+        }
+        Err(err)
+      }
+    }
   }
 
   /// Close an open document, this essentially clears any editor state that is
@@ -1155,6 +1181,39 @@ impl Documents {
     self.dirty = true;
   }
 
+  /// Tries to cache the call hierarchy items at a position of the provided
+  /// specifier if the document stored has the same script version.
+  pub fn try_cache_call_hierarchy_items(
+    &self,
+    specifier: &ModuleSpecifier,
+    script_version: &str,
+    position: u32,
+    items: Arc<CallHierarchyItems>,
+  ) -> Result<(), AnyError> {
+    if let Some(doc) = self.open_docs.get(specifier) {
+      doc.update_call_hierarchy_items_if_version(
+        position,
+        items,
+        script_version,
+      )
+    } else {
+      let mut file_system_docs = self.file_system_docs.lock();
+      if let Some(doc) = file_system_docs.docs.get_mut(specifier) {
+        doc.update_call_hierarchy_items_if_version(
+          position,
+          items,
+          script_version,
+        );
+      } else {
+        return Err(custom_error(
+          "NotFound",
+          format!("Specifier not found {specifier}"),
+        ));
+      }
+    }
+    Ok(())
+  }
+
   /// Tries to cache a navigation tree that is associated with the provided specifier
   /// if the document stored has the same script version.
   pub fn try_cache_navigation_tree(
@@ -1862,6 +1921,8 @@ mod tests {
   use crate::npm::NpmResolution;
 
   use super::*;
+  use deno_core::serde_json;
+  use deno_core::serde_json::json;
   use import_map::ImportMap;
   use pretty_assertions::assert_eq;
   use test_util::PathRef;
@@ -1937,6 +1998,52 @@ console.log(b, "hello deno");
     );
   }
 
+  #[test]
+  fn test_documents_cache_call_hierarchy_items() {
+    let temp_dir = TempDir::new();
+    let (mut documents, _) = setup(&temp_dir);
+    let specifier = ModuleSpecifier::parse("file:///a.ts").unwrap();
+    let document = documents.open(
+      specifier.clone(),
+      1,
+      "typescript".parse().unwrap(),
+      "function foo() {}\n".into(),
+    );
+    let items: CallHierarchyItems = serde_json::from_value(json!({
+      "name": "foo",
+      "kind": "function",
+      "file": "file:///a.ts",
+      "span": { "start": 0, "length": 17 },
+      "selectionSpan": { "start": 9, "length": 3 },
+    }))
+    .unwrap();
+    documents
+      .try_cache_call_hierarchy_items(
+        &specifier,
+        &document.script_version(),
+        10,
+        Arc::new(items),
+      )
+      .unwrap();
+    let document = documents.get(&specifier).unwrap();
+    assert!(document.maybe_call_hierarchy_items(10).is_some());
+    assert!(document.maybe_call_hierarchy_items(0).is_none());
+
+    documents
+      .change(
+        &specifier,
+        2,
+        vec![lsp::TextDocumentContentChangeEvent {
+          range: None,
+          range_length: None,
+          text: "function bar() {}\n".to_string(),
+        }],
+      )
+      .unwrap();
+    let document = documents.get(&specifier).unwrap();
+    assert!(document.maybe_call_hierarchy_items(10).is_none());
+  }
+
   #[test]
   fn test_documents_ensure_no_duplicates() {
     // it should never happen that a user of this API causes this to happen,
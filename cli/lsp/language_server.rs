@@ -4,11 +4,14 @@ use deno_ast::MediaType;
 use deno_core::anyhow::anyhow;
 use deno_core::anyhow::Context;
 use deno_core::error::AnyError;
+use deno_core::futures::future::join_all;
+use deno_core::parking_lot::Mutex;
 use deno_core::resolve_url;
 use deno_core::serde_json;
 use deno_core::serde_json::json;
 use deno_core::serde_json::Value;
 use deno_core::task::spawn;
+use deno_core::task::spawn_blocking;
 use deno_core::ModuleSpecifier;
 use deno_graph::GraphKind;
 use deno_lockfile::Lockfile;
@@ -24,20 +27,28 @@ use log::error;
 use serde_json::from_value;
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::collections::VecDeque;
 use std::env;
 use std::fmt::Write as _;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
 use tower_lsp::jsonrpc::Error as LspError;
+use tower_lsp::jsonrpc::ErrorCode;
 use tower_lsp::jsonrpc::Result as LspResult;
 use tower_lsp::lsp_types::request::*;
 use tower_lsp::lsp_types::*;
 
+use super::analysis::fix_ts_file_rename_changes;
 use super::analysis::fix_ts_import_changes;
+use super::analysis::merge_fix_all_changes;
 use super::analysis::ts_changes_to_edit;
 use super::analysis::CodeActionCollection;
 use super::analysis::CodeActionData;
 use super::analysis::TsResponseImportMapper;
+use super::analysis::ORGANIZE_IMPORTS_ACTION_KINDS;
+use super::analysis::SOURCE_FIX_ALL_DENO;
 use super::cache;
 use super::capabilities;
 use super::client::Client;
@@ -64,6 +75,7 @@ use super::performance::Performance;
 use super::performance::PerformanceMark;
 use super::refactor;
 use super::registries::ModuleRegistry;
+use super::symbols;
 use super::testing;
 use super::text;
 use super::tsc;
@@ -145,6 +157,10 @@ impl LspNpmConfigHash {
   }
 }
 
+/// How long to wait after a document is opened or changed before computing its
+/// navigation tree in the background.
+const NAVIGATION_TREE_PRECOMPUTE_DELAY: Duration = Duration::from_millis(500);
+
 #[derive(Debug, Clone)]
 pub struct LanguageServer(Arc<tokio::sync::RwLock<Inner>>);
 
@@ -159,6 +175,55 @@ pub struct StateSnapshot {
   pub maybe_npm_resolver: Option<Arc<CliNpmResolver>>,
 }
 
+/// The maximum number of remote modules whose doc nodes are cached.
+const REMOTE_MODULE_DOCS_CACHE_CAPACITY: usize = 50;
+
+type RemoteModuleDocsCacheEntry =
+  (ModuleSpecifier, String, Arc<Vec<deno_doc::DocNode>>);
+
+/// A least recently used cache of the doc nodes of remote modules, along with
+/// the script version of the module they were parsed from.
+#[derive(Debug, Default)]
+struct RemoteModuleDocsCache(Mutex<VecDeque<RemoteModuleDocsCacheEntry>>);
+
+impl RemoteModuleDocsCache {
+  fn get(
+    &self,
+    specifier: &ModuleSpecifier,
+    script_version: &str,
+  ) -> Option<Arc<Vec<deno_doc::DocNode>>> {
+    let mut entries = self.0.lock();
+    let index = entries
+      .iter()
+      .position(|(s, v, _)| s == specifier && v == script_version)?;
+    let entry = entries.remove(index)?;
+    let doc_nodes = entry.2.clone();
+    entries.push_back(entry);
+    Some(doc_nodes)
+  }
+
+  fn insert(
+    &self,
+    specifier: ModuleSpecifier,
+    script_version: String,
+    doc_nodes: Arc<Vec<deno_doc::DocNode>>,
+  ) {
+    let mut entries = self.0.lock();
+    entries.retain(|(s, _, _)| s != &specifier);
+    entries.push_back((specifier, script_version, doc_nodes));
+    while entries.len() > REMOTE_MODULE_DOCS_CACHE_CAPACITY {
+      entries.pop_front();
+    }
+  }
+
+  /// Clear the cache, which is needed when the configuration or the import
+  /// map change, as they affect how remote modules are resolved. The import
+  /// map is updated on every configuration change.
+  fn clear(&self) {
+    self.0.lock().clear();
+  }
+}
+
 #[derive(Debug)]
 pub struct Inner {
   /// Cached versions of "fixed" assets that can either be inlined in Rust or
@@ -196,16 +261,27 @@ pub struct Inner {
   lint_options: LintOptions,
   /// A lazily create "server" for handling test run requests.
   maybe_testing_server: Option<testing::TestServer>,
+  /// Cancel the pending background computations of the navigation trees of
+  /// documents.
+  navigation_tree_tokens: HashMap<ModuleSpecifier, CancellationToken>,
   /// Services used for dealing with npm related functionality.
   npm: LspNpmServices,
   /// A collection of measurements which instrument that performance of the LSP.
   performance: Arc<Performance>,
+  /// Resolved references code lenses, so they are not looked up again when
+  /// nothing has changed.
+  pub references_code_lens_cache: code_lens::ReferencesCodeLensCache,
+  /// The documentation of remote modules shown in hovers.
+  remote_module_docs_cache: RemoteModuleDocsCache,
   /// A memoized version of fixable diagnostic codes retrieved from TypeScript.
   ts_fixable_diagnostics: Vec<String>,
   /// An abstraction that handles interactions with TypeScript.
   pub ts_server: Arc<TsServer>,
   /// A map of specifiers and URLs used to translate over the LSP.
   pub url_map: urls::LspUrlMap,
+  /// Cancels the pending `workspace/diagnostic` requests when documents or
+  /// the configuration change, since their results would be stale.
+  workspace_diagnostic_token: Mutex<CancellationToken>,
 }
 
 impl LanguageServer {
@@ -369,6 +445,27 @@ impl LanguageServer {
     }
   }
 
+  pub async fn file_references_request(
+    &self,
+    params: lsp_custom::FileReferencesParams,
+  ) -> LspResult<Option<Vec<Location>>> {
+    self.0.read().await.file_references(params).await
+  }
+
+  pub async fn multi_document_highlight_request(
+    &self,
+    params: TextDocumentPositionParams,
+  ) -> LspResult<Option<Vec<lsp_custom::MultiDocumentHighlight>>> {
+    self.0.read().await.multi_document_highlight(params).await
+  }
+
+  pub async fn source_definition_request(
+    &self,
+    params: TextDocumentPositionParams,
+  ) -> LspResult<Option<GotoDefinitionResponse>> {
+    self.0.read().await.source_definition(params).await
+  }
+
   pub async fn inlay_hint(
     &self,
     params: InlayHintParams,
@@ -376,6 +473,155 @@ impl LanguageServer {
     self.0.read().await.inlay_hint(params).await
   }
 
+  pub async fn inlay_hint_resolve(
+    &self,
+    params: InlayHint,
+  ) -> LspResult<InlayHint> {
+    self.0.read().await.inlay_hint_resolve(params).await
+  }
+
+  pub async fn inline_value(
+    &self,
+    params: lsp_custom::InlineValueParams,
+  ) -> LspResult<Option<Vec<lsp_custom::InlineValueVariableLookup>>> {
+    self.0.read().await.inline_value(params).await
+  }
+
+  pub async fn document_diagnostic(
+    &self,
+    params: lsp_custom::DocumentDiagnosticParams,
+  ) -> LspResult<lsp_custom::DocumentDiagnosticReport> {
+    self.0.read().await.document_diagnostic(params).await
+  }
+
+  pub async fn workspace_diagnostic(
+    &self,
+    params: lsp_custom::WorkspaceDiagnosticParams,
+  ) -> LspResult<lsp_custom::WorkspaceDiagnosticReport> {
+    // This can take a while for a large workspace, so the diagnostics are
+    // generated outside of the lock. That also allows sending partial results.
+    let (
+      client,
+      performance,
+      ts_server,
+      state,
+      previous_result_ids,
+      batch_size,
+      token,
+    ) = {
+      let ls = self.0.read().await;
+      let previous_result_ids = params
+        .previous_result_ids
+        .into_iter()
+        .map(|r| (ls.url_map.normalize_url(&r.uri, LspUrlKind::File), r.value))
+        .collect::<HashMap<_, _>>();
+      let state = DiagnosticServerUpdateMessage {
+        snapshot: ls.snapshot(),
+        config: ls.config.snapshot(),
+        lint_options: ls.lint_options.clone(),
+        url_map: ls.url_map.clone(),
+      };
+      let token = ls.workspace_diagnostic_token.lock().child_token();
+      (
+        ls.client.clone(),
+        ls.performance.clone(),
+        ls.ts_server.clone(),
+        state,
+        previous_result_ids,
+        ls.config
+          .workspace_settings()
+          .lsp
+          .workspace_diagnostic_batch_size,
+        token,
+      )
+    };
+    // the request is dropped when the client cancels it, which should stop
+    // the work in tsc as well
+    let _drop_guard = token.clone().drop_guard();
+
+    let mark = performance.mark("workspace_diagnostic", None::<()>);
+    // Only local modules are part of the workspace, unless they are open.
+    let specifiers = state
+      .snapshot
+      .documents
+      .documents(DocumentsFilter::AllDiagnosable)
+      .into_iter()
+      .filter(|d| d.is_open() || d.specifier().scheme() == "file")
+      .map(|d| d.specifier().clone())
+      .collect::<Vec<_>>();
+    let mut items = Vec::new();
+    for specifiers in specifiers.chunks(batch_size.max(1)) {
+      let diagnostics = diagnostics::generate_documents_diagnostics(
+        state.snapshot.clone(),
+        &state.config,
+        &state.lint_options,
+        &ts_server,
+        specifiers.to_vec(),
+        token.clone(),
+      )
+      .await
+      .map_err(|err| {
+        error!("Unable to get diagnostics: {}", err);
+        LspError::internal_error()
+      })?;
+      // a cancelled batch has incomplete diagnostics, so it isn't reported
+      if token.is_cancelled() {
+        performance.measure(mark);
+        return Err(LspError {
+          code: ErrorCode::ServerError(lsp_custom::SERVER_CANCELLED),
+          message: "The workspace changed while computing diagnostics."
+            .to_string(),
+          data: Some(json!(lsp_custom::DiagnosticServerCancellationData {
+            retrigger_request: true,
+          })),
+        });
+      }
+      let reports = diagnostics
+        .into_iter()
+        .map(|(specifier, mut diagnostics)| {
+          diagnostics::normalize_related_information(
+            &mut diagnostics,
+            &state.url_map,
+          );
+          let version = state
+            .snapshot
+            .documents
+            .get(&specifier)
+            .and_then(|d| d.maybe_lsp_version());
+          let report = diagnostics::to_document_diagnostic_report(
+            diagnostics,
+            previous_result_ids.get(&specifier),
+          );
+          let uri = state
+            .url_map
+            .normalize_specifier(&specifier)
+            .unwrap_or_else(|_| LspClientUrl::new(specifier));
+          lsp_custom::WorkspaceDocumentDiagnosticReport {
+            uri: uri.into_url(),
+            version,
+            report,
+          }
+        })
+        .collect::<Vec<_>>();
+      match &params.partial_result_token {
+        Some(token) => {
+          client
+            .when_outside_lsp_lock()
+            .send_workspace_diagnostic_partial_result(
+              lsp_custom::WorkspaceDiagnosticPartialResultParams {
+                token: token.clone(),
+                value: lsp_custom::WorkspaceDiagnosticReport { items: reports },
+              },
+            )
+            .await;
+        }
+        None => items.extend(reports),
+      }
+    }
+    performance.measure(mark);
+    Ok(lsp_custom::WorkspaceDiagnosticReport { items })
+  }
+
   pub async fn virtual_text_document(
     &self,
     params: Option<Value>,
@@ -563,8 +809,11 @@ impl Inner {
     let documents = Documents::new(deps_http_cache.clone());
     let cache_metadata = cache::CacheMetadata::new(deps_http_cache.clone());
     let performance = Arc::new(Performance::default());
-    let ts_server =
-      Arc::new(TsServer::new(performance.clone(), deps_http_cache.clone()));
+    let ts_server = Arc::new(TsServer::new(
+      performance.clone(),
+      deps_http_cache.clone(),
+      Default::default(),
+    ));
     let config = Config::new();
     let diagnostics_server = DiagnosticsServer::new(
       client.clone(),
@@ -608,6 +857,7 @@ impl Inner {
       maybe_testing_server: None,
       module_registries,
       module_registries_location,
+      navigation_tree_tokens: Default::default(),
       npm: LspNpmServices {
         config_hash: LspNpmConfigHash(0), // this will be updated in initialize
         api: npm_api,
@@ -616,9 +866,12 @@ impl Inner {
         resolver: npm_resolver,
       },
       performance,
+      references_code_lens_cache: Default::default(),
+      remote_module_docs_cache: Default::default(),
       ts_fixable_diagnostics: Default::default(),
       ts_server,
       url_map: Default::default(),
+      workspace_diagnostic_token: Default::default(),
     }
   }
 
@@ -686,6 +939,53 @@ impl Inner {
     Ok(navigation_tree)
   }
 
+  /// Compute the navigation tree of a document in the background once editing
+  /// has settled, so the first document symbol or code lens request doesn't
+  /// have to wait for tsc. A pending computation for the same document is
+  /// cancelled, as its result would be for an outdated version.
+  fn precompute_navigation_tree(&mut self, document: &Document) {
+    self.cancel_navigation_tree_precompute(document.specifier());
+    if !document.is_diagnosable()
+      || !self.config.specifier_enabled(document.specifier())
+      || document.maybe_navigation_tree().is_some()
+    {
+      return;
+    }
+    let token = CancellationToken::new();
+    self
+      .navigation_tree_tokens
+      .insert(document.specifier().clone(), token.clone());
+    let document = document.clone();
+    let snapshot = self.snapshot();
+    let ts_server = self.ts_server.clone();
+    spawn(async move {
+      tokio::select! {
+        _ = token.cancelled() => return,
+        _ = tokio::time::sleep(NAVIGATION_TREE_PRECOMPUTE_DELAY) => {}
+      }
+      let specifier = document.specifier().clone();
+      let result = tokio::select! {
+        _ = token.cancelled() => return,
+        result = ts_server.get_navigation_tree(snapshot, specifier) => result,
+      };
+      match result {
+        Ok(navigation_tree) => document.update_navigation_tree_if_version(
+          Arc::new(navigation_tree),
+          &document.script_version(),
+        ),
+        Err(err) => {
+          lsp_warn!("Unable to precompute navigation tree: {:#}", err);
+        }
+      }
+    });
+  }
+
+  fn cancel_navigation_tree_precompute(&mut self, specifier: &ModuleSpecifier) {
+    if let Some(token) = self.navigation_tree_tokens.remove(specifier) {
+      token.cancel();
+    }
+  }
+
   fn get_config_file(&self) -> Result<Option<ConfigFile>, AnyError> {
     let workspace_settings = self.config.workspace_settings();
     let maybe_config = &workspace_settings.config;
@@ -985,6 +1285,7 @@ impl Inner {
 
   pub async fn update_import_map(&mut self) -> Result<(), AnyError> {
     let mark = self.performance.mark("update_import_map", None::<()>);
+    self.remote_module_docs_cache.clear();
 
     let maybe_import_map_url = self.resolve_import_map_specifier()?;
     if let Some(import_map_url) = maybe_import_map_url {
@@ -1123,6 +1424,70 @@ impl Inner {
     super::logging::set_lsp_debug_flag(internal_debug)
   }
 
+  /// Resolves a path from the workspace settings, taking absolute paths as
+  /// is and joining relative paths onto the root of the workspace.
+  fn resolve_workspace_path(
+    &self,
+    path_str: &str,
+    description: &str,
+  ) -> Result<PathBuf, AnyError> {
+    let path = PathBuf::from(path_str);
+    if path.is_absolute() {
+      Ok(path)
+    } else if let Some(root_uri) = &self.config.root_uri {
+      Ok(specifier_to_file_path(root_uri)?.join(path))
+    } else {
+      Err(anyhow!(
+        "The path to the {} (\"{}\") is not resolvable.",
+        description,
+        path_str
+      ))
+    }
+  }
+
+  fn update_trace_file(&self) -> Result<(), AnyError> {
+    let maybe_trace_path =
+      match &self.config.workspace_settings().lsp.trace.file {
+        Some(trace_str) => {
+          Some(self.resolve_workspace_path(trace_str, "trace file")?)
+        }
+        None => None,
+      };
+    if let Some(trace_path) = &maybe_trace_path {
+      lsp_log!("Writing performance trace to: \"{}\"", trace_path.display());
+    }
+    self.performance.set_trace_file(maybe_trace_path)
+  }
+
+  fn update_tsc_options(&self) {
+    self
+      .ts_server
+      .update_options((&self.config.workspace_settings().lsp.tsc).into());
+  }
+
+  fn update_untitled_base(&mut self) -> Result<(), AnyError> {
+    let maybe_base_path = match &self.config.workspace_settings().untitled_base
+    {
+      Some(base_str) => {
+        Some(self.resolve_workspace_path(base_str, "untitled base")?)
+      }
+      None => self
+        .config
+        .root_uri
+        .as_ref()
+        .and_then(|root_uri| specifier_to_file_path(root_uri).ok()),
+    };
+    let maybe_base = if let Some(base_path) = maybe_base_path {
+      Some(Url::from_directory_path(&base_path).map_err(|_| {
+        anyhow!("Bad file path for untitled base: {}", base_path.display())
+      })?)
+    } else {
+      None
+    };
+    self.url_map.set_untitled_base(maybe_base);
+    Ok(())
+  }
+
   async fn update_registries(&mut self) -> Result<(), AnyError> {
     let mark = self.performance.mark("update_registries", None::<()>);
     self.recreate_http_client_and_dependents().await?;
@@ -1179,6 +1544,24 @@ impl Inner {
     Ok(())
   }
 
+  /// Apply a change to the compiler options of the config file by
+  /// reconfiguring tsc, which keeps its language service and so avoids
+  /// rechecking everything. Returns `false` if anything other than the
+  /// compiler options changed, in which case the config file has to be
+  /// reloaded.
+  async fn update_compiler_options(&mut self) -> bool {
+    let Ok(Some(config_file)) = self.get_config_file() else {
+      return false;
+    };
+    if !self.config.update_config_file_compiler_options(config_file) {
+      return false;
+    }
+    if let Err(err) = self.update_tsconfig().await {
+      self.client.show_message(MessageType::WARNING, err);
+    }
+    true
+  }
+
   async fn update_tsconfig(&mut self) -> Result<(), AnyError> {
     let mark = self.performance.mark("update_tsconfig", None::<()>);
     let mut tsconfig = TsConfig::new(json!({
@@ -1229,6 +1612,21 @@ impl Inner {
       parent_process_checker::start(parent_pid)
     }
 
+    // the tsc watchdog runs on its own thread, so messages are sent back
+    // through the tokio runtime the language server is running on
+    let client = self.client.clone();
+    let runtime = tokio::runtime::Handle::current();
+    self.ts_server.set_timeout_handler(move |timeout| {
+      let _guard = runtime.enter();
+      client.show_message(
+        MessageType::WARNING,
+        format!(
+          "The TypeScript language service did not respond within {} seconds and was restarted. The request failed and was not retried. The timeout can be changed with \"deno.lsp.tsc.requestTimeout\".",
+          timeout.as_secs()
+        ),
+      );
+    });
+
     let capabilities = capabilities::server_capabilities(&params.capabilities);
 
     let version = format!(
@@ -1282,6 +1680,13 @@ impl Inner {
     }
 
     self.update_debug_flag();
+    if let Err(err) = self.update_trace_file() {
+      self.client.show_message(MessageType::WARNING, err);
+    }
+    self.update_tsc_options();
+    if let Err(err) = self.update_untitled_base() {
+      self.client.show_message(MessageType::WARNING, err);
+    }
     // Check to see if we need to change the cache path
     if let Err(err) = self.update_cache().await {
       self.client.show_message(MessageType::WARNING, err);
@@ -1392,6 +1797,7 @@ impl Inner {
     ) {
       Ok(document) => {
         if document.is_diagnosable() {
+          self.references_code_lens_cache.clear();
           self.refresh_npm_specifiers().await;
           self
             .diagnostics_server
@@ -1399,6 +1805,7 @@ impl Inner {
           self.send_diagnostics_update();
           self.send_testing_update();
         }
+        self.precompute_navigation_tree(&document);
       }
       Err(err) => error!("{}", err),
     }
@@ -1428,6 +1835,7 @@ impl Inner {
       .url_map
       .normalize_url(&params.text_document.uri, LspUrlKind::File);
 
+    self.cancel_navigation_tree_precompute(&specifier);
     if let Err(err) = self.documents.close(&specifier) {
       error!("{}", err);
     }
@@ -1465,6 +1873,13 @@ impl Inner {
     }
 
     self.update_debug_flag();
+    if let Err(err) = self.update_trace_file() {
+      self.client.show_message(MessageType::WARNING, err);
+    }
+    self.update_tsc_options();
+    if let Err(err) = self.update_untitled_base() {
+      self.client.show_message(MessageType::WARNING, err);
+    }
     if let Err(err) = self.update_cache().await {
       self.client.show_message(MessageType::WARNING, err);
     }
@@ -1561,21 +1976,29 @@ impl Inner {
       .performance
       .mark("did_change_watched_files", Some(&params));
     let mut touched = false;
+    let mut compiler_options_changed = false;
     let changes: HashSet<Url> = params
       .changes
       .iter()
       .map(|f| self.url_map.normalize_url(&f.uri, LspUrlKind::File))
       .collect();
+    // modules on disk may have changed, which can affect references
+    self.references_code_lens_cache.clear();
 
-    // if the current deno.json has changed, we need to reload it
+    // if the current deno.json has changed, we need to reload it, unless only
+    // its compiler options have changed
     if has_config_changed(&self.config, &changes) {
-      if let Err(err) = self.update_config_file().await {
-        self.client.show_message(MessageType::WARNING, err);
-      }
-      if let Err(err) = self.update_tsconfig().await {
-        self.client.show_message(MessageType::WARNING, err);
+      if self.update_compiler_options().await {
+        compiler_options_changed = true;
+      } else {
+        if let Err(err) = self.update_config_file().await {
+          self.client.show_message(MessageType::WARNING, err);
+        }
+        if let Err(err) = self.update_tsconfig().await {
+          self.client.show_message(MessageType::WARNING, err);
+        }
+        touched = true;
       }
-      touched = true;
     }
 
     if let Some(package_json) = &self.maybe_package_json {
@@ -1609,6 +2032,12 @@ impl Inner {
       self.ts_server.restart(self.snapshot()).await;
       self.send_diagnostics_update();
       self.send_testing_update();
+    } else if compiler_options_changed {
+      // options like `jsxImportSource` and `types` affect the dependencies of
+      // documents
+      self.refresh_documents_config().await;
+      self.diagnostics_server.invalidate_all();
+      self.send_diagnostics_update();
     }
     self.performance.measure(mark);
   }
@@ -1832,12 +2261,147 @@ impl Inner {
         .ts_server
         .get_quick_info(self.snapshot(), specifier.clone(), position)
         .await?;
-      maybe_quick_info.map(|qi| qi.to_hover(line_index, self))
+      match maybe_quick_info {
+        Some(quick_info) => {
+          let mut hover = quick_info.to_hover(line_index, self);
+          // TypeScript usually documents the symbol already, so the docs of
+          // the remote module are only looked up when it doesn't
+          if !quick_info.has_documentation() {
+            if let HoverContents::Array(parts) = &mut hover.contents {
+              if let Some(docs) =
+                self.get_remote_module_docs(&specifier, position).await
+              {
+                parts.push(MarkedString::from_markdown(docs));
+              }
+            }
+          }
+          Some(hover)
+        }
+        None => None,
+      }
     };
     self.performance.measure(mark);
     Ok(hover)
   }
 
+  /// When the symbol at the position is defined in a cached remote module,
+  /// render the documentation of the symbol and its module with deno_doc,
+  /// along with a link to its documentation on the registry.
+  async fn get_remote_module_docs(
+    &self,
+    specifier: &ModuleSpecifier,
+    position: u32,
+  ) -> Option<String> {
+    let definition = self
+      .ts_server
+      .get_definition(self.snapshot(), specifier.clone(), position)
+      .await
+      .ok()??
+      .definitions?
+      .into_iter()
+      .next()?;
+    let definition_specifier =
+      resolve_url(&definition.document_span.file_name).ok()?;
+    if !matches!(definition_specifier.scheme(), "http" | "https") {
+      return None;
+    }
+    let document = self.documents.get(&definition_specifier)?;
+    let doc_nodes = self.get_remote_module_doc_nodes(&document).await?;
+
+    let mut sections = Vec::new();
+    let module_doc = doc_nodes
+      .iter()
+      .find(|n| n.kind == deno_doc::DocNodeKind::ModuleDoc)
+      .and_then(|n| n.js_doc.doc.clone());
+    if let Some(module_doc) = module_doc {
+      sections.push(module_doc);
+    }
+    let symbol_doc = doc_nodes
+      .iter()
+      .find(|n| n.name == definition.name)
+      .and_then(|n| n.js_doc.doc.clone());
+    if let Some(symbol_doc) = symbol_doc {
+      sections.push(symbol_doc);
+    }
+    let mut doc_url = document.specifier().clone();
+    // deno.land renders the documentation of a symbol with the `s` parameter
+    if doc_url.host_str() == Some("deno.land") {
+      doc_url
+        .query_pairs_mut()
+        .clear()
+        .append_pair("s", &definition.name);
+    }
+    sections.push(format!("[View documentation]({doc_url})"));
+    Some(format!("\n\n---\n\n{}", sections.join("\n\n")))
+  }
+
+  /// Parse the doc nodes of a remote module, reusing the ones of an earlier
+  /// hover as long as the version of the module is the same.
+  async fn get_remote_module_doc_nodes(
+    &self,
+    document: &Document,
+  ) -> Option<Arc<Vec<deno_doc::DocNode>>> {
+    let doc_specifier = document.specifier().clone();
+    let script_version = document.script_version();
+    if let Some(doc_nodes) = self
+      .remote_module_docs_cache
+      .get(&doc_specifier, &script_version)
+    {
+      return Some(doc_nodes);
+    }
+    let content = document.content().to_string();
+    // building the graph isn't `Send`, so it is driven on a blocking thread,
+    // which also keeps the parsing of large modules off the server's thread
+    let doc_nodes = spawn_blocking({
+      let doc_specifier = doc_specifier.clone();
+      move || {
+        deno_core::futures::executor::block_on(async move {
+          let mut loader = deno_graph::source::MemoryLoader::new(
+            vec![(
+              doc_specifier.to_string(),
+              deno_graph::source::Source::Module {
+                specifier: doc_specifier.to_string(),
+                content,
+                maybe_headers: None,
+              },
+            )],
+            Vec::new(),
+          );
+          let analyzer = deno_graph::CapturingModuleAnalyzer::default();
+          let mut graph = deno_graph::ModuleGraph::new(GraphKind::TypesOnly);
+          graph
+            .build(
+              vec![doc_specifier.clone()],
+              &mut loader,
+              deno_graph::BuildOptions {
+                module_analyzer: Some(&analyzer),
+                ..Default::default()
+              },
+            )
+            .await;
+          let doc_parser = deno_doc::DocParser::new(
+            graph,
+            false,
+            analyzer.as_capturing_parser(),
+          );
+          doc_parser
+            .parse_module(&doc_specifier)
+            .ok()
+            .map(|m| m.definitions)
+        })
+      }
+    })
+    .await
+    .ok()??;
+    let doc_nodes = Arc::new(doc_nodes);
+    self.remote_module_docs_cache.insert(
+      doc_specifier,
+      script_version,
+      doc_nodes.clone(),
+    );
+    Some(doc_nodes)
+  }
+
   async fn code_action(
     &self,
     params: CodeActionParams,
@@ -1901,6 +2465,7 @@ impl Inner {
                   ..line_index.offset_tsc(diagnostic.range.end)?,
                 codes,
                 (&self.fmt_options.options).into(),
+                self.auto_import_preferences(),
               )
               .await;
             for action in actions {
@@ -1944,6 +2509,131 @@ impl Inner {
       all_actions.extend(code_actions.get_response());
     }
 
+    // Source actions, which are only returned when asked for
+    let requested_kinds = params.context.only.as_deref().unwrap_or_default();
+    for action_kind in ORGANIZE_IMPORTS_ACTION_KINDS {
+      if !requested_kinds
+        .iter()
+        .any(|kind| action_kind.kind.as_str().starts_with(kind.as_str()))
+      {
+        continue;
+      }
+      let changes = self
+        .ts_server
+        .organize_imports(
+          self.snapshot(),
+          specifier.clone(),
+          (&self.fmt_options.options).into(),
+          action_kind.mode,
+        )
+        .await?;
+      if !changes.is_empty() {
+        all_actions.push(CodeActionOrCommand::CodeAction(CodeAction {
+          title: action_kind.title.to_string(),
+          kind: Some(action_kind.kind.clone()),
+          diagnostics: None,
+          edit: ts_changes_to_edit(&changes, self).map_err(|err| {
+            error!("Unable to convert changes to edits: {}", err);
+            LspError::internal_error()
+          })?,
+          command: None,
+          is_preferred: None,
+          disabled: None,
+          data: None,
+        }));
+      }
+    }
+
+    if requested_kinds
+      .iter()
+      .any(|kind| SOURCE_FIX_ALL_DENO.as_str().starts_with(kind.as_str()))
+    {
+      let file_diagnostics = self
+        .diagnostics_server
+        .get_ts_diagnostics(&specifier, asset_or_doc.document_lsp_version());
+      // the code fixes of all the diagnostics are requested at once, as tsc
+      // only needs them to tell which fix ids apply to the file
+      let snapshot = self.snapshot();
+      let mut code_fix_requests = Vec::new();
+      for diagnostic in &file_diagnostics {
+        let code = match &diagnostic.code {
+          Some(NumberOrString::String(code)) => code.to_string(),
+          Some(NumberOrString::Number(code)) => code.to_string(),
+          None => continue,
+        };
+        if !self.ts_fixable_diagnostics.contains(&code) {
+          continue;
+        }
+        code_fix_requests.push(self.ts_server.get_code_fixes(
+          snapshot.clone(),
+          specifier.clone(),
+          line_index.offset_tsc(diagnostic.range.start)?
+            ..line_index.offset_tsc(diagnostic.range.end)?,
+          vec![code],
+          (&self.fmt_options.options).into(),
+          self.auto_import_preferences(),
+        ));
+      }
+      let mut fix_ids = Vec::new();
+      for actions in join_all(code_fix_requests).await {
+        for fix_id in actions.into_iter().filter_map(|a| a.fix_id) {
+          if !fix_ids.contains(&fix_id) {
+            fix_ids.push(fix_id);
+          }
+        }
+      }
+      let mut fixes = Vec::new();
+      for fix_id in fix_ids {
+        let code_action_data = CodeActionData {
+          specifier: specifier.clone(),
+          fix_id,
+        };
+        let combined_code_actions = self
+          .ts_server
+          .get_combined_code_fix(
+            self.snapshot(),
+            &code_action_data,
+            (&self.fmt_options.options).into(),
+            self.auto_import_preferences(),
+          )
+          .await?;
+        // Fixes which need commands to be applied are not supported.
+        if combined_code_actions.commands.is_some() {
+          continue;
+        }
+        let changes = if code_action_data.fix_id == "fixMissingImport" {
+          fix_ts_import_changes(
+            &specifier,
+            &combined_code_actions.changes,
+            &self.get_ts_response_import_mapper(),
+          )
+          .map_err(|err| {
+            error!("Unable to remap changes: {}", err);
+            LspError::internal_error()
+          })?
+        } else {
+          combined_code_actions.changes
+        };
+        fixes.push(changes);
+      }
+      let changes = merge_fix_all_changes(fixes);
+      if !changes.is_empty() {
+        all_actions.push(CodeActionOrCommand::CodeAction(CodeAction {
+          title: "Fix all fixable problems".to_string(),
+          kind: Some(SOURCE_FIX_ALL_DENO),
+          diagnostics: None,
+          edit: ts_changes_to_edit(&changes, self).map_err(|err| {
+            error!("Unable to convert changes to edits: {}", err);
+            LspError::internal_error()
+          })?,
+          command: None,
+          is_preferred: None,
+          disabled: None,
+          data: None,
+        }));
+      }
+    }
+
     // Refactor
     let only = params
       .context
@@ -1974,10 +2664,26 @@ impl Inner {
 
     let code_action_disabled_support =
       self.config.client_capabilities.code_action_disabled_support;
-    let actions: Vec<CodeActionOrCommand> = all_actions.into_iter().filter(|ca| {
+    let mut actions: Vec<CodeActionOrCommand> = all_actions.into_iter().filter(|ca| {
       code_action_disabled_support
         || matches!(ca, CodeActionOrCommand::CodeAction(ca) if ca.disabled.is_none())
     }).collect();
+    // The edits of refactors and fix all actions are only computed when the
+    // action is resolved, which clients that can't resolve code actions need
+    // to get right away.
+    if !self.config.client_capabilities.code_action_resolve_support {
+      for action in actions.iter_mut() {
+        if let CodeActionOrCommand::CodeAction(code_action) = action {
+          if code_action.edit.is_none()
+            && code_action.data.is_some()
+            && code_action.disabled.is_none()
+          {
+            *code_action =
+              self.code_action_resolve(code_action.clone()).await?;
+          }
+        }
+      }
+    }
     let response = if actions.is_empty() {
       None
     } else {
@@ -2013,6 +2719,7 @@ impl Inner {
           self.snapshot(),
           &code_action_data,
           (&self.fmt_options.options).into(),
+          self.auto_import_preferences(),
         )
         .await?;
       if combined_code_actions.commands.is_some() {
@@ -2086,6 +2793,58 @@ impl Inner {
     )
   }
 
+  /// Return the `deno.suggest.autoImports.excludePatterns` setting in the form
+  /// tsc expects, where relative patterns are resolved against the root.
+  fn auto_import_file_exclude_patterns(&self) -> Option<Vec<String>> {
+    let patterns = &self
+      .config
+      .workspace_settings()
+      .suggest
+      .auto_imports
+      .exclude_patterns;
+    if patterns.is_empty() {
+      return None;
+    }
+    let maybe_root_uri = self.config.root_uri.as_ref().map(|root_uri| {
+      let mut root_uri = root_uri.clone();
+      if !root_uri.path().ends_with('/') {
+        root_uri.set_path(&format!("{}/", root_uri.path()));
+      }
+      root_uri
+    });
+    let patterns = patterns
+      .iter()
+      .filter_map(|pattern| {
+        if pattern.starts_with('*')
+          || pattern.starts_with('/')
+          || Url::parse(pattern).is_ok()
+        {
+          Some(pattern.clone())
+        } else {
+          maybe_root_uri
+            .as_ref()?
+            .join(pattern)
+            .ok()
+            .map(|url| url.to_string())
+        }
+      })
+      .collect();
+    Some(patterns)
+  }
+
+  /// The preferences for requests to tsc which can generate imports.
+  fn auto_import_preferences(&self) -> tsc::UserPreferences {
+    let preferences = &self.config.workspace_settings().preferences;
+    tsc::UserPreferences {
+      auto_import_file_exclude_patterns: self
+        .auto_import_file_exclude_patterns(),
+      import_module_specifier_ending: Some(
+        preferences.import_module_specifier_ending.into(),
+      ),
+      ..(&self.fmt_options.options).into()
+    }
+  }
+
   async fn code_lens(
     &self,
     params: CodeLensParams,
@@ -2189,24 +2948,73 @@ impl Inner {
     }
   }
 
-  async fn references(
+  /// Like `document_highlight`, but also searches the other open documents
+  /// for occurrences of the symbol.
+  async fn multi_document_highlight(
     &self,
-    params: ReferenceParams,
-  ) -> LspResult<Option<Vec<Location>>> {
-    let specifier = self.url_map.normalize_url(
-      &params.text_document_position.text_document.uri,
-      LspUrlKind::File,
-    );
+    params: TextDocumentPositionParams,
+  ) -> LspResult<Option<Vec<lsp_custom::MultiDocumentHighlight>>> {
+    let specifier = self
+      .url_map
+      .normalize_url(&params.text_document.uri, LspUrlKind::File);
     if !self.is_diagnosable(&specifier)
       || !self.config.specifier_enabled(&specifier)
     {
       return Ok(None);
     }
 
-    let mark = self.performance.mark("references", Some(&params));
+    let mark = self
+      .performance
+      .mark("multi_document_highlight", Some(&params));
     let asset_or_doc = self.get_asset_or_document(&specifier)?;
     let line_index = asset_or_doc.line_index();
-    let maybe_referenced_symbols = self
+    let mut files_to_search = vec![specifier.clone()];
+    files_to_search.extend(
+      self
+        .documents
+        .documents(DocumentsFilter::OpenDiagnosable)
+        .into_iter()
+        .map(|d| d.specifier().clone())
+        .filter(|s| s != &specifier && self.config.specifier_enabled(s)),
+    );
+    let maybe_document_highlights = self
+      .ts_server
+      .get_document_highlights(
+        self.snapshot(),
+        specifier,
+        line_index.offset_tsc(params.position)?,
+        files_to_search,
+      )
+      .await?;
+
+    let result = maybe_document_highlights.map(|document_highlights| {
+      document_highlights
+        .iter()
+        .filter_map(|dh| dh.to_multi_document_highlight(self))
+        .collect()
+    });
+    self.performance.measure(mark);
+    Ok(result)
+  }
+
+  async fn references(
+    &self,
+    params: ReferenceParams,
+  ) -> LspResult<Option<Vec<Location>>> {
+    let specifier = self.url_map.normalize_url(
+      &params.text_document_position.text_document.uri,
+      LspUrlKind::File,
+    );
+    if !self.is_diagnosable(&specifier)
+      || !self.config.specifier_enabled(&specifier)
+    {
+      return Ok(None);
+    }
+
+    let mark = self.performance.mark("references", Some(&params));
+    let asset_or_doc = self.get_asset_or_document(&specifier)?;
+    let line_index = asset_or_doc.line_index();
+    let maybe_referenced_symbols = self
       .ts_server
       .find_references(
         self.snapshot(),
@@ -2245,6 +3053,35 @@ impl Inner {
     }
   }
 
+  async fn file_references(
+    &self,
+    params: lsp_custom::FileReferencesParams,
+  ) -> LspResult<Option<Vec<Location>>> {
+    let specifier = self
+      .url_map
+      .normalize_url(&params.text_document.uri, LspUrlKind::File);
+    if !self.is_diagnosable(&specifier)
+      || !self.config.specifier_enabled(&specifier)
+    {
+      return Ok(None);
+    }
+
+    let mark = self.performance.mark("file_references", Some(&params));
+    let entries = self
+      .ts_server
+      .get_file_references(self.snapshot(), specifier)
+      .await?;
+    let mut results = Vec::new();
+    for entry in entries {
+      let reference_specifier =
+        resolve_url(&entry.document_span.file_name).unwrap();
+      let asset_or_doc = self.get_asset_or_document(&reference_specifier)?;
+      results.push(entry.to_location(asset_or_doc.line_index(), &self.url_map));
+    }
+    self.performance.measure(mark);
+    Ok(Some(results))
+  }
+
   async fn goto_definition(
     &self,
     params: GotoDefinitionParams,
@@ -2281,6 +3118,39 @@ impl Inner {
     }
   }
 
+  async fn source_definition(
+    &self,
+    params: TextDocumentPositionParams,
+  ) -> LspResult<Option<GotoDefinitionResponse>> {
+    let specifier = self
+      .url_map
+      .normalize_url(&params.text_document.uri, LspUrlKind::File);
+    if !self.is_diagnosable(&specifier)
+      || !self.config.specifier_enabled(&specifier)
+    {
+      return Ok(None);
+    }
+
+    let mark = self.performance.mark("source_definition", Some(&params));
+    let asset_or_doc = self.get_asset_or_document(&specifier)?;
+    let line_index = asset_or_doc.line_index();
+    let maybe_definition = self
+      .ts_server
+      .get_source_definition(
+        self.snapshot(),
+        specifier,
+        line_index.offset_tsc(params.position)?,
+      )
+      .await?;
+
+    let results = match maybe_definition {
+      Some(definition) => definition.to_definition(line_index, self).await,
+      None => None,
+    };
+    self.performance.measure(mark);
+    Ok(results)
+  }
+
   async fn goto_type_definition(
     &self,
     params: GotoTypeDefinitionParams,
@@ -2382,13 +3252,15 @@ impl Inner {
               allow_text_changes_in_new_files: Some(
                 specifier.scheme() == "file",
               ),
-              import_module_specifier_ending: Some(
-                tsc::ImportModuleSpecifierEnding::Index,
-              ),
               include_automatic_optional_chain_completions: Some(true),
               include_completions_for_import_statements: Some(true),
               include_completions_for_module_exports: Some(
-                self.config.workspace_settings().suggest.auto_imports,
+                self
+                  .config
+                  .workspace_settings()
+                  .suggest
+                  .auto_imports
+                  .enabled,
               ),
               include_completions_with_object_literal_method_snippets: Some(
                 use_snippets,
@@ -2404,7 +3276,7 @@ impl Inner {
               provide_prefix_and_suffix_text_for_rename: Some(true),
               provide_refactor_not_applicable_reason: Some(true),
               use_label_details_in_completion_entries: Some(true),
-              ..Default::default()
+              ..self.auto_import_preferences()
             },
             trigger_character,
             trigger_kind,
@@ -2446,6 +3318,7 @@ impl Inner {
         let specifier = &data.specifier;
         let args = GetCompletionDetailsArgs {
           format_code_settings: Some((&self.fmt_options.options).into()),
+          preferences: Some(self.auto_import_preferences()),
           ..data.into()
         };
         let result = self
@@ -2684,41 +3557,55 @@ impl Inner {
       .mark("prepare_call_hierarchy", Some(&params));
     let asset_or_doc = self.get_asset_or_document(&specifier)?;
     let line_index = asset_or_doc.line_index();
+    let position =
+      line_index.offset_tsc(params.text_document_position_params.position)?;
+
+    let maybe_cached_items = asset_or_doc
+      .document()
+      .and_then(|doc| doc.maybe_call_hierarchy_items(position));
+    let maybe_items = if let Some(items) = maybe_cached_items {
+      Some(items)
+    } else {
+      let maybe_items = self
+        .ts_server
+        .prepare_call_hierarchy(self.snapshot(), specifier.clone(), position)
+        .await?
+        .map(Arc::new);
+      // items declared in other modules can change without this document
+      // changing, so only local ones are cached
+      if let (Some(items), Some(doc)) = (&maybe_items, asset_or_doc.document())
+      {
+        if items.iter().all(|item| item.is_in(&specifier)) {
+          self
+            .documents
+            .try_cache_call_hierarchy_items(
+              &specifier,
+              &doc.script_version(),
+              position,
+              items.clone(),
+            )
+            .map_err(|err| {
+              error!("Unable to cache call hierarchy items: {}", err);
+              LspError::internal_error()
+            })?;
+        }
+      }
+      maybe_items
+    };
 
-    let maybe_one_or_many = self
-      .ts_server
-      .prepare_call_hierarchy(
-        self.snapshot(),
-        specifier,
-        line_index.offset_tsc(params.text_document_position_params.position)?,
-      )
-      .await?;
-
-    let response = if let Some(one_or_many) = maybe_one_or_many {
+    let response = if let Some(items) = maybe_items {
       let maybe_root_path_owned = self
         .config
         .root_uri
         .as_ref()
         .and_then(|uri| specifier_to_file_path(uri).ok());
       let mut resolved_items = Vec::<CallHierarchyItem>::new();
-      match one_or_many {
-        tsc::OneOrMany::One(item) => {
-          if let Some(resolved) = item.try_resolve_call_hierarchy_item(
-            self,
-            maybe_root_path_owned.as_deref(),
-          ) {
-            resolved_items.push(resolved)
-          }
-        }
-        tsc::OneOrMany::Many(items) => {
-          for item in items.iter() {
-            if let Some(resolved) = item.try_resolve_call_hierarchy_item(
-              self,
-              maybe_root_path_owned.as_deref(),
-            ) {
-              resolved_items.push(resolved);
-            }
-          }
+      for item in items.iter() {
+        if let Some(resolved) = item.try_resolve_call_hierarchy_item(
+          self,
+          maybe_root_path_owned.as_deref(),
+        ) {
+          resolved_items.push(resolved);
         }
       }
       Some(resolved_items)
@@ -2747,12 +3634,29 @@ impl Inner {
     let asset_or_doc = self.get_asset_or_document(&specifier)?;
     let line_index = asset_or_doc.line_index();
 
+    // renaming an import specifier renames the module it points to
+    if let Some((_, dependency, _)) =
+      asset_or_doc.get_maybe_dependency(&params.text_document_position.position)
+    {
+      let result = match dependency.get_code() {
+        Some(target) if target.scheme() == "file" => {
+          self
+            .rename_module(&specifier, target, &params.new_name)
+            .await?
+        }
+        _ => None,
+      };
+      self.performance.measure(mark);
+      return Ok(result);
+    }
+
     let maybe_locations = self
       .ts_server
       .find_rename_locations(
         self.snapshot(),
         specifier,
         line_index.offset_tsc(params.text_document_position.position)?,
+        &self.config.workspace_settings().preferences,
       )
       .await?;
 
@@ -2773,6 +3677,127 @@ impl Inner {
     }
   }
 
+  /// Rename the module `target` imported by `referrer` to `new_name`, which
+  /// is either a specifier relative to `referrer` or a new file name for the
+  /// module, and update the imports of it.
+  async fn rename_module(
+    &self,
+    referrer: &ModuleSpecifier,
+    target: &ModuleSpecifier,
+    new_name: &str,
+  ) -> LspResult<Option<WorkspaceEdit>> {
+    let old_specifier = self.url_map.normalize_url(target, LspUrlKind::File);
+    let base = if new_name.starts_with("./") || new_name.starts_with("../") {
+      referrer
+    } else {
+      &old_specifier
+    };
+    let Ok(new_specifier) = base.join(new_name) else {
+      return Ok(None);
+    };
+    if new_specifier == old_specifier {
+      return Ok(None);
+    }
+    let changes = self
+      .get_file_rename_changes(&old_specifier, &new_specifier)
+      .await?;
+    let mut operations = Vec::new();
+    if let Some(WorkspaceEdit {
+      document_changes: Some(DocumentChanges::Edits(edits)),
+      ..
+    }) = ts_changes_to_edit(&changes, self).map_err(|err| {
+      error!("Unable to convert changes to edits: {}", err);
+      LspError::internal_error()
+    })? {
+      operations.extend(edits.into_iter().map(DocumentChangeOperation::Edit));
+    }
+    let (Ok(old_uri), Ok(new_uri)) = (
+      self.url_map.normalize_specifier(&old_specifier),
+      self.url_map.normalize_specifier(&new_specifier),
+    ) else {
+      return Ok(None);
+    };
+    operations.push(DocumentChangeOperation::Op(ResourceOp::Rename(
+      RenameFile {
+        old_uri: old_uri.as_url().clone(),
+        new_uri: new_uri.as_url().clone(),
+        options: None,
+        annotation_id: None,
+      },
+    )));
+    Ok(Some(WorkspaceEdit {
+      changes: None,
+      document_changes: Some(DocumentChanges::Operations(operations)),
+      change_annotations: None,
+    }))
+  }
+
+  /// Get the changes to the imports of a module when it is renamed.
+  async fn get_file_rename_changes(
+    &self,
+    old_specifier: &ModuleSpecifier,
+    new_specifier: &ModuleSpecifier,
+  ) -> LspResult<Vec<tsc::FileTextChanges>> {
+    let file_rename_changes = self
+      .ts_server
+      .get_edits_for_file_rename(
+        self.snapshot(),
+        old_specifier.clone(),
+        new_specifier.clone(),
+        (&self.fmt_options.options).into(),
+        self.auto_import_preferences(),
+      )
+      .await?;
+    fix_ts_file_rename_changes(
+      file_rename_changes,
+      old_specifier,
+      new_specifier,
+      &self.documents,
+    )
+    .map_err(|err| {
+      error!("Unable to remap changes: {}", err);
+      LspError::internal_error()
+    })
+  }
+
+  async fn will_rename_files(
+    &self,
+    params: RenameFilesParams,
+  ) -> LspResult<Option<WorkspaceEdit>> {
+    let mark = self.performance.mark("will_rename_files", Some(&params));
+    let mut changes = Vec::new();
+    for file_rename in params.files {
+      let (Ok(old_uri), Ok(new_uri)) = (
+        ModuleSpecifier::parse(&file_rename.old_uri),
+        ModuleSpecifier::parse(&file_rename.new_uri),
+      ) else {
+        continue;
+      };
+      let old_specifier =
+        self.url_map.normalize_url(&old_uri, LspUrlKind::File);
+      let new_specifier =
+        self.url_map.normalize_url(&new_uri, LspUrlKind::File);
+      if !self.config.specifier_enabled(&old_specifier) {
+        continue;
+      }
+      changes.extend(
+        self
+          .get_file_rename_changes(&old_specifier, &new_specifier)
+          .await?,
+      );
+    }
+    let result = if changes.is_empty() {
+      None
+    } else {
+      ts_changes_to_edit(&changes, self).map_err(|err| {
+        error!("Unable to convert changes to edits: {}", err);
+        LspError::internal_error()
+      })?
+    };
+    self.performance.measure(mark);
+    Ok(result)
+  }
+
   async fn selection_range(
     &self,
     params: SelectionRangeParams,
@@ -2790,20 +3815,20 @@ impl Inner {
     let asset_or_doc = self.get_asset_or_document(&specifier)?;
     let line_index = asset_or_doc.line_index();
 
-    let mut selection_ranges = Vec::<SelectionRange>::new();
-    for position in params.positions {
-      let selection_range: tsc::SelectionRange = self
-        .ts_server
-        .get_smart_selection_range(
-          self.snapshot(),
-          specifier.clone(),
-          line_index.offset_tsc(position)?,
-        )
-        .await?;
-
-      selection_ranges
-        .push(selection_range.to_selection_range(line_index.clone()));
-    }
+    let positions = params
+      .positions
+      .into_iter()
+      .map(|position| line_index.offset_tsc(position))
+      .collect::<Result<Vec<_>, _>>()?;
+    let selection_ranges = self
+      .ts_server
+      .get_smart_selection_ranges(self.snapshot(), specifier, positions)
+      .await?
+      .into_iter()
+      .map(|selection_range| {
+        selection_range.to_selection_range(line_index.clone())
+      })
+      .collect();
     self.performance.measure(mark);
     Ok(Some(selection_ranges))
   }
@@ -2938,30 +3963,47 @@ impl Inner {
     &self,
     params: WorkspaceSymbolParams,
   ) -> LspResult<Option<Vec<SymbolInformation>>> {
-    let mark = self.performance.mark("symbol", Some(&params));
+    // this matches vscode's hard coded result count
+    const MAX_RESULT_COUNT: usize = 256;
 
-    let navigate_to_items = self
-      .ts_server
-      .get_navigate_to_items(
-        self.snapshot(),
-        tsc::GetNavigateToItemsArgs {
-          search: params.query,
-          // this matches vscode's hard coded result count
-          max_result_count: Some(256),
-          file: None,
-        },
-      )
-      .await?;
+    let mark = self.performance.mark("symbol", Some(&params));
+    let query = symbols::WorkspaceSymbolQuery::parse(&params.query);
 
-    let maybe_symbol_information = if navigate_to_items.is_empty() {
+    let symbol_information = if query.search.is_empty() {
+      // tsc doesn't match any item for an empty search, so the symbols of the
+      // open documents are listed instead, which is what a query like
+      // `@function` is after
+      self.open_document_symbols().await
+    } else {
+      let navigate_to_items = self
+        .ts_server
+        .get_navigate_to_items(
+          self.snapshot(),
+          tsc::GetNavigateToItemsArgs {
+            search: query.search.to_string(),
+            // when filtering by kind, the matching items could be beyond the
+            // limit, so the results are truncated after filtering instead
+            max_result_count: if query.kind.is_some() {
+              None
+            } else {
+              Some(MAX_RESULT_COUNT as u32)
+            },
+            file: None,
+          },
+        )
+        .await?;
+      navigate_to_items
+        .iter()
+        .filter_map(|item| {
+          Some((item.specifier(self)?, item.to_symbol_information(self)?))
+        })
+        .collect()
+    };
+    let symbol_information =
+      symbols::rank_symbols(&query, symbol_information, MAX_RESULT_COUNT);
+    let maybe_symbol_information = if symbol_information.is_empty() {
       None
     } else {
-      let mut symbol_information = Vec::new();
-      for item in navigate_to_items {
-        if let Some(info) = item.to_symbol_information(self) {
-          symbol_information.push(info);
-        }
-      }
       Some(symbol_information)
     };
 
@@ -2969,7 +4011,62 @@ impl Inner {
     Ok(maybe_symbol_information)
   }
 
+  /// The symbols declared in the open documents, paired with the specifier of
+  /// their document.
+  async fn open_document_symbols(
+    &self,
+  ) -> Vec<(ModuleSpecifier, SymbolInformation)> {
+    let mut symbol_information = Vec::new();
+    for document in self.documents.documents(DocumentsFilter::OpenDiagnosable) {
+      let specifier = document.specifier();
+      if !self.config.specifier_enabled(specifier) {
+        continue;
+      }
+      let navigation_tree = match self.get_navigation_tree(specifier).await {
+        Ok(navigation_tree) => navigation_tree,
+        Err(err) => {
+          error!("Error getting navigation tree for {}: {}", specifier, err);
+          continue;
+        }
+      };
+      let Ok(uri) = self.url_map.normalize_specifier(specifier) else {
+        continue;
+      };
+      let mut symbols = Vec::new();
+      navigation_tree.collect_symbol_information(
+        document.line_index(),
+        &uri.into_url(),
+        &mut symbols,
+      );
+      symbol_information
+        .extend(symbols.into_iter().map(|s| (specifier.clone(), s)));
+    }
+    symbol_information
+  }
+
+  async fn execute_command(
+    &mut self,
+    params: ExecuteCommandParams,
+  ) -> LspResult<Option<Value>> {
+    if params.command != lsp_custom::RESTART_TS_SERVER_COMMAND {
+      return Err(LspError::invalid_params(format!(
+        "Unknown command: {}",
+        params.command
+      )));
+    }
+    let mark = self.performance.mark("execute_command", Some(&params));
+    // the runtimes are replaced with ones configured like the current ones,
+    // and the diagnostics of the open documents are generated again
+    self.ts_server.respawn();
+    self.diagnostics_server.invalidate_all();
+    self.send_diagnostics_update();
+    self.send_testing_update();
+    self.performance.measure(mark);
+    Ok(None)
+  }
+
   fn send_diagnostics_update(&self) {
+    std::mem::take(&mut *self.workspace_diagnostic_token.lock()).cancel();
     let snapshot = DiagnosticServerUpdateMessage {
       snapshot: self.snapshot(),
       config: self.config.snapshot(),
@@ -3095,6 +4192,7 @@ impl tower_lsp::LanguageServer for LanguageServer {
           inner.send_diagnostics_update();
           inner.send_testing_update();
         }
+        inner.precompute_navigation_tree(&document);
       }
       (client, client_uri, specifier, has_specifier_settings)
     };
@@ -3355,6 +4453,13 @@ impl tower_lsp::LanguageServer for LanguageServer {
     self.0.read().await.selection_range(params).await
   }
 
+  async fn will_rename_files(
+    &self,
+    params: RenameFilesParams,
+  ) -> LspResult<Option<WorkspaceEdit>> {
+    self.0.read().await.will_rename_files(params).await
+  }
+
   async fn semantic_tokens_full(
     &self,
     params: SemanticTokensParams,
@@ -3382,6 +4487,13 @@ impl tower_lsp::LanguageServer for LanguageServer {
   ) -> LspResult<Option<Vec<SymbolInformation>>> {
     self.0.read().await.symbol(params).await
   }
+
+  async fn execute_command(
+    &self,
+    params: ExecuteCommandParams,
+  ) -> LspResult<Option<Value>> {
+    self.0.write().await.execute_command(params).await
+  }
 }
 
 struct PrepareCacheResult {
@@ -3503,7 +4615,7 @@ impl Inner {
       .ts_server
       .provide_inlay_hints(
         self.snapshot(),
-        specifier,
+        specifier.clone(),
         text_span,
         workspace_settings.into(),
       )
@@ -3511,13 +4623,123 @@ impl Inner {
     let maybe_inlay_hints = maybe_inlay_hints.map(|hints| {
       hints
         .iter()
-        .map(|hint| hint.to_lsp(line_index.clone()))
+        .map(|hint| hint.to_lsp(line_index.clone(), &specifier))
         .collect()
     });
     self.performance.measure(mark);
     Ok(maybe_inlay_hints)
   }
 
+  async fn inlay_hint_resolve(
+    &self,
+    mut hint: InlayHint,
+  ) -> LspResult<InlayHint> {
+    let Some(data) = hint.data.clone() else {
+      return Ok(hint);
+    };
+    let data: tsc::InlayHintData = serde_json::from_value(data)
+      .map_err(|err| LspError::invalid_params(err.to_string()))?;
+    let mark = self.performance.mark("inlay_hint_resolve", Some(&data));
+    // the name the hint is for ends right before the hint
+    let position = data.position.saturating_sub(1);
+    let definitions = self
+      .ts_server
+      .get_type_definition(self.snapshot(), data.specifier.clone(), position)
+      .await?
+      .unwrap_or_default();
+    if let InlayHintLabel::String(label) = &hint.label {
+      if let Some(parts) =
+        tsc::inlay_hint_label_parts(label, &definitions, self)
+      {
+        hint.label = InlayHintLabel::LabelParts(parts);
+      }
+    }
+    let maybe_quick_info = self
+      .ts_server
+      .get_quick_info(self.snapshot(), data.specifier, position)
+      .await?;
+    hint.tooltip = maybe_quick_info.and_then(|q| q.to_inlay_hint_tooltip(self));
+    self.performance.measure(mark);
+    Ok(hint)
+  }
+
+  async fn inline_value(
+    &self,
+    params: lsp_custom::InlineValueParams,
+  ) -> LspResult<Option<Vec<lsp_custom::InlineValueVariableLookup>>> {
+    let specifier = self
+      .url_map
+      .normalize_url(&params.text_document.uri, LspUrlKind::File);
+    if !self.is_diagnosable(&specifier)
+      || !self.config.specifier_enabled(&specifier)
+    {
+      return Ok(None);
+    }
+
+    let mark = self.performance.mark("inline_value", Some(&params));
+    let asset_or_doc = self.get_asset_or_document(&specifier)?;
+    let line_index = asset_or_doc.line_index();
+    let text_span =
+      tsc::TextSpan::from_range(&params.range, line_index.clone()).map_err(
+        |err| {
+          error!("Failed to convert range to text_span: {}", err);
+          LspError::internal_error()
+        },
+      )?;
+    let stopped_position =
+      line_index.offset_tsc(params.context.stopped_location.end)?;
+    let inline_values = self
+      .ts_server
+      .provide_inline_values(
+        self.snapshot(),
+        specifier,
+        text_span,
+        stopped_position,
+      )
+      .await?;
+    let inline_values = inline_values
+      .iter()
+      .map(|value| value.to_lsp(line_index.clone()))
+      .collect();
+    self.performance.measure(mark);
+    Ok(Some(inline_values))
+  }
+
+  async fn document_diagnostic(
+    &self,
+    params: lsp_custom::DocumentDiagnosticParams,
+  ) -> LspResult<lsp_custom::DocumentDiagnosticReport> {
+    let specifier = self
+      .url_map
+      .normalize_url(&params.text_document.uri, LspUrlKind::File);
+    let mut diagnostics = if self.is_diagnosable(&specifier) {
+      let mark = self.performance.mark("document_diagnostic", Some(&params));
+      let diagnostics = diagnostics::generate_document_diagnostics(
+        self.snapshot(),
+        &self.config.snapshot(),
+        &self.lint_options,
+        &self.ts_server,
+        &specifier,
+        CancellationToken::new(),
+      )
+      .await
+      .map_err(|err| {
+        error!("Unable to get diagnostics: {}", err);
+        LspError::internal_error()
+      })?;
+      self.performance.measure(mark);
+      diagnostics
+    } else {
+      Vec::new()
+    };
+
+    diagnostics::normalize_related_information(&mut diagnostics, &self.url_map);
+    Ok(diagnostics::to_document_diagnostic_report(
+      diagnostics,
+      params.previous_result_id.as_ref(),
+    ))
+  }
+
   async fn reload_import_registries(&mut self) -> LspResult<Option<Value>> {
     remove_dir_all_if_exists(&self.module_registries_location)
       .await
@@ -3532,6 +4754,39 @@ impl Inner {
     Ok(Some(json!(true)))
   }
 
+  /// Describe the scripts which are the root names of the tsc project, which
+  /// helps figuring out why a module is or isn't type checked.
+  fn tsc_project_contents(&self) -> String {
+    let script_names = tsc::script_names(&self.documents);
+    let mut contents = String::from("# TypeScript Project\n\n");
+    writeln!(contents, "Scripts: {}\n", script_names.len()).unwrap();
+    contents
+      .push_str("|Script|Version|Media Type|Injected|\n|---|---|---|---|\n");
+    for (specifier, origin) in script_names {
+      let maybe_asset_or_doc = self.get_maybe_asset_or_document(&specifier);
+      let version = match &maybe_asset_or_doc {
+        Some(AssetOrDocument::Document(doc)) => doc.script_version(),
+        Some(AssetOrDocument::Asset(_)) => "1".to_string(),
+        None => "-".to_string(),
+      };
+      let media_type = maybe_asset_or_doc
+        .map(|a| a.media_type())
+        .unwrap_or_else(|| MediaType::from_specifier(&specifier));
+      let injected = match origin {
+        tsc::ScriptNameOrigin::NodeTypes => "node types",
+        tsc::ScriptNameOrigin::GraphImport => "graph import",
+        tsc::ScriptNameOrigin::Document => "",
+      };
+      writeln!(
+        contents,
+        "|{}|{}|{}|{}|",
+        specifier, version, media_type, injected
+      )
+      .unwrap();
+    }
+    contents
+  }
+
   fn virtual_text_document(
     &self,
     params: lsp_custom::VirtualTextDocumentParams,
@@ -3593,6 +4848,15 @@ impl Inner {
           .join("\n    - ")
       )
       .unwrap();
+      contents.push_str(
+        "\n## TypeScript Runtimes\n\n|Runtime|Heap Size|\n|---|---|\n",
+      );
+      for (index, heap_size) in
+        self.performance.tsc_heap_sizes().into_iter().enumerate()
+      {
+        writeln!(contents, "|{}|{}MB|", index, heap_size / (1024 * 1024))
+          .unwrap();
+      }
       contents
         .push_str("\n## Performance\n\n|Name|Duration|Count|\n|---|---|---|\n");
       let mut averages = self.performance.averages();
@@ -3606,6 +4870,8 @@ impl Inner {
         .unwrap();
       }
       Some(contents)
+    } else if specifier.as_str() == "deno:/tsc-project" {
+      Some(self.tsc_project_contents())
     } else {
       let asset_or_doc = self.get_maybe_asset_or_document(&specifier);
       if let Some(asset_or_doc) = asset_or_doc {
@@ -5,7 +5,11 @@ use deno_core::serde::Serialize;
 use tower_lsp::lsp_types as lsp;
 
 pub const CACHE_REQUEST: &str = "deno/cache";
+pub const FILE_REFERENCES_REQUEST: &str = "deno/fileReferences";
+pub const MULTI_DOCUMENT_HIGHLIGHT_REQUEST: &str =
+  "deno/multiDocumentHighlight";
 pub const PERFORMANCE_REQUEST: &str = "deno/performance";
+pub const SOURCE_DEFINITION_REQUEST: &str = "deno/sourceDefinition";
 pub const TASK_REQUEST: &str = "deno/task";
 pub const RELOAD_IMPORT_REGISTRIES_REQUEST: &str =
   "deno/reloadImportRegistries";
@@ -13,8 +17,16 @@ pub const VIRTUAL_TEXT_DOCUMENT: &str = "deno/virtualTextDocument";
 pub const LATEST_DIAGNOSTIC_BATCH_INDEX: &str =
   "deno/internalLatestDiagnosticBatchIndex";
 
+pub const RESTART_TS_SERVER_COMMAND: &str = "deno.restartTsServer";
+
 // While lsp_types supports inlay hints currently, tower_lsp does not.
 pub const INLAY_HINT: &str = "textDocument/inlayHint";
+pub const INLAY_HINT_RESOLVE: &str = "inlayHint/resolve";
+// Neither lsp_types nor tower_lsp support inline values currently.
+pub const INLINE_VALUE: &str = "textDocument/inlineValue";
+// Neither lsp_types nor tower_lsp support pull diagnostics currently.
+pub const DOCUMENT_DIAGNOSTIC: &str = "textDocument/diagnostic";
+pub const WORKSPACE_DIAGNOSTIC: &str = "workspace/diagnostic";
 
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -27,6 +39,130 @@ pub struct CacheParams {
   pub uris: Vec<lsp::TextDocumentIdentifier>,
 }
 
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileReferencesParams {
+  /// The module to find the importing modules of.
+  pub text_document: lsp::TextDocumentIdentifier,
+}
+
+/// The highlights of the occurrences of a symbol in one of the documents
+/// searched by a multi document highlight request.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiDocumentHighlight {
+  pub uri: lsp::Url,
+  pub highlights: Vec<lsp::DocumentHighlight>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InlineValueContext {
+  pub frame_id: i32,
+  /// The document range where execution has stopped.
+  pub stopped_location: lsp::Range,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InlineValueParams {
+  pub text_document: lsp::TextDocumentIdentifier,
+  /// The document range for which inline values should be computed.
+  pub range: lsp::Range,
+  pub context: InlineValueContext,
+}
+
+/// Asks the debugger to look up the value of a variable. This is the only
+/// kind of `InlineValue` the server returns.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InlineValueVariableLookup {
+  pub range: lsp::Range,
+  pub variable_name: Option<String>,
+  pub case_sensitive_lookup: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentDiagnosticParams {
+  pub text_document: lsp::TextDocumentIdentifier,
+  /// The identifier the diagnostic provider was registered with, if any.
+  pub identifier: Option<String>,
+  /// The `result_id` of the report the client received last for this
+  /// document.
+  pub previous_result_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum DocumentDiagnosticReport {
+  #[serde(rename_all = "camelCase")]
+  Full {
+    result_id: Option<String>,
+    items: Vec<lsp::Diagnostic>,
+  },
+  /// The diagnostics are the same as the ones of the report with
+  /// `result_id`, so the client can keep them.
+  #[serde(rename_all = "camelCase")]
+  Unchanged { result_id: String },
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PreviousResultId {
+  pub uri: lsp::Url,
+  pub value: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceDiagnosticParams {
+  pub identifier: Option<String>,
+  /// The result ids of the reports the client currently holds.
+  pub previous_result_ids: Vec<PreviousResultId>,
+  /// When set, reports are streamed to the client as partial results instead
+  /// of being part of the response.
+  pub partial_result_token: Option<lsp::ProgressToken>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct WorkspaceDocumentDiagnosticReport {
+  pub uri: lsp::Url,
+  /// The version of the document, or `None` if it is not open.
+  pub version: Option<i32>,
+  #[serde(flatten)]
+  pub report: DocumentDiagnosticReport,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct WorkspaceDiagnosticReport {
+  pub items: Vec<WorkspaceDocumentDiagnosticReport>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct WorkspaceDiagnosticPartialResultParams {
+  pub token: lsp::ProgressToken,
+  pub value: WorkspaceDiagnosticReport,
+}
+
+/// The error code of a diagnostic request which the server cancelled.
+pub const SERVER_CANCELLED: i64 = -32802;
+
+/// The data of a `ServerCancelled` error for a diagnostic request.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosticServerCancellationData {
+  /// Whether the client should send the request again.
+  pub retrigger_request: bool,
+}
+
+pub enum WorkspaceDiagnosticPartialResult {}
+
+impl lsp::notification::Notification for WorkspaceDiagnosticPartialResult {
+  type Params = WorkspaceDiagnosticPartialResultParams;
+
+  const METHOD: &'static str = "$/progress";
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct RegistryStateNotificationParams {
   pub origin: String,
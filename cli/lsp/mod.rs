@@ -29,6 +29,7 @@ mod refactor;
 mod registries;
 mod repl;
 mod semantic_tokens;
+mod symbols;
 mod testing;
 mod text;
 mod tsc;
@@ -42,6 +43,14 @@ pub async fn start() -> Result<(), AnyError> {
     language_server::LanguageServer::new(client::Client::from_tower(client))
   })
   .custom_method(lsp_custom::CACHE_REQUEST, LanguageServer::cache_request)
+  .custom_method(
+    lsp_custom::FILE_REFERENCES_REQUEST,
+    LanguageServer::file_references_request,
+  )
+  .custom_method(
+    lsp_custom::MULTI_DOCUMENT_HIGHLIGHT_REQUEST,
+    LanguageServer::multi_document_highlight_request,
+  )
   .custom_method(
     lsp_custom::PERFORMANCE_REQUEST,
     LanguageServer::performance_request,
@@ -50,6 +59,10 @@ pub async fn start() -> Result<(), AnyError> {
     lsp_custom::RELOAD_IMPORT_REGISTRIES_REQUEST,
     LanguageServer::reload_import_registries_request,
   )
+  .custom_method(
+    lsp_custom::SOURCE_DEFINITION_REQUEST,
+    LanguageServer::source_definition_request,
+  )
   .custom_method(lsp_custom::TASK_REQUEST, LanguageServer::task_request)
   .custom_method(testing::TEST_RUN_REQUEST, LanguageServer::test_run_request)
   .custom_method(
@@ -60,7 +73,20 @@ pub async fn start() -> Result<(), AnyError> {
     lsp_custom::VIRTUAL_TEXT_DOCUMENT,
     LanguageServer::virtual_text_document,
   )
-  .custom_method(lsp_custom::INLAY_HINT, LanguageServer::inlay_hint);
+  .custom_method(lsp_custom::INLAY_HINT, LanguageServer::inlay_hint)
+  .custom_method(
+    lsp_custom::INLAY_HINT_RESOLVE,
+    LanguageServer::inlay_hint_resolve,
+  )
+  .custom_method(lsp_custom::INLINE_VALUE, LanguageServer::inline_value)
+  .custom_method(
+    lsp_custom::DOCUMENT_DIAGNOSTIC,
+    LanguageServer::document_diagnostic,
+  )
+  .custom_method(
+    lsp_custom::WORKSPACE_DIAGNOSTIC,
+    LanguageServer::workspace_diagnostic,
+  );
 
   let builder = if should_send_diagnostic_batch_index_notifications() {
     builder.custom_method(
@@ -1,17 +1,34 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
+use deno_core::error::AnyError;
 use deno_core::parking_lot::Mutex;
 use deno_core::serde::Deserialize;
 use deno_core::serde::Serialize;
 use deno_core::serde_json::json;
+use deno_core::serde_json::Value;
 use std::cmp;
 use std::collections::HashMap;
 use std::collections::VecDeque;
 use std::fmt;
+use std::fs::File;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
 use std::time::Duration;
 use std::time::Instant;
 
 use super::logging::lsp_debug;
+use super::logging::lsp_warn;
+
+static NEXT_TRACE_THREAD_ID: AtomicU64 = AtomicU64::new(1);
+
+thread_local! {
+  /// A stable numeric id of the current thread for trace events, as
+  /// `std::thread::ThreadId` can't be converted to a number on stable Rust.
+  static TRACE_THREAD_ID: u64 =
+    NEXT_TRACE_THREAD_ID.fetch_add(1, Ordering::Relaxed);
+}
 
 #[derive(Debug, Deserialize, Serialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
@@ -38,6 +55,7 @@ impl Ord for PerformanceAverage {
 pub struct PerformanceMark {
   name: String,
   count: u32,
+  args: Option<Value>,
   start: Instant,
 }
 
@@ -65,6 +83,15 @@ impl From<PerformanceMark> for PerformanceMeasure {
   }
 }
 
+/// A file which measurements are written to as events of the Chrome trace
+/// event format, which can be loaded in `chrome://tracing` or the performance
+/// panel of the Chrome DevTools.
+#[derive(Debug)]
+struct TraceFile {
+  path: PathBuf,
+  file: File,
+}
+
 /// A simple structure for marking a start of something to measure the duration
 /// of and measuring that duration.  Each measurement is identified by a string
 /// name and a counter is incremented each time a new measurement is marked.
@@ -76,6 +103,11 @@ pub struct Performance {
   counts: Mutex<HashMap<String, u32>>,
   max_size: usize,
   measures: Mutex<VecDeque<PerformanceMeasure>>,
+  /// The used heap size in bytes of each tsc runtime.
+  tsc_heap_sizes: Mutex<Vec<usize>>,
+  /// The instant which the timestamps of trace events are relative to.
+  time_origin: Instant,
+  trace_file: Mutex<Option<TraceFile>>,
 }
 
 impl Default for Performance {
@@ -84,6 +116,9 @@ impl Default for Performance {
       counts: Default::default(),
       max_size: 3_000,
       measures: Default::default(),
+      tsc_heap_sizes: Default::default(),
+      time_origin: Instant::now(),
+      trace_file: Default::default(),
     }
   }
 }
@@ -144,7 +179,8 @@ impl Performance {
     let mut counts = self.counts.lock();
     let count = counts.entry(name.to_string()).or_insert(0);
     *count += 1;
-    let msg = if let Some(args) = maybe_args {
+    let maybe_args = maybe_args.map(|args| json!(args));
+    let msg = if let Some(args) = &maybe_args {
       json!({
         "type": "mark",
         "name": name,
@@ -161,6 +197,7 @@ impl Performance {
     PerformanceMark {
       name: name.to_string(),
       count: *count,
+      args: maybe_args,
       start: Instant::now(),
     }
   }
@@ -169,6 +206,7 @@ impl Performance {
   /// be used to finalize the duration of the span being measured, and add the
   /// measurement to the internal buffer.
   pub fn measure(&self, mark: PerformanceMark) -> Duration {
+    self.write_trace_event(&mark);
     let measure = PerformanceMeasure::from(mark);
     lsp_debug!(
       "{},",
@@ -188,15 +226,91 @@ impl Performance {
     duration
   }
 
+  /// Write a "complete" trace event of the span of the mark to the trace file,
+  /// if one is set.
+  fn write_trace_event(&self, mark: &PerformanceMark) {
+    let mut trace_file = self.trace_file.lock();
+    let Some(trace) = trace_file.as_mut() else {
+      return;
+    };
+    let mut args = json!({ "count": mark.count });
+    if let Some(mark_args) = &mark.args {
+      args["args"] = mark_args.clone();
+    }
+    let event = json!({
+      "name": mark.name,
+      "cat": "lsp",
+      "ph": "X",
+      "ts": mark.start.saturating_duration_since(self.time_origin).as_micros()
+        as u64,
+      "dur": mark.start.elapsed().as_micros() as u64,
+      "pid": std::process::id(),
+      "tid": TRACE_THREAD_ID.with(|id| *id),
+      "args": args,
+    });
+    if let Err(err) = writeln!(trace.file, "{event},") {
+      lsp_warn!(
+        "Unable to write to trace file \"{}\": {}",
+        trace.path.display(),
+        err
+      );
+      *trace_file = None;
+    }
+  }
+
+  /// Set the file which measurements are written to as Chrome trace events,
+  /// or stop writing them when `None`. Setting the same path again keeps
+  /// appending to the current file.
+  pub fn set_trace_file(
+    &self,
+    maybe_path: Option<PathBuf>,
+  ) -> Result<(), AnyError> {
+    let mut trace_file = self.trace_file.lock();
+    if trace_file.as_ref().map(|t| &t.path) == maybe_path.as_ref() {
+      return Ok(());
+    }
+    *trace_file = None;
+    if let Some(path) = maybe_path {
+      let mut file = File::create(&path)?;
+      // the closing bracket of the array is optional in the trace event
+      // format, which allows appending events as they are measured
+      file.write_all(b"[\n")?;
+      *trace_file = Some(TraceFile { path, file });
+    }
+    Ok(())
+  }
+
   pub fn to_vec(&self) -> Vec<PerformanceMeasure> {
     let measures = self.measures.lock();
     measures.iter().cloned().collect()
   }
+
+  /// Record the used heap size of the tsc runtime at the index of the pool.
+  pub fn set_tsc_heap_size(&self, index: usize, size: usize) {
+    let mut tsc_heap_sizes = self.tsc_heap_sizes.lock();
+    if tsc_heap_sizes.len() <= index {
+      tsc_heap_sizes.resize(index + 1, 0);
+    }
+    tsc_heap_sizes[index] = size;
+  }
+
+  /// Forget the heap sizes of the tsc runtimes which were removed from the
+  /// pool.
+  pub fn truncate_tsc_heap_sizes(&self, len: usize) {
+    self.tsc_heap_sizes.lock().truncate(len);
+  }
+
+  /// Return the last recorded used heap size of each tsc runtime.
+  pub fn tsc_heap_sizes(&self) -> Vec<usize> {
+    self.tsc_heap_sizes.lock().clone()
+  }
 }
 
 #[cfg(test)]
 mod tests {
   use super::*;
+  use deno_core::serde_json;
+  use test_util::TempDir;
 
   #[test]
   fn test_average() {
@@ -214,6 +328,45 @@ mod tests {
     assert!(performance.average("c").is_none());
   }
 
+  #[test]
+  fn test_tsc_heap_sizes() {
+    let performance = Performance::default();
+    assert!(performance.tsc_heap_sizes().is_empty());
+    performance.set_tsc_heap_size(1, 2048);
+    performance.set_tsc_heap_size(0, 1024);
+    performance.set_tsc_heap_size(1, 4096);
+    assert_eq!(performance.tsc_heap_sizes(), vec![1024, 4096]);
+  }
+
+  #[test]
+  fn test_trace_file() {
+    let temp_dir = TempDir::new();
+    let trace_path = temp_dir.path().join("trace.json");
+    let performance = Performance::default();
+    performance
+      .set_trace_file(Some(trace_path.to_path_buf()))
+      .unwrap();
+    let mark = performance.mark("a", Some(json!({ "method": "b" })));
+    performance.measure(mark);
+    performance.set_trace_file(None).unwrap();
+    let mark = performance.mark("c", None::<()>);
+    performance.measure(mark);
+    let text = trace_path.read_to_string();
+    assert!(text.starts_with("[\n"));
+    let events: Vec<Value> = serde_json::from_str(&format!(
+      "{}]",
+      text.trim_end().trim_end_matches(',')
+    ))
+    .unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0]["name"], json!("a"));
+    assert_eq!(events[0]["ph"], json!("X"));
+    assert_eq!(
+      events[0]["args"],
+      json!({ "count": 1, "args": { "method": "b" } })
+    );
+  }
+
   #[test]
   fn test_averages() {
     let performance = Performance::default();
@@ -130,6 +130,20 @@ pub static REWRITE_PROPERTY_GENERATEACCESSORS: Lazy<RefactorCodeActionKind> =
     }),
   });
 
+pub static REWRITE_FUNCTION_RETURN_TYPE: Lazy<RefactorCodeActionKind> =
+  Lazy::new(|| RefactorCodeActionKind {
+    kind: [
+      lsp::CodeActionKind::REFACTOR_REWRITE.as_str(),
+      "function",
+      "returnType",
+    ]
+    .join(".")
+    .into(),
+    matches_callback: Box::new(|tag: &str| {
+      tag.starts_with("Infer function return type")
+    }),
+  });
+
 pub static ALL_KNOWN_REFACTOR_ACTION_KINDS: Lazy<
   Vec<&'static RefactorCodeActionKind>,
 > = Lazy::new(|| {
@@ -144,6 +158,7 @@ pub static ALL_KNOWN_REFACTOR_ACTION_KINDS: Lazy<
     &REWRITE_ARROW_BRACES,
     &REWRITE_PARAMETERS_TO_DESTRUCTURED,
     &REWRITE_PROPERTY_GENERATEACCESSORS,
+    &REWRITE_FUNCTION_RETURN_TYPE,
   ]
 });
 
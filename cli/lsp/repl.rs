@@ -32,6 +32,7 @@ use tower_lsp::lsp_types::WorkDoneProgressParams;
 use tower_lsp::LanguageServer;
 
 use super::client::Client;
+use super::config::AutoImportSettings;
 use super::config::CompletionSettings;
 use super::config::ImportCompletionSettings;
 use super::config::TestingSettings;
@@ -295,19 +296,27 @@ pub fn get_repl_workspace_settings() -> WorkspaceSettings {
     internal_debug: false,
     lint: false,
     document_preload_limit: 0, // don't pre-load any modules as it's expensive and not useful for the repl
+    lsp: Default::default(),
     tls_certificate: None,
     unsafely_ignore_certificate_errors: None,
     unstable: false,
+    untitled_base: None,
+    preferences: Default::default(),
     suggest: CompletionSettings {
       complete_function_calls: false,
       names: false,
       paths: false,
-      auto_imports: false,
+      auto_imports: AutoImportSettings {
+        enabled: false,
+        exclude_patterns: vec![],
+      },
       imports: ImportCompletionSettings {
         auto_discover: false,
         hosts: HashMap::from([("https://deno.land".to_string(), true)]),
       },
+      commit_characters: Default::default(),
     },
+    suggestion_diagnostics: true,
     testing: TestingSettings {
       args: vec![],
       enable: false,
@@ -0,0 +1,263 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use deno_core::ModuleSpecifier;
+use std::collections::HashSet;
+use tower_lsp::lsp_types as lsp;
+
+/// A parsed workspace symbol query. A query can start with a symbol kind
+/// filter prefixed with `#` or `@`, for example `#class Foo` or `@function`,
+/// which limits the results to symbols of that kind.
+#[derive(Debug, PartialEq, Eq)]
+pub struct WorkspaceSymbolQuery<'a> {
+  pub kind: Option<lsp::SymbolKind>,
+  pub search: &'a str,
+}
+
+impl<'a> WorkspaceSymbolQuery<'a> {
+  pub fn parse(query: &'a str) -> Self {
+    let query = query.trim();
+    if let Some(filter) = query.strip_prefix('#').or(query.strip_prefix('@')) {
+      let (name, search) = filter
+        .split_once(char::is_whitespace)
+        .unwrap_or((filter, ""));
+      if let Some(kind) = symbol_kind_from_filter(name) {
+        return Self {
+          kind: Some(kind),
+          search: search.trim(),
+        };
+      }
+    }
+    Self {
+      kind: None,
+      search: query,
+    }
+  }
+}
+
+fn symbol_kind_from_filter(name: &str) -> Option<lsp::SymbolKind> {
+  let kind = match name.to_lowercase().as_str() {
+    "class" => lsp::SymbolKind::CLASS,
+    "const" | "constant" => lsp::SymbolKind::CONSTANT,
+    "constructor" => lsp::SymbolKind::CONSTRUCTOR,
+    "enum" => lsp::SymbolKind::ENUM,
+    "enummember" => lsp::SymbolKind::ENUM_MEMBER,
+    "field" => lsp::SymbolKind::FIELD,
+    "function" | "fn" => lsp::SymbolKind::FUNCTION,
+    "interface" => lsp::SymbolKind::INTERFACE,
+    "method" => lsp::SymbolKind::METHOD,
+    "module" | "namespace" => lsp::SymbolKind::MODULE,
+    "property" => lsp::SymbolKind::PROPERTY,
+    "type" | "typeparameter" => lsp::SymbolKind::TYPE_PARAMETER,
+    "var" | "variable" => lsp::SymbolKind::VARIABLE,
+    _ => return None,
+  };
+  Some(kind)
+}
+
+fn is_word_start(chars: &[char], index: usize) -> bool {
+  if index == 0 {
+    return true;
+  }
+  let previous = chars[index - 1];
+  let current = chars[index];
+  (current.is_uppercase() && previous.is_lowercase())
+    || (current.is_alphanumeric() && !previous.is_alphanumeric())
+}
+
+fn chars_eq_ignore_case(a: char, b: char) -> bool {
+  a == b || a.to_lowercase().eq(b.to_lowercase())
+}
+
+/// Score how well `query` matches `name`, returning `None` if the characters
+/// of the query don't appear in order in the name. Matches at the start of
+/// the name or of a camelCase or `snake_case` word score higher, as do runs
+/// of consecutive matching characters, so `fB` ranks `fooBar` above `fob`.
+pub fn fuzzy_score(query: &str, name: &str) -> Option<u32> {
+  let name_chars: Vec<char> = name.chars().collect();
+  let mut score = 0;
+  let mut index = 0;
+  let mut previous_match: Option<usize> = None;
+  for query_char in query.chars() {
+    let is_match = |i: &usize| chars_eq_ignore_case(query_char, name_chars[*i]);
+    // prefer continuing a run of matches, then the start of a word, then any
+    // later occurrence of the character
+    let matched = if index < name_chars.len()
+      && previous_match.is_some()
+      && is_match(&index)
+    {
+      index
+    } else {
+      (index..name_chars.len())
+        .filter(|i| is_word_start(&name_chars, *i))
+        .find(is_match)
+        .or_else(|| (index..name_chars.len()).find(is_match))?
+    };
+    score += 1;
+    if matched == 0 {
+      score += 8;
+    } else if is_word_start(&name_chars, matched) {
+      score += 6;
+    }
+    if previous_match.map(|p| p + 1 == matched).unwrap_or(false) {
+      score += 4;
+    }
+    if query_char == name_chars[matched] {
+      score += 1;
+    }
+    previous_match = Some(matched);
+    index = matched + 1;
+  }
+  if query.len() == name.len() && query.eq_ignore_ascii_case(name) {
+    score += 100;
+  }
+  Some(score)
+}
+
+/// Filter the symbols by the kind of the query, drop duplicates (which occur
+/// when the same module is loaded via redirected specifiers) and sort the
+/// rest by how well they match the search, keeping at most `max_count`. Each
+/// symbol is paired with the specifier of its module after resolving
+/// redirects, which identifies the duplicates.
+pub fn rank_symbols(
+  query: &WorkspaceSymbolQuery,
+  symbols: Vec<(ModuleSpecifier, lsp::SymbolInformation)>,
+  max_count: usize,
+) -> Vec<lsp::SymbolInformation> {
+  let mut seen = HashSet::new();
+  let mut scored = symbols
+    .into_iter()
+    .filter(|(_, s)| query.kind.map(|k| k == s.kind).unwrap_or(true))
+    .filter(|(specifier, s)| {
+      let range = s.location.range;
+      seen.insert((
+        specifier.clone(),
+        s.name.clone(),
+        range.start.line,
+        range.start.character,
+        range.end.line,
+        range.end.character,
+      ))
+    })
+    .filter_map(|(_, s)| Some((fuzzy_score(query.search, &s.name)?, s)))
+    .collect::<Vec<_>>();
+  scored.sort_by(|(a_score, a), (b_score, b)| {
+    b_score
+      .cmp(a_score)
+      .then_with(|| a.name.len().cmp(&b.name.len()))
+      .then_with(|| a.name.cmp(&b.name))
+  });
+  scored.into_iter().take(max_count).map(|(_, s)| s).collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[allow(deprecated)]
+  fn symbol(
+    name: &str,
+    kind: lsp::SymbolKind,
+    specifier: &str,
+  ) -> (ModuleSpecifier, lsp::SymbolInformation) {
+    let specifier = ModuleSpecifier::parse(specifier).unwrap();
+    let symbol = lsp::SymbolInformation {
+      name: name.to_string(),
+      kind,
+      tags: None,
+      deprecated: None,
+      location: lsp::Location {
+        uri: specifier.clone(),
+        range: lsp::Range::default(),
+      },
+      container_name: None,
+    };
+    (specifier, symbol)
+  }
+
+  #[test]
+  fn test_workspace_symbol_query_parse() {
+    assert_eq!(
+      WorkspaceSymbolQuery::parse("#class Foo"),
+      WorkspaceSymbolQuery {
+        kind: Some(lsp::SymbolKind::CLASS),
+        search: "Foo",
+      }
+    );
+    assert_eq!(
+      WorkspaceSymbolQuery::parse("@function"),
+      WorkspaceSymbolQuery {
+        kind: Some(lsp::SymbolKind::FUNCTION),
+        search: "",
+      }
+    );
+    assert_eq!(
+      WorkspaceSymbolQuery::parse("#private"),
+      WorkspaceSymbolQuery {
+        kind: None,
+        search: "#private",
+      }
+    );
+  }
+
+  #[test]
+  fn test_fuzzy_score() {
+    assert_eq!(fuzzy_score("fb", "faa"), None);
+    assert_eq!(fuzzy_score("", "foo"), Some(0));
+    assert!(fuzzy_score("fB", "fooBar") > fuzzy_score("fB", "fob"));
+    assert!(fuzzy_score("foo", "foo") > fuzzy_score("foo", "fooBar"));
+    assert!(fuzzy_score("gn", "get_name") > fuzzy_score("gn", "gone"));
+  }
+
+  #[test]
+  fn test_rank_symbols() {
+    let query = WorkspaceSymbolQuery::parse("#class fb");
+    let actual = rank_symbols(
+      &query,
+      vec![
+        symbol("fileBuffer", lsp::SymbolKind::FUNCTION, "file:///a.ts"),
+        symbol("FooBar", lsp::SymbolKind::CLASS, "file:///a.ts"),
+        symbol("FooBar", lsp::SymbolKind::CLASS, "file:///a.ts"),
+        symbol("FoolsBarrier", lsp::SymbolKind::CLASS, "file:///b.ts"),
+        symbol("Fab", lsp::SymbolKind::CLASS, "file:///b.ts"),
+        symbol("Baz", lsp::SymbolKind::CLASS, "file:///b.ts"),
+      ],
+      256,
+    );
+    let names = actual.iter().map(|s| s.name.as_str()).collect::<Vec<_>>();
+    assert_eq!(names, vec!["FooBar", "FoolsBarrier", "Fab"]);
+  }
+
+  #[test]
+  fn test_rank_symbols_redirected_duplicates() {
+    let query = WorkspaceSymbolQuery::parse("foo");
+    let (specifier, symbol_a) =
+      symbol("foo", lsp::SymbolKind::FUNCTION, "https://deno.land/x/a.ts");
+    // the same module loaded through a redirect has another uri
+    let mut symbol_b = symbol_a.clone();
+    symbol_b.location.uri =
+      lsp::Url::parse("deno:/https/deno.land/x%2Fa.ts").unwrap();
+    let actual = rank_symbols(
+      &query,
+      vec![(specifier.clone(), symbol_a), (specifier, symbol_b)],
+      256,
+    );
+    assert_eq!(actual.len(), 1);
+  }
+
+  #[test]
+  fn test_rank_symbols_empty_search() {
+    // a query which only filters by kind matches every symbol of the kind
+    let query = WorkspaceSymbolQuery::parse("@function");
+    let actual = rank_symbols(
+      &query,
+      vec![
+        symbol("parse", lsp::SymbolKind::FUNCTION, "file:///a.ts"),
+        symbol("Foo", lsp::SymbolKind::CLASS, "file:///a.ts"),
+        symbol("run", lsp::SymbolKind::FUNCTION, "file:///b.ts"),
+      ],
+      256,
+    );
+    let names = actual.iter().map(|s| s.name.as_str()).collect::<Vec<_>>();
+    assert_eq!(names, vec!["run", "parse"]);
+  }
+}
@@ -5,6 +5,7 @@ use deno_core::error::AnyError;
 use dissimilar::diff;
 use dissimilar::Chunk;
 use std::collections::HashMap;
+use std::ops::Range;
 use text_size::TextRange;
 use text_size::TextSize;
 use tower_lsp::jsonrpc;
@@ -120,6 +121,94 @@ impl LineIndex {
     }
   }
 
+  /// Replace the range of the text with the new text and update the index to
+  /// match. Only the lines touched by the edit are rescanned, the offsets of
+  /// the lines after it are shifted, which is linear in the number of lines.
+  pub fn apply_change(
+    &mut self,
+    text: &mut String,
+    range: lsp::Range,
+    new_text: &str,
+  ) -> Result<(), AnyError> {
+    let text_range = self.get_text_range(range)?;
+    let start_line = range.start.line as usize;
+    let end_line = range.end.line as usize;
+    let line_count = self.utf8_offsets.len() - 1;
+    if end_line >= line_count {
+      // the end of the range is past the last line, so just start over
+      text.replace_range(Range::<usize>::from(text_range), new_text);
+      *self = LineIndex::new(text);
+      return Ok(());
+    }
+    let removed = &text[Range::<usize>::from(text_range)];
+    let delta_utf8 = new_text.len() as i64 - removed.len() as i64;
+    let delta_utf16 = new_text.encode_utf16().count() as i64
+      - removed.encode_utf16().count() as i64;
+    text.replace_range(Range::<usize>::from(text_range), new_text);
+
+    // index the edited lines, leaving out the line break of the last one
+    // unless it is the last line of the text
+    let start_utf8 = self.utf8_offsets[start_line];
+    let start_utf16 = self.utf16_offsets[start_line];
+    let mut end = shift_offset(self.utf8_offsets[end_line + 1], delta_utf8);
+    if end_line + 1 < line_count {
+      end -= TextSize::from(1);
+    }
+    let edited = LineIndex::new(&text[TextRange::new(start_utf8, end)]);
+    let edited_line_count = edited.utf8_offsets.len() - 1;
+
+    self.utf8_offsets.splice(
+      start_line..=end_line,
+      edited.utf8_offsets[..edited_line_count]
+        .iter()
+        .map(|offset| start_utf8 + *offset),
+    );
+    for offset in &mut self.utf8_offsets[start_line + edited_line_count..] {
+      *offset = shift_offset(*offset, delta_utf8);
+    }
+    self.utf16_offsets.splice(
+      start_line..=end_line,
+      edited.utf16_offsets[..edited_line_count]
+        .iter()
+        .map(|offset| start_utf16 + *offset),
+    );
+    for offset in &mut self.utf16_offsets[start_line + edited_line_count..] {
+      *offset = shift_offset(*offset, delta_utf16);
+    }
+
+    let line_delta =
+      edited_line_count as i64 - (end_line - start_line + 1) as i64;
+    let start_line = start_line as u32;
+    let end_line = end_line as u32;
+    if line_delta == 0 {
+      // the lines after the edit keep their numbers, so only the edited ones
+      // need to be replaced
+      for line in start_line..=end_line {
+        self.utf16_lines.remove(&line);
+      }
+    } else {
+      self.utf16_lines = std::mem::take(&mut self.utf16_lines)
+        .into_iter()
+        .filter_map(|(line, chars)| {
+          if line < start_line {
+            Some((line, chars))
+          } else if line > end_line {
+            Some(((line as i64 + line_delta) as u32, chars))
+          } else {
+            None
+          }
+        })
+        .collect();
+    }
+    self.utf16_lines.extend(
+      edited
+        .utf16_lines
+        .into_iter()
+        .map(|(line, chars)| (line + start_line, chars)),
+    );
+    Ok(())
+  }
+
   /// Convert a u16 based range to a u8 TextRange.
   pub fn get_text_range(
     &self,
@@ -204,6 +293,10 @@ impl LineIndex {
   }
 }
 
+fn shift_offset(offset: TextSize, delta: i64) -> TextSize {
+  TextSize::from((u32::from(offset) as i64 + delta) as u32)
+}
+
 /// Compare two strings and return a vector of text edit records which are
 /// supported by the Language Server Protocol.
 pub fn get_edits(a: &str, b: &str, line_index: &LineIndex) -> Vec<TextEdit> {
@@ -527,4 +620,39 @@ const C: char = \"メ メ\";
       ]
     )
   }
+
+  #[test]
+  fn test_line_index_apply_change() {
+    let fixtures = [
+      ("a\nb\nc", ((1, 0), (1, 1)), "x\ny"),
+      ("a\nb\nc", ((0, 1), (2, 0)), ""),
+      ("a😀\nb\n", ((2, 0), (2, 0)), "é\n"),
+      ("ab\ncé", ((1, 1), (1, 2)), "😀\n中"),
+      ("", ((0, 0), (0, 0)), "\n\n"),
+      ("a\n\nb中", ((0, 1), (1, 0)), "b"),
+    ];
+    for (text, ((start_line, start_char), (end_line, end_char)), new_text) in
+      fixtures
+    {
+      let mut text = text.to_string();
+      let mut line_index = LineIndex::new(&text);
+      line_index
+        .apply_change(
+          &mut text,
+          lsp::Range {
+            start: lsp::Position {
+              line: start_line,
+              character: start_char,
+            },
+            end: lsp::Position {
+              line: end_line,
+              character: end_char,
+            },
+          },
+          new_text,
+        )
+        .unwrap();
+      assert_eq!(line_index, LineIndex::new(&text));
+    }
+  }
 }
@@ -1,5 +1,6 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
+use super::analysis::fix_ts_refactor_import_changes;
 use super::analysis::CodeActionData;
 use super::analysis::TsResponseImportMapper;
 use super::code_lens;
@@ -8,6 +9,7 @@ use super::documents::AssetOrDocument;
 use super::documents::DocumentsFilter;
 use super::language_server;
 use super::language_server::StateSnapshot;
+use super::lsp_custom;
 use super::performance::Performance;
 use super::refactor::RefactorCodeActionData;
 use super::refactor::ALL_KNOWN_REFACTOR_ACTION_KINDS;
@@ -38,6 +40,7 @@ use deno_core::error::AnyError;
 use deno_core::located_script_name;
 use deno_core::op;
 use deno_core::parking_lot::Mutex;
+use deno_core::parking_lot::RwLock;
 use deno_core::resolve_url;
 use deno_core::serde::de;
 use deno_core::serde::Deserialize;
@@ -45,6 +48,7 @@ use deno_core::serde::Serialize;
 use deno_core::serde_json;
 use deno_core::serde_json::json;
 use deno_core::serde_json::Value;
+use deno_core::v8;
 use deno_core::JsRuntime;
 use deno_core::ModuleSpecifier;
 use deno_core::OpState;
@@ -52,6 +56,7 @@ use deno_core::RuntimeOptions;
 use deno_runtime::tokio_util::create_basic_runtime;
 use lazy_regex::lazy_regex;
 use once_cell::sync::Lazy;
+use once_cell::sync::OnceCell;
 use regex::Captures;
 use regex::Regex;
 use serde_repr::Deserialize_repr;
@@ -61,8 +66,14 @@ use std::collections::HashMap;
 use std::collections::HashSet;
 use std::ops::Range;
 use std::path::Path;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
+use std::sync::Weak;
 use std::thread;
+use std::time::Duration;
+use std::time::Instant;
 use text_size::TextRange;
 use text_size::TextSize;
 use tokio::sync::mpsc;
@@ -79,6 +90,7 @@ static CAPTION_RE: Lazy<Regex> =
 static CODEBLOCK_RE: Lazy<Regex> = lazy_regex!(r"^\s*[~`]{3}");
 static EMAIL_MATCH_RE: Lazy<Regex> = lazy_regex!(r"(.+)\s<([-.\w]+@[-.\w]+)>");
 static HTTP_RE: Lazy<Regex> = lazy_regex!(r#"(?i)^https?:"#);
+static IDENTIFIER_RE: Lazy<Regex> = lazy_regex!(r"[A-Za-z_$][\w$]*");
 static JSDOC_LINKS_RE: Lazy<Regex> = lazy_regex!(
   r"(?i)\{@(link|linkplain|linkcode) (https?://[^ |}]+?)(?:[| ]([^{}\n]+?))?\}"
 );
@@ -125,33 +137,128 @@ pub enum SemicolonPreference {
   Remove,
 }
 
-#[derive(Clone, Debug)]
-pub struct TsServer(mpsc::UnboundedSender<Request>);
+/// Which changes `organizeImports` makes, see `ts.OrganizeImportsMode`.
+#[derive(Clone, Copy, Debug, Serialize)]
+pub enum OrganizeImportsMode {
+  All,
+  SortAndCombine,
+  RemoveUnused,
+}
+
+/// The options of the pool of tsc runtimes, from the `deno.lsp.tsc` settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TsServerOptions {
+  /// The number of runtimes of the pool.
+  pub pool_size: usize,
+  /// The time a runtime may spend on a request without calling back into the
+  /// language server before it is considered to hang.
+  pub request_timeout: Option<Duration>,
+  /// The heap size in bytes above which an idle runtime is restarted.
+  pub max_heap_size: Option<usize>,
+}
+
+impl From<&config::TscSettings> for TsServerOptions {
+  fn from(settings: &config::TscSettings) -> Self {
+    Self {
+      pool_size: settings.pool_size.max(1),
+      request_timeout: (settings.request_timeout > 0)
+        .then(|| Duration::from_secs(settings.request_timeout)),
+      max_heap_size: (settings.max_heap_size > 0)
+        .then_some(settings.max_heap_size.saturating_mul(1024 * 1024)),
+    }
+  }
+}
+
+impl Default for TsServerOptions {
+  fn default() -> Self {
+    (&config::TscSettings::default()).into()
+  }
+}
+
+const WATCHDOG_INTERVAL: Duration = Duration::from_secs(1);
+/// The minimum time between two restarts of a runtime because of the size of
+/// its heap, so that a project which needs more memory than the maximum
+/// doesn't have its runtime restarted over and over.
+const MIN_HEAP_RECYCLE_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// A tsc runtime of the pool, which runs on its own thread.
+struct TscRuntime {
+  sender: mpsc::UnboundedSender<Request>,
+  isolate_handle: v8::IsolateHandle,
+  /// While the runtime processes a request, the last time it called back into
+  /// the language server, which tsc does regularly unless it hangs.
+  heartbeat: Arc<Mutex<Option<Instant>>>,
+  /// Set when the runtime is replaced, so its thread stops.
+  terminated: Arc<AtomicBool>,
+  /// The used heap size of the isolate in bytes after its last request, or
+  /// after the garbage collection which followed it if it was too large.
+  heap_size: Arc<AtomicUsize>,
+  started: Instant,
+}
+
+type TimeoutHandler = Box<dyn Fn(Duration) + Send + Sync>;
+
+struct TsServerInner {
+  performance: Arc<Performance>,
+  cache: Arc<dyn HttpCache>,
+  /// The runtimes of the pool. The first one processes the interactive
+  /// requests and the others, if there are any, the diagnostics.
+  runtimes: RwLock<Vec<Mutex<TscRuntime>>>,
+  next_diagnostics_runtime: AtomicUsize,
+  /// The last configuration of the server, which is replayed when a runtime
+  /// is added to the pool or restarted.
+  last_config: Mutex<Option<(TsConfig, Arc<StateSnapshot>)>>,
+  options: Arc<Mutex<TsServerOptions>>,
+  /// Called when a runtime is restarted because a request hung.
+  timeout_handler: Mutex<Option<TimeoutHandler>>,
+}
+
+#[derive(Clone)]
+pub struct TsServer(Arc<TsServerInner>);
+
+impl std::fmt::Debug for TsServer {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_tuple("TsServer").finish()
+  }
+}
 
 impl TsServer {
-  pub fn new(performance: Arc<Performance>, cache: Arc<dyn HttpCache>) -> Self {
-    let (tx, mut rx) = mpsc::unbounded_channel::<Request>();
-    let _join_handle = thread::spawn(move || {
-      let mut ts_runtime = js_runtime(performance, cache);
-
-      let runtime = create_basic_runtime();
-      runtime.block_on(async {
-        let mut started = false;
-        while let Some((req, state_snapshot, tx, token)) = rx.recv().await {
-          if !started {
-            // TODO(@kitsonk) need to reflect the debug state of the lsp here
-            start(&mut ts_runtime, false).unwrap();
-            started = true;
-          }
-          let value = request(&mut ts_runtime, state_snapshot, req, token);
-          if tx.send(value).is_err() {
-            lsp_warn!("Unable to send result to client.");
-          }
-        }
-      })
+  pub fn new(
+    performance: Arc<Performance>,
+    cache: Arc<dyn HttpCache>,
+    options: TsServerOptions,
+  ) -> Self {
+    let inner = Arc::new(TsServerInner {
+      performance,
+      cache,
+      runtimes: Default::default(),
+      next_diagnostics_runtime: Default::default(),
+      last_config: Default::default(),
+      options: Arc::new(Mutex::new(options)),
+      timeout_handler: Default::default(),
     });
+    resize_pool(&inner, options.pool_size);
+    spawn_watchdog(Arc::downgrade(&inner));
+    Self(inner)
+  }
+
+  /// Apply changed settings. Runtimes are added to or removed from the pool
+  /// when its size changes, the other options apply to the next check of the
+  /// watchdog.
+  pub fn update_options(&self, options: TsServerOptions) {
+    let previous = std::mem::replace(&mut *self.0.options.lock(), options);
+    if previous.pool_size != options.pool_size {
+      resize_pool(&self.0, options.pool_size);
+    }
+  }
 
-    Self(tx)
+  /// Set the function which is called when a runtime is restarted because
+  /// a request didn't complete within the request timeout.
+  pub fn set_timeout_handler(
+    &self,
+    handler: impl Fn(Duration) + Send + Sync + 'static,
+  ) {
+    *self.0.timeout_handler.lock() = Some(Box::new(handler));
   }
 
   pub async fn get_diagnostics(
@@ -161,7 +268,15 @@ impl TsServer {
     token: CancellationToken,
   ) -> Result<HashMap<String, Vec<crate::tsc::Diagnostic>>, AnyError> {
     let req = RequestMethod::GetDiagnostics(specifiers);
-    self.request_with_cancellation(snapshot, req, token).await
+    match self
+      .request_with_cancellation(snapshot, req, token.clone())
+      .await
+    {
+      // like tsc does when it is cancelled while checking, no diagnostics
+      // are returned for a request which was skipped
+      Err(_) if token.is_cancelled() => Ok(Default::default()),
+      result => result,
+    }
   }
 
   pub async fn find_references(
@@ -180,6 +295,18 @@ impl TsServer {
     })
   }
 
+  pub async fn get_file_references(
+    &self,
+    snapshot: Arc<StateSnapshot>,
+    specifier: ModuleSpecifier,
+  ) -> Result<Vec<ReferenceEntry>, LspError> {
+    let req = RequestMethod::GetFileReferences(specifier);
+    self.request(snapshot, req).await.map_err(|err| {
+      log::error!("Unable to get file references from TypeScript: {}", err);
+      LspError::internal_error()
+    })
+  }
+
   pub async fn get_navigation_tree(
     &self,
     snapshot: Arc<StateSnapshot>,
@@ -233,6 +360,7 @@ impl TsServer {
     range: Range<u32>,
     codes: Vec<String>,
     format_code_settings: FormatCodeSettings,
+    preferences: UserPreferences,
   ) -> Vec<CodeFixAction> {
     let req = RequestMethod::GetCodeFixes((
       specifier,
@@ -240,6 +368,7 @@ impl TsServer {
       range.end,
       codes,
       format_code_settings,
+      preferences,
     ));
     match self.request(snapshot, req).await {
       Ok(items) => items,
@@ -280,11 +409,13 @@ impl TsServer {
     snapshot: Arc<StateSnapshot>,
     code_action_data: &CodeActionData,
     format_code_settings: FormatCodeSettings,
+    preferences: UserPreferences,
   ) -> Result<CombinedCodeActions, LspError> {
     let req = RequestMethod::GetCombinedCodeFix((
       code_action_data.specifier.clone(),
       json!(code_action_data.fix_id.clone()),
       format_code_settings,
+      preferences,
     ));
     self.request(snapshot, req).await.map_err(|err| {
       log::error!("Unable to get combined fix from TypeScript: {}", err);
@@ -292,6 +423,41 @@ impl TsServer {
     })
   }
 
+  pub async fn organize_imports(
+    &self,
+    snapshot: Arc<StateSnapshot>,
+    specifier: ModuleSpecifier,
+    format_code_settings: FormatCodeSettings,
+    mode: OrganizeImportsMode,
+  ) -> Result<Vec<FileTextChanges>, LspError> {
+    let req =
+      RequestMethod::OrganizeImports((specifier, format_code_settings, mode));
+    self.request(snapshot, req).await.map_err(|err| {
+      log::error!("Unable to organize imports from TypeScript: {}", err);
+      LspError::internal_error()
+    })
+  }
+
+  pub async fn get_edits_for_file_rename(
+    &self,
+    snapshot: Arc<StateSnapshot>,
+    old_specifier: ModuleSpecifier,
+    new_specifier: ModuleSpecifier,
+    format_code_settings: FormatCodeSettings,
+    preferences: UserPreferences,
+  ) -> Result<Vec<FileTextChanges>, LspError> {
+    let req = RequestMethod::GetEditsForFileRename((
+      old_specifier,
+      new_specifier,
+      format_code_settings,
+      preferences,
+    ));
+    self.request(snapshot, req).await.map_err(|err| {
+      log::error!("Unable to get file rename edits from TypeScript: {}", err);
+      LspError::internal_error()
+    })
+  }
+
   pub async fn get_edits_for_refactor(
     &self,
     snapshot: Arc<StateSnapshot>,
@@ -348,6 +514,19 @@ impl TsServer {
     })
   }
 
+  pub async fn get_source_definition(
+    &self,
+    snapshot: Arc<StateSnapshot>,
+    specifier: ModuleSpecifier,
+    position: u32,
+  ) -> Result<Option<DefinitionInfoAndBoundSpan>, LspError> {
+    let req = RequestMethod::GetSourceDefinition((specifier, position));
+    self.request(snapshot, req).await.map_err(|err| {
+      log::error!("Unable to get source definition from TypeScript: {}", err);
+      LspError::internal_error()
+    })
+  }
+
   pub async fn get_type_definition(
     &self,
     snapshot: Arc<StateSnapshot>,
@@ -467,13 +646,15 @@ impl TsServer {
     snapshot: Arc<StateSnapshot>,
     specifier: ModuleSpecifier,
     position: u32,
+    preferences: &config::PreferencesSettings,
   ) -> Result<Option<Vec<RenameLocation>>, LspError> {
     let req = RequestMethod::FindRenameLocations {
       specifier,
       position,
-      find_in_strings: false,
-      find_in_comments: false,
-      provide_prefix_and_suffix_text_for_rename: false,
+      find_in_strings: preferences.rename_in_strings,
+      find_in_comments: preferences.rename_in_comments,
+      provide_prefix_and_suffix_text_for_rename: preferences
+        .use_aliases_for_renames,
     };
     self.request(snapshot, req).await.map_err(|err| {
       log::error!("Failed to request to tsserver {}", err);
@@ -481,13 +662,13 @@ impl TsServer {
     })
   }
 
-  pub async fn get_smart_selection_range(
+  pub async fn get_smart_selection_ranges(
     &self,
     snapshot: Arc<StateSnapshot>,
     specifier: ModuleSpecifier,
-    position: u32,
-  ) -> Result<SelectionRange, LspError> {
-    let req = RequestMethod::GetSmartSelectionRange((specifier, position));
+    positions: Vec<u32>,
+  ) -> Result<Vec<SelectionRange>, LspError> {
+    let req = RequestMethod::GetSmartSelectionRanges((specifier, positions));
 
     self.request(snapshot, req).await.map_err(|err| {
       log::error!("Failed to request to tsserver {}", err);
@@ -559,6 +740,34 @@ impl TsServer {
     })
   }
 
+  pub async fn provide_inline_values(
+    &self,
+    snapshot: Arc<StateSnapshot>,
+    specifier: ModuleSpecifier,
+    text_span: TextSpan,
+    stopped_position: u32,
+  ) -> Result<Vec<InlineValue>, LspError> {
+    let req = RequestMethod::ProvideInlineValues((
+      specifier,
+      text_span,
+      stopped_position,
+    ));
+    self.request(snapshot, req).await.map_err(|err| {
+      log::error!("Unable to get inline values: {}", err);
+      LspError::internal_error()
+    })
+  }
+
+  /// Replace all the runtimes of the pool with new ones, which recovers from
+  /// a language service which is in a bad state. Pending requests fail, and
+  /// the last configuration is replayed to the new runtimes.
+  pub fn respawn(&self) {
+    let runtimes = self.0.runtimes.read();
+    for runtime in runtimes.iter() {
+      respawn_runtime(&self.0, runtime);
+    }
+  }
+
   pub async fn restart(&self, snapshot: Arc<StateSnapshot>) {
     let _: bool = self
       .request(snapshot, RequestMethod::Restart)
@@ -588,35 +797,115 @@ impl TsServer {
   where
     R: de::DeserializeOwned,
   {
+    // The request is cancelled when the future is dropped before it
+    // completes, which is the case when the client cancels the request. A
+    // child token is used so the token of the caller isn't affected.
+    let token = token.child_token();
+    let droppable_token = token.clone().drop_guard();
+    let value = if req.changes_state() {
+      // Every runtime of the pool has to apply the change, the result of the
+      // first one is returned.
+      if let RequestMethod::Configure(config) = &req {
+        *self.0.last_config.lock() = Some((config.clone(), snapshot.clone()));
+      }
+      let pool_size = self.0.runtimes.read().len();
+      let mut receivers = Vec::with_capacity(pool_size);
+      for index in 1..pool_size {
+        let req = req.copy_state_change();
+        receivers.push(self.send(
+          index,
+          req,
+          snapshot.clone(),
+          token.clone(),
+        )?);
+      }
+      let value = self.send(0, req, snapshot, token)?.await??;
+      for rx in receivers {
+        rx.await??;
+      }
+      value
+    } else {
+      let index = self.runtime_index(&req);
+      self.send(index, req, snapshot, token)?.await??
+    };
+    droppable_token.disarm();
+    Ok(serde_json::from_value::<R>(value)?)
+  }
+
+  /// Diagnostics are spread over the runtimes of the pool other than the
+  /// first, so they can be generated in parallel with interactive requests.
+  fn runtime_index(&self, req: &RequestMethod) -> usize {
+    let pool_size = self.0.runtimes.read().len();
+    match req {
+      RequestMethod::GetDiagnostics(_) if pool_size > 1 => {
+        let next = self
+          .0
+          .next_diagnostics_runtime
+          .fetch_add(1, Ordering::Relaxed);
+        1 + next % (pool_size - 1)
+      }
+      _ => 0,
+    }
+  }
+
+  fn send(
+    &self,
+    index: usize,
+    req: RequestMethod,
+    snapshot: Arc<StateSnapshot>,
+    token: CancellationToken,
+  ) -> Result<oneshot::Receiver<Result<Value, AnyError>>, AnyError> {
     let (tx, rx) = oneshot::channel::<Result<Value, AnyError>>();
-    if self.0.send((req, snapshot, tx, token)).is_err() {
+    // the pool could have shrunk since the index was picked
+    let sender = {
+      let runtimes = self.0.runtimes.read();
+      let runtime = runtimes.get(index).unwrap_or(&runtimes[0]);
+      let sender = runtime.lock().sender.clone();
+      sender
+    };
+    if sender.send((req, snapshot, tx, token)).is_err() {
       return Err(anyhow!("failed to send request to tsc thread"));
     }
-    let value = rx.await??;
-    Ok(serde_json::from_value::<R>(value)?)
+    Ok(rx)
   }
 }
 
 #[derive(Debug, Clone)]
 struct AssetDocumentInner {
   specifier: ModuleSpecifier,
-  text: Arc<str>,
-  line_index: Arc<LineIndex>,
+  /// The text of a static asset built into Rust, which is only copied into
+  /// `text` when it is first accessed.
+  maybe_static_text: Option<&'static str>,
+  text: OnceCell<Arc<str>>,
+  line_index: OnceCell<Arc<LineIndex>>,
   maybe_navigation_tree: Option<Arc<NavigationTree>>,
 }
 
 /// An lsp representation of an asset in memory, that has either been retrieved
 /// from static assets built into Rust, or static assets built into tsc.
+///
+/// The text and line index of an asset are loaded lazily, as most of the
+/// assets are never accessed by a client.
 #[derive(Debug, Clone)]
 pub struct AssetDocument(Arc<AssetDocumentInner>);
 
 impl AssetDocument {
   pub fn new(specifier: ModuleSpecifier, text: impl AsRef<str>) -> Self {
-    let text = text.as_ref();
     Self(Arc::new(AssetDocumentInner {
       specifier,
-      text: text.into(),
-      line_index: Arc::new(LineIndex::new(text)),
+      maybe_static_text: None,
+      text: OnceCell::with_value(text.as_ref().into()),
+      line_index: OnceCell::new(),
+      maybe_navigation_tree: None,
+    }))
+  }
+
+  fn new_static(specifier: ModuleSpecifier, text: &'static str) -> Self {
+    Self(Arc::new(AssetDocumentInner {
+      specifier,
+      maybe_static_text: Some(text),
+      text: OnceCell::new(),
+      line_index: OnceCell::new(),
       maybe_navigation_tree: None,
     }))
   }
@@ -636,11 +925,19 @@ impl AssetDocument {
   }
 
   pub fn text(&self) -> Arc<str> {
-    self.0.text.clone()
+    self
+      .0
+      .text
+      .get_or_init(|| self.0.maybe_static_text.unwrap_or_default().into())
+      .clone()
   }
 
   pub fn line_index(&self) -> Arc<LineIndex> {
-    self.0.line_index.clone()
+    self
+      .0
+      .line_index
+      .get_or_init(|| Arc::new(LineIndex::new(&self.text())))
+      .clone()
   }
 
   pub fn maybe_navigation_tree(&self) -> Option<Arc<NavigationTree>> {
@@ -656,7 +953,7 @@ fn new_assets_map() -> Arc<Mutex<AssetsMap>> {
     .map(|(k, v)| {
       let url_str = format!("asset:///{k}");
       let specifier = resolve_url(&url_str).unwrap();
-      let asset = AssetDocument::new(specifier.clone(), v);
+      let asset = AssetDocument::new_static(specifier.clone(), v);
       (specifier, asset)
     })
     .collect::<AssetsMap>();
@@ -881,6 +1178,15 @@ pub enum OneOrMany<T> {
   Many(Vec<T>),
 }
 
+impl<T> OneOrMany<T> {
+  pub fn iter(&self) -> std::slice::Iter<'_, T> {
+    match self {
+      OneOrMany::One(item) => std::slice::from_ref(item).iter(),
+      OneOrMany::Many(items) => items.iter(),
+    }
+  }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
 pub enum ScriptElementKind {
   #[serde(rename = "")]
@@ -1197,6 +1503,37 @@ fn display_parts_to_string(
 }
 
 impl QuickInfo {
+  pub fn to_inlay_hint_tooltip(
+    &self,
+    language_server: &language_server::Inner,
+  ) -> Option<lsp::InlayHintTooltip> {
+    let display_string = self
+      .display_parts
+      .as_ref()
+      .map(|p| display_parts_to_string(p, language_server))?;
+    let mut value = format!("```typescript\n{display_string}\n```");
+    if let Some(documentation) = &self.documentation {
+      let documentation =
+        display_parts_to_string(documentation, language_server);
+      if !documentation.is_empty() {
+        value.push_str("\n\n");
+        value.push_str(&documentation);
+      }
+    }
+    Some(lsp::InlayHintTooltip::MarkupContent(lsp::MarkupContent {
+      kind: lsp::MarkupKind::Markdown,
+      value,
+    }))
+  }
+
+  pub fn has_documentation(&self) -> bool {
+    self
+      .documentation
+      .as_ref()
+      .map(|d| !d.is_empty())
+      .unwrap_or(false)
+  }
+
   pub fn to_hover(
     &self,
     line_index: Arc<LineIndex>,
@@ -1294,6 +1631,24 @@ impl DocumentSpan {
     Some(link)
   }
 
+  pub fn to_location(
+    &self,
+    language_server: &language_server::Inner,
+  ) -> Option<lsp::Location> {
+    let specifier = normalize_specifier(&self.file_name).ok()?;
+    let asset_or_doc =
+      language_server.get_maybe_asset_or_document(&specifier)?;
+    let uri = language_server
+      .url_map
+      .normalize_specifier(&specifier)
+      .ok()?
+      .into_url();
+    Some(lsp::Location {
+      uri,
+      range: self.text_span.to_range(asset_or_doc.line_index()),
+    })
+  }
+
   /// Convert the `DocumentSpan` into a specifier that can be sent to the client
   /// to link to the target document span. Used for converting JSDoc symbol
   /// links to markdown links.
@@ -1348,11 +1703,27 @@ pub struct NavigateToItem {
 }
 
 impl NavigateToItem {
+  /// The specifier of the module the item is declared in. Redirected modules
+  /// are reported under their final specifier, so duplicates can be
+  /// identified.
+  pub fn specifier(
+    &self,
+    language_server: &language_server::Inner,
+  ) -> Option<ModuleSpecifier> {
+    let specifier = normalize_specifier(&self.file_name).ok()?;
+    Some(
+      language_server
+        .documents
+        .resolve_redirected(&specifier)
+        .unwrap_or(specifier),
+    )
+  }
+
   pub fn to_symbol_information(
     &self,
     language_server: &language_server::Inner,
   ) -> Option<lsp::SymbolInformation> {
-    let specifier = normalize_specifier(&self.file_name).ok()?;
+    let specifier = self.specifier(language_server)?;
     let asset_or_doc =
       language_server.get_asset_or_document(&specifier).ok()?;
     let line_index = asset_or_doc.line_index();
@@ -1415,7 +1786,19 @@ pub struct InlayHint {
 }
 
 impl InlayHint {
-  pub fn to_lsp(&self, line_index: Arc<LineIndex>) -> lsp::InlayHint {
+  pub fn to_lsp(
+    &self,
+    line_index: Arc<LineIndex>,
+    specifier: &ModuleSpecifier,
+  ) -> lsp::InlayHint {
+    // only type hints have a definition and tooltip to resolve
+    let data = match self.kind {
+      InlayHintKind::Type => Some(json!(InlayHintData {
+        specifier: specifier.clone(),
+        position: self.position,
+      })),
+      InlayHintKind::Parameter | InlayHintKind::Enum => None,
+    };
     lsp::InlayHint {
       position: line_index.position_tsc(self.position.into()),
       label: lsp::InlayHintLabel::String(self.text.clone()),
@@ -1424,7 +1807,90 @@ impl InlayHint {
       padding_right: self.whitespace_after,
       text_edits: None,
       tooltip: None,
-      data: None,
+      data,
+    }
+  }
+}
+
+/// The data of a type hint, which is used to resolve it.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InlayHintData {
+  pub specifier: ModuleSpecifier,
+  /// The position of the hint, which directly follows the name of what it is
+  /// the type of.
+  pub position: u32,
+}
+
+/// Split the label of a type hint into parts, where the names of the types
+/// link to their definitions. `None` is returned when there is nothing to
+/// link to.
+pub fn inlay_hint_label_parts(
+  label: &str,
+  definitions: &[DefinitionInfo],
+  language_server: &language_server::Inner,
+) -> Option<Vec<lsp::InlayHintLabelPart>> {
+  let locations = definitions
+    .iter()
+    .filter_map(|d| {
+      Some((
+        d.name.as_str(),
+        d.document_span.to_location(language_server)?,
+      ))
+    })
+    .collect::<Vec<_>>();
+  fn to_part(
+    value: &str,
+    location: Option<lsp::Location>,
+  ) -> lsp::InlayHintLabelPart {
+    lsp::InlayHintLabelPart {
+      value: value.to_string(),
+      tooltip: None,
+      location,
+      command: None,
+    }
+  }
+
+  let mut parts = Vec::new();
+  let mut last_end = 0;
+  for name_match in IDENTIFIER_RE.find_iter(label) {
+    let Some((_, location)) =
+      locations.iter().find(|(name, _)| *name == name_match.as_str())
+    else {
+      continue;
+    };
+    if name_match.start() > last_end {
+      parts.push(to_part(&label[last_end..name_match.start()], None));
+    }
+    parts.push(to_part(name_match.as_str(), Some(location.clone())));
+    last_end = name_match.end();
+  }
+  if parts.is_empty() {
+    return None;
+  }
+  if last_end < label.len() {
+    parts.push(to_part(&label[last_end..], None));
+  }
+  Some(parts)
+}
+
+/// An identifier that refers to a variable or parameter.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InlineValue {
+  pub name: String,
+  pub text_span: TextSpan,
+}
+
+impl InlineValue {
+  pub fn to_lsp(
+    &self,
+    line_index: Arc<LineIndex>,
+  ) -> lsp_custom::InlineValueVariableLookup {
+    lsp_custom::InlineValueVariableLookup {
+      range: self.text_span.to_range(line_index),
+      variable_name: Some(self.name.clone()),
+      case_sensitive_lookup: true,
     }
   }
 }
@@ -1555,6 +2021,61 @@ impl NavigationTree {
     should_include
   }
 
+  /// Flatten the declarations of the tree into symbol information, with the
+  /// enclosing declaration as the container of a symbol.
+  pub fn collect_symbol_information(
+    &self,
+    line_index: Arc<LineIndex>,
+    uri: &lsp::Url,
+    symbols: &mut Vec<lsp::SymbolInformation>,
+  ) {
+    // the root of the tree is the module itself
+    for child in self.child_items.iter().flatten() {
+      child.collect_child_symbol_information(
+        line_index.clone(),
+        uri,
+        None,
+        symbols,
+      );
+    }
+  }
+
+  fn collect_child_symbol_information(
+    &self,
+    line_index: Arc<LineIndex>,
+    uri: &lsp::Url,
+    container_name: Option<&str>,
+    symbols: &mut Vec<lsp::SymbolInformation>,
+  ) {
+    if let Some(span) =
+      self.spans.first().filter(|_| self.should_include_entry())
+    {
+      let tags = parse_kind_modifier(&self.kind_modifiers)
+        .contains("deprecated")
+        .then(|| vec![lsp::SymbolTag::DEPRECATED]);
+      #[allow(deprecated)]
+      symbols.push(lsp::SymbolInformation {
+        name: self.text.clone(),
+        kind: self.kind.clone().into(),
+        tags,
+        deprecated: None,
+        location: lsp::Location {
+          uri: uri.clone(),
+          range: span.to_range(line_index.clone()),
+        },
+        container_name: container_name.map(|n| n.to_string()),
+      });
+    }
+    for child in self.child_items.iter().flatten() {
+      child.collect_child_symbol_information(
+        line_index.clone(),
+        uri,
+        Some(&self.text),
+        symbols,
+      );
+    }
+  }
+
   fn should_include_entry(&self) -> bool {
     if let ScriptElementKind::Alias = self.kind {
       return false;
@@ -1633,8 +2154,8 @@ pub struct RenameLocation {
   #[serde(flatten)]
   document_span: DocumentSpan,
   // RenameLocation props
-  // prefix_text: Option<String>,
-  // suffix_text: Option<String>,
+  prefix_text: Option<String>,
+  suffix_text: Option<String>,
 }
 
 pub struct RenameLocations {
@@ -1678,7 +2199,12 @@ impl RenameLocations {
           .document_span
           .text_span
           .to_range(asset_or_doc.line_index()),
-        new_text: new_name.to_string(),
+        new_text: format!(
+          "{}{}{}",
+          location.prefix_text.as_deref().unwrap_or_default(),
+          new_name,
+          location.suffix_text.as_deref().unwrap_or_default()
+        ),
       }));
     }
 
@@ -1718,7 +2244,7 @@ pub struct HighlightSpan {
 #[serde(rename_all = "camelCase")]
 pub struct DefinitionInfo {
   // kind: ScriptElementKind,
-  // name: String,
+  pub name: String,
   // container_kind: Option<ScriptElementKind>,
   // container_name: Option<String>,
   #[serde(flatten)]
@@ -1758,11 +2284,28 @@ impl DefinitionInfoAndBoundSpan {
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DocumentHighlights {
-  // file_name: String,
+  file_name: String,
   highlight_spans: Vec<HighlightSpan>,
 }
 
 impl DocumentHighlights {
+  pub fn to_multi_document_highlight(
+    &self,
+    language_server: &language_server::Inner,
+  ) -> Option<lsp_custom::MultiDocumentHighlight> {
+    let specifier = normalize_specifier(&self.file_name).ok()?;
+    let asset_or_doc =
+      language_server.get_asset_or_document(&specifier).ok()?;
+    let uri = language_server
+      .url_map
+      .normalize_specifier(&specifier)
+      .ok()?;
+    Some(lsp_custom::MultiDocumentHighlight {
+      uri: uri.into_url(),
+      highlights: self.to_highlight(asset_or_doc.line_index()),
+    })
+  }
+
   pub fn to_highlight(
     &self,
     line_index: Arc<LineIndex>,
@@ -2081,7 +2624,11 @@ impl RefactorEditInfo {
     language_server: &language_server::Inner,
   ) -> Result<Option<lsp::WorkspaceEdit>, AnyError> {
     let mut all_ops = Vec::<lsp::DocumentChangeOperation>::new();
-    for edit in self.edits.iter() {
+    let edits = fix_ts_refactor_import_changes(
+      &self.edits,
+      &language_server.get_ts_response_import_mapper(),
+    )?;
+    for edit in edits.iter() {
       let ops = edit.to_text_document_change_ops(language_server)?;
       all_ops.extend(ops);
     }
@@ -2195,6 +2742,13 @@ pub struct CallHierarchyItem {
 }
 
 impl CallHierarchyItem {
+  /// If the item is declared in the module `specifier`.
+  pub fn is_in(&self, specifier: &ModuleSpecifier) -> bool {
+    normalize_specifier(&self.file)
+      .map(|file| &file == specifier)
+      .unwrap_or(false)
+  }
+
   pub fn try_resolve_call_hierarchy_item(
     &self,
     language_server: &language_server::Inner,
@@ -2422,6 +2976,78 @@ fn parse_code_actions(
   }
 }
 
+fn escape_snippet_text(text: &str) -> String {
+  text
+    .replace('\\', "\\\\")
+    .replace('$', "\\$")
+    .replace('}', "\\}")
+}
+
+/// Build a snippet which calls the function `name`, with a tab stop for each
+/// of its required parameters, based on the display parts of its signature.
+fn get_function_call_snippet(
+  name: &str,
+  display_parts: &[SymbolDisplayPart],
+) -> String {
+  let mut parameters = Vec::new();
+  let mut has_optional_parameters = false;
+  let mut paren_count = 0;
+  let mut brace_count = 0;
+  let mut is_in_function = false;
+  for (i, part) in display_parts.iter().enumerate() {
+    match part.kind.as_str() {
+      "methodName" | "functionName" | "text" | "propertyName" => {
+        if paren_count == 0 && brace_count == 0 {
+          is_in_function = true;
+        }
+      }
+      "parameterName" => {
+        if paren_count == 1 && brace_count == 0 && is_in_function {
+          let is_optional = display_parts
+            .get(i + 1)
+            .map(|next| next.text == "?")
+            .unwrap_or(false);
+          if is_optional {
+            has_optional_parameters = true;
+          } else if part.text != "this" {
+            parameters.push(part.text.as_str());
+          }
+        }
+      }
+      "punctuation" => match part.text.as_str() {
+        "(" => paren_count += 1,
+        ")" => {
+          paren_count -= 1;
+          if paren_count <= 0 && is_in_function {
+            break;
+          }
+        }
+        "..." if paren_count == 1 => has_optional_parameters = true,
+        "{" => brace_count += 1,
+        "}" => brace_count -= 1,
+        _ => {}
+      },
+      _ => {}
+    }
+  }
+  let mut snippet = format!("{}(", escape_snippet_text(name));
+  snippet.push_str(
+    &parameters
+      .iter()
+      .enumerate()
+      .map(|(i, parameter)| {
+        format!("${{{}:{}}}", i + 1, escape_snippet_text(parameter))
+      })
+      .collect::<Vec<_>>()
+      .join(", "),
+  );
+  if has_optional_parameters {
+    snippet.push_str(&format!("${}", parameters.len() + 1));
+  }
+  snippet.push_str(")$0");
+  snippet
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CompletionEntryDetails {
@@ -2476,7 +3102,39 @@ impl CompletionEntryDetails {
       specifier,
       language_server,
     )?;
-    // TODO(@kitsonk) add `use_code_snippet`
+    let (insert_text, insert_text_format, text_edit) = if data.use_code_snippet
+    {
+      let name = original_item
+        .insert_text
+        .clone()
+        .unwrap_or_else(|| self.name.clone());
+      let snippet = get_function_call_snippet(&name, &self.display_parts);
+      let text_edit = original_item.text_edit.clone().map(|edit| match edit {
+        lsp::CompletionTextEdit::Edit(edit) => {
+          lsp::CompletionTextEdit::Edit(lsp::TextEdit {
+            new_text: snippet.clone(),
+            ..edit
+          })
+        }
+        lsp::CompletionTextEdit::InsertAndReplace(edit) => {
+          lsp::CompletionTextEdit::InsertAndReplace(lsp::InsertReplaceEdit {
+            new_text: snippet.clone(),
+            ..edit
+          })
+        }
+      });
+      (
+        Some(snippet),
+        Some(lsp::InsertTextFormat::SNIPPET),
+        text_edit,
+      )
+    } else {
+      (
+        original_item.insert_text.clone(),
+        original_item.insert_text_format,
+        original_item.text_edit.clone(),
+      )
+    };
 
     Ok(lsp::CompletionItem {
       data: None,
@@ -2484,6 +3142,9 @@ impl CompletionEntryDetails {
       documentation,
       command,
       additional_text_edits,
+      insert_text,
+      insert_text_format,
+      text_edit,
       // NOTE(bartlomieju): it's not entirely clear to me why we need to do that,
       // but when `completionItem/resolve` is called, we get a list of commit chars
       // even though we might have returned an empty list in `completion` request.
@@ -2503,6 +3164,9 @@ pub struct CompletionInfo {
   is_global_completion: bool,
   is_member_completion: bool,
   is_new_identifier_location: bool,
+  /// Set when the entries were truncated in `getCompletions`.
+  #[serde(default)]
+  is_incomplete: bool,
   metadata: Option<Value>,
   optional_replacement_span: Option<TextSpan>,
 }
@@ -2541,7 +3205,7 @@ impl CompletionInfo {
       })
       .unwrap_or(false);
     lsp::CompletionResponse::List(lsp::CompletionList {
-      is_incomplete,
+      is_incomplete: is_incomplete || self.is_incomplete,
       items,
     })
   }
@@ -2638,9 +3302,22 @@ impl CompletionEntry {
     info: &CompletionInfo,
     settings: &config::CompletionSettings,
   ) -> Option<Vec<String>> {
-    if info.is_new_identifier_location {
+    let commit_characters_settings = &settings.commit_characters;
+    if !commit_characters_settings.enabled || info.is_new_identifier_location {
       return None;
     }
+    if !commit_characters_settings.kinds.is_empty() {
+      if let Some(commit_characters) = json!(self.kind)
+        .as_str()
+        .and_then(|kind| commit_characters_settings.kinds.get(kind))
+      {
+        return if commit_characters.is_empty() {
+          None
+        } else {
+          Some(commit_characters.clone())
+        };
+      }
+    }
 
     let mut commit_characters = vec![];
     match self.kind {
@@ -2740,6 +3417,24 @@ impl CompletionEntry {
       Some(true) => Some(lsp::InsertTextFormat::SNIPPET),
       _ => None,
     };
+    let mut label_details = self.label_details.as_ref().map(|details| {
+      lsp::CompletionItemLabelDetails {
+        detail: details.detail.clone(),
+        description: details.description.clone(),
+      }
+    });
+    // auto-imports show the module they are imported from
+    if let Some(source_display) = &self.source_display {
+      let label_details = label_details.get_or_insert_with(Default::default);
+      if label_details.description.is_none() {
+        label_details.description = Some(
+          source_display
+            .iter()
+            .map(|part| part.text.as_str())
+            .collect::<String>(),
+        );
+      }
+    }
     let range = self.replacement_span.clone();
     let mut filter_text = self.get_filter_text();
     let mut tags = None;
@@ -2800,6 +3495,7 @@ impl CompletionEntry {
 
     lsp::CompletionItem {
       label,
+      label_details,
       kind,
       sort_text,
       preselect,
@@ -2925,13 +3621,21 @@ impl SignatureHelpItems {
     self,
     language_server: &language_server::Inner,
   ) -> lsp::SignatureHelp {
+    let argument_index = self.argument_index;
+    let active_parameter = self
+      .items
+      .get(self.selected_item_index as usize)
+      .map(|item| item.active_parameter(argument_index))
+      .unwrap_or(argument_index);
     lsp::SignatureHelp {
       signatures: self
         .items
         .into_iter()
-        .map(|item| item.into_signature_information(language_server))
+        .map(|item| {
+          item.into_signature_information(argument_index, language_server)
+        })
         .collect(),
-      active_parameter: Some(self.argument_index),
+      active_parameter: Some(active_parameter),
       active_signature: Some(self.selected_item_index),
     }
   }
@@ -2940,7 +3644,7 @@ impl SignatureHelpItems {
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SignatureHelpItem {
-  // is_variadic: bool,
+  is_variadic: bool,
   prefix_display_parts: Vec<SymbolDisplayPart>,
   suffix_display_parts: Vec<SymbolDisplayPart>,
   // separator_display_parts: Vec<SymbolDisplayPart>,
@@ -2950,10 +3654,25 @@ pub struct SignatureHelpItem {
 }
 
 impl SignatureHelpItem {
+  /// Get the parameter of this signature that the argument at the index is
+  /// passed to, where all the trailing arguments of a variadic signature are
+  /// passed to its rest parameter. This mirrors `getActiveParameter` in
+  /// `signatureHelp.ts` in vscode, but is applied to each signature instead
+  /// of just the selected one.
+  fn active_parameter(&self, argument_index: u32) -> u32 {
+    if self.is_variadic && !self.parameters.is_empty() {
+      argument_index.min(self.parameters.len() as u32 - 1)
+    } else {
+      argument_index
+    }
+  }
+
   pub fn into_signature_information(
     self,
+    argument_index: u32,
     language_server: &language_server::Inner,
   ) -> lsp::SignatureInformation {
+    let active_parameter = self.active_parameter(argument_index);
     let prefix_text =
       display_parts_to_string(&self.prefix_display_parts, language_server);
     let params_text = self
@@ -2983,7 +3702,7 @@ impl SignatureHelpItem {
           .map(|param| param.into_parameter_information(language_server))
           .collect(),
       ),
-      active_parameter: None,
+      active_parameter: Some(active_parameter),
     }
   }
 }
@@ -3049,6 +3768,8 @@ struct Response {
 
 struct State {
   last_id: usize,
+  /// See `TscRuntime::heartbeat`.
+  heartbeat: Arc<Mutex<Option<Instant>>>,
   performance: Arc<Performance>,
   response: Option<Response>,
   state_snapshot: Arc<StateSnapshot>,
@@ -3063,6 +3784,7 @@ impl State {
   ) -> Self {
     Self {
       last_id: 1,
+      heartbeat: Default::default(),
       performance,
       response: None,
       state_snapshot,
@@ -3146,6 +3868,12 @@ struct SpecifierArgs {
 #[op]
 fn op_is_cancelled(state: &mut OpState) -> bool {
   let state = state.borrow_mut::<State>();
+  // tsc checks for cancellation regularly while it works on a request
+  let mut heartbeat = state.heartbeat.lock();
+  if heartbeat.is_some() {
+    *heartbeat = Some(Instant::now());
+  }
+  drop(heartbeat);
   state.token.is_cancelled()
 }
 
@@ -3229,10 +3957,23 @@ fn op_respond(state: &mut OpState, args: Response) -> bool {
   true
 }
 
-#[op]
-fn op_script_names(state: &mut OpState) -> Vec<String> {
-  let state = state.borrow_mut::<State>();
-  let documents = &state.state_snapshot.documents;
+/// The reason a script is part of the root names of the tsc project.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptNameOrigin {
+  /// The types of the `@types/node` package, injected when an npm package
+  /// or a `node:` specifier is used.
+  NodeTypes,
+  /// A global import of the module graph, like the `types` compiler option.
+  GraphImport,
+  /// A diagnosable document or one of its dependencies.
+  Document,
+}
+
+/// Collect the script names which are fed to tsc via `op_script_names`,
+/// along with why each of them is included.
+pub fn script_names(
+  documents: &Documents,
+) -> Vec<(ModuleSpecifier, ScriptNameOrigin)> {
   let all_docs = documents.documents(DocumentsFilter::AllDiagnosable);
   let mut seen = HashSet::new();
   let mut result = Vec::new();
@@ -3240,14 +3981,17 @@ fn op_script_names(state: &mut OpState) -> Vec<String> {
   if documents.has_injected_types_node_package() {
     // ensure this is first so it resolves the node types first
     let specifier = "asset:///node_types.d.ts";
-    result.push(specifier.to_string());
+    result.push((
+      ModuleSpecifier::parse(specifier).unwrap(),
+      ScriptNameOrigin::NodeTypes,
+    ));
     seen.insert(specifier);
   }
 
   // inject these next because they're global
   for import in documents.module_graph_imports() {
     if seen.insert(import.as_str()) {
-      result.push(import.to_string());
+      result.push((import.clone(), ScriptNameOrigin::GraphImport));
     }
   }
 
@@ -3264,7 +4008,7 @@ fn op_script_names(state: &mut OpState) -> Vec<String> {
         if let Some(specifier) = documents.resolve_redirected(specifier) {
           // only include dependencies we know to exist otherwise typescript will error
           if documents.exists(&specifier) {
-            result.push(specifier.to_string());
+            result.push((specifier, ScriptNameOrigin::Document));
           }
         }
       }
@@ -3274,6 +4018,15 @@ fn op_script_names(state: &mut OpState) -> Vec<String> {
   result
 }
 
+#[op]
+fn op_script_names(state: &mut OpState) -> Vec<String> {
+  let state = state.borrow_mut::<State>();
+  script_names(&state.state_snapshot.documents)
+    .into_iter()
+    .map(|(specifier, _)| specifier.to_string())
+    .collect()
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 struct ScriptVersionArgs {
@@ -3386,7 +4139,6 @@ pub enum ImportModuleSpecifierPreference {
 
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "kebab-case")]
-#[allow(dead_code)]
 pub enum ImportModuleSpecifierEnding {
   Auto,
   Minimal,
@@ -3394,6 +4146,17 @@ pub enum ImportModuleSpecifierEnding {
   Js,
 }
 
+impl From<config::ImportModuleSpecifierEnding> for ImportModuleSpecifierEnding {
+  fn from(ending: config::ImportModuleSpecifierEnding) -> Self {
+    match ending {
+      config::ImportModuleSpecifierEnding::Auto => Self::Auto,
+      config::ImportModuleSpecifierEnding::Minimal => Self::Minimal,
+      config::ImportModuleSpecifierEnding::Index => Self::Index,
+      config::ImportModuleSpecifierEnding::Js => Self::Js,
+    }
+  }
+}
+
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "kebab-case")]
 #[allow(dead_code)]
@@ -3508,6 +4271,18 @@ pub struct UserPreferences {
   pub auto_import_file_exclude_patterns: Option<Vec<String>>,
 }
 
+impl From<&FmtOptionsConfig> for UserPreferences {
+  fn from(config: &FmtOptionsConfig) -> Self {
+    UserPreferences {
+      quote_preference: Some(match config.single_quote {
+        Some(true) => QuotePreference::Single,
+        _ => QuotePreference::Double,
+      }),
+      ..Default::default()
+    }
+  }
+}
+
 impl From<&config::WorkspaceSettings> for UserPreferences {
   fn from(workspace_settings: &config::WorkspaceSettings) -> Self {
     let inlay_hints = &workspace_settings.inlay_hints;
@@ -3644,8 +4419,27 @@ enum RequestMethod {
       String,
     ),
   ),
+  /// Get the edits to the imports of other modules when a module or a
+  /// directory is renamed.
+  GetEditsForFileRename(
+    (
+      ModuleSpecifier,
+      ModuleSpecifier,
+      FormatCodeSettings,
+      UserPreferences,
+    ),
+  ),
   /// Retrieve code fixes for a range of a file with the provided error codes.
-  GetCodeFixes((ModuleSpecifier, u32, u32, Vec<String>, FormatCodeSettings)),
+  GetCodeFixes(
+    (
+      ModuleSpecifier,
+      u32,
+      u32,
+      Vec<String>,
+      FormatCodeSettings,
+      UserPreferences,
+    ),
+  ),
   /// Get completion information at a given position (IntelliSense).
   GetCompletions(
     (
@@ -3658,7 +4452,9 @@ enum RequestMethod {
   /// Get details about a specific completion entry.
   GetCompletionDetails(GetCompletionDetailsArgs),
   /// Retrieve the combined code fixes for a fix id for a module.
-  GetCombinedCodeFix((ModuleSpecifier, Value, FormatCodeSettings)),
+  GetCombinedCodeFix(
+    (ModuleSpecifier, Value, FormatCodeSettings, UserPreferences),
+  ),
   /// Get declaration information for a specific position.
   GetDefinition((ModuleSpecifier, u32)),
   /// Return diagnostics for given file.
@@ -3667,6 +4463,8 @@ enum RequestMethod {
   GetDocumentHighlights((ModuleSpecifier, u32, Vec<ModuleSpecifier>)),
   /// Get semantic highlights information for a particular file.
   GetEncodedSemanticClassifications((ModuleSpecifier, TextSpan)),
+  /// Get the locations in other modules which import a specific module.
+  GetFileReferences(ModuleSpecifier),
   /// Get implementation information for a specific position.
   GetImplementation((ModuleSpecifier, u32)),
   /// Get "navigate to" items, which are converted to workspace symbols
@@ -3684,8 +4482,11 @@ enum RequestMethod {
   },
   /// Get signature help items for a specific position.
   GetSignatureHelpItems((ModuleSpecifier, u32, SignatureHelpItemsOptions)),
-  /// Get a selection range for a specific position.
-  GetSmartSelectionRange((ModuleSpecifier, u32)),
+  /// Get the selection ranges for a set of positions.
+  GetSmartSelectionRanges((ModuleSpecifier, Vec<u32>)),
+  /// Get declaration information for a specific position, preferring the
+  /// implementation over type declarations.
+  GetSourceDefinition((ModuleSpecifier, u32)),
   /// Get the diagnostic codes that support some form of code fix.
   GetSupportedCodeFixes,
   /// Get the type definition information for a specific position.
@@ -3693,6 +4494,8 @@ enum RequestMethod {
     specifier: ModuleSpecifier,
     position: u32,
   },
+  /// Sort, combine and/or remove unused imports of a module.
+  OrganizeImports((ModuleSpecifier, FormatCodeSettings, OrganizeImportsMode)),
   /// Resolve a call hierarchy item for a specific position.
   PrepareCallHierarchy((ModuleSpecifier, u32)),
   /// Resolve incoming call hierarchy items for a specific position.
@@ -3701,12 +4504,33 @@ enum RequestMethod {
   ProvideCallHierarchyOutgoingCalls((ModuleSpecifier, u32)),
   /// Resolve inlay hints for a specific text span
   ProvideInlayHints((ModuleSpecifier, TextSpan, UserPreferences)),
+  /// Resolve the variables in a text span whose values a debugger can show,
+  /// up to the position where execution stopped.
+  ProvideInlineValues((ModuleSpecifier, TextSpan, u32)),
 
   // Special request, used only internally by the LSP
   Restart,
 }
 
 impl RequestMethod {
+  /// Whether the request changes the state of the server, in which case it is
+  /// never skipped and every runtime of a pool processes it.
+  fn changes_state(&self) -> bool {
+    matches!(self, RequestMethod::Configure(_) | RequestMethod::Restart)
+  }
+
+  /// Copy a request which changes the state of the server, for another
+  /// runtime of a pool.
+  fn copy_state_change(&self) -> Self {
+    match self {
+      RequestMethod::Configure(config) => {
+        RequestMethod::Configure(config.clone())
+      }
+      RequestMethod::Restart => RequestMethod::Restart,
+      _ => unreachable!("request does not change the state of the server"),
+    }
+  }
+
   fn to_value(&self, state: &State, id: usize) -> Value {
     match self {
       RequestMethod::Configure(config) => json!({
@@ -3757,12 +4581,26 @@ impl RequestMethod {
         "refactorName": refactor_name,
         "actionName": action_name,
       }),
+      RequestMethod::GetEditsForFileRename((
+        old_specifier,
+        new_specifier,
+        format_code_settings,
+        preferences,
+      )) => json!({
+        "id": id,
+        "method": "getEditsForFileRename",
+        "oldSpecifier": state.denormalize_specifier(old_specifier),
+        "newSpecifier": state.denormalize_specifier(new_specifier),
+        "formatCodeSettings": format_code_settings,
+        "preferences": preferences,
+      }),
       RequestMethod::GetCodeFixes((
         specifier,
         start_pos,
         end_pos,
         error_codes,
         format_code_settings,
+        preferences,
       )) => json!({
         "id": id,
         "method": "getCodeFixes",
@@ -3771,17 +4609,20 @@ impl RequestMethod {
         "endPosition": end_pos,
         "errorCodes": error_codes,
         "formatCodeSettings": format_code_settings,
+        "preferences": preferences,
       }),
       RequestMethod::GetCombinedCodeFix((
         specifier,
         fix_id,
         format_code_settings,
+        preferences,
       )) => json!({
         "id": id,
         "method": "getCombinedCodeFix",
         "specifier": state.denormalize_specifier(specifier),
         "fixId": fix_id,
         "formatCodeSettings": format_code_settings,
+        "preferences": preferences,
       }),
       RequestMethod::GetCompletionDetails(args) => json!({
         "id": id,
@@ -3833,6 +4674,11 @@ impl RequestMethod {
           "span": span,
         })
       }
+      RequestMethod::GetFileReferences(specifier) => json!({
+        "id": id,
+        "method": "getFileReferences",
+        "specifier": state.denormalize_specifier(specifier),
+      }),
       RequestMethod::GetImplementation((specifier, position)) => json!({
         "id": id,
         "method": "getImplementation",
@@ -3884,14 +4730,20 @@ impl RequestMethod {
           "options": options,
         })
       }
-      RequestMethod::GetSmartSelectionRange((specifier, position)) => {
+      RequestMethod::GetSmartSelectionRanges((specifier, positions)) => {
         json!({
           "id": id,
-          "method": "getSmartSelectionRange",
+          "method": "getSmartSelectionRanges",
           "specifier": state.denormalize_specifier(specifier),
-          "position": position
+          "positions": positions
         })
       }
+      RequestMethod::GetSourceDefinition((specifier, position)) => json!({
+        "id": id,
+        "method": "getSourceDefinitionAndBoundSpan",
+        "specifier": state.denormalize_specifier(specifier),
+        "position": position,
+      }),
       RequestMethod::GetSupportedCodeFixes => json!({
         "id": id,
         "method": "getSupportedCodeFixes",
@@ -3905,6 +4757,17 @@ impl RequestMethod {
         "specifier": state.denormalize_specifier(specifier),
         "position": position
       }),
+      RequestMethod::OrganizeImports((
+        specifier,
+        format_code_settings,
+        mode,
+      )) => json!({
+        "id": id,
+        "method": "organizeImports",
+        "specifier": state.denormalize_specifier(specifier),
+        "formatCodeSettings": format_code_settings,
+        "mode": mode,
+      }),
       RequestMethod::PrepareCallHierarchy((specifier, position)) => {
         json!({
           "id": id,
@@ -3944,6 +4807,17 @@ impl RequestMethod {
           "preferences": preferences,
         })
       }
+      RequestMethod::ProvideInlineValues((
+        specifier,
+        span,
+        stopped_position,
+      )) => json!({
+        "id": id,
+        "method": "provideInlineValues",
+        "specifier": state.denormalize_specifier(specifier),
+        "span": span,
+        "stoppedPosition": stopped_position,
+      }),
       RequestMethod::Restart => json!({
         "id": id,
         "method": "restart",
@@ -3952,6 +4826,199 @@ impl RequestMethod {
   }
 }
 
+/// Start a tsc runtime on its own thread, which processes the requests sent
+/// to it in order. A runtime which is added to a configured pool, or which
+/// replaces one that was hanging, is configured like the others before any
+/// other request.
+fn spawn_runtime(server: &TsServerInner) -> TscRuntime {
+  let performance = server.performance.clone();
+  let cache = server.cache.clone();
+  let config = server.last_config.lock().clone();
+  let options = server.options.clone();
+  let (tx, mut rx) = mpsc::unbounded_channel::<Request>();
+  let (handle_tx, handle_rx) = std::sync::mpsc::sync_channel(1);
+  let heartbeat = Arc::new(Mutex::new(None));
+  let terminated = Arc::new(AtomicBool::new(false));
+  let heap_size = Arc::new(AtomicUsize::new(0));
+  let runtime_heartbeat = heartbeat.clone();
+  let runtime_terminated = terminated.clone();
+  let runtime_heap_size = heap_size.clone();
+  let _join_handle = thread::spawn(move || {
+    let mut ts_runtime = js_runtime(performance, cache);
+    handle_tx
+      .send(ts_runtime.v8_isolate().thread_safe_handle())
+      .unwrap();
+    ts_runtime
+      .op_state()
+      .borrow_mut()
+      .borrow_mut::<State>()
+      .heartbeat = runtime_heartbeat.clone();
+
+    let mut started = false;
+    if let Some((config, state_snapshot)) = config {
+      start(&mut ts_runtime, false).unwrap();
+      started = true;
+      let req = RequestMethod::Configure(config);
+      if let Err(err) =
+        request(&mut ts_runtime, state_snapshot, req, Default::default())
+      {
+        lsp_warn!("Unable to configure the new tsc runtime: {}", err);
+      }
+    }
+
+    let runtime = create_basic_runtime();
+    runtime.block_on(async {
+      while let Some((req, state_snapshot, tx, token)) = rx.recv().await {
+        if !started {
+          // TODO(@kitsonk) need to reflect the debug state of the lsp here
+          start(&mut ts_runtime, false).unwrap();
+          started = true;
+        }
+        // A request which was cancelled while it was queued, for example
+        // because the client moved on, doesn't need to be processed. The
+        // requests which change the state of the server always are.
+        if token.is_cancelled() && !req.changes_state() {
+          if tx.send(Err(anyhow!("request cancelled"))).is_err() {
+            lsp_warn!("Unable to send result to client.");
+          }
+          continue;
+        }
+        *runtime_heartbeat.lock() = Some(Instant::now());
+        let value = request(&mut ts_runtime, state_snapshot, req, token);
+        *runtime_heartbeat.lock() = None;
+        let max_heap_size = options.lock().max_heap_size;
+        let mut heap_size = used_heap_size(&mut ts_runtime);
+        if max_heap_size.map(|max| heap_size > max).unwrap_or(false) {
+          // only a heap which is still too large once the garbage is
+          // collected makes the runtime restart
+          ts_runtime.v8_isolate().low_memory_notification();
+          heap_size = used_heap_size(&mut ts_runtime);
+        }
+        runtime_heap_size.store(heap_size, Ordering::Relaxed);
+        if tx.send(value).is_err() {
+          lsp_warn!("Unable to send result to client.");
+        }
+        if runtime_terminated.load(Ordering::Relaxed) {
+          // the requests which are still queued fail, as their senders are
+          // dropped
+          break;
+        }
+      }
+    })
+  });
+  TscRuntime {
+    sender: tx,
+    isolate_handle: handle_rx.recv().unwrap(),
+    heartbeat,
+    terminated,
+    heap_size,
+    started: Instant::now(),
+  }
+}
+
+fn used_heap_size(runtime: &mut JsRuntime) -> usize {
+  let mut heap_statistics = v8::HeapStatistics::default();
+  runtime
+    .v8_isolate()
+    .get_heap_statistics(&mut heap_statistics);
+  heap_statistics.used_heap_size()
+}
+
+/// Add runtimes to or remove runtimes from the pool. The requests queued on a
+/// removed runtime fail.
+fn resize_pool(server: &TsServerInner, size: usize) {
+  let size = size.max(1);
+  let len = server.runtimes.read().len();
+  let new_runtimes = (len..size)
+    .map(|_| Mutex::new(spawn_runtime(server)))
+    .collect::<Vec<_>>();
+  let mut runtimes = server.runtimes.write();
+  runtimes.extend(new_runtimes);
+  for runtime in runtimes.drain(size..) {
+    let runtime = runtime.into_inner();
+    runtime.terminated.store(true, Ordering::Relaxed);
+    runtime.isolate_handle.terminate_execution();
+  }
+  server.performance.truncate_tsc_heap_sizes(size);
+}
+
+/// Periodically check whether a runtime of the pool has stopped calling back
+/// into the language server while processing a request for longer than the
+/// request timeout, which means it hangs, or whether an idle runtime uses
+/// more memory than the maximum heap size. In both cases its execution is
+/// terminated and it is replaced by a new runtime. A request which times out
+/// fails and isn't retried. The heap sizes of the runtimes are recorded with
+/// the performance of the server. The watchdog stops when the server is
+/// dropped.
+fn spawn_watchdog(server: Weak<TsServerInner>) {
+  thread::spawn(move || loop {
+    thread::sleep(WATCHDOG_INTERVAL);
+    let Some(server) = server.upgrade() else {
+      break;
+    };
+    let options = *server.options.lock();
+    let runtimes = server.runtimes.read();
+    for (index, runtime) in runtimes.iter().enumerate() {
+      let (heartbeat, heap_size, started) = {
+        let runtime = runtime.lock();
+        let heartbeat = *runtime.heartbeat.lock();
+        let heap_size = runtime.heap_size.load(Ordering::Relaxed);
+        (heartbeat, heap_size, runtime.started)
+      };
+      server.performance.set_tsc_heap_size(index, heap_size);
+      let hanging_timeout = match (heartbeat, options.request_timeout) {
+        (Some(heartbeat), Some(timeout)) if heartbeat.elapsed() > timeout => {
+          Some(timeout)
+        }
+        _ => None,
+      };
+      let is_oversized = heartbeat.is_none()
+        && started.elapsed() > MIN_HEAP_RECYCLE_INTERVAL
+        && options
+          .max_heap_size
+          .map(|max_heap_size| heap_size > max_heap_size)
+          .unwrap_or(false);
+      if let Some(timeout) = hanging_timeout {
+        lsp_warn!(
+          "A tsc request did not respond within {} seconds, restarting the runtime.",
+          timeout.as_secs()
+        );
+        respawn_runtime(&server, runtime);
+        if let Some(handler) = &*server.timeout_handler.lock() {
+          handler(timeout);
+        }
+      } else if is_oversized {
+        lsp_warn!(
+          "A tsc runtime uses {}MB of heap after garbage collection, restarting the runtime.",
+          heap_size / (1024 * 1024)
+        );
+        respawn_runtime(&server, runtime);
+      }
+    }
+  });
+}
+
+/// Terminate a runtime of the pool and replace it with a new one, which is
+/// configured with the last configuration of the server. The new runtime is
+/// created without holding the lock of the slot, so that requests to the pool
+/// aren't blocked while its isolate is set up; they fail on the terminated
+/// runtime in the meantime.
+fn respawn_runtime(server: &TsServerInner, slot: &Mutex<TscRuntime>) {
+  let terminated = {
+    let runtime = slot.lock();
+    runtime.terminated.store(true, Ordering::Relaxed);
+    runtime.isolate_handle.terminate_execution();
+    runtime.terminated.clone()
+  };
+  let new_runtime = spawn_runtime(server);
+  let mut runtime = slot.lock();
+  // the slot could have been respawned concurrently, in which case the
+  // runtime created here is dropped, which stops its thread
+  if Arc::ptr_eq(&runtime.terminated, &terminated) {
+    *runtime = new_runtime;
+  }
+}
+
 /// Send a request into a runtime and return the JSON value of the response.
 fn request(
   runtime: &mut JsRuntime,
@@ -4500,6 +5567,21 @@ mod tests {
     assert!(total_size < 2_000_000); // currently as of TS 4.6, it's 0.7MB
   }
 
+  #[test]
+  fn test_static_assets_lazy() {
+    let assets = AssetsSnapshot::default();
+    let specifier = resolve_url("asset:///lib.dom.d.ts").unwrap();
+    let asset = assets.get(&specifier).unwrap();
+    assert!(asset.0.text.get().is_none());
+    assert!(asset.0.line_index.get().is_none());
+    let line_index = asset.line_index();
+    assert!(asset.0.text.get().is_some());
+    assert_eq!(
+      line_index.text_content_length_utf16(),
+      TextSize::from(asset.text().encode_utf16().count() as u32)
+    );
+  }
+
   #[test]
   fn test_modify_sources() {
     let temp_dir = TempDir::new();
@@ -4618,6 +5700,200 @@ mod tests {
     assert_eq!(actual, Some("abc".to_string()));
   }
 
+  #[test]
+  fn test_user_preferences_from_fmt_options() {
+    let fmt_options_config = FmtOptionsConfig {
+      single_quote: Some(true),
+      ..Default::default()
+    };
+    assert_eq!(
+      json!(UserPreferences::from(&fmt_options_config)),
+      json!({ "quotePreference": "single" })
+    );
+    assert_eq!(
+      json!(UserPreferences::from(&FmtOptionsConfig::default())),
+      json!({ "quotePreference": "double" })
+    );
+  }
+
+  #[test]
+  fn test_completion_entry_commit_characters() {
+    let info = CompletionInfo {
+      entries: vec![],
+      is_global_completion: true,
+      is_member_completion: false,
+      is_new_identifier_location: false,
+      is_incomplete: false,
+      metadata: None,
+      optional_replacement_span: None,
+    };
+    let fixture = CompletionEntry {
+      kind: ScriptElementKind::FunctionElement,
+      name: "foo".to_string(),
+      ..Default::default()
+    };
+    let mut settings = config::CompletionSettings::default();
+    assert_eq!(
+      fixture.get_commit_characters(&info, &settings),
+      Some(vec![
+        ".".to_string(),
+        ",".to_string(),
+        ";".to_string(),
+        "(".to_string()
+      ])
+    );
+
+    settings
+      .commit_characters
+      .kinds
+      .insert("function".to_string(), vec![";".to_string()]);
+    assert_eq!(
+      fixture.get_commit_characters(&info, &settings),
+      Some(vec![";".to_string()])
+    );
+
+    settings.commit_characters.enabled = false;
+    assert_eq!(fixture.get_commit_characters(&info, &settings), None);
+  }
+
+  #[test]
+  fn test_signature_help_item_active_parameter() {
+    let item = |is_variadic: bool| -> SignatureHelpItem {
+      serde_json::from_value(json!({
+        "isVariadic": is_variadic,
+        "prefixDisplayParts": [],
+        "suffixDisplayParts": [],
+        "parameters": [
+          { "documentation": [], "displayParts": [] },
+          { "documentation": [], "displayParts": [] },
+        ],
+        "documentation": [],
+      }))
+      .unwrap()
+    };
+    assert_eq!(item(false).active_parameter(1), 1);
+    assert_eq!(item(false).active_parameter(3), 3);
+    assert_eq!(item(true).active_parameter(1), 1);
+    assert_eq!(item(true).active_parameter(3), 1);
+  }
+
+  #[test]
+  fn test_get_function_call_snippet() {
+    let part = |text: &str, kind: &str| SymbolDisplayPart {
+      text: text.to_string(),
+      kind: kind.to_string(),
+      target: None,
+    };
+    // function foo(a: string, b?: { c: number }): void
+    let display_parts = vec![
+      part("function", "keyword"),
+      part(" ", "space"),
+      part("foo", "functionName"),
+      part("(", "punctuation"),
+      part("a", "parameterName"),
+      part(":", "punctuation"),
+      part(" ", "space"),
+      part("string", "keyword"),
+      part(",", "punctuation"),
+      part(" ", "space"),
+      part("b", "parameterName"),
+      part("?", "punctuation"),
+      part(":", "punctuation"),
+      part(" ", "space"),
+      part("{", "punctuation"),
+      part("c", "propertyName"),
+      part(":", "punctuation"),
+      part("number", "keyword"),
+      part("}", "punctuation"),
+      part(")", "punctuation"),
+      part(":", "punctuation"),
+      part(" ", "space"),
+      part("void", "keyword"),
+    ];
+    assert_eq!(
+      get_function_call_snippet("foo", &display_parts),
+      "foo(${1:a}$2)$0"
+    );
+    let display_parts = vec![
+      part("function", "keyword"),
+      part(" ", "space"),
+      part("bar", "functionName"),
+      part("(", "punctuation"),
+      part(")", "punctuation"),
+      part(":", "punctuation"),
+      part(" ", "space"),
+      part("void", "keyword"),
+    ];
+    assert_eq!(get_function_call_snippet("bar", &display_parts), "bar()$0");
+  }
+
+  #[test]
+  fn test_completion_info_incomplete() {
+    let info: CompletionInfo = serde_json::from_value(json!({
+      "entries": [{ "kind": "const", "name": "fooBar", "sortText": "11" }],
+      "isGlobalCompletion": true,
+      "isMemberCompletion": false,
+      "isNewIdentifierLocation": false,
+      "isIncomplete": true,
+    }))
+    .unwrap();
+    let specifier = resolve_url("file:///a.ts").unwrap();
+    let line_index = Arc::new(LineIndex::new("foob"));
+    let settings = config::CompletionSettings::default();
+    let lsp::CompletionResponse::List(list) =
+      info.as_completion_response(line_index, &settings, &specifier, 4)
+    else {
+      unreachable!();
+    };
+    assert!(list.is_incomplete);
+    assert_eq!(list.items.len(), 1);
+  }
+
+  #[test]
+  fn test_completions_truncated() {
+    let mut fixture = String::new();
+    for i in 0..1000 {
+      fixture.push_str(&format!("const a{i} = {i};\n"));
+    }
+    fixture.push_str("function fooBar() {}\nfoob");
+    let line_index = LineIndex::new(&fixture);
+    let position = line_index
+      .offset_tsc(lsp::Position {
+        line: 1001,
+        character: 4,
+      })
+      .unwrap();
+    let temp_dir = TempDir::new();
+    let (mut runtime, state_snapshot, _) = setup(
+      &temp_dir,
+      false,
+      json!({
+        "target": "esnext",
+        "module": "esnext",
+        "lib": ["deno.ns", "deno.window"],
+        "noEmit": true,
+      }),
+      &[("file:///a.ts", &fixture, 1, LanguageId::TypeScript)],
+    );
+    let specifier = resolve_url("file:///a.ts").expect("could not resolve url");
+    let result = request(
+      &mut runtime,
+      state_snapshot,
+      RequestMethod::GetCompletions((
+        specifier,
+        position,
+        Default::default(),
+        Default::default(),
+      )),
+      Default::default(),
+    );
+    let response: CompletionInfo =
+      serde_json::from_value(result.unwrap()).unwrap();
+    assert!(response.is_incomplete);
+    assert_eq!(response.entries.len(), 1000);
+    assert_eq!(response.entries[0].name, "fooBar");
+  }
+
   #[test]
   fn test_completions() {
     let fixture = r#"
@@ -4978,4 +6254,95 @@ mod tests {
       Some(false)
     );
   }
+
+  #[test]
+  fn test_navigation_tree_collect_symbol_information() {
+    let text = "export class Foo {\n  bar() {}\n}\n/** @deprecated */\nfunction baz() {}\n";
+    let navigation_tree: NavigationTree = serde_json::from_value(json!({
+      "text": "<global>",
+      "kind": "script",
+      "kindModifiers": "",
+      "spans": [{ "start": 0, "length": 69 }],
+      "childItems": [
+        {
+          "text": "Foo",
+          "kind": "class",
+          "kindModifiers": "export",
+          "spans": [{ "start": 0, "length": 31 }],
+          "nameSpan": { "start": 13, "length": 3 },
+          "childItems": [
+            {
+              "text": "bar",
+              "kind": "method",
+              "kindModifiers": "",
+              "spans": [{ "start": 21, "length": 8 }],
+              "nameSpan": { "start": 21, "length": 3 },
+            },
+          ],
+        },
+        {
+          "text": "baz",
+          "kind": "function",
+          "kindModifiers": "deprecated",
+          "spans": [{ "start": 51, "length": 17 }],
+          "nameSpan": { "start": 60, "length": 3 },
+        },
+      ],
+    }))
+    .unwrap();
+    let uri = resolve_url("file:///a.ts").unwrap();
+    let mut symbols = Vec::new();
+    navigation_tree.collect_symbol_information(
+      Arc::new(LineIndex::new(text)),
+      &uri,
+      &mut symbols,
+    );
+    let range = |start: (u32, u32), end: (u32, u32)| lsp::Range {
+      start: lsp::Position {
+        line: start.0,
+        character: start.1,
+      },
+      end: lsp::Position {
+        line: end.0,
+        character: end.1,
+      },
+    };
+    #[allow(deprecated)]
+    let expected = vec![
+      lsp::SymbolInformation {
+        name: "Foo".to_string(),
+        kind: lsp::SymbolKind::CLASS,
+        tags: None,
+        deprecated: None,
+        location: lsp::Location {
+          uri: uri.clone(),
+          range: range((0, 0), (2, 1)),
+        },
+        container_name: None,
+      },
+      lsp::SymbolInformation {
+        name: "bar".to_string(),
+        kind: lsp::SymbolKind::METHOD,
+        tags: None,
+        deprecated: None,
+        location: lsp::Location {
+          uri: uri.clone(),
+          range: range((1, 2), (1, 10)),
+        },
+        container_name: Some("Foo".to_string()),
+      },
+      lsp::SymbolInformation {
+        name: "baz".to_string(),
+        kind: lsp::SymbolKind::FUNCTION,
+        tags: Some(vec![lsp::SymbolTag::DEPRECATED]),
+        deprecated: None,
+        location: lsp::Location {
+          uri,
+          range: range((4, 0), (4, 17)),
+        },
+        container_name: None,
+      },
+    ];
+    assert_eq!(symbols, expected);
+  }
 }
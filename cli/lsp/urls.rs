@@ -60,6 +60,24 @@ fn hash_data_specifier(specifier: &ModuleSpecifier) -> String {
   crate::util::checksum::gen(&[file_name_str.as_bytes()])
 }
 
+/// Give an untitled document a file specifier in the `base` directory, so its
+/// relative imports resolve against that directory. The file name is the last
+/// segment of its URL, prefixed with a hash of the whole URL so that it can't
+/// collide with a real file or another untitled document of the same name.
+fn to_untitled_specifier(
+  base: &ModuleSpecifier,
+  url: &Url,
+) -> Option<ModuleSpecifier> {
+  let name = url.path().rsplit('/').find(|s| !s.is_empty())?;
+  let hash = crate::util::checksum::gen(&[url.as_str().as_bytes()]);
+  let file_name = format!("untitled-{}-{}", &hash[..8], name);
+  base
+    .join(
+      &percent_encoding::utf8_percent_encode(&file_name, COMPONENT).to_string(),
+    )
+    .ok()
+}
+
 /// This exists to make it a little bit harder to accidentally use a `Url`
 /// in the wrong place where a client url should be used.
 #[derive(Debug, Clone, Hash, PartialEq, Eq, Ord, PartialOrd)]
@@ -124,6 +142,7 @@ pub enum LspUrlKind {
 #[derive(Debug, Default, Clone)]
 pub struct LspUrlMap {
   local_http_cache: Option<Arc<LocalLspHttpCache>>,
+  untitled_base: Option<ModuleSpecifier>,
   inner: Arc<Mutex<LspUrlMapInner>>,
 }
 
@@ -132,6 +151,12 @@ impl LspUrlMap {
     self.local_http_cache = http_cache;
   }
 
+  /// Set the directory that untitled documents are placed in. Documents which
+  /// were already mapped keep their specifier.
+  pub fn set_untitled_base(&mut self, base: Option<ModuleSpecifier>) {
+    self.untitled_base = base;
+  }
+
   /// Normalize a specifier that is used internally within Deno (or tsc) to a
   /// URL that can be handled as a "virtual" document by an LSP client.
   pub fn normalize_specifier(
@@ -221,6 +246,12 @@ impl LspUrlMap {
         } else {
           url.clone()
         }
+      } else if url.scheme() == "untitled" {
+        self
+          .untitled_base
+          .as_ref()
+          .and_then(|base| to_untitled_specifier(base, url))
+          .unwrap_or_else(|| url.clone())
       } else {
         url.clone()
       };
@@ -322,6 +353,40 @@ mod tests {
     assert_eq!(actual, expected);
   }
 
+  #[test]
+  fn test_normalize_untitled() {
+    let map = LspUrlMap::default();
+    let fixture = resolve_url("untitled:Untitled-1").unwrap();
+    assert_eq!(map.normalize_url(&fixture, LspUrlKind::File), fixture);
+
+    let mut map = LspUrlMap::default();
+    map.set_untitled_base(Some(resolve_url("file:///a/").unwrap()));
+    let actual = map.normalize_url(&fixture, LspUrlKind::File);
+    let expected =
+      resolve_url("file:///a/untitled-9f9424a2-Untitled-1").unwrap();
+    assert_eq!(actual, expected);
+    assert_eq!(map.normalize_specifier(&actual).unwrap().as_url(), &fixture);
+
+    // the specifier is stable when the base changes
+    map.set_untitled_base(Some(resolve_url("file:///b/").unwrap()));
+    assert_eq!(map.normalize_url(&fixture, LspUrlKind::File), expected);
+
+    // documents with the same name don't share a specifier
+    let actual = map.normalize_url(
+      &resolve_url("untitled:/home/deno/mod.ts").unwrap(),
+      LspUrlKind::File,
+    );
+    let other = map.normalize_url(
+      &resolve_url("untitled:/home/other/mod.ts").unwrap(),
+      LspUrlKind::File,
+    );
+    assert_eq!(
+      actual,
+      resolve_url("file:///b/untitled-2b572ca2-mod.ts").unwrap()
+    );
+    assert_ne!(actual, other);
+  }
+
   #[test]
   fn test_normalize_deno_status() {
     let map = LspUrlMap::default();
@@ -1,6 +1,9 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
+use crate::args::crypto_backend_from_env;
+use crate::args::fips_from_env;
 use crate::args::get_root_cert_store;
+use crate::args::max_keygen_tasks_from_env;
 use crate::args::npm_pkg_req_ref_to_binary_command;
 use crate::args::CaData;
 use crate::args::CacheSetting;
@@ -453,6 +456,9 @@ pub async fn run(
       .map(|req_ref| npm_pkg_req_ref_to_binary_command(&req_ref)),
       origin_data_folder_path: None,
       seed: metadata.seed,
+      fips: fips_from_env()?,
+      crypto_backend: crypto_backend_from_env()?,
+      max_keygen_tasks: max_keygen_tasks_from_env()?,
       unsafely_ignore_certificate_errors: metadata
         .unsafely_ignore_certificate_errors,
       unstable: metadata.unstable,
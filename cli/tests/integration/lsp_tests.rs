@@ -12,6 +12,7 @@ use std::process::Stdio;
 use test_util::assert_starts_with;
 use test_util::deno_cmd_with_deno_dir;
 use test_util::env_vars_for_npm_tests;
+use test_util::lsp::InitializeParamsBuilder;
 use test_util::lsp::LspClient;
 use test_util::testdata_path;
 use test_util::TestContextBuilder;
@@ -432,6 +433,105 @@ fn lsp_import_map_embedded_in_config_file_after_initialize() {
   client.shutdown();
 }
 
+#[test]
+fn lsp_config_file_compiler_options_changed() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  temp_dir.write("deno.json", r#"{ "compilerOptions": { "strict": false } }"#);
+
+  let mut client = context.new_lsp_command().build();
+  client.initialize(|builder| {
+    builder.set_config("./deno.json");
+  });
+
+  let uri = temp_dir.uri().join("a.ts").unwrap();
+  let diagnostics = client.did_open(json!({
+    "textDocument": {
+      "uri": uri,
+      "languageId": "typescript",
+      "version": 1,
+      "text": "function f(a) {\n  return a;\n}\n\nconsole.log(f(1));\n"
+    }
+  }));
+  assert_eq!(diagnostics.all().len(), 0);
+
+  temp_dir.write("deno.json", r#"{ "compilerOptions": { "strict": true } }"#);
+  client.did_change_watched_files(json!({
+    "changes": [{
+      "uri": temp_dir.uri().join("deno.json").unwrap(),
+      "type": 2
+    }]
+  }));
+  assert_eq!(
+    json!(client.read_diagnostics().all()),
+    json!([{
+      "range": {
+        "start": { "line": 0, "character": 11 },
+        "end": { "line": 0, "character": 12 }
+      },
+      "severity": 1,
+      "code": 7006,
+      "source": "deno-ts",
+      "message": "Parameter 'a' implicitly has an 'any' type."
+    }])
+  );
+  client.shutdown();
+}
+
+#[test]
+fn lsp_untitled_document() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  temp_dir.write("a.ts", r#"export const a: string = "a";"#);
+  temp_dir.create_dir_all("lib");
+  temp_dir.write("lib/b.ts", r#"export const b: string = "b";"#);
+
+  let mut client = context.new_lsp_command().build();
+  client.initialize_default();
+  let diagnostics = client.did_open(json!({
+    "textDocument": {
+      "uri": "untitled:Untitled-1",
+      "languageId": "typescript",
+      "version": 1,
+      "text": "import { a } from \"./a.ts\";\n\nconst c: number = a;\nconsole.log(c);\n"
+    }
+  }));
+  assert_eq!(
+    diagnostics.messages_with_source("deno-ts"),
+    serde_json::from_value(json!({
+      "uri": "untitled:Untitled-1",
+      "diagnostics": [{
+        "range": {
+          "start": { "line": 2, "character": 6 },
+          "end": { "line": 2, "character": 7 }
+        },
+        "severity": 1,
+        "code": 2322,
+        "source": "deno-ts",
+        "message": "Type 'string' is not assignable to type 'number'."
+      }],
+      "version": 1
+    }))
+    .unwrap()
+  );
+  client.shutdown();
+
+  let mut client = context.new_lsp_command().build();
+  client.initialize(|builder| {
+    builder.set_untitled_base("lib");
+  });
+  let diagnostics = client.did_open(json!({
+    "textDocument": {
+      "uri": "untitled:Untitled-1",
+      "languageId": "typescript",
+      "version": 1,
+      "text": "import { b } from \"./b.ts\";\n\nconsole.log(b);\n"
+    }
+  }));
+  assert_eq!(diagnostics.all().len(), 0);
+  client.shutdown();
+}
+
 #[test]
 fn lsp_import_map_config_file_auto_discovered() {
   let context = TestContextBuilder::new().use_temp_cwd().build();
@@ -976,6 +1076,74 @@ fn lsp_hover() {
   client.shutdown();
 }
 
+#[test]
+fn lsp_restart_ts_server() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let mut client = context.new_lsp_command().build();
+  client.initialize_default();
+  client.did_open(json!({
+    "textDocument": {
+      "uri": "file:///a/file.ts",
+      "languageId": "typescript",
+      "version": 1,
+      "text": "const a: string = 1;\nconsole.log(a);\n"
+    }
+  }));
+  let res = client.write_request(
+    "workspace/executeCommand",
+    json!({
+      "command": "deno.restartTsServer",
+      "arguments": [],
+    }),
+  );
+  assert_eq!(res, json!(null));
+  // the restarted server still knows about the open document
+  let res = client.write_request(
+    "textDocument/hover",
+    json!({
+      "textDocument": {
+        "uri": "file:///a/file.ts"
+      },
+      "position": { "line": 1, "character": 12 }
+    }),
+  );
+  assert_eq!(
+    res["contents"][0],
+    json!({
+      "language": "typescript",
+      "value": "const a: string",
+    })
+  );
+  client.shutdown();
+}
+
+#[test]
+fn lsp_tsc_project_document() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let mut client = context.new_lsp_command().build();
+  client.initialize_default();
+  client.did_open(json!({
+    "textDocument": {
+      "uri": "file:///a/file.ts",
+      "languageId": "typescript",
+      "version": 3,
+      "text": "console.log(Date.now());\n"
+    }
+  }));
+  let res = client.write_request(
+    "deno/virtualTextDocument",
+    json!({
+      "textDocument": {
+        "uri": "deno:/tsc-project"
+      }
+    }),
+  );
+  let contents = res.as_str().unwrap();
+  assert!(contents.starts_with("# TypeScript Project\n\nScripts: 1\n"));
+  assert!(contents.contains("|file:///a/file.ts|3|TypeScript||\n"));
+  client.shutdown();
+}
+
 #[test]
 fn lsp_hover_asset() {
   let context = TestContextBuilder::new().use_temp_cwd().build();
@@ -1119,7 +1287,8 @@ fn lsp_inlay_hints() {
         "position": { "line": 0, "character": 21 },
         "label": ": string",
         "kind": 1,
-        "paddingLeft": true
+        "paddingLeft": true,
+        "data": { "specifier": "file:///a/file.ts", "position": 21 }
       }, {
         "position": { "line": 4, "character": 10 },
         "label": "b:",
@@ -1143,12 +1312,14 @@ fn lsp_inlay_hints() {
         "position": { "line": 12, "character": 15 },
         "label": ": number",
         "kind": 1,
-        "paddingLeft": true
+        "paddingLeft": true,
+        "data": { "specifier": "file:///a/file.ts", "position": 159 }
       }, {
         "position": { "line": 15, "character": 11 },
         "label": ": number",
         "kind": 1,
-        "paddingLeft": true
+        "paddingLeft": true,
+        "data": { "specifier": "file:///a/file.ts", "position": 204 }
       }, {
         "position": { "line": 18, "character": 18 },
         "label": "callbackfn:",
@@ -1158,12 +1329,14 @@ fn lsp_inlay_hints() {
         "position": { "line": 18, "character": 20 },
         "label": ": string",
         "kind": 1,
-        "paddingLeft": true
+        "paddingLeft": true,
+        "data": { "specifier": "file:///a/file.ts", "position": 250 }
       }, {
         "position": { "line": 18, "character": 21 },
         "label": ": string",
         "kind": 1,
-        "paddingLeft": true
+        "paddingLeft": true,
+        "data": { "specifier": "file:///a/file.ts", "position": 251 }
       }
     ])
   );
@@ -1217,73 +1390,394 @@ fn lsp_inlay_hints_not_enabled() {
 }
 
 #[test]
-fn lsp_workspace_enable_paths() {
-  fn run_test(use_trailing_slash: bool) {
-    let context = TestContextBuilder::new().use_temp_cwd().build();
-    let temp_dir = context.temp_dir();
-    temp_dir.create_dir_all("worker");
-    temp_dir.write("worker/shared.ts", "export const a = 1");
-    temp_dir.write("worker/other.ts", "import { a } from './shared.ts';\na;");
-
-    let root_specifier = temp_dir.uri();
-
-    let mut client = context.new_lsp_command().build();
-    client.initialize_with_config(
-      |builder| {
-        builder
-          .set_enable_paths(vec!["./worker".to_string()])
-          .set_root_uri(root_specifier.clone())
-          .set_workspace_folders(vec![lsp::WorkspaceFolder {
-            uri: if use_trailing_slash {
-              root_specifier.clone()
-            } else {
-              ModuleSpecifier::parse(
-                root_specifier.as_str().strip_suffix('/').unwrap(),
-              )
-              .unwrap()
-            },
-            name: "project".to_string(),
-          }])
-          .set_deno_enable(false);
+fn lsp_inlay_hint_resolve() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let mut client = context.new_lsp_command().build();
+  client.initialize(|builder| {
+    builder.enable_inlay_hints();
+  });
+  client.did_open(json!({
+    "textDocument": {
+      "uri": "file:///a/file.ts",
+      "languageId": "typescript",
+      "version": 1,
+      "text": "class Foo {}\nfunction make() {\n  return new Foo();\n}\nconst bar = make();\n"
+    }
+  }));
+  let res = client.write_request(
+    "inlayHint/resolve",
+    json!({
+      "position": { "line": 4, "character": 9 },
+      "label": ": Foo",
+      "kind": 1,
+      "paddingLeft": true,
+      "data": { "specifier": "file:///a/file.ts", "position": 62 }
+    }),
+  );
+  assert_eq!(
+    res,
+    json!({
+      "position": { "line": 4, "character": 9 },
+      "label": [
+        { "value": ": " },
+        {
+          "value": "Foo",
+          "location": {
+            "uri": "file:///a/file.ts",
+            "range": {
+              "start": { "line": 0, "character": 6 },
+              "end": { "line": 0, "character": 9 }
+            }
+          }
+        }
+      ],
+      "kind": 1,
+      "tooltip": {
+        "kind": "markdown",
+        "value": "```typescript\nconst bar: Foo\n```"
       },
-      json!([{
-        "enable": false,
-        "enablePaths": ["./worker"],
-      }]),
-    );
-
-    client.did_open(json!({
-      "textDocument": {
-        "uri": root_specifier.join("./file.ts").unwrap(),
-        "languageId": "typescript",
-        "version": 1,
-        "text": "console.log(Date.now());\n"
-      }
-    }));
+      "paddingLeft": true,
+      "data": { "specifier": "file:///a/file.ts", "position": 62 }
+    })
+  );
+  client.shutdown();
+}
 
-    client.did_open(json!({
-      "textDocument": {
-        "uri": root_specifier.join("./other/file.ts").unwrap(),
-        "languageId": "typescript",
-        "version": 1,
-        "text": "console.log(Date.now());\n"
-      }
-    }));
+#[test]
+fn lsp_custom_capabilities() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let mut client = context.new_lsp_command().build();
+  let mut builder = InitializeParamsBuilder::new();
+  builder.set_root_uri(context.temp_dir().uri());
+  let res = client.write_request("initialize", builder.build());
+  let experimental = &res["capabilities"]["experimental"];
+  assert_eq!(experimental["inlineValueProvider"], json!(true));
+  assert_eq!(
+    experimental["diagnosticProvider"],
+    json!({
+      "interFileDependencies": true,
+      "workspaceDiagnostics": true,
+    })
+  );
+  client.write_notification("initialized", json!({}));
+  client.handle_configuration_request(json!([{ "enable": true }]));
+  client.shutdown();
+}
 
-    client.did_open(json!({
+#[test]
+fn lsp_inline_value() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let mut client = context.new_lsp_command().build();
+  client.initialize_default();
+  client.did_open(json!({
+    "textDocument": {
+      "uri": "file:///a/file.ts",
+      "languageId": "typescript",
+      "version": 1,
+      "text": "function add(a: number, b: number) {\n  const sum = a + b;\n  return sum;\n}\n\nconst later = add(1, 2);\n"
+    }
+  }));
+  let res = client.write_request(
+    "textDocument/inlineValue",
+    json!({
       "textDocument": {
-        "uri": root_specifier.join("./worker/file.ts").unwrap(),
-        "languageId": "typescript",
-        "version": 1,
-        "text": concat!(
-          "console.log(Date.now());\n",
-          "import { a } from './shared.ts';\n",
-          "a;\n",
-        ),
+        "uri": "file:///a/file.ts",
+      },
+      "range": {
+        "start": { "line": 0, "character": 0 },
+        "end": { "line": 6, "character": 0 }
+      },
+      "context": {
+        "frameId": 1,
+        "stoppedLocation": {
+          "start": { "line": 2, "character": 0 },
+          "end": { "line": 2, "character": 13 }
+        }
       }
-    }));
-
-    client.did_open(json!({
+    }),
+  );
+  // Functions are not included, and neither is code after the stopped location.
+  assert_eq!(
+    res,
+    json!([
+      {
+        "range": {
+          "start": { "line": 0, "character": 13 },
+          "end": { "line": 0, "character": 14 }
+        },
+        "variableName": "a",
+        "caseSensitiveLookup": true
+      },
+      {
+        "range": {
+          "start": { "line": 0, "character": 24 },
+          "end": { "line": 0, "character": 25 }
+        },
+        "variableName": "b",
+        "caseSensitiveLookup": true
+      },
+      {
+        "range": {
+          "start": { "line": 1, "character": 8 },
+          "end": { "line": 1, "character": 11 }
+        },
+        "variableName": "sum",
+        "caseSensitiveLookup": true
+      },
+      {
+        "range": {
+          "start": { "line": 1, "character": 14 },
+          "end": { "line": 1, "character": 15 }
+        },
+        "variableName": "a",
+        "caseSensitiveLookup": true
+      },
+      {
+        "range": {
+          "start": { "line": 1, "character": 18 },
+          "end": { "line": 1, "character": 19 }
+        },
+        "variableName": "b",
+        "caseSensitiveLookup": true
+      },
+      {
+        "range": {
+          "start": { "line": 2, "character": 9 },
+          "end": { "line": 2, "character": 12 }
+        },
+        "variableName": "sum",
+        "caseSensitiveLookup": true
+      }
+    ])
+  );
+  client.shutdown();
+}
+
+#[test]
+fn lsp_document_diagnostic() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let mut client = context.new_lsp_command().build();
+  client.initialize_default();
+  client.did_open(json!({
+    "textDocument": {
+      "uri": "file:///a/file.ts",
+      "languageId": "typescript",
+      "version": 1,
+      "text": "export const a: string = 1;\n"
+    }
+  }));
+  let res = client.write_request(
+    "textDocument/diagnostic",
+    json!({
+      "textDocument": {
+        "uri": "file:///a/file.ts",
+      },
+    }),
+  );
+  let result_id = res["resultId"].as_str().unwrap().to_string();
+  assert_eq!(
+    res,
+    json!({
+      "kind": "full",
+      "resultId": result_id,
+      "items": [
+        {
+          "range": {
+            "start": { "line": 0, "character": 13 },
+            "end": { "line": 0, "character": 14 }
+          },
+          "severity": 1,
+          "code": 2322,
+          "source": "deno-ts",
+          "message": "Type 'number' is not assignable to type 'string'."
+        }
+      ]
+    })
+  );
+
+  // Nothing changed, so the client can keep the previous report.
+  let res = client.write_request(
+    "textDocument/diagnostic",
+    json!({
+      "textDocument": {
+        "uri": "file:///a/file.ts",
+      },
+      "previousResultId": result_id,
+    }),
+  );
+  assert_eq!(res, json!({ "kind": "unchanged", "resultId": result_id }));
+
+  client.write_notification(
+    "textDocument/didChange",
+    json!({
+      "textDocument": {
+        "uri": "file:///a/file.ts",
+        "version": 2
+      },
+      "contentChanges": [
+        {
+          "range": {
+            "start": { "line": 0, "character": 25 },
+            "end": { "line": 0, "character": 26 }
+          },
+          "text": "\"1\""
+        }
+      ]
+    }),
+  );
+  let res = client.write_request(
+    "textDocument/diagnostic",
+    json!({
+      "textDocument": {
+        "uri": "file:///a/file.ts",
+      },
+      "previousResultId": result_id,
+    }),
+  );
+  assert_eq!(res["kind"], "full");
+  assert_ne!(res["resultId"], result_id);
+  assert_eq!(res["items"], json!([]));
+  client.shutdown();
+}
+
+#[test]
+fn lsp_workspace_diagnostic() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  temp_dir.write("./a.ts", "export const a: string = 1;\n");
+  temp_dir.write("./b.ts", "export const b = 1;\n");
+  let temp_dir_url = temp_dir.uri();
+  let mut client = context.new_lsp_command().build();
+  client.initialize_default();
+  client.did_open(json!({
+    "textDocument": {
+      "uri": temp_dir_url.join("b.ts").unwrap(),
+      "languageId": "typescript",
+      "version": 1,
+      "text": "export const b = 1;\n"
+    }
+  }));
+  let res = client
+    .write_request("workspace/diagnostic", json!({ "previousResultIds": [] }));
+  let mut items = res["items"].as_array().unwrap().clone();
+  items.sort_by_key(|item| item["uri"].as_str().unwrap().to_string());
+  let a_result_id = items[0]["resultId"].as_str().unwrap().to_string();
+  let b_result_id = items[1]["resultId"].as_str().unwrap().to_string();
+  // Files that are not open are included as well.
+  assert_eq!(
+    json!(items),
+    json!([
+      {
+        "uri": temp_dir_url.join("a.ts").unwrap(),
+        "version": null,
+        "kind": "full",
+        "resultId": a_result_id,
+        "items": [
+          {
+            "range": {
+              "start": { "line": 0, "character": 13 },
+              "end": { "line": 0, "character": 14 }
+            },
+            "severity": 1,
+            "code": 2322,
+            "source": "deno-ts",
+            "message": "Type 'number' is not assignable to type 'string'."
+          }
+        ]
+      },
+      {
+        "uri": temp_dir_url.join("b.ts").unwrap(),
+        "version": 1,
+        "kind": "full",
+        "resultId": b_result_id,
+        "items": []
+      }
+    ])
+  );
+
+  let res = client.write_request(
+    "workspace/diagnostic",
+    json!({
+      "previousResultIds": [
+        { "uri": temp_dir_url.join("a.ts").unwrap(), "value": a_result_id },
+        { "uri": temp_dir_url.join("b.ts").unwrap(), "value": b_result_id },
+      ]
+    }),
+  );
+  let items = res["items"].as_array().unwrap();
+  assert_eq!(items.len(), 2);
+  assert!(items.iter().all(|item| item["kind"] == "unchanged"));
+  client.shutdown();
+}
+
+#[test]
+fn lsp_workspace_enable_paths() {
+  fn run_test(use_trailing_slash: bool) {
+    let context = TestContextBuilder::new().use_temp_cwd().build();
+    let temp_dir = context.temp_dir();
+    temp_dir.create_dir_all("worker");
+    temp_dir.write("worker/shared.ts", "export const a = 1");
+    temp_dir.write("worker/other.ts", "import { a } from './shared.ts';\na;");
+
+    let root_specifier = temp_dir.uri();
+
+    let mut client = context.new_lsp_command().build();
+    client.initialize_with_config(
+      |builder| {
+        builder
+          .set_enable_paths(vec!["./worker".to_string()])
+          .set_root_uri(root_specifier.clone())
+          .set_workspace_folders(vec![lsp::WorkspaceFolder {
+            uri: if use_trailing_slash {
+              root_specifier.clone()
+            } else {
+              ModuleSpecifier::parse(
+                root_specifier.as_str().strip_suffix('/').unwrap(),
+              )
+              .unwrap()
+            },
+            name: "project".to_string(),
+          }])
+          .set_deno_enable(false);
+      },
+      json!([{
+        "enable": false,
+        "enablePaths": ["./worker"],
+      }]),
+    );
+
+    client.did_open(json!({
+      "textDocument": {
+        "uri": root_specifier.join("./file.ts").unwrap(),
+        "languageId": "typescript",
+        "version": 1,
+        "text": "console.log(Date.now());\n"
+      }
+    }));
+
+    client.did_open(json!({
+      "textDocument": {
+        "uri": root_specifier.join("./other/file.ts").unwrap(),
+        "languageId": "typescript",
+        "version": 1,
+        "text": "console.log(Date.now());\n"
+      }
+    }));
+
+    client.did_open(json!({
+      "textDocument": {
+        "uri": root_specifier.join("./worker/file.ts").unwrap(),
+        "languageId": "typescript",
+        "version": 1,
+        "text": concat!(
+          "console.log(Date.now());\n",
+          "import { a } from './shared.ts';\n",
+          "a;\n",
+        ),
+      }
+    }));
+
+    client.did_open(json!({
       "textDocument": {
         "uri": root_specifier.join("./worker/subdir/file.ts").unwrap(),
         "languageId": "typescript",
@@ -2093,6 +2587,55 @@ fn lsp_hover_typescript_types() {
   client.shutdown();
 }
 
+#[test]
+fn lsp_hover_remote_module_docs() {
+  let context = TestContextBuilder::new()
+    .use_http_server()
+    .use_temp_cwd()
+    .build();
+  let mut client = context.new_lsp_command().build();
+  client.initialize_default();
+  client.did_open(json!({
+    "textDocument": {
+      "uri": "file:///a/file.ts",
+      "languageId": "typescript",
+      "version": 1,
+      "text": "import { greet } from \"http://localhost:4545/lsp/greet.ts\";\n\ngreet(\"Deno\");\n",
+    }
+  }));
+  client.write_request(
+    "deno/cache",
+    json!({
+      "referrer": {
+        "uri": "file:///a/file.ts",
+      },
+      "uris": [],
+    }),
+  );
+  let res = client.write_request(
+    "textDocument/hover",
+    json!({
+      "textDocument": {
+        "uri": "file:///a/file.ts"
+      },
+      "position": { "line": 2, "character": 1 }
+    }),
+  );
+  let contents = res["contents"].as_array().unwrap();
+  assert_eq!(
+    contents.first().unwrap(),
+    &json!({
+      "language": "typescript",
+      "value": "function greet(name: string): string",
+    })
+  );
+  assert_eq!(
+    contents.last().unwrap(),
+    &json!("\n\n---\n\nUtilities for greeting people.\n\n[View documentation](http://localhost:4545/lsp/greet.ts)")
+  );
+  client.shutdown();
+}
+
 #[test]
 fn lsp_hover_jsdoc_symbol_link() {
   let context = TestContextBuilder::new().use_temp_cwd().build();
@@ -2188,13 +2731,159 @@ fn lsp_goto_type_definition() {
 }
 
 #[test]
-fn lsp_call_hierarchy() {
+fn lsp_source_definition() {
   let context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  temp_dir.write(
+    "./mod.d.ts",
+    "export declare function add(a: number, b: number): number;\n",
+  );
+  temp_dir.write(
+    "./mod.js",
+    "export function add(a, b) {\n  return a + b;\n}\n",
+  );
+  let temp_dir_url = temp_dir.uri();
   let mut client = context.new_lsp_command().build();
   client.initialize_default();
-  client.did_open(
-    json!({
-      "textDocument": {
+  client.did_open(json!({
+    "textDocument": {
+      "uri": temp_dir_url.join("main.ts").unwrap(),
+      "languageId": "typescript",
+      "version": 1,
+      "text": "// @deno-types=\"./mod.d.ts\"\nimport { add } from \"./mod.js\";\nconsole.log(add(1, 2));\n"
+    }
+  }));
+  let res = client.write_request(
+    "deno/sourceDefinition",
+    json!({
+      "textDocument": {
+        "uri": temp_dir_url.join("main.ts").unwrap(),
+      },
+      "position": { "line": 2, "character": 12 }
+    }),
+  );
+  assert_eq!(
+    res,
+    json!([{
+      "targetUri": temp_dir_url.join("mod.js").unwrap(),
+      "targetRange": {
+        "start": { "line": 0, "character": 0 },
+        "end": { "line": 2, "character": 1 }
+      },
+      "targetSelectionRange": {
+        "start": { "line": 0, "character": 16 },
+        "end": { "line": 0, "character": 19 }
+      }
+    }])
+  );
+  client.shutdown();
+}
+
+#[test]
+fn lsp_source_definition_re_export() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  temp_dir.write(
+    "./mod.d.ts",
+    "export declare function add(a: number, b: number): number;\n",
+  );
+  temp_dir.write("./mod.js", "export { sum as add } from \"./lib/sum.js\";\n");
+  temp_dir.create_dir_all("./lib");
+  temp_dir.write(
+    "./lib/sum.js",
+    "export function sum(a, b) {\n  return a + b;\n}\n",
+  );
+  let temp_dir_url = temp_dir.uri();
+  let mut client = context.new_lsp_command().build();
+  client.initialize_default();
+  client.did_open(json!({
+    "textDocument": {
+      "uri": temp_dir_url.join("main.ts").unwrap(),
+      "languageId": "typescript",
+      "version": 1,
+      "text": "// @deno-types=\"./mod.d.ts\"\nimport { add } from \"./mod.js\";\nconsole.log(add(1, 2));\n"
+    }
+  }));
+  let res = client.write_request(
+    "deno/sourceDefinition",
+    json!({
+      "textDocument": {
+        "uri": temp_dir_url.join("main.ts").unwrap(),
+      },
+      "position": { "line": 2, "character": 12 }
+    }),
+  );
+  assert_eq!(
+    res,
+    json!([{
+      "targetUri": temp_dir_url.join("lib/sum.js").unwrap(),
+      "targetRange": {
+        "start": { "line": 0, "character": 0 },
+        "end": { "line": 2, "character": 1 }
+      },
+      "targetSelectionRange": {
+        "start": { "line": 0, "character": 16 },
+        "end": { "line": 0, "character": 19 }
+      }
+    }])
+  );
+  client.shutdown();
+}
+
+#[test]
+fn lsp_source_definition_declaration_only() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  temp_dir.write(
+    "./types.d.ts",
+    "export declare function add(a: number, b: number): number;\n",
+  );
+  let temp_dir_url = temp_dir.uri();
+  let mut client = context.new_lsp_command().build();
+  client.initialize_default();
+  client.did_open(json!({
+    "textDocument": {
+      "uri": temp_dir_url.join("main.ts").unwrap(),
+      "languageId": "typescript",
+      "version": 1,
+      "text": "import { add } from \"./types.d.ts\";\nconsole.log(add(1, 2));\n"
+    }
+  }));
+  // without an implementation module, the declaration is the definition
+  let res = client.write_request(
+    "deno/sourceDefinition",
+    json!({
+      "textDocument": {
+        "uri": temp_dir_url.join("main.ts").unwrap(),
+      },
+      "position": { "line": 1, "character": 12 }
+    }),
+  );
+  assert_eq!(
+    res,
+    json!([{
+      "targetUri": temp_dir_url.join("types.d.ts").unwrap(),
+      "targetRange": {
+        "start": { "line": 0, "character": 0 },
+        "end": { "line": 0, "character": 58 }
+      },
+      "targetSelectionRange": {
+        "start": { "line": 0, "character": 24 },
+        "end": { "line": 0, "character": 27 }
+      }
+    }])
+  );
+  client.shutdown();
+}
+
+#[test]
+fn lsp_call_hierarchy() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let mut client = context.new_lsp_command().build();
+  client.initialize_default();
+  client.did_open(
+    json!({
+      "textDocument": {
         "uri": "file:///a/file.ts",
         "languageId": "typescript",
         "version": 1,
@@ -2719,6 +3408,100 @@ fn lsp_rename() {
   client.shutdown();
 }
 
+#[test]
+fn lsp_rename_in_strings_and_comments() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let mut client = context.new_lsp_command().build();
+  client.initialize(|builder| {
+    builder.set_preferences(json!({
+      "renameInStrings": true,
+      "renameInComments": true,
+    }));
+  });
+  client.did_open(json!({
+    "textDocument": {
+      "uri": "file:///a/file.ts",
+      "languageId": "typescript",
+      "version": 1,
+      "text": "let variable = 'a'; // variable\nconsole.log(variable);\n\"variable\";\n"
+    }
+  }));
+  let res = client.write_request(
+    "textDocument/rename",
+    json!({
+      "textDocument": {
+        "uri": "file:///a/file.ts"
+      },
+      "position": { "line": 0, "character": 4 },
+      "newName": "variable_modified"
+    }),
+  );
+  let edits = res["documentChanges"][0]["edits"].as_array().unwrap();
+  let mut ranges = edits
+    .iter()
+    .map(|edit| {
+      let start = &edit["range"]["start"];
+      (
+        start["line"].as_u64().unwrap(),
+        start["character"].as_u64().unwrap(),
+      )
+    })
+    .collect::<Vec<_>>();
+  ranges.sort();
+  assert_eq!(ranges, vec![(0, 4), (0, 23), (1, 12), (2, 1)]);
+  client.shutdown();
+}
+
+#[test]
+fn lsp_rename_shorthand_property() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let mut client = context.new_lsp_command().build();
+  client.initialize_default();
+  client.did_open(json!({
+    "textDocument": {
+      "uri": "file:///a/file.ts",
+      "languageId": "typescript",
+      "version": 1,
+      "text": "const foo = 1;\nconst obj = { foo };\n"
+    }
+  }));
+  let res = client.write_request(
+    "textDocument/rename",
+    json!({
+      "textDocument": {
+        "uri": "file:///a/file.ts"
+      },
+      "position": { "line": 0, "character": 6 },
+      "newName": "bar"
+    }),
+  );
+  assert_eq!(
+    res,
+    json!({
+      "documentChanges": [{
+        "textDocument": {
+          "uri": "file:///a/file.ts",
+          "version": 1
+        },
+        "edits": [{
+          "range": {
+            "start": { "line": 0, "character": 6 },
+            "end": { "line": 0, "character": 9 }
+          },
+          "newText": "bar"
+        }, {
+          "range": {
+            "start": { "line": 1, "character": 14 },
+            "end": { "line": 1, "character": 17 }
+          },
+          "newText": "foo: bar"
+        }]
+      }]
+    })
+  );
+  client.shutdown();
+}
+
 #[test]
 fn lsp_selection_range() {
   let context = TestContextBuilder::new().use_temp_cwd().build();
@@ -3837,7 +4620,8 @@ fn lsp_signature_help() {
                 "value": "This is a second number."
               }
             }
-          ]
+          ],
+          "activeParameter": 0
         }
       ],
       "activeSignature": 0,
@@ -3895,7 +4679,8 @@ fn lsp_signature_help() {
                 "value": "This is a second number."
               }
             }
-          ]
+          ],
+          "activeParameter": 1
         }
       ],
       "activeSignature": 0,
@@ -4094,53 +4879,283 @@ fn lsp_code_actions() {
 }
 
 #[test]
-fn lsp_code_actions_deno_cache() {
+fn lsp_code_actions_convert_to_async_function() {
   let context = TestContextBuilder::new().use_temp_cwd().build();
   let mut client = context.new_lsp_command().build();
   client.initialize_default();
   let diagnostics = client.did_open(json!({
+    "textDocument": {
+      "uri": "file:///a/file.ts",
+      "languageId": "typescript",
+      "version": 1,
+      "text": "export function a() {\n  return Promise.resolve(1).then((x) => x + 1);\n}\n\nexport function b() {\n  return Promise.resolve(2).then((x) => x + 2);\n}\n"
+    }
+  }));
+  assert_eq!(
+    json!(diagnostics.all()),
+    json!([{
+      "range": {
+        "start": { "line": 0, "character": 16 },
+        "end": { "line": 0, "character": 17 }
+      },
+      "severity": 4,
+      "code": 80006,
+      "source": "deno-ts",
+      "message": "This may be converted to an async function."
+    }, {
+      "range": {
+        "start": { "line": 4, "character": 16 },
+        "end": { "line": 4, "character": 17 }
+      },
+      "severity": 4,
+      "code": 80006,
+      "source": "deno-ts",
+      "message": "This may be converted to an async function."
+    }])
+  );
+  let res = client.write_request(
+    "textDocument/codeAction",
+    json!({
       "textDocument": {
-        "uri": "file:///a/file.ts",
-        "languageId": "typescript",
-        "version": 1,
-        "text": "import * as a from \"https://deno.land/x/a/mod.ts\";\n\nconsole.log(a);\n"
+        "uri": "file:///a/file.ts"
+      },
+      "range": {
+        "start": { "line": 0, "character": 16 },
+        "end": { "line": 0, "character": 17 }
+      },
+      "context": {
+        "diagnostics": [{
+          "range": {
+            "start": { "line": 0, "character": 16 },
+            "end": { "line": 0, "character": 17 }
+          },
+          "severity": 4,
+          "code": 80006,
+          "source": "deno-ts",
+          "message": "This may be converted to an async function."
+        }],
+        "only": ["quickfix"]
       }
-    }));
+    }),
+  );
   assert_eq!(
-    diagnostics.messages_with_source("deno"),
-    serde_json::from_value(json!({
-      "uri": "file:///a/file.ts",
+    res,
+    json!([{
+      "title": "Convert to async function",
+      "kind": "quickfix",
       "diagnostics": [{
         "range": {
-          "start": { "line": 0, "character": 19 },
-          "end": { "line": 0, "character": 49 }
+          "start": { "line": 0, "character": 16 },
+          "end": { "line": 0, "character": 17 }
         },
-        "severity": 1,
-        "code": "no-cache",
-        "source": "deno",
-        "message": "Uncached or missing remote URL: https://deno.land/x/a/mod.ts",
-        "data": { "specifier": "https://deno.land/x/a/mod.ts" }
+        "severity": 4,
+        "code": 80006,
+        "source": "deno-ts",
+        "message": "This may be converted to an async function."
       }],
-      "version": 1
-    })).unwrap()
-  );
-
-  let res =
-    client
-    .write_request(      "textDocument/codeAction",
-      json!({
-        "textDocument": {
-          "uri": "file:///a/file.ts"
-        },
-        "range": {
-          "start": { "line": 0, "character": 19 },
-          "end": { "line": 0, "character": 49 }
-        },
-        "context": {
-          "diagnostics": [{
+      "edit": {
+        "documentChanges": [{
+          "textDocument": {
+            "uri": "file:///a/file.ts",
+            "version": 1
+          },
+          "edits": [{
             "range": {
-              "start": { "line": 0, "character": 19 },
-              "end": { "line": 0, "character": 49 }
+              "start": { "line": 0, "character": 7 },
+              "end": { "line": 0, "character": 7 }
+            },
+            "newText": "async "
+          }, {
+            "range": {
+              "start": { "line": 1, "character": 2 },
+              "end": { "line": 1, "character": 47 }
+            },
+            "newText": "const x = await Promise.resolve(1);\n  return x + 1;"
+          }]
+        }]
+      }
+    }, {
+      "title": "Convert all to async functions",
+      "kind": "quickfix",
+      "diagnostics": [{
+        "range": {
+          "start": { "line": 0, "character": 16 },
+          "end": { "line": 0, "character": 17 }
+        },
+        "severity": 4,
+        "code": 80006,
+        "source": "deno-ts",
+        "message": "This may be converted to an async function."
+      }],
+      "data": {
+        "specifier": "file:///a/file.ts",
+        "fixId": "convertToAsyncFunction"
+      }
+    }])
+  );
+  let res = client.write_request(
+    "codeAction/resolve",
+    json!({
+      "title": "Convert all to async functions",
+      "kind": "quickfix",
+      "diagnostics": [{
+        "range": {
+          "start": { "line": 0, "character": 16 },
+          "end": { "line": 0, "character": 17 }
+        },
+        "severity": 4,
+        "code": 80006,
+        "source": "deno-ts",
+        "message": "This may be converted to an async function."
+      }],
+      "data": {
+        "specifier": "file:///a/file.ts",
+        "fixId": "convertToAsyncFunction"
+      }
+    }),
+  );
+  assert_eq!(
+    res,
+    json!({
+      "title": "Convert all to async functions",
+      "kind": "quickfix",
+      "diagnostics": [{
+        "range": {
+          "start": { "line": 0, "character": 16 },
+          "end": { "line": 0, "character": 17 }
+        },
+        "severity": 4,
+        "code": 80006,
+        "source": "deno-ts",
+        "message": "This may be converted to an async function."
+      }],
+      "edit": {
+        "documentChanges": [{
+          "textDocument": {
+            "uri": "file:///a/file.ts",
+            "version": 1
+          },
+          "edits": [{
+            "range": {
+              "start": { "line": 0, "character": 7 },
+              "end": { "line": 0, "character": 7 }
+            },
+            "newText": "async "
+          }, {
+            "range": {
+              "start": { "line": 1, "character": 2 },
+              "end": { "line": 1, "character": 47 }
+            },
+            "newText": "const x = await Promise.resolve(1);\n  return x + 1;"
+          }, {
+            "range": {
+              "start": { "line": 4, "character": 7 },
+              "end": { "line": 4, "character": 7 }
+            },
+            "newText": "async "
+          }, {
+            "range": {
+              "start": { "line": 5, "character": 2 },
+              "end": { "line": 5, "character": 47 }
+            },
+            "newText": "const x = await Promise.resolve(2);\n  return x + 2;"
+          }]
+        }]
+      },
+      "data": {
+        "specifier": "file:///a/file.ts",
+        "fixId": "convertToAsyncFunction"
+      }
+    })
+  );
+  client.shutdown();
+}
+
+#[test]
+fn lsp_suggestion_diagnostics_disabled() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let mut client = context.new_lsp_command().build();
+  client.initialize_with_config(
+    |_| {},
+    json!([{
+      "enable": true,
+      "suggestionDiagnostics": false
+    }]),
+  );
+  let diagnostics = client.did_open(json!({
+    "textDocument": {
+      "uri": "file:///a/file.ts",
+      "languageId": "typescript",
+      "version": 1,
+      "text": "export function a() {\n  return Promise.resolve(1).then((x) => x + 1);\n}\n\nfunction b() {}\n"
+    }
+  }));
+  // Only the diagnostic marking `b` as unused is published.
+  assert_eq!(
+    json!(diagnostics.all()),
+    json!([{
+      "range": {
+        "start": { "line": 4, "character": 9 },
+        "end": { "line": 4, "character": 10 }
+      },
+      "severity": 4,
+      "code": 6133,
+      "source": "deno-ts",
+      "message": "'b' is declared but its value is never read.",
+      "tags": [1]
+    }])
+  );
+  client.shutdown();
+}
+
+#[test]
+fn lsp_code_actions_deno_cache() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let mut client = context.new_lsp_command().build();
+  client.initialize_default();
+  let diagnostics = client.did_open(json!({
+      "textDocument": {
+        "uri": "file:///a/file.ts",
+        "languageId": "typescript",
+        "version": 1,
+        "text": "import * as a from \"https://deno.land/x/a/mod.ts\";\n\nconsole.log(a);\n"
+      }
+    }));
+  assert_eq!(
+    diagnostics.messages_with_source("deno"),
+    serde_json::from_value(json!({
+      "uri": "file:///a/file.ts",
+      "diagnostics": [{
+        "range": {
+          "start": { "line": 0, "character": 19 },
+          "end": { "line": 0, "character": 49 }
+        },
+        "severity": 1,
+        "code": "no-cache",
+        "source": "deno",
+        "message": "Uncached or missing remote URL: https://deno.land/x/a/mod.ts",
+        "data": { "specifier": "https://deno.land/x/a/mod.ts" }
+      }],
+      "version": 1
+    })).unwrap()
+  );
+
+  let res =
+    client
+    .write_request(      "textDocument/codeAction",
+      json!({
+        "textDocument": {
+          "uri": "file:///a/file.ts"
+        },
+        "range": {
+          "start": { "line": 0, "character": 19 },
+          "end": { "line": 0, "character": 49 }
+        },
+        "context": {
+          "diagnostics": [{
+            "range": {
+              "start": { "line": 0, "character": 19 },
+              "end": { "line": 0, "character": 49 }
             },
             "severity": 1,
             "code": "no-cache",
@@ -4631,56 +5646,422 @@ fn lsp_code_actions_refactor() {
       "title": "Convert named imports to default import",
       "kind": "refactor.rewrite.import.default",
       "isPreferred": false,
-      "disabled": {
-        "reason": "Selection is not an import declaration."
-      },
+      "disabled": {
+        "reason": "Selection is not an import declaration."
+      },
+      "data": {
+        "specifier": "file:///a/file.ts",
+        "range": {
+          "start": { "line": 0, "character": 0 },
+          "end": { "line": 1, "character": 0 }
+        },
+        "refactorName": "Convert import",
+        "actionName": "Convert named imports to default import"
+      }
+    }, {
+      "title": "Convert named imports to namespace import",
+      "kind": "refactor.rewrite.import.namespace",
+      "isPreferred": false,
+      "disabled": {
+        "reason": "Selection is not an import declaration."
+      },
+      "data": {
+        "specifier": "file:///a/file.ts",
+        "range": {
+          "start": { "line": 0, "character": 0 },
+          "end": { "line": 1, "character": 0 }
+        },
+        "refactorName": "Convert import",
+        "actionName": "Convert named imports to namespace import"
+      }
+    }])
+  );
+  let res = client.write_request(
+    "codeAction/resolve",
+    json!({
+      "title": "Extract to interface",
+      "kind": "refactor.extract.interface",
+      "isPreferred": true,
+      "data": {
+        "specifier": "file:///a/file.ts",
+        "range": {
+          "start": { "line": 0, "character": 7 },
+          "end": { "line": 0, "character": 33 }
+        },
+        "refactorName": "Extract type",
+        "actionName": "Extract to interface"
+      }
+    }),
+  );
+  assert_eq!(
+    res,
+    json!({
+      "title": "Extract to interface",
+      "kind": "refactor.extract.interface",
+      "edit": {
+        "documentChanges": [{
+          "textDocument": {
+            "uri": "file:///a/file.ts",
+            "version": 1
+          },
+          "edits": [{
+            "range": {
+              "start": { "line": 0, "character": 0 },
+              "end": { "line": 0, "character": 0 }
+            },
+            "newText": "interface NewType {\n  a?: number;\n  b?: string;\n}\n\n"
+          }, {
+            "range": {
+              "start": { "line": 0, "character": 7 },
+              "end": { "line": 0, "character": 33 }
+            },
+            "newText": "NewType"
+          }]
+        }]
+      },
+      "isPreferred": true,
+      "data": {
+        "specifier": "file:///a/file.ts",
+        "range": {
+          "start": { "line": 0, "character": 7 },
+          "end": { "line": 0, "character": 33 }
+        },
+        "refactorName": "Extract type",
+        "actionName": "Extract to interface"
+      }
+    })
+  );
+  client.shutdown();
+}
+
+#[test]
+fn lsp_code_actions_refactor_no_disabled_support() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let mut client = context.new_lsp_command().build();
+  client.initialize(|builder| {
+    builder.with_capabilities(|c| {
+      let doc = c.text_document.as_mut().unwrap();
+      let code_action = doc.code_action.as_mut().unwrap();
+      code_action.disabled_support = Some(false);
+    });
+  });
+  client.did_open(
+    json!({
+      "textDocument": {
+        "uri": "file:///a/file.ts",
+        "languageId": "typescript",
+        "version": 1,
+        "text": "interface A {\n  a: string;\n}\n\ninterface B {\n  b: string;\n}\n\nclass AB implements A, B {\n  a = \"a\";\n  b = \"b\";\n}\n\nnew AB().a;\n"
+      }
+    }),
+  );
+  let res = client.write_request(
+    "textDocument/codeAction",
+    json!({
+      "textDocument": {
+        "uri": "file:///a/file.ts"
+      },
+      "range": {
+        "start": { "line": 0, "character": 0 },
+        "end": { "line": 14, "character": 0 }
+      },
+      "context": {
+        "diagnostics": [],
+        "only": ["refactor"]
+      }
+    }),
+  );
+  assert_eq!(
+    res,
+    json!([{
+      "title": "Move to a new file",
+      "kind": "refactor.move.newFile",
+      "isPreferred": false,
+      "data": {
+        "specifier": "file:///a/file.ts",
+        "range": {
+          "start": { "line": 0, "character": 0 },
+          "end": { "line": 14, "character": 0 }
+        },
+        "refactorName": "Move to a new file",
+        "actionName": "Move to a new file"
+      }
+    }, {
+      "title": "Extract to function in module scope",
+      "kind": "refactor.extract.function",
+      "isPreferred": false,
+      "data": {
+        "specifier": "file:///a/file.ts",
+        "range": {
+          "start": { "line": 0, "character": 0 },
+          "end": { "line": 14, "character": 0 }
+        },
+        "refactorName": "Extract Symbol",
+        "actionName": "function_scope_0"
+      }
+    }])
+  );
+  client.shutdown();
+}
+
+#[test]
+fn lsp_code_actions_refactor_move_to_new_file() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let mut client = context.new_lsp_command().build();
+  client.initialize_default();
+  client.did_open(json!({
+    "textDocument": {
+      "uri": "file:///a/file.ts",
+      "languageId": "typescript",
+      "version": 1,
+      "text": "export function a() {\n  return 1;\n}\n\nexport function b() {\n  return a();\n}\n"
+    }
+  }));
+  let res = client.write_request(
+    "textDocument/codeAction",
+    json!({
+      "textDocument": {
+        "uri": "file:///a/file.ts"
+      },
+      "range": {
+        "start": { "line": 0, "character": 0 },
+        "end": { "line": 2, "character": 1 }
+      },
+      "context": {
+        "diagnostics": [],
+        "only": ["refactor.move.newFile"]
+      }
+    }),
+  );
+  assert_eq!(
+    res,
+    json!([{
+      "title": "Move to a new file",
+      "kind": "refactor.move.newFile",
+      "isPreferred": false,
+      "data": {
+        "specifier": "file:///a/file.ts",
+        "range": {
+          "start": { "line": 0, "character": 0 },
+          "end": { "line": 2, "character": 1 }
+        },
+        "refactorName": "Move to a new file",
+        "actionName": "Move to a new file"
+      }
+    }])
+  );
+  let res = client.write_request(
+    "codeAction/resolve",
+    json!({
+      "title": "Move to a new file",
+      "kind": "refactor.move.newFile",
+      "isPreferred": false,
+      "data": {
+        "specifier": "file:///a/file.ts",
+        "range": {
+          "start": { "line": 0, "character": 0 },
+          "end": { "line": 2, "character": 1 }
+        },
+        "refactorName": "Move to a new file",
+        "actionName": "Move to a new file"
+      }
+    }),
+  );
+  assert_eq!(
+    res,
+    json!({
+      "title": "Move to a new file",
+      "kind": "refactor.move.newFile",
+      "edit": {
+        "documentChanges": [{
+          "textDocument": {
+            "uri": "file:///a/file.ts",
+            "version": 1
+          },
+          "edits": [{
+            "range": {
+              "start": { "line": 0, "character": 0 },
+              "end": { "line": 0, "character": 0 }
+            },
+            "newText": "import { a } from \"./a.ts\";\n\n"
+          }, {
+            "range": {
+              "start": { "line": 0, "character": 0 },
+              "end": { "line": 4, "character": 0 }
+            },
+            "newText": ""
+          }]
+        }, {
+          "kind": "create",
+          "uri": "file:///a/a.ts",
+          "options": {
+            "ignoreIfExists": true
+          }
+        }, {
+          "textDocument": {
+            "uri": "file:///a/a.ts",
+            "version": null
+          },
+          "edits": [{
+            "range": {
+              "start": { "line": 0, "character": 0 },
+              "end": { "line": 0, "character": 0 }
+            },
+            "newText": "export function a() {\n  return 1;\n}\n"
+          }]
+        }]
+      },
+      "isPreferred": false,
+      "data": {
+        "specifier": "file:///a/file.ts",
+        "range": {
+          "start": { "line": 0, "character": 0 },
+          "end": { "line": 2, "character": 1 }
+        },
+        "refactorName": "Move to a new file",
+        "actionName": "Move to a new file"
+      }
+    })
+  );
+  client.shutdown();
+}
+
+#[test]
+fn lsp_code_actions_refactor_infer_return_type() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let mut client = context.new_lsp_command().build();
+  client.initialize_default();
+  client.did_open(json!({
+    "textDocument": {
+      "uri": "file:///a/file.ts",
+      "languageId": "typescript",
+      "version": 1,
+      "text": "export function a() {\n  return 1;\n}\n"
+    }
+  }));
+  let res = client.write_request(
+    "textDocument/codeAction",
+    json!({
+      "textDocument": {
+        "uri": "file:///a/file.ts"
+      },
+      "range": {
+        "start": { "line": 0, "character": 16 },
+        "end": { "line": 0, "character": 17 }
+      },
+      "context": {
+        "diagnostics": [],
+        "only": ["refactor.rewrite.function.returnType"]
+      }
+    }),
+  );
+  assert_eq!(
+    res,
+    json!([{
+      "title": "Infer function return type",
+      "kind": "refactor.rewrite.function.returnType",
+      "isPreferred": false,
       "data": {
         "specifier": "file:///a/file.ts",
         "range": {
-          "start": { "line": 0, "character": 0 },
-          "end": { "line": 1, "character": 0 }
+          "start": { "line": 0, "character": 16 },
+          "end": { "line": 0, "character": 17 }
         },
-        "refactorName": "Convert import",
-        "actionName": "Convert named imports to default import"
+        "refactorName": "Infer function return type",
+        "actionName": "Infer function return type"
       }
-    }, {
-      "title": "Convert named imports to namespace import",
-      "kind": "refactor.rewrite.import.namespace",
+    }])
+  );
+  let res = client.write_request(
+    "codeAction/resolve",
+    json!({
+      "title": "Infer function return type",
+      "kind": "refactor.rewrite.function.returnType",
       "isPreferred": false,
-      "disabled": {
-        "reason": "Selection is not an import declaration."
-      },
       "data": {
         "specifier": "file:///a/file.ts",
         "range": {
-          "start": { "line": 0, "character": 0 },
-          "end": { "line": 1, "character": 0 }
+          "start": { "line": 0, "character": 16 },
+          "end": { "line": 0, "character": 17 }
         },
-        "refactorName": "Convert import",
-        "actionName": "Convert named imports to namespace import"
+        "refactorName": "Infer function return type",
+        "actionName": "Infer function return type"
       }
-    }])
+    }),
   );
-  let res = client.write_request(
-    "codeAction/resolve",
+  assert_eq!(
+    res,
     json!({
-      "title": "Extract to interface",
-      "kind": "refactor.extract.interface",
-      "isPreferred": true,
+      "title": "Infer function return type",
+      "kind": "refactor.rewrite.function.returnType",
+      "edit": {
+        "documentChanges": [{
+          "textDocument": {
+            "uri": "file:///a/file.ts",
+            "version": 1
+          },
+          "edits": [{
+            "range": {
+              "start": { "line": 0, "character": 19 },
+              "end": { "line": 0, "character": 19 }
+            },
+            "newText": ": number"
+          }]
+        }]
+      },
+      "isPreferred": false,
       "data": {
         "specifier": "file:///a/file.ts",
         "range": {
-          "start": { "line": 0, "character": 7 },
-          "end": { "line": 0, "character": 33 }
+          "start": { "line": 0, "character": 16 },
+          "end": { "line": 0, "character": 17 }
         },
-        "refactorName": "Extract type",
-        "actionName": "Extract to interface"
+        "refactorName": "Infer function return type",
+        "actionName": "Infer function return type"
+      }
+    })
+  );
+  client.shutdown();
+}
+
+#[test]
+fn lsp_code_actions_refactor_no_resolve_support() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let mut client = context.new_lsp_command().build();
+  client.initialize(|builder| {
+    builder.with_capabilities(|c| {
+      let doc = c.text_document.as_mut().unwrap();
+      let code_action = doc.code_action.as_mut().unwrap();
+      code_action.resolve_support = None;
+    });
+  });
+  client.did_open(json!({
+    "textDocument": {
+      "uri": "file:///a/file.ts",
+      "languageId": "typescript",
+      "version": 1,
+      "text": "var x: { a?: number; b?: string } = {};\n"
+    }
+  }));
+  let res = client.write_request(
+    "textDocument/codeAction",
+    json!({
+      "textDocument": {
+        "uri": "file:///a/file.ts"
+      },
+      "range": {
+        "start": { "line": 0, "character": 7 },
+        "end": { "line": 0, "character": 33 }
+      },
+      "context": {
+        "diagnostics": [],
+        "only": ["refactor.extract.interface"]
       }
     }),
   );
   assert_eq!(
     res,
-    json!({
+    json!([{
       "title": "Extract to interface",
       "kind": "refactor.extract.interface",
       "edit": {
@@ -4714,32 +6095,69 @@ fn lsp_code_actions_refactor() {
         "refactorName": "Extract type",
         "actionName": "Extract to interface"
       }
-    })
+    }])
   );
   client.shutdown();
 }
 
 #[test]
-fn lsp_code_actions_refactor_no_disabled_support() {
+fn lsp_code_actions_organize_imports() {
   let context = TestContextBuilder::new().use_temp_cwd().build();
   let mut client = context.new_lsp_command().build();
-  client.initialize(|builder| {
-    builder.with_capabilities(|c| {
-      let doc = c.text_document.as_mut().unwrap();
-      let code_action = doc.code_action.as_mut().unwrap();
-      code_action.disabled_support = Some(false);
-    });
-  });
-  client.did_open(
+  client.initialize_default();
+  client.did_open(json!({
+    "textDocument": {
+      "uri": "file:///a/file.ts",
+      "languageId": "typescript",
+      "version": 1,
+      "text": "import { b, a } from \"./b.ts\";\nimport { c } from \"./c.ts\";\nconsole.log(a, b);\n"
+    }
+  }));
+  let res = client.write_request(
+    "textDocument/codeAction",
     json!({
       "textDocument": {
-        "uri": "file:///a/file.ts",
-        "languageId": "typescript",
-        "version": 1,
-        "text": "interface A {\n  a: string;\n}\n\ninterface B {\n  b: string;\n}\n\nclass AB implements A, B {\n  a = \"a\";\n  b = \"b\";\n}\n\nnew AB().a;\n"
+        "uri": "file:///a/file.ts"
+      },
+      "range": {
+        "start": { "line": 0, "character": 0 },
+        "end": { "line": 0, "character": 0 }
+      },
+      "context": {
+        "diagnostics": [],
+        "only": ["source.organizeImports"]
       }
     }),
   );
+  assert_eq!(
+    res,
+    json!([{
+      "title": "Organize imports",
+      "kind": "source.organizeImports",
+      "edit": {
+        "documentChanges": [{
+          "textDocument": {
+            "uri": "file:///a/file.ts",
+            "version": 1
+          },
+          "edits": [{
+            "range": {
+              "start": { "line": 0, "character": 0 },
+              "end": { "line": 1, "character": 0 }
+            },
+            "newText": "import { a, b } from \"./b.ts\";\n"
+          }, {
+            "range": {
+              "start": { "line": 1, "character": 0 },
+              "end": { "line": 2, "character": 0 }
+            },
+            "newText": ""
+          }]
+        }]
+      }
+    }])
+  );
+
   let res = client.write_request(
     "textDocument/codeAction",
     json!({
@@ -4748,44 +6166,95 @@ fn lsp_code_actions_refactor_no_disabled_support() {
       },
       "range": {
         "start": { "line": 0, "character": 0 },
-        "end": { "line": 14, "character": 0 }
+        "end": { "line": 0, "character": 0 }
       },
       "context": {
         "diagnostics": [],
-        "only": ["refactor"]
+        "only": ["source.removeUnusedImports"]
       }
     }),
   );
   assert_eq!(
     res,
     json!([{
-      "title": "Move to a new file",
-      "kind": "refactor.move.newFile",
-      "isPreferred": false,
-      "data": {
-        "specifier": "file:///a/file.ts",
-        "range": {
-          "start": { "line": 0, "character": 0 },
-          "end": { "line": 14, "character": 0 }
-        },
-        "refactorName": "Move to a new file",
-        "actionName": "Move to a new file"
-      }
-    }, {
-      "title": "Extract to function in module scope",
-      "kind": "refactor.extract.function",
-      "isPreferred": false,
-      "data": {
-        "specifier": "file:///a/file.ts",
-        "range": {
-          "start": { "line": 0, "character": 0 },
-          "end": { "line": 14, "character": 0 }
-        },
-        "refactorName": "Extract Symbol",
-        "actionName": "function_scope_0"
+      "title": "Remove unused imports",
+      "kind": "source.removeUnusedImports",
+      "edit": {
+        "documentChanges": [{
+          "textDocument": {
+            "uri": "file:///a/file.ts",
+            "version": 1
+          },
+          "edits": [{
+            "range": {
+              "start": { "line": 0, "character": 0 },
+              "end": { "line": 1, "character": 0 }
+            },
+            "newText": "import { b, a } from \"./b.ts\";\n"
+          }, {
+            "range": {
+              "start": { "line": 1, "character": 0 },
+              "end": { "line": 2, "character": 0 }
+            },
+            "newText": ""
+          }]
+        }]
       }
     }])
   );
+
+  // All of the import source actions are returned for the parent kind.
+  let res = client.write_request(
+    "textDocument/codeAction",
+    json!({
+      "textDocument": {
+        "uri": "file:///a/file.ts"
+      },
+      "range": {
+        "start": { "line": 0, "character": 0 },
+        "end": { "line": 0, "character": 0 }
+      },
+      "context": {
+        "diagnostics": [],
+        "only": ["source"]
+      }
+    }),
+  );
+  let kinds = res
+    .as_array()
+    .unwrap()
+    .iter()
+    .map(|action| action["kind"].as_str().unwrap())
+    .collect::<Vec<_>>();
+  assert_eq!(
+    kinds,
+    vec![
+      "source.organizeImports",
+      "source.sortImports",
+      "source.removeUnusedImports"
+    ]
+  );
+
+  // Source actions are not returned unless they are asked for.
+  let res = client.write_request(
+    "textDocument/codeAction",
+    json!({
+      "textDocument": {
+        "uri": "file:///a/file.ts"
+      },
+      "range": {
+        "start": { "line": 0, "character": 0 },
+        "end": { "line": 0, "character": 0 }
+      },
+      "context": {
+        "diagnostics": []
+      }
+    }),
+  );
+  let actions = res.as_array().cloned().unwrap_or_default();
+  assert!(actions
+    .iter()
+    .all(|action| action["kind"] != "source.organizeImports"));
   client.shutdown();
 }
 
@@ -5117,6 +6586,9 @@ fn lsp_completions_auto_import() {
 
   let req = json!({
     "label": "foo",
+    "labelDetails": {
+      "description": "./b.ts"
+    },
     "kind": 6,
     "sortText": "￿16",
     "commitCharacters": [
@@ -5148,6 +6620,9 @@ fn lsp_completions_auto_import() {
     res,
     json!({
       "label": "foo",
+      "labelDetails": {
+        "description": "./b.ts"
+      },
       "kind": 6,
       "detail": "const foo: \"foo\"",
       "documentation": {
@@ -6895,22 +8370,70 @@ fn lsp_redirect_quick_fix() {
         "changes": {
           "file:///a/file.ts": [
             {
-              "range": {
-                "start": { "line": 0, "character": 19 },
-                "end": { "line": 0, "character": 60 }
+              "range": {
+                "start": { "line": 0, "character": 19 },
+                "end": { "line": 0, "character": 60 }
+              },
+              "newText": "\"http://127.0.0.1:4545/lsp/x_deno_warning_redirect.js\""
+            }
+          ]
+        }
+      }
+    }])
+  );
+  client.shutdown();
+}
+
+#[test]
+fn lsp_diagnostics_deprecated() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let mut client = context.new_lsp_command().build();
+  client.initialize_default();
+  let diagnostics = client.did_open(json!({
+    "textDocument": {
+      "uri": "file:///a/file.ts",
+      "languageId": "typescript",
+      "version": 1,
+      "text": "/** @deprecated */\nexport const a = \"a\";\n\na;\n",
+    },
+  }));
+  assert_eq!(
+    json!(diagnostics.all_messages()),
+    json!([{
+      "uri": "file:///a/file.ts",
+      "diagnostics": [
+        {
+          "range": {
+            "start": { "line": 3, "character": 0 },
+            "end": { "line": 3, "character": 1 }
+          },
+          "severity": 4,
+          "code": 6385,
+          "source": "deno-ts",
+          "message": "'a' is deprecated.",
+          "relatedInformation": [
+            {
+              "location": {
+                "uri": "file:///a/file.ts",
+                "range": {
+                  "start": { "line": 0, "character": 4 },
+                  "end": { "line": 0, "character": 16 }
+                }
               },
-              "newText": "\"http://127.0.0.1:4545/lsp/x_deno_warning_redirect.js\""
+              "message": "The declaration was marked as deprecated here."
             }
-          ]
+          ],
+          "tags": [2]
         }
-      }
+      ],
+      "version": 1
     }])
   );
   client.shutdown();
 }
 
 #[test]
-fn lsp_diagnostics_deprecated() {
+fn lsp_diagnostics_related_information() {
   let context = TestContextBuilder::new().use_temp_cwd().build();
   let mut client = context.new_lsp_command().build();
   client.initialize_default();
@@ -6919,7 +8442,7 @@ fn lsp_diagnostics_deprecated() {
       "uri": "file:///a/file.ts",
       "languageId": "typescript",
       "version": 1,
-      "text": "/** @deprecated */\nexport const a = \"a\";\n\na;\n",
+      "text": "interface Foo {\n  a: string;\n}\nexport const foo: Foo = { a: 1 };\n",
     },
   }));
   assert_eq!(
@@ -6929,15 +8452,25 @@ fn lsp_diagnostics_deprecated() {
       "diagnostics": [
         {
           "range": {
-            "start": { "line": 3, "character": 0 },
-            "end": { "line": 3, "character": 1 }
+            "start": { "line": 3, "character": 26 },
+            "end": { "line": 3, "character": 27 }
           },
-          "severity": 4,
-          "code": 6385,
+          "severity": 1,
+          "code": 2322,
           "source": "deno-ts",
-          "message": "'a' is deprecated.",
-          "relatedInformation": [],
-          "tags": [2]
+          "message": "Type 'number' is not assignable to type 'string'.",
+          "relatedInformation": [
+            {
+              "location": {
+                "uri": "file:///a/file.ts",
+                "range": {
+                  "start": { "line": 1, "character": 2 },
+                  "end": { "line": 1, "character": 3 }
+                }
+              },
+              "message": "The expected type comes from property 'a' which is declared here on type 'Foo'"
+            }
+          ]
         }
       ],
       "version": 1
@@ -7813,6 +9346,53 @@ fn lsp_workspace_symbol() {
       "containerName": "",
     }])
   );
+  let res = client.write_request(
+    "workspace/symbol",
+    json!({
+      "query": "#field fieldD"
+    }),
+  );
+  assert_eq!(
+    res,
+    json!([{
+      "name": "fieldD",
+      "kind": 8,
+      "location": {
+        "uri": "file:///a/file_01.ts",
+        "range": {
+          "start": { "line": 2, "character": 2 },
+          "end": { "line": 2, "character": 17 }
+        }
+      },
+      "containerName": "B"
+    }])
+  );
+  // the cached items of a changed file are not reused
+  client.write_notification(
+    "textDocument/didChange",
+    json!({
+      "textDocument": {
+        "uri": "file:///a/file_01.ts",
+        "version": 2
+      },
+      "contentChanges": [
+        {
+          "range": {
+            "start": { "line": 2, "character": 2 },
+            "end": { "line": 2, "character": 8 }
+          },
+          "text": "fieldE"
+        }
+      ]
+    }),
+  );
+  let res = client.write_request(
+    "workspace/symbol",
+    json!({
+      "query": "#field fieldD"
+    }),
+  );
+  assert_eq!(res, json!(null));
   client.shutdown();
 }
 
@@ -8409,6 +9989,250 @@ Deno.test({
   client.shutdown();
 }
 
+#[test]
+fn lsp_will_rename_files() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  temp_dir.write("./a.ts", "export const a = 1;\n");
+  temp_dir.create_dir_all("sub");
+  temp_dir.write("./sub/b.ts", "export const b = 1;\n");
+  let temp_dir_url = temp_dir.uri();
+  let mut client = context.new_lsp_command().build();
+  client.initialize_default();
+  client.did_open(json!({
+    "textDocument": {
+      "uri": temp_dir_url.join("main.ts").unwrap(),
+      "languageId": "typescript",
+      "version": 1,
+      "text": "import { a } from \"./a.ts\";\nimport { b } from \"./sub/b.ts\";\nconsole.log(a, b);\n"
+    }
+  }));
+  let res = client.write_request(
+    "workspace/willRenameFiles",
+    json!({
+      "files": [{
+        "oldUri": temp_dir_url.join("a.ts").unwrap(),
+        "newUri": temp_dir_url.join("c.ts").unwrap(),
+      }]
+    }),
+  );
+  assert_eq!(
+    res,
+    json!({
+      "documentChanges": [{
+        "textDocument": {
+          "uri": temp_dir_url.join("main.ts").unwrap(),
+          "version": 1
+        },
+        "edits": [{
+          "range": {
+            "start": { "line": 0, "character": 19 },
+            "end": { "line": 0, "character": 25 }
+          },
+          "newText": "./c.ts"
+        }]
+      }]
+    })
+  );
+
+  // renaming a directory updates the imports of the modules inside it
+  let res = client.write_request(
+    "workspace/willRenameFiles",
+    json!({
+      "files": [{
+        "oldUri": temp_dir_url.join("sub").unwrap(),
+        "newUri": temp_dir_url.join("other").unwrap(),
+      }]
+    }),
+  );
+  assert_eq!(
+    res,
+    json!({
+      "documentChanges": [{
+        "textDocument": {
+          "uri": temp_dir_url.join("main.ts").unwrap(),
+          "version": 1
+        },
+        "edits": [{
+          "range": {
+            "start": { "line": 1, "character": 19 },
+            "end": { "line": 1, "character": 29 }
+          },
+          "newText": "./other/b.ts"
+        }]
+      }]
+    })
+  );
+  client.shutdown();
+}
+
+#[test]
+fn lsp_rename_import_specifier() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  temp_dir.write("./a.ts", "export const a = 1;\n");
+  let temp_dir_url = temp_dir.uri();
+  let mut client = context.new_lsp_command().build();
+  client.initialize_default();
+  client.did_open(json!({
+    "textDocument": {
+      "uri": temp_dir_url.join("main.ts").unwrap(),
+      "languageId": "typescript",
+      "version": 1,
+      "text": "import { a } from \"./a.ts\";\nconsole.log(a);\n"
+    }
+  }));
+  let res = client.write_request(
+    "textDocument/rename",
+    json!({
+      "textDocument": {
+        "uri": temp_dir_url.join("main.ts").unwrap()
+      },
+      "position": { "line": 0, "character": 22 },
+      "newName": "./c.ts"
+    }),
+  );
+  assert_eq!(
+    res,
+    json!({
+      "documentChanges": [{
+        "textDocument": {
+          "uri": temp_dir_url.join("main.ts").unwrap(),
+          "version": 1
+        },
+        "edits": [{
+          "range": {
+            "start": { "line": 0, "character": 19 },
+            "end": { "line": 0, "character": 25 }
+          },
+          "newText": "./c.ts"
+        }]
+      }, {
+        "kind": "rename",
+        "oldUri": temp_dir_url.join("a.ts").unwrap(),
+        "newUri": temp_dir_url.join("c.ts").unwrap()
+      }]
+    })
+  );
+  client.shutdown();
+}
+
+#[test]
+fn lsp_multi_document_highlight() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let mut client = context.new_lsp_command().build();
+  client.initialize_default();
+  client.did_open(json!({
+    "textDocument": {
+      "uri": "file:///a/a.ts",
+      "languageId": "typescript",
+      "version": 1,
+      "text": "export const foo = 1;\n"
+    }
+  }));
+  client.did_open(json!({
+    "textDocument": {
+      "uri": "file:///a/b.ts",
+      "languageId": "typescript",
+      "version": 1,
+      "text": "import { foo } from \"./a.ts\";\nconsole.log(foo);\n"
+    }
+  }));
+  client.did_open(json!({
+    "textDocument": {
+      "uri": "file:///a/c.ts",
+      "languageId": "typescript",
+      "version": 1,
+      "text": "console.log(1);\n"
+    }
+  }));
+  let res = client.write_request(
+    "deno/multiDocumentHighlight",
+    json!({
+      "textDocument": {
+        "uri": "file:///a/a.ts"
+      },
+      "position": { "line": 0, "character": 14 }
+    }),
+  );
+  let mut uris = res
+    .as_array()
+    .unwrap()
+    .iter()
+    .map(|h| h["uri"].as_str().unwrap().to_string())
+    .collect::<Vec<_>>();
+  uris.sort();
+  assert_eq!(uris, vec!["file:///a/a.ts", "file:///a/b.ts"]);
+  client.shutdown();
+}
+
+#[test]
+fn lsp_file_references() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  temp_dir.write("./a.ts", "export const a = 1;\n");
+  let temp_dir_url = temp_dir.uri();
+  let mut client = context.new_lsp_command().build();
+  client.initialize_default();
+  client.did_open(json!({
+    "textDocument": {
+      "uri": temp_dir_url.join("main.ts").unwrap(),
+      "languageId": "typescript",
+      "version": 1,
+      "text": "import { a } from \"./a.ts\";\nconsole.log(a);\n"
+    }
+  }));
+  let res = client.write_request(
+    "deno/fileReferences",
+    json!({
+      "textDocument": {
+        "uri": temp_dir_url.join("a.ts").unwrap(),
+      }
+    }),
+  );
+  let locations = json!([{
+    "uri": temp_dir_url.join("main.ts").unwrap(),
+    "range": {
+      "start": { "line": 0, "character": 19 },
+      "end": { "line": 0, "character": 25 }
+    }
+  }]);
+  assert_eq!(res, locations);
+
+  let res = client.write_request(
+    "codeLens/resolve",
+    json!({
+      "range": {
+        "start": { "line": 0, "character": 0 },
+        "end": { "line": 0, "character": 0 }
+      },
+      "data": {
+        "specifier": temp_dir_url.join("a.ts").unwrap(),
+        "source": "fileReferences"
+      }
+    }),
+  );
+  assert_eq!(
+    res,
+    json!({
+      "range": {
+        "start": { "line": 0, "character": 0 },
+        "end": { "line": 0, "character": 0 }
+      },
+      "command": {
+        "title": "1 module reference",
+        "command": "deno.showReferences",
+        "arguments": [
+          temp_dir_url.join("a.ts").unwrap(),
+          { "line": 0, "character": 0 },
+          locations,
+        ]
+      }
+    })
+  );
+  client.shutdown();
+}
+
 #[test]
 fn lsp_closed_file_find_references() {
   let context = TestContextBuilder::new().use_temp_cwd().build();
@@ -1999,6 +1999,32 @@ fn no_validate_asm() {
   assert!(output.stdout.is_empty());
 }
 
+#[test]
+fn seed_random_worker() {
+  let run = || {
+    let output = util::deno_cmd()
+      .current_dir(util::testdata_path())
+      .arg("run")
+      .arg("--seed=100")
+      .arg("run/seed_worker_random.js")
+      .stdout(Stdio::piped())
+      .spawn()
+      .unwrap()
+      .wait_with_output()
+      .unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap()
+  };
+
+  let first = run();
+  // Workers are seeded deterministically, but don't repeat the values drawn
+  // on the main thread.
+  assert_eq!(first, run());
+  let lines = first.lines().collect::<Vec<_>>();
+  assert_eq!(lines.len(), 2);
+  assert_ne!(lines[0], lines[1]);
+}
+
 #[test]
 fn exec_path() {
   let output = util::deno_cmd()
@@ -4353,6 +4379,49 @@ itest!(config_file_lock_true {
   exit_code: 10,
 });
 
+#[cfg(feature = "fips")]
+itest!(webcrypto_fips_mode {
+  args: "run --quiet run/webcrypto_fips.ts",
+  output: "run/webcrypto_fips.ts.out",
+  envs: vec![
+    ("DENO_FIPS".to_string(), "1".to_string()),
+    ("DENO_CRYPTO_BACKEND".to_string(), "aws-lc-rs".to_string()),
+  ],
+});
+
+itest!(webcrypto_fips_mode_ring {
+  args: "run --quiet run/001_hello.js",
+  output: "run/webcrypto_fips_ring.out",
+  envs: vec![("DENO_FIPS".to_string(), "1".to_string())],
+  exit_code: 1,
+});
+
+itest!(webcrypto_backend_ring {
+  args: "run --quiet run/001_hello.js",
+  output: "run/001_hello.js.out",
+  envs: vec![("DENO_CRYPTO_BACKEND".to_string(), "ring".to_string())],
+});
+
+itest!(webcrypto_backend_invalid {
+  args: "run --quiet run/001_hello.js",
+  output: "run/webcrypto_backend_invalid.out",
+  envs: vec![("DENO_CRYPTO_BACKEND".to_string(), "bogus".to_string())],
+  exit_code: 1,
+});
+
+itest!(webcrypto_keygen_tasks {
+  args: "run --quiet run/001_hello.js",
+  output: "run/001_hello.js.out",
+  envs: vec![("DENO_KEYGEN_TASKS".to_string(), "1".to_string())],
+});
+
+itest!(webcrypto_keygen_tasks_invalid {
+  args: "run --quiet run/001_hello.js",
+  output: "run/webcrypto_keygen_tasks_invalid.out",
+  envs: vec![("DENO_KEYGEN_TASKS".to_string(), "0".to_string())],
+  exit_code: 1,
+});
+
 itest!(permission_args {
   args: "run run/001_hello.js --allow-net",
   output: "run/permission_args.out",
@@ -0,0 +1,9 @@
+/**
+ * Utilities for greeting people.
+ *
+ * @module
+ */
+
+export function greet(name: string): string {
+  return `Hello, ${name}!`;
+}
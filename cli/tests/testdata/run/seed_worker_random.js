@@ -0,0 +1,10 @@
+console.log(crypto.randomUUID());
+
+const worker = new Worker(
+  new URL("./seed_worker_random_worker.js", import.meta.url).href,
+  { type: "module" },
+);
+worker.onmessage = (e) => {
+  console.log(e.data);
+  worker.terminate();
+};
@@ -0,0 +1 @@
+postMessage(crypto.randomUUID());
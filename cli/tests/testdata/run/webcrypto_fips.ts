@@ -0,0 +1,59 @@
+const data = new TextEncoder().encode("hello");
+
+console.log(await crypto.subtle.digest("SHA-256", data));
+
+for (const algorithm of ["X25519", "Ed25519", "ChaCha20-Poly1305"]) {
+  try {
+    await crypto.subtle.generateKey(algorithm, false, ["sign"]);
+  } catch (err) {
+    console.log(algorithm, err.name, err.message);
+  }
+}
+
+try {
+  await crypto.subtle.generateKey(
+    { name: "ECDSA", namedCurve: "secp256k1" },
+    false,
+    ["sign"],
+  );
+} catch (err) {
+  console.log("secp256k1", err.name, err.message);
+}
+
+const { privateKey } = await crypto.subtle.generateKey(
+  { name: "ECDSA", namedCurve: "P-256" },
+  false,
+  ["sign"],
+);
+try {
+  await crypto.subtle.sign(
+    { name: "ECDSA", hash: "SHA-1" },
+    privateKey,
+    data,
+  );
+} catch (err) {
+  console.log("SHA-1", err.name, err.message);
+}
+const signature = await crypto.subtle.sign(
+  { name: "ECDSA", hash: "SHA-256" },
+  privateKey,
+  data,
+);
+console.log(signature.byteLength);
+
+// The ops check FIPS mode too, for APIs outside of `crypto.subtle`.
+// @ts-ignore Deno[Deno.internal].core is not a public interface
+const { core } = Deno[Deno.internal];
+try {
+  core.ops.op_crypto_generate_bls12381_keypair(
+    new Uint8Array(32),
+    new Uint8Array(48),
+  );
+} catch (err) {
+  console.log("BLS12-381", err.name, err.message);
+}
+try {
+  await core.opAsync("op_crypto_bcrypt_hash", data, 4);
+} catch (err) {
+  console.log("bcrypt", err.name, err.message);
+}
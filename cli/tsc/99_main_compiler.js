@@ -52,7 +52,9 @@ delete Object.prototype.__proto__;
     "funlockSync",
     "listen",
     "listenDatagram",
+    "loadCryptoKey",
     "openKv",
+    "saveCryptoKey",
     "upgradeHttp",
     "umask",
   ]);
@@ -521,6 +523,20 @@ delete Object.prototype.__proto__;
   /** @type {ts.LanguageService} */
   let languageService;
 
+  const cancellationToken = new ThrottledCancellationToken();
+
+  /**
+   * Stop the current language server request early if it was cancelled, in
+   * the host operations which tsc itself doesn't check for cancellation, such
+   * as loading and resolving modules. There is nothing to cancel when type
+   * checking from the command line.
+   */
+  function throwIfCancellationRequested() {
+    if (languageService) {
+      cancellationToken.throwIfCancellationRequested();
+    }
+  }
+
   /** An object literal of the incremental compiler host, which provides the
    * specific "bindings" to the Deno environment that tsc needs to work.
    *
@@ -542,7 +558,7 @@ delete Object.prototype.__proto__;
     },
     getCancellationToken() {
       // createLanguageService will call this immediately and cache it
-      return new ThrottledCancellationToken();
+      return cancellationToken;
     },
     getSourceFile(
       specifier,
@@ -566,6 +582,7 @@ delete Object.prototype.__proto__;
       if (sourceFile) {
         return sourceFile;
       }
+      throwIfCancellationRequested();
 
       /** @type {{ data: string; scriptKind: ts.ScriptKind; version: string; }} */
       const { data, scriptKind, version } = ops.op_load(
@@ -672,6 +689,7 @@ delete Object.prototype.__proto__;
         debug(`  base: ${base}`);
         debug(`  specifiers: ${specifiers.join(", ")}`);
       }
+      throwIfCancellationRequested();
       /** @type {Array<[string, ts.Extension] | undefined>} */
       const resolved = ops.op_resolve({
         specifiers,
@@ -758,6 +776,7 @@ delete Object.prototype.__proto__;
         };
       }
 
+      throwIfCancellationRequested();
       const fileInfo = ops.op_load(
         { specifier },
       );
@@ -960,6 +979,393 @@ delete Object.prototype.__proto__;
     return assets;
   }
 
+  /**
+   * Identifiers in the span that refer to variables or parameters, whose
+   * values a debugger can show inline. Code after the position where execution
+   * stopped is skipped, as its variables do not hold meaningful values yet.
+   * @param {string} specifier
+   * @param {ts.TextSpan} span
+   * @param {number} stoppedPosition
+   */
+  function getInlineValues(specifier, span, stoppedPosition) {
+    const program = languageService.getProgram();
+    const sourceFile = program?.getSourceFile(specifier);
+    if (!program || !sourceFile) {
+      return [];
+    }
+    const checker = program.getTypeChecker();
+    const end = Math.min(span.start + span.length, stoppedPosition);
+    /** @type {{ name: string; textSpan: ts.TextSpan; }[]} */
+    const values = [];
+    /** @param {ts.Node} node */
+    function visit(node) {
+      if (node.end <= span.start || node.pos >= end) {
+        return;
+      }
+      if (ts.isIdentifier(node)) {
+        const start = node.getStart(sourceFile);
+        const symbol = start >= span.start && node.end <= end &&
+          checker.getSymbolAtLocation(node);
+        if (symbol && (symbol.flags & ts.SymbolFlags.Variable)) {
+          values.push({
+            name: node.text,
+            textSpan: { start, length: node.end - start },
+          });
+        }
+        return;
+      }
+      ts.forEachChild(node, visit);
+    }
+    visit(sourceFile);
+    return values;
+  }
+
+  /**
+   * Like `getDefinitionAndBoundSpan()`, but definitions in declaration files
+   * are replaced by the declarations of the same name in the implementation
+   * module next to them, if there is one, following its relative re-exports.
+   * This is mostly useful for npm packages, where the type declarations hide
+   * the actual code. The language service only offers this through tsserver,
+   * which builds a second program for it.
+   * @param {string} specifier
+   * @param {number} position
+   */
+  function getSourceDefinitionAndBoundSpan(specifier, position) {
+    const info = languageService.getDefinitionAndBoundSpan(
+      specifier,
+      position,
+    );
+    if (!info?.definitions) {
+      return info;
+    }
+    const definitions = info.definitions.flatMap((definition) => {
+      const match = /\.d\.([cm]?)ts$/.exec(definition.fileName);
+      const implementationFile = match &&
+        loadSourceFile(
+          `${definition.fileName.slice(0, match.index)}.${match[1]}js`,
+        );
+      if (!implementationFile) {
+        return [definition];
+      }
+      const declarations = findSourceDeclarations(
+        implementationFile,
+        definition.name,
+      );
+      // when the name can't be found, at least point to the module
+      if (declarations.length === 0) {
+        declarations.push({
+          sourceFile: implementationFile,
+          node: implementationFile,
+        });
+      }
+      return declarations.map(({ sourceFile, node }) => {
+        const name = node === sourceFile
+          ? undefined
+          : ts.getNameOfDeclaration(node);
+        const contextStart = node.getStart(sourceFile);
+        const start = name?.getStart(sourceFile) ?? contextStart;
+        return {
+          kind: definition.kind,
+          name: definition.name,
+          containerKind: definition.containerKind,
+          containerName: definition.containerName,
+          fileName: sourceFile.fileName,
+          textSpan: { start, length: name ? name.end - start : 0 },
+          contextSpan: name
+            ? { start: contextStart, length: node.end - contextStart }
+            : undefined,
+        };
+      });
+    });
+    return { ...info, definitions };
+  }
+
+  /**
+   * Parse a module outside of the program, e.g. the JavaScript module a
+   * declaration file describes, if it exists.
+   * @param {string} specifier
+   * @returns {ts.SourceFile | undefined}
+   */
+  function loadSourceFile(specifier) {
+    /** @type {{ data: string; scriptKind: ts.ScriptKind; } | null} */
+    const fileInfo = ops.op_load({ specifier });
+    if (!fileInfo) {
+      return undefined;
+    }
+    return ts.createSourceFile(
+      specifier,
+      fileInfo.data,
+      ts.ScriptTarget.ESNext,
+      true,
+      fileInfo.scriptKind,
+    );
+  }
+
+  /**
+   * Find the declarations with the provided name in an implementation module,
+   * or in the modules it re-exports the name from with a relative specifier.
+   * @param {ts.SourceFile} sourceFile
+   * @param {string} name
+   * @param {Set<string>} visited
+   * @returns {{ sourceFile: ts.SourceFile; node: ts.Node }[]}
+   */
+  function findSourceDeclarations(sourceFile, name, visited = new Set()) {
+    visited.add(sourceFile.fileName);
+    const declarations = findDeclarations(sourceFile, name);
+    if (declarations.length > 0) {
+      return declarations.map((node) => ({ sourceFile, node }));
+    }
+    for (const statement of sourceFile.statements) {
+      if (
+        !ts.isExportDeclaration(statement) || !statement.moduleSpecifier ||
+        !ts.isStringLiteral(statement.moduleSpecifier) ||
+        !statement.moduleSpecifier.text.startsWith(".")
+      ) {
+        continue;
+      }
+      // `export * from` re-exports the name as it is, `export { a as b } from`
+      // re-exports `a` as `b`, `export * as ns from` only re-exports `ns`
+      let exportedName = name;
+      if (statement.exportClause) {
+        const element = ts.isNamedExports(statement.exportClause)
+          ? statement.exportClause.elements.find((element) =>
+            element.name.text === name
+          )
+          : undefined;
+        if (!element) {
+          continue;
+        }
+        exportedName = (element.propertyName ?? element.name).text;
+      }
+      const specifier = resolveRelativeSpecifier(
+        statement.moduleSpecifier.text,
+        sourceFile.fileName,
+      );
+      if (visited.has(specifier)) {
+        continue;
+      }
+      const reExportedFile = loadSourceFile(specifier);
+      if (!reExportedFile) {
+        continue;
+      }
+      const reExported = findSourceDeclarations(
+        reExportedFile,
+        exportedName,
+        visited,
+      );
+      if (reExported.length > 0) {
+        return reExported;
+      }
+    }
+    return [];
+  }
+
+  /**
+   * Resolve a relative specifier like `./a.js` or `../a.js` against the
+   * specifier of the module it appears in.
+   * @param {string} specifier
+   * @param {string} base
+   */
+  function resolveRelativeSpecifier(specifier, base) {
+    const segments = base.split("/").slice(0, -1);
+    for (const segment of specifier.split("/")) {
+      if (segment === "..") {
+        segments.pop();
+      } else if (segment !== ".") {
+        segments.push(segment);
+      }
+    }
+    return segments.join("/");
+  }
+
+  /**
+   * The navigate to items of each file for the last searched value, which are
+   * reused while the version of the file doesn't change.
+   * @type {Map<string, { version: string; search: string; items: ts.NavigateToItem[] }>}
+   */
+  const navigateToItemsCache = new Map();
+
+  /**
+   * The program the navigate to items cache was last checked against. The
+   * language service only creates a new program when the project changed, so
+   * this acts as the version of the project: as long as it is the same, the
+   * versions of the files don't have to be asked for again.
+   * @type {ts.Program | undefined}
+   */
+  let navigateToItemsProgram;
+
+  /**
+   * The sorted results of the last search for the current program.
+   * @type {{ search: string; items: ts.NavigateToItem[] } | undefined}
+   */
+  let navigateToItemsResult;
+
+  const navigateToMatchKindOrder = ["exact", "prefix", "substring", "camelCase"];
+
+  /**
+   * Get the navigate to items of all the files in the program, only asking
+   * the language service about files that have changed since the last search,
+   * or which can still match the search.
+   * @param {string} search
+   * @param {number | undefined} maxResultCount
+   * @returns {ts.NavigateToItem[]}
+   */
+  function getNavigateToItems(search, maxResultCount) {
+    const program = languageService.getProgram();
+    if (!program) {
+      return [];
+    }
+    const projectChanged = program !== navigateToItemsProgram;
+    if (
+      !projectChanged && navigateToItemsResult &&
+      navigateToItemsResult.search === search
+    ) {
+      const { items } = navigateToItemsResult;
+      return maxResultCount ? items.slice(0, maxResultCount) : items;
+    }
+    /** @type {ts.NavigateToItem[]} */
+    const items = [];
+    const fileNames = new Set();
+    for (const { fileName } of program.getSourceFiles()) {
+      fileNames.add(fileName);
+      const cached = navigateToItemsCache.get(fileName);
+      const version = projectChanged || !cached
+        ? host.getScriptVersion(fileName)
+        : cached.version;
+      if (cached && cached.version === version) {
+        if (cached.search === search) {
+          items.push(...cached.items);
+          continue;
+        }
+        // a file without matches for a search has no matches for a longer
+        // search starting with it either, unless it qualifies a container
+        if (
+          cached.items.length === 0 && search.startsWith(cached.search) &&
+          !search.includes(".")
+        ) {
+          cached.search = search;
+          continue;
+        }
+      }
+      const fileItems = languageService.getNavigateToItems(
+        search,
+        undefined,
+        fileName,
+      );
+      navigateToItemsCache.set(fileName, { version, search, items: fileItems });
+      items.push(...fileItems);
+    }
+    if (projectChanged) {
+      for (const fileName of navigateToItemsCache.keys()) {
+        if (!fileNames.has(fileName)) {
+          navigateToItemsCache.delete(fileName);
+        }
+      }
+      navigateToItemsProgram = program;
+    }
+    // this mirrors the ordering of the results of the language service
+    items.sort((a, b) =>
+      navigateToMatchKindOrder.indexOf(a.matchKind) -
+        navigateToMatchKindOrder.indexOf(b.matchKind) ||
+      a.name.localeCompare(b.name)
+    );
+    navigateToItemsResult = { search, items };
+    return maxResultCount ? items.slice(0, maxResultCount) : items;
+  }
+
+  /** The maximum number of completion entries returned to the language
+   * server. Longer lists are truncated and flagged as incomplete, so the
+   * client asks again once the user has typed more of the identifier. */
+  const MAX_COMPLETION_ENTRIES = 1000;
+
+  /**
+   * Keep the completion entries which best match the identifier being
+   * completed, so that huge lists don't have to be serialized. Entries
+   * starting with what has been typed so far are ranked first, then case
+   * insensitive matches, then everything else, with ties being broken by the
+   * sort text TypeScript assigned.
+   * @param {ts.CompletionInfo | undefined} info
+   * @param {string} specifier
+   * @param {number} position
+   * @returns {ts.CompletionInfo | undefined}
+   */
+  function truncateCompletionInfo(info, specifier, position) {
+    if (!info || info.entries.length <= MAX_COMPLETION_ENTRIES) {
+      return info;
+    }
+    const text = languageService.getProgram()?.getSourceFile(specifier)?.text ??
+      "";
+    let start = position;
+    while (
+      start > 0 &&
+      ts.isIdentifierPart(text.charCodeAt(start - 1), ts.ScriptTarget.ESNext)
+    ) {
+      start--;
+    }
+    const prefix = text.slice(start, position);
+    const lowerCasePrefix = prefix.toLowerCase();
+    /** @param {string} name */
+    const prefixRank = (name) => {
+      if (name.startsWith(prefix)) {
+        return 0;
+      }
+      return name.slice(0, prefix.length).toLowerCase() === lowerCasePrefix
+        ? 1
+        : 2;
+    };
+    /** @param {string} a @param {string} b */
+    const compare = (a, b) => a < b ? -1 : a > b ? 1 : 0;
+    const entries = info.entries.map((entry) => ({
+      entry,
+      rank: prefixRank(entry.name),
+    }));
+    entries.sort((a, b) =>
+      a.rank - b.rank ||
+      Number(a.entry.source !== undefined) -
+        Number(b.entry.source !== undefined) ||
+      compare(a.entry.sortText, b.entry.sortText) ||
+      compare(a.entry.name, b.entry.name)
+    );
+    return {
+      ...info,
+      entries: entries.slice(0, MAX_COMPLETION_ENTRIES).map(({ entry }) =>
+        entry
+      ),
+      isIncomplete: true,
+    };
+  }
+
+  /**
+   * Find the declarations, including CommonJS export assignments, with the
+   * provided name in a module.
+   * @param {ts.SourceFile} sourceFile
+   * @param {string} name
+   */
+  function findDeclarations(sourceFile, name) {
+    /** @type {ts.Node[]} */
+    const declarations = [];
+    /** @param {ts.Node} node */
+    function visit(node) {
+      if (
+        ts.isFunctionDeclaration(node) || ts.isClassDeclaration(node) ||
+        ts.isVariableDeclaration(node) || ts.isMethodDeclaration(node) ||
+        ts.isPropertyDeclaration(node) || ts.isGetAccessorDeclaration(node) ||
+        ts.isSetAccessorDeclaration(node) || ts.isBinaryExpression(node)
+      ) {
+        const declarationName = ts.getNameOfDeclaration(node);
+        if (
+          declarationName && ts.isIdentifier(declarationName) &&
+          declarationName.text === name
+        ) {
+          declarations.push(node);
+        }
+      }
+      ts.forEachChild(node, visit);
+    }
+    visit(sourceFile);
+    return declarations;
+  }
+
   /**
    * @param {number} id
    * @param {any} data
@@ -1045,6 +1451,24 @@ delete Object.prototype.__proto__;
             request.actionName,
             {
               quotePreference: "double",
+              allowTextChangesInNewFiles: true,
+            },
+          ),
+        );
+      }
+      case "getEditsForFileRename": {
+        return respond(
+          id,
+          languageService.getEditsForFileRename(
+            request.oldSpecifier,
+            request.newSpecifier,
+            {
+              ...request.formatCodeSettings,
+              indentStyle: ts.IndentStyle.Block,
+            },
+            {
+              quotePreference: "double",
+              ...request.preferences,
             },
           ),
         );
@@ -1063,6 +1487,7 @@ delete Object.prototype.__proto__;
             },
             {
               quotePreference: "double",
+              ...request.preferences,
             },
           ),
         );
@@ -1082,6 +1507,7 @@ delete Object.prototype.__proto__;
             },
             {
               quotePreference: "double",
+              ...request.preferences,
             },
           ),
         );
@@ -1106,11 +1532,15 @@ delete Object.prototype.__proto__;
       case "getCompletions": {
         return respond(
           id,
-          languageService.getCompletionsAtPosition(
+          truncateCompletionInfo(
+            languageService.getCompletionsAtPosition(
+              request.specifier,
+              request.position,
+              request.preferences,
+              request.formatCodeSettings,
+            ),
             request.specifier,
             request.position,
-            request.preferences,
-            request.formatCodeSettings,
           ),
         );
       }
@@ -1169,6 +1599,12 @@ delete Object.prototype.__proto__;
           ),
         );
       }
+      case "getFileReferences": {
+        return respond(
+          id,
+          languageService.getFileReferences(request.specifier),
+        );
+      }
       case "getImplementation": {
         return respond(
           id,
@@ -1181,11 +1617,13 @@ delete Object.prototype.__proto__;
       case "getNavigateToItems": {
         return respond(
           id,
-          languageService.getNavigateToItems(
-            request.search,
-            request.maxResultCount,
-            request.fileName,
-          ),
+          request.fileName
+            ? languageService.getNavigateToItems(
+              request.search,
+              request.maxResultCount,
+              request.fileName,
+            )
+            : getNavigateToItems(request.search, request.maxResultCount),
         );
       }
       case "getNavigationTree": {
@@ -1230,15 +1668,20 @@ delete Object.prototype.__proto__;
           ),
         );
       }
-      case "getSmartSelectionRange": {
+      case "getSmartSelectionRanges": {
         return respond(
           id,
-          languageService.getSmartSelectionRange(
-            request.specifier,
-            request.position,
+          request.positions.map((position) =>
+            languageService.getSmartSelectionRange(request.specifier, position)
           ),
         );
       }
+      case "getSourceDefinitionAndBoundSpan": {
+        return respond(
+          id,
+          getSourceDefinitionAndBoundSpan(request.specifier, request.position),
+        );
+      }
       case "getSupportedCodeFixes": {
         return respond(
           id,
@@ -1254,6 +1697,25 @@ delete Object.prototype.__proto__;
           ),
         );
       }
+      case "organizeImports": {
+        return respond(
+          id,
+          languageService.organizeImports(
+            {
+              type: "file",
+              fileName: request.specifier,
+              mode: request.mode,
+            },
+            {
+              ...request.formatCodeSettings,
+              indentStyle: ts.IndentStyle.Block,
+            },
+            {
+              quotePreference: "double",
+            },
+          ),
+        );
+      }
       case "prepareCallHierarchy": {
         return respond(
           id,
@@ -1290,6 +1752,15 @@ delete Object.prototype.__proto__;
             request.preferences,
           ),
         );
+      case "provideInlineValues":
+        return respond(
+          id,
+          getInlineValues(
+            request.specifier,
+            request.span,
+            request.stoppedPosition,
+          ),
+        );
       default:
         throw new TypeError(
           // @ts-ignore exhausted case statement sets type to never
@@ -1312,6 +1783,7 @@ delete Object.prototype.__proto__;
   function serverRestart() {
     languageService = ts.createLanguageService(host, documentRegistry);
     isNodeSourceFileCache.clear();
+    navigateToItemsCache.clear();
     debug("serverRestart()");
   }
 
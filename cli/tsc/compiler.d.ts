@@ -64,6 +64,7 @@ declare global {
     | GetAssets
     | GetApplicableRefactors
     | GetEditsForRefactor
+    | GetEditsForFileRename
     | GetCodeFixes
     | GetCombinedCodeFix
     | GetCompletionDetails
@@ -72,6 +73,7 @@ declare global {
     | GetDiagnosticsRequest
     | GetDocumentHighlightsRequest
     | GetEncodedSemanticClassifications
+    | GetFileReferences
     | GetImplementationRequest
     | GetNavigateToItems
     | GetNavigationTree
@@ -79,13 +81,16 @@ declare global {
     | GetQuickInfoRequest
     | FindReferencesRequest
     | GetSignatureHelpItemsRequest
-    | GetSmartSelectionRange
+    | GetSmartSelectionRanges
+    | GetSourceDefinitionAndBoundSpan
     | GetSupportedCodeFixes
     | GetTypeDefinitionRequest
+    | OrganizeImports
     | PrepareCallHierarchy
     | ProvideCallHierarchyIncomingCalls
     | ProvideCallHierarchyOutgoingCalls
-    | ProvideInlayHints;
+    | ProvideInlayHints
+    | ProvideInlineValues;
 
   interface BaseLanguageServerRequest {
     id: number;
@@ -127,6 +132,14 @@ declare global {
     actionName: string;
   }
 
+  interface GetEditsForFileRename extends BaseLanguageServerRequest {
+    method: "getEditsForFileRename";
+    oldSpecifier: string;
+    newSpecifier: string;
+    formatCodeSettings: ts.FormatCodeSettings;
+    preferences: ts.UserPreferences;
+  }
+
   interface GetCodeFixes extends BaseLanguageServerRequest {
     method: "getCodeFixes";
     specifier: string;
@@ -134,6 +147,7 @@ declare global {
     endPosition: number;
     errorCodes: string[];
     formatCodeSettings: ts.FormatCodeSettings;
+    preferences: ts.UserPreferences;
   }
 
   interface GetCombinedCodeFix extends BaseLanguageServerRequest {
@@ -142,6 +156,7 @@ declare global {
     // deno-lint-ignore ban-types
     fixId: {};
     formatCodeSettings: ts.FormatCodeSettings;
+    preferences: ts.UserPreferences;
   }
 
   interface GetCompletionDetails extends BaseLanguageServerRequest {
@@ -190,6 +205,11 @@ declare global {
     span: ts.TextSpan;
   }
 
+  interface GetFileReferences extends BaseLanguageServerRequest {
+    method: "getFileReferences";
+    specifier: string;
+  }
+
   interface GetImplementationRequest extends BaseLanguageServerRequest {
     method: "getImplementation";
     specifier: string;
@@ -232,8 +252,14 @@ declare global {
     options: ts.SignatureHelpItemsOptions;
   }
 
-  interface GetSmartSelectionRange extends BaseLanguageServerRequest {
-    method: "getSmartSelectionRange";
+  interface GetSmartSelectionRanges extends BaseLanguageServerRequest {
+    method: "getSmartSelectionRanges";
+    specifier: string;
+    positions: number[];
+  }
+
+  interface GetSourceDefinitionAndBoundSpan extends BaseLanguageServerRequest {
+    method: "getSourceDefinitionAndBoundSpan";
     specifier: string;
     position: number;
   }
@@ -248,6 +274,13 @@ declare global {
     position: number;
   }
 
+  interface OrganizeImports extends BaseLanguageServerRequest {
+    method: "organizeImports";
+    specifier: string;
+    formatCodeSettings: ts.FormatCodeSettings;
+    mode: ts.OrganizeImportsMode;
+  }
+
   interface PrepareCallHierarchy extends BaseLanguageServerRequest {
     method: "prepareCallHierarchy";
     specifier: string;
@@ -275,6 +308,13 @@ declare global {
     preferences?: ts.UserPreferences;
   }
 
+  interface ProvideInlineValues extends BaseLanguageServerRequest {
+    method: "provideInlineValues";
+    specifier: string;
+    span: ts.TextSpan;
+    stoppedPosition: number;
+  }
+
   interface Restart extends BaseLanguageServerRequest {
     method: "restart";
   }
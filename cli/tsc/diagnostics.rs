@@ -118,6 +118,8 @@ pub struct Diagnostic {
   pub source_line: Option<String>,
   pub file_name: Option<String>,
   pub related_information: Option<Vec<Diagnostic>>,
+  pub reports_deprecated: Option<bool>,
+  pub reports_unnecessary: Option<bool>,
 }
 
 impl Diagnostic {
@@ -1885,6 +1885,50 @@ declare namespace Deno {
     /** The value of this unsigned 64-bit integer, represented as a bigint. */
     readonly value: bigint;
   }
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * Writes an extractable {@linkcode CryptoKey} to a file, encrypted with a
+   * key derived from `passphrase` using Argon2id. The file is only readable by
+   * its owner.
+   *
+   * ```ts
+   * const key = await crypto.subtle.generateKey(
+   *   { name: "HMAC", hash: "SHA-256" },
+   *   true,
+   *   ["sign", "verify"],
+   * );
+   * await Deno.saveCryptoKey("./hmac.key", key, "correct horse battery staple");
+   * ```
+   *
+   * @tags allow-write
+   * @category Web Crypto API
+   */
+  export function saveCryptoKey(
+    path: string | URL,
+    key: CryptoKey,
+    passphrase: string,
+  ): Promise<void>;
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * Reads a key written by {@linkcode Deno.saveCryptoKey}. The key keeps the
+   * algorithm, usages and extractable flag it was saved with.
+   *
+   * ```ts
+   * const key = await Deno.loadCryptoKey(
+   *   "./hmac.key",
+   *   "correct horse battery staple",
+   * );
+   * ```
+   *
+   * @tags allow-read
+   * @category Web Crypto API
+   */
+  export function loadCryptoKey(
+    path: string | URL,
+    passphrase: string,
+  ): Promise<CryptoKey>;
 }
 
 /** **UNSTABLE**: New API, yet to be vetted.
@@ -1191,6 +1191,8 @@ mod tests {
           source_line: None,
           file_name: None,
           related_information: None,
+          reports_deprecated: None,
+          reports_unnecessary: None,
         }]),
         stats: Stats(vec![("a".to_string(), 12)])
       })
@@ -21,6 +21,7 @@ use deno_core::SourceMapGetter;
 use deno_lockfile::Lockfile;
 use deno_runtime::colors;
 use deno_runtime::deno_broadcast_channel::InMemoryBroadcastChannel;
+use deno_runtime::deno_crypto::CryptoBackend;
 use deno_runtime::deno_fs;
 use deno_runtime::deno_node;
 use deno_runtime::deno_node::NodeResolution;
@@ -86,6 +87,9 @@ pub struct CliMainWorkerOptions {
   pub maybe_binary_npm_command_name: Option<String>,
   pub origin_data_folder_path: Option<PathBuf>,
   pub seed: Option<u64>,
+  pub fips: bool,
+  pub crypto_backend: CryptoBackend,
+  pub max_keygen_tasks: Option<usize>,
   pub unsafely_ignore_certificate_errors: Option<Vec<String>>,
   pub unstable: bool,
 }
@@ -436,6 +440,9 @@ impl CliMainWorkerFactory {
         .clone(),
       root_cert_store_provider: Some(shared.root_cert_store_provider.clone()),
       seed: shared.options.seed,
+      fips: shared.options.fips,
+      crypto_backend: shared.options.crypto_backend,
+      max_keygen_tasks: shared.options.max_keygen_tasks,
       source_map_getter: maybe_source_map_getter,
       format_js_error_fn: Some(Arc::new(format_js_error)),
       create_web_worker_cb,
@@ -590,7 +597,10 @@ fn create_web_worker_callback(
         .unsafely_ignore_certificate_errors
         .clone(),
       root_cert_store_provider: Some(shared.root_cert_store_provider.clone()),
-      seed: shared.options.seed,
+      seed: args.seed,
+      fips: shared.options.fips,
+      crypto_backend: shared.options.crypto_backend,
+      max_keygen_tasks: shared.options.max_keygen_tasks,
       create_web_worker_cb,
       format_js_error_fn: Some(Arc::new(format_js_error)),
       source_map_getter: maybe_source_map_getter,
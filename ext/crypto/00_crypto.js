@@ -11,6 +11,9 @@ const ops = core.ops;
 const primordials = globalThis.__bootstrap.primordials;
 import * as webidl from "ext:deno_webidl/00_webidl.js";
 import DOMException from "ext:deno_web/01_dom_exception.js";
+import * as abortSignal from "ext:deno_web/03_abort_signal.js";
+import { clearInterval, setInterval } from "ext:deno_web/02_timers.js";
+import { pathFromURL } from "ext:deno_web/00_infra.js";
 const {
   ArrayBufferIsView,
   ArrayBufferPrototype,
@@ -20,6 +23,7 @@ const {
   ArrayPrototypeFilter,
   ArrayPrototypeFind,
   ArrayPrototypeIncludes,
+  ArrayPrototypeSlice,
   DataViewPrototypeGetBuffer,
   DataViewPrototypeGetByteLength,
   DataViewPrototypeGetByteOffset,
@@ -30,6 +34,7 @@ const {
   ObjectHasOwn,
   ObjectPrototypeIsPrototypeOf,
   SafeArrayIterator,
+  SafeFinalizationRegistry,
   SafeWeakMap,
   StringFromCharCode,
   StringPrototypeCharCodeAt,
@@ -49,8 +54,7 @@ const {
   WeakMapPrototypeSet,
 } = primordials;
 
-// P-521 is not yet supported.
-const supportedNamedCurves = ["P-256", "P-384"];
+const supportedNamedCurves = ["P-256", "P-384", "P-521", "secp256k1"];
 const recognisedUsages = [
   "encrypt",
   "decrypt",
@@ -64,6 +68,10 @@ const recognisedUsages = [
 
 const simpleAlgorithmDictionaries = {
   AesGcmParams: { iv: "BufferSource", additionalData: "BufferSource" },
+  ChaCha20Poly1305Params: {
+    iv: "BufferSource",
+    additionalData: "BufferSource",
+  },
   RsaHashedKeyGenParams: { hash: "HashAlgorithmIdentifier" },
   EcKeyGenParams: {},
   HmacKeyGenParams: { hash: "HashAlgorithmIdentifier" },
@@ -76,9 +84,14 @@ const simpleAlgorithmDictionaries = {
     info: "BufferSource",
   },
   Pbkdf2Params: { hash: "HashAlgorithmIdentifier", salt: "BufferSource" },
+  Argon2Params: { salt: "BufferSource" },
+  ScryptParams: { salt: "BufferSource" },
   RsaOaepParams: { label: "BufferSource" },
   RsaHashedImportParams: { hash: "HashAlgorithmIdentifier" },
   EcKeyImportParams: {},
+  CShakeParams: { functionName: "BufferSource", customization: "BufferSource" },
+  KmacKeyGenParams: {},
+  KmacParams: { customization: "BufferSource" },
 };
 
 const supportedAlgorithms = {
@@ -87,6 +100,8 @@ const supportedAlgorithms = {
     "SHA-256": null,
     "SHA-384": null,
     "SHA-512": null,
+    "cSHAKE128": "CShakeParams",
+    "cSHAKE256": "CShakeParams",
   },
   "generateKey": {
     "RSASSA-PKCS1-v1_5": "RsaHashedKeyGenParams",
@@ -98,23 +113,37 @@ const supportedAlgorithms = {
     "AES-CBC": "AesKeyGenParams",
     "AES-GCM": "AesKeyGenParams",
     "AES-KW": "AesKeyGenParams",
+    "AES-KWP": "AesKeyGenParams",
     "HMAC": "HmacKeyGenParams",
+    "KMAC128": "KmacKeyGenParams",
+    "KMAC256": "KmacKeyGenParams",
     "X25519": null,
     "Ed25519": null,
+    "X448": null,
+    "Ed448": null,
+    "ChaCha20-Poly1305": null,
+    "XChaCha20-Poly1305": null,
+    "AES-256-GCM-SIV": null,
   },
   "sign": {
     "RSASSA-PKCS1-v1_5": null,
     "RSA-PSS": "RsaPssParams",
     "ECDSA": "EcdsaParams",
     "HMAC": null,
+    "KMAC128": "KmacParams",
+    "KMAC256": "KmacParams",
     "Ed25519": null,
+    "Ed448": null,
   },
   "verify": {
     "RSASSA-PKCS1-v1_5": null,
     "RSA-PSS": "RsaPssParams",
     "ECDSA": "EcdsaParams",
     "HMAC": null,
+    "KMAC128": "KmacParams",
+    "KMAC256": "KmacParams",
     "Ed25519": null,
+    "Ed448": null,
   },
   "importKey": {
     "RSASSA-PKCS1-v1_5": "RsaHashedImportParams",
@@ -123,50 +152,112 @@ const supportedAlgorithms = {
     "ECDSA": "EcKeyImportParams",
     "ECDH": "EcKeyImportParams",
     "HMAC": "HmacImportParams",
+    "KMAC128": null,
+    "KMAC256": null,
     "HKDF": null,
     "PBKDF2": null,
+    "Argon2id": null,
+    "scrypt": null,
     "AES-CTR": null,
     "AES-CBC": null,
     "AES-GCM": null,
     "AES-KW": null,
+    "AES-KWP": null,
     "Ed25519": null,
     "X25519": null,
+    "Ed448": null,
+    "X448": null,
+    "ChaCha20-Poly1305": null,
+    "XChaCha20-Poly1305": null,
+    "AES-256-GCM-SIV": null,
   },
   "deriveBits": {
     "HKDF": "HkdfParams",
     "PBKDF2": "Pbkdf2Params",
+    "Argon2id": "Argon2Params",
+    "scrypt": "ScryptParams",
     "ECDH": "EcdhKeyDeriveParams",
     "X25519": "EcdhKeyDeriveParams",
+    "X448": "EcdhKeyDeriveParams",
   },
   "encrypt": {
     "RSA-OAEP": "RsaOaepParams",
     "AES-CBC": "AesCbcParams",
     "AES-GCM": "AesGcmParams",
     "AES-CTR": "AesCtrParams",
+    "ChaCha20-Poly1305": "ChaCha20Poly1305Params",
+    "XChaCha20-Poly1305": "ChaCha20Poly1305Params",
+    "AES-256-GCM-SIV": "ChaCha20Poly1305Params",
   },
   "decrypt": {
     "RSA-OAEP": "RsaOaepParams",
     "AES-CBC": "AesCbcParams",
     "AES-GCM": "AesGcmParams",
     "AES-CTR": "AesCtrParams",
+    "ChaCha20-Poly1305": "ChaCha20Poly1305Params",
+    "XChaCha20-Poly1305": "ChaCha20Poly1305Params",
+    "AES-256-GCM-SIV": "ChaCha20Poly1305Params",
   },
   "get key length": {
     "AES-CBC": "AesDerivedKeyParams",
     "AES-CTR": "AesDerivedKeyParams",
     "AES-GCM": "AesDerivedKeyParams",
     "AES-KW": "AesDerivedKeyParams",
+    "AES-KWP": "AesDerivedKeyParams",
     "HMAC": "HmacImportParams",
     "HKDF": null,
     "PBKDF2": null,
+    "Argon2id": null,
+    "scrypt": null,
   },
   "wrapKey": {
     "AES-KW": null,
+    "AES-KWP": null,
+    "RSA-OAEP": "RsaOaepParams",
+    "AES-GCM": "AesGcmParams",
   },
   "unwrapKey": {
     "AES-KW": null,
+    "AES-KWP": null,
+    "RSA-OAEP": "RsaOaepParams",
+    "AES-GCM": "AesGcmParams",
   },
 };
 
+// Algorithms approved by FIPS 140-3 (SP 800-140C), the only ones available
+// when the runtime is started with `DENO_FIPS=1`.
+const fipsApprovedAlgorithms = [
+  "SHA-1",
+  "SHA-256",
+  "SHA-384",
+  "SHA-512",
+  "cSHAKE128",
+  "cSHAKE256",
+  "RSASSA-PKCS1-v1_5",
+  "RSA-PSS",
+  "RSA-OAEP",
+  "ECDSA",
+  "ECDH",
+  "AES-CTR",
+  "AES-CBC",
+  "AES-GCM",
+  "AES-KW",
+  "AES-KWP",
+  "HMAC",
+  "KMAC128",
+  "KMAC256",
+  "HKDF",
+  "PBKDF2",
+];
+
+let fipsEnabled;
+function isFipsEnabled() {
+  if (fipsEnabled === undefined) {
+    fipsEnabled = ops.op_crypto_fips_enabled();
+  }
+  return fipsEnabled;
+}
+
 const aesJwkAlg = {
   "AES-CTR": {
     128: "A128CTR",
@@ -188,6 +279,38 @@ const aesJwkAlg = {
     192: "A192KW",
     256: "A256KW",
   },
+  // JOSE has no registered "alg" value for RFC 5649 key wrapping.
+  "AES-KWP": {},
+};
+
+// See https://www.rfc-editor.org/rfc/rfc7518#section-4.7 and
+// https://datatracker.ietf.org/doc/html/draft-irtf-cfrg-xchacha
+// JOSE has no registered "alg" value for AES-GCM-SIV (RFC 8452).
+const chachaJwkAlg = {
+  "ChaCha20-Poly1305": "C20P",
+  "XChaCha20-Poly1305": "XC20P",
+};
+
+// Output length in bytes of each hash function.
+const hashOutputLength = {
+  "SHA-1": 20,
+  "SHA-256": 32,
+  "SHA-384": 48,
+  "SHA-512": 64,
+};
+
+// https://wicg.github.io/webcrypto-modern-algos/#kmac
+const kmacJwkAlg = {
+  "KMAC128": "K128",
+  "KMAC256": "K256",
+};
+
+// 96-bit nonce for ChaCha20-Poly1305 and AES-256-GCM-SIV, 192-bit for
+// XChaCha20-Poly1305.
+const chachaNonceLength = {
+  "ChaCha20-Poly1305": 12,
+  "XChaCha20-Poly1305": 24,
+  "AES-256-GCM-SIV": 12,
 };
 
 // See https://www.w3.org/TR/WebCryptoAPI/#dfn-normalize-an-algorithm
@@ -227,6 +350,14 @@ function normalizeAlgorithm(algorithm, op) {
       "NotSupportedError",
     );
   }
+  if (
+    isFipsEnabled() && !ArrayPrototypeIncludes(fipsApprovedAlgorithms, algName)
+  ) {
+    throw new DOMException(
+      "Algorithm is not approved in FIPS mode",
+      "NotSupportedError",
+    );
+  }
 
   // Fast path everything below if the registered dictionary is "None".
   if (desiredType === null) {
@@ -242,6 +373,13 @@ function normalizeAlgorithm(algorithm, op) {
   // 7.
   normalizedAlgorithm.name = algName;
 
+  if (isFipsEnabled() && normalizedAlgorithm.namedCurve === "secp256k1") {
+    throw new DOMException(
+      "Curve is not approved in FIPS mode",
+      "NotSupportedError",
+    );
+  }
+
   // 9.
   const dict = simpleAlgorithmDictionaries[desiredType];
   // 10.
@@ -395,16 +533,36 @@ function usageIntersection(a, b) {
   );
 }
 
-// TODO(lucacasonato): this should be moved to rust
-/** @type {WeakMap<object, object>} */
+/** @type {WeakMap<object, { type: string, handle: number }>} */
 const KEY_STORE = new SafeWeakMap();
 
+// Releases the Rust-side key material once the key is GC'ed.
+const KEY_HANDLE_REGISTRY = new SafeFinalizationRegistry((handle) => {
+  ops.op_crypto_key_release(handle);
+});
+
+/**
+ * Moves the key material to Rust when a key is created, so that JS only
+ * holds a handle to it. Rust refuses to export the private or secret parts
+ * of keys stored as non-extractable.
+ * @param {{ type: string, data: Uint8Array }} keyData
+ * @param {boolean} extractable
+ * @returns {{ type: string, handle: number }}
+ */
+function storeKey(keyData, extractable) {
+  const handle = ops.op_crypto_key_store(keyData, extractable);
+  const storedKey = { type: keyData.type, handle };
+  KEY_HANDLE_REGISTRY.register(storedKey, handle);
+  return storedKey;
+}
+
 function getKeyLength(algorithm) {
   switch (algorithm.name) {
     case "AES-CBC":
     case "AES-CTR":
     case "AES-GCM":
-    case "AES-KW": {
+    case "AES-KW":
+    case "AES-KWP": {
       // 1.
       if (!ArrayPrototypeIncludes([128, 192, 256], algorithm.length)) {
         throw new DOMException(
@@ -456,6 +614,10 @@ function getKeyLength(algorithm) {
       // 1.
       return null;
     }
+    case "Argon2id":
+    case "scrypt": {
+      return null;
+    }
     default:
       throw new TypeError("unreachable");
   }
@@ -486,11 +648,23 @@ class SubtleCrypto {
 
     algorithm = normalizeAlgorithm(algorithm, "digest");
 
-    const result = await core.opAsync(
-      "op_crypto_subtle_digest",
-      algorithm.name,
-      data,
-    );
+    let result;
+    if (
+      algorithm.name === "cSHAKE128" || algorithm.name === "cSHAKE256"
+    ) {
+      result = await core.opAsync("op_crypto_cshake", {
+        algorithm: algorithm.name,
+        length: algorithm.length,
+        functionName: algorithm.functionName || null,
+        customization: algorithm.customization || null,
+      }, data);
+    } else {
+      result = await core.opAsync(
+        "op_crypto_subtle_digest",
+        algorithm.name,
+        data,
+      );
+    }
 
     return TypedArrayPrototypeGetBuffer(result);
   }
@@ -736,6 +910,48 @@ class SubtleCrypto {
         // 9.
         return TypedArrayPrototypeGetBuffer(plaintext);
       }
+      case "ChaCha20-Poly1305":
+      case "XChaCha20-Poly1305":
+      case "AES-256-GCM-SIV": {
+        normalizedAlgorithm.iv = copyBuffer(normalizedAlgorithm.iv);
+
+        // 1.
+        if (
+          TypedArrayPrototypeGetByteLength(normalizedAlgorithm.iv) !==
+            chachaNonceLength[normalizedAlgorithm.name]
+        ) {
+          throw new DOMException(
+            "Initialization vector length not supported",
+            "OperationError",
+          );
+        }
+
+        // 2. The authentication tag is always 128 bits.
+        if (TypedArrayPrototypeGetByteLength(data) < 16) {
+          throw new DOMException(
+            "Tag length overflows ciphertext",
+            "OperationError",
+          );
+        }
+
+        // 3.
+        if (normalizedAlgorithm.additionalData !== undefined) {
+          normalizedAlgorithm.additionalData = copyBuffer(
+            normalizedAlgorithm.additionalData,
+          );
+        }
+
+        // 4-5.
+        const plaintext = await core.opAsync("op_crypto_decrypt", {
+          key: keyData,
+          algorithm: normalizedAlgorithm.name,
+          iv: normalizedAlgorithm.iv,
+          additionalData: normalizedAlgorithm.additionalData || null,
+        }, data);
+
+        // 6.
+        return TypedArrayPrototypeGetBuffer(plaintext);
+      }
       default:
         throw new DOMException("Not implemented", "NotSupportedError");
     }
@@ -844,7 +1060,11 @@ class SubtleCrypto {
           (key[_algorithm].namedCurve === "P-256" &&
             hashAlgorithm !== "SHA-256") ||
           (key[_algorithm].namedCurve === "P-384" &&
-            hashAlgorithm !== "SHA-384")
+            hashAlgorithm !== "SHA-384") ||
+          (key[_algorithm].namedCurve === "P-521" &&
+            hashAlgorithm !== "SHA-512") ||
+          (key[_algorithm].namedCurve === "secp256k1" &&
+            hashAlgorithm !== "SHA-256")
         ) {
           throw new DOMException(
             "Not implemented",
@@ -872,6 +1092,17 @@ class SubtleCrypto {
 
         return TypedArrayPrototypeGetBuffer(signature);
       }
+      case "KMAC128":
+      case "KMAC256": {
+        const signature = await core.opAsync("op_crypto_sign_key", {
+          key: keyData,
+          algorithm: normalizedAlgorithm.name,
+          length: normalizedAlgorithm.length,
+          customization: normalizedAlgorithm.customization || null,
+        }, data);
+
+        return TypedArrayPrototypeGetBuffer(signature);
+      }
       case "Ed25519": {
         // 1.
         if (key[_type] !== "private") {
@@ -892,6 +1123,26 @@ class SubtleCrypto {
         }
         return TypedArrayPrototypeGetBuffer(signature);
       }
+      case "Ed448": {
+        // 1.
+        if (key[_type] !== "private") {
+          throw new DOMException(
+            "Key type not supported",
+            "InvalidAccessError",
+          );
+        }
+
+        // https://www.rfc-editor.org/rfc/rfc8032#section-5.2.6
+        const SIGNATURE_LEN = 57 * 2;
+        const signature = new Uint8Array(SIGNATURE_LEN);
+        if (!ops.op_crypto_sign_ed448(keyData, data, signature)) {
+          throw new DOMException(
+            "Failed to sign",
+            "OperationError",
+          );
+        }
+        return TypedArrayPrototypeGetBuffer(signature);
+      }
     }
 
     throw new TypeError("unreachable");
@@ -961,6 +1212,16 @@ class SubtleCrypto {
           keyUsages,
         );
       }
+      case "KMAC128":
+      case "KMAC256": {
+        return importKeyKMAC(
+          format,
+          normalizedAlgorithm,
+          keyData,
+          extractable,
+          keyUsages,
+        );
+      }
       case "ECDH":
       case "ECDSA": {
         return importKeyEC(
@@ -988,6 +1249,12 @@ class SubtleCrypto {
       case "PBKDF2": {
         return importKeyPBKDF2(format, keyData, extractable, keyUsages);
       }
+      case "Argon2id": {
+        return importKeyArgon2id(format, keyData, extractable, keyUsages);
+      }
+      case "scrypt": {
+        return importKeyScrypt(format, keyData, extractable, keyUsages);
+      }
       case "AES-CTR":
       case "AES-CBC":
       case "AES-GCM": {
@@ -1000,7 +1267,8 @@ class SubtleCrypto {
           ["encrypt", "decrypt", "wrapKey", "unwrapKey"],
         );
       }
-      case "AES-KW": {
+      case "AES-KW":
+      case "AES-KWP": {
         return importKeyAES(
           format,
           normalizedAlgorithm,
@@ -1026,6 +1294,33 @@ class SubtleCrypto {
           keyUsages,
         );
       }
+      case "X448": {
+        return importKeyX448(
+          format,
+          keyData,
+          extractable,
+          keyUsages,
+        );
+      }
+      case "Ed448": {
+        return importKeyEd448(
+          format,
+          keyData,
+          extractable,
+          keyUsages,
+        );
+      }
+      case "ChaCha20-Poly1305":
+      case "XChaCha20-Poly1305":
+      case "AES-256-GCM-SIV": {
+        return importKeyChaCha20(
+          format,
+          normalizedAlgorithm,
+          keyData,
+          extractable,
+          keyUsages,
+        );
+      }
       default:
         throw new DOMException("Not implemented", "NotSupportedError");
     }
@@ -1057,6 +1352,11 @@ class SubtleCrypto {
         result = exportKeyHMAC(format, key, innerKey);
         break;
       }
+      case "KMAC128":
+      case "KMAC256": {
+        result = exportKeyKMAC(format, key, innerKey);
+        break;
+      }
       case "RSASSA-PKCS1-v1_5":
       case "RSA-PSS":
       case "RSA-OAEP": {
@@ -1076,13 +1376,28 @@ class SubtleCrypto {
         result = exportKeyX25519(format, key, innerKey);
         break;
       }
+      case "Ed448": {
+        result = exportKeyEd448(format, key, innerKey);
+        break;
+      }
+      case "X448": {
+        result = exportKeyX448(format, key, innerKey);
+        break;
+      }
       case "AES-CTR":
       case "AES-CBC":
       case "AES-GCM":
-      case "AES-KW": {
+      case "AES-KW":
+      case "AES-KWP": {
         result = exportKeyAES(format, key, innerKey);
         break;
       }
+      case "ChaCha20-Poly1305":
+      case "XChaCha20-Poly1305":
+      case "AES-256-GCM-SIV": {
+        result = exportKeyChaCha20(format, key, innerKey);
+        break;
+      }
       default:
         throw new DOMException("Not implemented", "NotSupportedError");
     }
@@ -1103,7 +1418,7 @@ class SubtleCrypto {
    * @param {number | null} length
    * @returns {Promise<ArrayBuffer>}
    */
-  async deriveBits(algorithm, baseKey, length) {
+  async deriveBits(algorithm, baseKey, length, options = {}) {
     webidl.assertBranded(this, SubtleCryptoPrototype);
     const prefix = "Failed to execute 'deriveBits' on 'SubtleCrypto'";
     webidl.requiredArguments(arguments.length, 3, prefix);
@@ -1120,7 +1435,12 @@ class SubtleCrypto {
     // 2.
     const normalizedAlgorithm = normalizeAlgorithm(algorithm, "deriveBits");
     // 4-6.
-    const result = await deriveBits(normalizedAlgorithm, baseKey, length);
+    const result = await deriveBits(
+      normalizedAlgorithm,
+      baseKey,
+      length,
+      options,
+    );
     // 7.
     if (normalizedAlgorithm.name !== baseKey[_algorithm].name) {
       throw new DOMException("Invalid algorithm name", "InvalidAccessError");
@@ -1324,6 +1644,16 @@ class SubtleCrypto {
           signature,
         }, data);
       }
+      case "KMAC128":
+      case "KMAC256": {
+        return await core.opAsync("op_crypto_verify_key", {
+          key: keyData,
+          algorithm: normalizedAlgorithm.name,
+          signature,
+          length: normalizedAlgorithm.length,
+          customization: normalizedAlgorithm.customization || null,
+        }, data);
+      }
       case "ECDSA": {
         // 1.
         if (key[_type] !== "public") {
@@ -1337,7 +1667,9 @@ class SubtleCrypto {
 
         if (
           (key[_algorithm].namedCurve === "P-256" && hash !== "SHA-256") ||
-          (key[_algorithm].namedCurve === "P-384" && hash !== "SHA-384")
+          (key[_algorithm].namedCurve === "P-384" && hash !== "SHA-384") ||
+          (key[_algorithm].namedCurve === "P-521" && hash !== "SHA-512") ||
+          (key[_algorithm].namedCurve === "secp256k1" && hash !== "SHA-256")
         ) {
           throw new DOMException(
             "Not implemented",
@@ -1365,6 +1697,17 @@ class SubtleCrypto {
 
         return ops.op_crypto_verify_ed25519(keyData, data, signature);
       }
+      case "Ed448": {
+        // 1.
+        if (key[_type] !== "public") {
+          throw new DOMException(
+            "Key type not supported",
+            "InvalidAccessError",
+          );
+        }
+
+        return ops.op_crypto_verify_ed448(keyData, data, signature);
+      }
     }
 
     throw new TypeError("unreachable");
@@ -1452,7 +1795,8 @@ class SubtleCrypto {
       const keyData = WeakMapPrototypeGet(KEY_STORE, handle);
 
       switch (normalizedAlgorithm.name) {
-        case "AES-KW": {
+        case "AES-KW":
+        case "AES-KWP": {
           const cipherText = await ops.op_crypto_wrap_key({
             key: keyData,
             algorithm: normalizedAlgorithm.name,
@@ -1461,6 +1805,36 @@ class SubtleCrypto {
           // 4.
           return TypedArrayPrototypeGetBuffer(cipherText);
         }
+        case "RSA-OAEP": {
+          if (wrappingKey[_type] !== "public") {
+            throw new DOMException(
+              "Key type not supported",
+              "InvalidAccessError",
+            );
+          }
+
+          const cipherText = ops.op_crypto_wrap_key({
+            key: keyData,
+            algorithm: "RSA-OAEP",
+            hash: wrappingKey[_algorithm].hash.name,
+            label: normalizedAlgorithm.label
+              ? copyBuffer(normalizedAlgorithm.label)
+              : new Uint8Array(),
+          }, bytes);
+
+          return TypedArrayPrototypeGetBuffer(cipherText);
+        }
+        case "AES-GCM": {
+          const params = aesGcmWrapParams(normalizedAlgorithm);
+          const cipherText = ops.op_crypto_wrap_key({
+            key: keyData,
+            algorithm: "AES-GCM",
+            length: wrappingKey[_algorithm].length,
+            ...params,
+          }, bytes);
+
+          return TypedArrayPrototypeGetBuffer(cipherText);
+        }
         default: {
           throw new DOMException(
             "Not implemented",
@@ -1584,7 +1958,8 @@ class SubtleCrypto {
       const keyData = WeakMapPrototypeGet(KEY_STORE, handle);
 
       switch (normalizedAlgorithm.name) {
-        case "AES-KW": {
+        case "AES-KW":
+        case "AES-KWP": {
           const plainText = await ops.op_crypto_unwrap_key({
             key: keyData,
             algorithm: normalizedAlgorithm.name,
@@ -1594,6 +1969,38 @@ class SubtleCrypto {
           key = TypedArrayPrototypeGetBuffer(plainText);
           break;
         }
+        case "RSA-OAEP": {
+          if (unwrappingKey[_type] !== "private") {
+            throw new DOMException(
+              "Key type not supported",
+              "InvalidAccessError",
+            );
+          }
+
+          const plainText = ops.op_crypto_unwrap_key({
+            key: keyData,
+            algorithm: "RSA-OAEP",
+            hash: unwrappingKey[_algorithm].hash.name,
+            label: normalizedAlgorithm.label
+              ? copyBuffer(normalizedAlgorithm.label)
+              : new Uint8Array(),
+          }, wrappedKey);
+
+          key = TypedArrayPrototypeGetBuffer(plainText);
+          break;
+        }
+        case "AES-GCM": {
+          const params = aesGcmWrapParams(normalizedAlgorithm);
+          const plainText = ops.op_crypto_unwrap_key({
+            key: keyData,
+            algorithm: "AES-GCM",
+            length: unwrappingKey[_algorithm].length,
+            ...params,
+          }, wrappedKey);
+
+          key = TypedArrayPrototypeGetBuffer(plainText);
+          break;
+        }
         default: {
           throw new DOMException(
             "Not implemented",
@@ -1665,7 +2072,7 @@ class SubtleCrypto {
    * @param {KeyUsage[]} keyUsages
    * @returns {Promise<any>}
    */
-  async generateKey(algorithm, extractable, keyUsages) {
+  async generateKey(algorithm, extractable, keyUsages, options = {}) {
     webidl.assertBranded(this, SubtleCryptoPrototype);
     const prefix = "Failed to execute 'generateKey' on 'SubtleCrypto'";
     webidl.requiredArguments(arguments.length, 3, prefix);
@@ -1687,11 +2094,17 @@ class SubtleCrypto {
 
     const usages = keyUsages;
 
+    // Non-standard: RSA key generation can take seconds, so Deno allows
+    // aborting it.
+    const signal = options?.signal;
+    signal?.throwIfAborted();
+
     const normalizedAlgorithm = normalizeAlgorithm(algorithm, "generateKey");
     const result = await generateKey(
       normalizedAlgorithm,
       extractable,
       usages,
+      signal,
     );
 
     if (ObjectPrototypeIsPrototypeOf(CryptoKeyPrototype, result)) {
@@ -1712,7 +2125,77 @@ class SubtleCrypto {
 }
 const SubtleCryptoPrototype = SubtleCrypto.prototype;
 
-async function generateKey(normalizedAlgorithm, extractable, usages) {
+// How often `onProgress` callbacks are called, in milliseconds.
+const PROGRESS_INTERVAL = 100;
+
+/**
+ * Non-standard: runs an op that takes long enough to be worth aborting with
+ * `options.signal`, or reporting progress for to `options.onProgress`.
+ * @param {{ signal?: AbortSignal, onProgress?: Function }} options
+ * @param {number} total the amount of work reported once the op completes
+ * @param {(progressRid?: number, cancelRid?: number) => Promise<any>} fn
+ */
+async function runLongOperation(options, total, fn) {
+  const signal = options?.signal;
+  const onProgress = options?.onProgress;
+  signal?.throwIfAborted();
+
+  let cancelRid;
+  let abortHandler;
+  if (signal) {
+    cancelRid = ops.op_cancel_handle();
+    abortHandler = () => core.tryClose(cancelRid);
+    signal[abortSignal.add](abortHandler);
+  }
+
+  let progressRid;
+  let progressInterval;
+  if (onProgress) {
+    progressRid = ops.op_crypto_progress_create();
+    progressInterval = setInterval(
+      () => onProgress(ops.op_crypto_progress(progressRid), total),
+      PROGRESS_INTERVAL,
+    );
+  }
+
+  try {
+    const result = await fn(progressRid, cancelRid);
+    if (onProgress) {
+      onProgress(ops.op_crypto_progress(progressRid), total);
+    }
+    return result;
+  } finally {
+    if (onProgress) {
+      clearInterval(progressInterval);
+      core.close(progressRid);
+    }
+    if (signal) {
+      signal[abortSignal.remove](abortHandler);
+
+      // always throw the abort error when aborted
+      signal.throwIfAborted();
+    }
+  }
+}
+
+function generateKeyDataRSA(normalizedAlgorithm, signal) {
+  return runLongOperation(
+    { signal },
+    undefined,
+    (_progressRid, cancelRid) =>
+      core.opAsync(
+        "op_crypto_generate_key",
+        {
+          algorithm: "RSA",
+          modulusLength: normalizedAlgorithm.modulusLength,
+          publicExponent: normalizedAlgorithm.publicExponent,
+        },
+        cancelRid,
+      ),
+  );
+}
+
+async function generateKey(normalizedAlgorithm, extractable, usages, signal) {
   const algorithmName = normalizedAlgorithm.name;
 
   switch (algorithmName) {
@@ -1729,19 +2212,12 @@ async function generateKey(normalizedAlgorithm, extractable, usages) {
       }
 
       // 2.
-      const keyData = await core.opAsync(
-        "op_crypto_generate_key",
-        {
-          algorithm: "RSA",
-          modulusLength: normalizedAlgorithm.modulusLength,
-          publicExponent: normalizedAlgorithm.publicExponent,
-        },
-      );
+      const keyData = await generateKeyDataRSA(normalizedAlgorithm, signal);
       const handle = {};
-      WeakMapPrototypeSet(KEY_STORE, handle, {
+      WeakMapPrototypeSet(KEY_STORE, handle, storeKey({
         type: "private",
         data: keyData,
-      });
+      }, extractable));
 
       // 4-8.
       const algorithm = {
@@ -1789,19 +2265,12 @@ async function generateKey(normalizedAlgorithm, extractable, usages) {
       }
 
       // 2.
-      const keyData = await core.opAsync(
-        "op_crypto_generate_key",
-        {
-          algorithm: "RSA",
-          modulusLength: normalizedAlgorithm.modulusLength,
-          publicExponent: normalizedAlgorithm.publicExponent,
-        },
-      );
+      const keyData = await generateKeyDataRSA(normalizedAlgorithm, signal);
       const handle = {};
-      WeakMapPrototypeSet(KEY_STORE, handle, {
+      WeakMapPrototypeSet(KEY_STORE, handle, storeKey({
         type: "private",
         data: keyData,
-      });
+      }, extractable));
 
       // 4-8.
       const algorithm = {
@@ -1857,10 +2326,10 @@ async function generateKey(normalizedAlgorithm, extractable, usages) {
           algorithm: "EC",
           namedCurve,
         });
-        WeakMapPrototypeSet(KEY_STORE, handle, {
+        WeakMapPrototypeSet(KEY_STORE, handle, storeKey({
           type: "private",
           data: keyData,
-        });
+        }, extractable));
       } else {
         throw new DOMException("Curve not supported", "NotSupportedError");
       }
@@ -1917,10 +2386,10 @@ async function generateKey(normalizedAlgorithm, extractable, usages) {
           algorithm: "EC",
           namedCurve,
         });
-        WeakMapPrototypeSet(KEY_STORE, handle, {
+        WeakMapPrototypeSet(KEY_STORE, handle, storeKey({
           type: "private",
           data: keyData,
-        });
+        }, extractable));
       } else {
         throw new DOMException("Curve not supported", "NotSupportedError");
       }
@@ -1973,7 +2442,8 @@ async function generateKey(normalizedAlgorithm, extractable, usages) {
 
       return generateKeyAES(normalizedAlgorithm, extractable, usages);
     }
-    case "AES-KW": {
+    case "AES-KW":
+    case "AES-KWP": {
       // 1.
       if (
         ArrayPrototypeFind(
@@ -1986,6 +2456,27 @@ async function generateKey(normalizedAlgorithm, extractable, usages) {
 
       return generateKeyAES(normalizedAlgorithm, extractable, usages);
     }
+    case "ChaCha20-Poly1305":
+    case "XChaCha20-Poly1305":
+    case "AES-256-GCM-SIV": {
+      // 1.
+      if (
+        ArrayPrototypeFind(
+          usages,
+          (u) =>
+            !ArrayPrototypeIncludes([
+              "encrypt",
+              "decrypt",
+              "wrapKey",
+              "unwrapKey",
+            ], u),
+        ) !== undefined
+      ) {
+        throw new DOMException("Invalid key usages", "SyntaxError");
+      }
+
+      return generateKeyChaCha20(normalizedAlgorithm, extractable, usages);
+    }
     case "X25519": {
       if (
         ArrayPrototypeFind(
@@ -2000,10 +2491,16 @@ async function generateKey(normalizedAlgorithm, extractable, usages) {
       ops.op_crypto_generate_x25519_keypair(privateKeyData, publicKeyData);
 
       const handle = {};
-      WeakMapPrototypeSet(KEY_STORE, handle, privateKeyData);
+      WeakMapPrototypeSet(KEY_STORE, handle, storeKey({
+        type: "private",
+        data: privateKeyData,
+      }, extractable));
 
       const publicHandle = {};
-      WeakMapPrototypeSet(KEY_STORE, publicHandle, publicKeyData);
+      WeakMapPrototypeSet(KEY_STORE, publicHandle, storeKey({
+        type: "public",
+        data: publicKeyData,
+      }, true));
 
       const algorithm = {
         name: algorithmName,
@@ -2048,10 +2545,16 @@ async function generateKey(normalizedAlgorithm, extractable, usages) {
       }
 
       const handle = {};
-      WeakMapPrototypeSet(KEY_STORE, handle, privateKeyData);
+      WeakMapPrototypeSet(KEY_STORE, handle, storeKey({
+        type: "private",
+        data: privateKeyData,
+      }, extractable));
 
       const publicHandle = {};
-      WeakMapPrototypeSet(KEY_STORE, publicHandle, publicKeyData);
+      WeakMapPrototypeSet(KEY_STORE, publicHandle, storeKey({
+        type: "public",
+        data: publicKeyData,
+      }, true));
 
       const algorithm = {
         name: algorithmName,
@@ -2075,19 +2578,124 @@ async function generateKey(normalizedAlgorithm, extractable, usages) {
 
       return { publicKey, privateKey };
     }
-    case "HMAC": {
-      // 1.
+    case "X448": {
       if (
         ArrayPrototypeFind(
           usages,
-          (u) => !ArrayPrototypeIncludes(["sign", "verify"], u),
+          (u) => !ArrayPrototypeIncludes(["deriveKey", "deriveBits"], u),
         ) !== undefined
       ) {
         throw new DOMException("Invalid key usages", "SyntaxError");
       }
-
-      // 2.
-      let length;
+      const privateKeyData = new Uint8Array(56);
+      const publicKeyData = new Uint8Array(56);
+      if (
+        !ops.op_crypto_generate_x448_keypair(privateKeyData, publicKeyData)
+      ) {
+        throw new DOMException("Failed to generate key", "OperationError");
+      }
+
+      const handle = {};
+      WeakMapPrototypeSet(KEY_STORE, handle, storeKey({
+        type: "private",
+        data: privateKeyData,
+      }, extractable));
+
+      const publicHandle = {};
+      WeakMapPrototypeSet(KEY_STORE, publicHandle, storeKey({
+        type: "public",
+        data: publicKeyData,
+      }, true));
+
+      const algorithm = {
+        name: algorithmName,
+      };
+
+      const publicKey = constructKey(
+        "public",
+        true,
+        usageIntersection(usages, []),
+        algorithm,
+        publicHandle,
+      );
+
+      const privateKey = constructKey(
+        "private",
+        extractable,
+        usageIntersection(usages, ["deriveKey", "deriveBits"]),
+        algorithm,
+        handle,
+      );
+
+      return { publicKey, privateKey };
+    }
+    case "Ed448": {
+      if (
+        ArrayPrototypeFind(
+          usages,
+          (u) => !ArrayPrototypeIncludes(["sign", "verify"], u),
+        ) !== undefined
+      ) {
+        throw new DOMException("Invalid key usages", "SyntaxError");
+      }
+
+      const ED448_SEED_LEN = 57;
+      const ED448_PUBLIC_KEY_LEN = 57;
+      const privateKeyData = new Uint8Array(ED448_SEED_LEN);
+      const publicKeyData = new Uint8Array(ED448_PUBLIC_KEY_LEN);
+      if (
+        !ops.op_crypto_generate_ed448_keypair(privateKeyData, publicKeyData)
+      ) {
+        throw new DOMException("Failed to generate key", "OperationError");
+      }
+
+      const handle = {};
+      WeakMapPrototypeSet(KEY_STORE, handle, storeKey({
+        type: "private",
+        data: privateKeyData,
+      }, extractable));
+
+      const publicHandle = {};
+      WeakMapPrototypeSet(KEY_STORE, publicHandle, storeKey({
+        type: "public",
+        data: publicKeyData,
+      }, true));
+
+      const algorithm = {
+        name: algorithmName,
+      };
+
+      const publicKey = constructKey(
+        "public",
+        true,
+        usageIntersection(usages, ["verify"]),
+        algorithm,
+        publicHandle,
+      );
+
+      const privateKey = constructKey(
+        "private",
+        extractable,
+        usageIntersection(usages, ["sign"]),
+        algorithm,
+        handle,
+      );
+
+      return { publicKey, privateKey };
+    }
+    case "HMAC": {
+      // 1.
+      if (
+        ArrayPrototypeFind(
+          usages,
+          (u) => !ArrayPrototypeIncludes(["sign", "verify"], u),
+        ) !== undefined
+      ) {
+        throw new DOMException("Invalid key usages", "SyntaxError");
+      }
+
+      // 2.
+      let length;
       if (normalizedAlgorithm.length === undefined) {
         length = null;
       } else if (normalizedAlgorithm.length !== 0) {
@@ -2103,10 +2711,10 @@ async function generateKey(normalizedAlgorithm, extractable, usages) {
         length,
       });
       const handle = {};
-      WeakMapPrototypeSet(KEY_STORE, handle, {
+      WeakMapPrototypeSet(KEY_STORE, handle, storeKey({
         type: "secret",
         data: keyData,
-      });
+      }, extractable));
 
       // 6-10.
       const algorithm = {
@@ -2129,6 +2737,48 @@ async function generateKey(normalizedAlgorithm, extractable, usages) {
       // 14.
       return key;
     }
+    case "KMAC128":
+    case "KMAC256": {
+      if (
+        ArrayPrototypeFind(
+          usages,
+          (u) => !ArrayPrototypeIncludes(["sign", "verify"], u),
+        ) !== undefined
+      ) {
+        throw new DOMException("Invalid key usages", "SyntaxError");
+      }
+
+      // Defaults to the security strength of the algorithm.
+      let length = normalizedAlgorithm.length;
+      if (length === undefined) {
+        length = algorithmName === "KMAC128" ? 128 : 256;
+      } else if (length === 0 || length % 8 !== 0) {
+        throw new DOMException("Invalid length", "OperationError");
+      }
+
+      const keyData = await core.opAsync("op_crypto_generate_key", {
+        algorithm: "KMAC",
+        length,
+      });
+      const handle = {};
+      WeakMapPrototypeSet(KEY_STORE, handle, storeKey({
+        type: "secret",
+        data: keyData,
+      }, extractable));
+
+      const algorithm = {
+        name: algorithmName,
+        length,
+      };
+
+      return constructKey(
+        "secret",
+        extractable,
+        usages,
+        algorithm,
+        handle,
+      );
+    }
   }
 }
 
@@ -2151,7 +2801,10 @@ function importKeyEd25519(
       }
 
       const handle = {};
-      WeakMapPrototypeSet(KEY_STORE, handle, keyData);
+      WeakMapPrototypeSet(KEY_STORE, handle, storeKey({
+        type: "public",
+        data: keyData,
+      }, extractable));
 
       // 2-3.
       const algorithm = {
@@ -2184,7 +2837,10 @@ function importKeyEd25519(
       }
 
       const handle = {};
-      WeakMapPrototypeSet(KEY_STORE, handle, publicKeyData);
+      WeakMapPrototypeSet(KEY_STORE, handle, storeKey({
+        type: "public",
+        data: publicKeyData,
+      }, extractable));
 
       const algorithm = {
         name: "Ed25519",
@@ -2215,7 +2871,10 @@ function importKeyEd25519(
       }
 
       const handle = {};
-      WeakMapPrototypeSet(KEY_STORE, handle, privateKeyData);
+      WeakMapPrototypeSet(KEY_STORE, handle, storeKey({
+        type: "private",
+        data: privateKeyData,
+      }, extractable));
 
       const algorithm = {
         name: "Ed25519",
@@ -2327,7 +2986,10 @@ function importKeyEd25519(
         }
 
         const handle = {};
-        WeakMapPrototypeSet(KEY_STORE, handle, privateKeyData);
+        WeakMapPrototypeSet(KEY_STORE, handle, storeKey({
+          type: "private",
+          data: privateKeyData,
+        }, extractable));
 
         const algorithm = {
           name: "Ed25519",
@@ -2350,7 +3012,10 @@ function importKeyEd25519(
         }
 
         const handle = {};
-        WeakMapPrototypeSet(KEY_STORE, handle, publicKeyData);
+        WeakMapPrototypeSet(KEY_STORE, handle, storeKey({
+          type: "public",
+          data: publicKeyData,
+        }, extractable));
 
         const algorithm = {
           name: "Ed25519",
@@ -2384,7 +3049,10 @@ function importKeyX25519(
       }
 
       const handle = {};
-      WeakMapPrototypeSet(KEY_STORE, handle, keyData);
+      WeakMapPrototypeSet(KEY_STORE, handle, storeKey({
+        type: "public",
+        data: keyData,
+      }, extractable));
 
       // 2-3.
       const algorithm = {
@@ -2412,7 +3080,10 @@ function importKeyX25519(
       }
 
       const handle = {};
-      WeakMapPrototypeSet(KEY_STORE, handle, publicKeyData);
+      WeakMapPrototypeSet(KEY_STORE, handle, storeKey({
+        type: "public",
+        data: publicKeyData,
+      }, extractable));
 
       const algorithm = {
         name: "X25519",
@@ -2443,7 +3114,10 @@ function importKeyX25519(
       }
 
       const handle = {};
-      WeakMapPrototypeSet(KEY_STORE, handle, privateKeyData);
+      WeakMapPrototypeSet(KEY_STORE, handle, storeKey({
+        type: "private",
+        data: privateKeyData,
+      }, extractable));
 
       const algorithm = {
         name: "X25519",
@@ -2537,7 +3211,10 @@ function importKeyX25519(
         const privateKeyData = ops.op_crypto_base64url_decode(jwk.d);
 
         const handle = {};
-        WeakMapPrototypeSet(KEY_STORE, handle, privateKeyData);
+        WeakMapPrototypeSet(KEY_STORE, handle, storeKey({
+          type: "private",
+          data: privateKeyData,
+        }, extractable));
 
         const algorithm = {
           name: "X25519",
@@ -2555,7 +3232,10 @@ function importKeyX25519(
         const publicKeyData = ops.op_crypto_base64url_decode(jwk.x);
 
         const handle = {};
-        WeakMapPrototypeSet(KEY_STORE, handle, publicKeyData);
+        WeakMapPrototypeSet(KEY_STORE, handle, storeKey({
+          type: "public",
+          data: publicKeyData,
+        }, extractable));
 
         const algorithm = {
           name: "X25519",
@@ -2575,103 +3255,579 @@ function importKeyX25519(
   }
 }
 
-function exportKeyAES(
+function importKeyEd448(
   format,
-  key,
-  innerKey,
+  keyData,
+  extractable,
+  keyUsages,
 ) {
   switch (format) {
-    // 2.
     case "raw": {
       // 1.
-      const data = innerKey.data;
-      // 2.
-      return TypedArrayPrototypeGetBuffer(data);
-    }
-    case "jwk": {
-      // 1-2.
-      const jwk = {
-        kty: "oct",
-      };
-
-      // 3.
-      const data = ops.op_crypto_export_key({
-        format: "jwksecret",
-        algorithm: "AES",
-      }, innerKey);
-      ObjectAssign(jwk, data);
-
-      // 4.
-      const algorithm = key[_algorithm];
-      switch (algorithm.length) {
-        case 128:
-          jwk.alg = aesJwkAlg[algorithm.name][128];
-          break;
-        case 192:
-          jwk.alg = aesJwkAlg[algorithm.name][192];
-          break;
-        case 256:
-          jwk.alg = aesJwkAlg[algorithm.name][256];
-          break;
-        default:
-          throw new DOMException(
-            "Invalid key length",
-            "NotSupportedError",
-          );
+      if (
+        ArrayPrototypeFind(
+          keyUsages,
+          (u) => !ArrayPrototypeIncludes(["verify"], u),
+        ) !== undefined
+      ) {
+        throw new DOMException("Invalid key usages", "SyntaxError");
       }
 
-      // 5.
-      jwk.key_ops = key.usages;
+      const handle = {};
+      WeakMapPrototypeSet(KEY_STORE, handle, storeKey({
+        type: "public",
+        data: keyData,
+      }, extractable));
 
-      // 6.
-      jwk.ext = key[_extractable];
+      // 2-3.
+      const algorithm = {
+        name: "Ed448",
+      };
 
-      // 7.
-      return jwk;
+      // 4-6.
+      return constructKey(
+        "public",
+        extractable,
+        usageIntersection(keyUsages, recognisedUsages),
+        algorithm,
+        handle,
+      );
     }
-    default:
-      throw new DOMException("Not implemented", "NotSupportedError");
-  }
-}
+    case "spki": {
+      // 1.
+      if (
+        ArrayPrototypeFind(
+          keyUsages,
+          (u) => !ArrayPrototypeIncludes(["verify"], u),
+        ) !== undefined
+      ) {
+        throw new DOMException("Invalid key usages", "SyntaxError");
+      }
 
-function importKeyAES(
-  format,
-  normalizedAlgorithm,
-  keyData,
-  extractable,
-  keyUsages,
-  supportedKeyUsages,
-) {
-  // 1.
-  if (
-    ArrayPrototypeFind(
-      keyUsages,
-      (u) => !ArrayPrototypeIncludes(supportedKeyUsages, u),
-    ) !== undefined
-  ) {
-    throw new DOMException("Invalid key usages", "SyntaxError");
-  }
+      const publicKeyData = new Uint8Array(57);
+      if (!ops.op_crypto_import_spki_ed448(keyData, publicKeyData)) {
+        throw new DOMException("Invalid key data", "DataError");
+      }
 
-  const algorithmName = normalizedAlgorithm.name;
+      const handle = {};
+      WeakMapPrototypeSet(KEY_STORE, handle, storeKey({
+        type: "public",
+        data: publicKeyData,
+      }, extractable));
 
-  // 2.
-  let data = keyData;
+      const algorithm = {
+        name: "Ed448",
+      };
 
-  switch (format) {
-    case "raw": {
-      // 2.
+      return constructKey(
+        "public",
+        extractable,
+        usageIntersection(keyUsages, recognisedUsages),
+        algorithm,
+        handle,
+      );
+    }
+    case "pkcs8": {
+      // 1.
       if (
-        !ArrayPrototypeIncludes(
-          [128, 192, 256],
-          TypedArrayPrototypeGetByteLength(keyData) * 8,
-        )
+        ArrayPrototypeFind(
+          keyUsages,
+          (u) => !ArrayPrototypeIncludes(["sign"], u),
+        ) !== undefined
       ) {
-        throw new DOMException("Invalid key length", "DataError");
+        throw new DOMException("Invalid key usages", "SyntaxError");
       }
 
-      break;
-    }
-    case "jwk": {
+      const privateKeyData = new Uint8Array(57);
+      if (!ops.op_crypto_import_pkcs8_ed448(keyData, privateKeyData)) {
+        throw new DOMException("Invalid key data", "DataError");
+      }
+
+      const handle = {};
+      WeakMapPrototypeSet(KEY_STORE, handle, storeKey({
+        type: "private",
+        data: privateKeyData,
+      }, extractable));
+
+      const algorithm = {
+        name: "Ed448",
+      };
+
+      return constructKey(
+        "private",
+        extractable,
+        usageIntersection(keyUsages, recognisedUsages),
+        algorithm,
+        handle,
+      );
+    }
+    case "jwk": {
+      // 1.
+      const jwk = keyData;
+
+      // 2.
+      if (jwk.d !== undefined) {
+        if (
+          ArrayPrototypeFind(
+            keyUsages,
+            (u) =>
+              !ArrayPrototypeIncludes(
+                ["sign"],
+                u,
+              ),
+          ) !== undefined
+        ) {
+          throw new DOMException("Invalid key usages", "SyntaxError");
+        }
+      } else {
+        if (
+          ArrayPrototypeFind(
+            keyUsages,
+            (u) =>
+              !ArrayPrototypeIncludes(
+                ["verify"],
+                u,
+              ),
+          ) !== undefined
+        ) {
+          throw new DOMException("Invalid key usages", "SyntaxError");
+        }
+      }
+
+      // 3.
+      if (jwk.kty !== "OKP") {
+        throw new DOMException("Invalid key type", "DataError");
+      }
+
+      // 4.
+      if (jwk.crv !== "Ed448") {
+        throw new DOMException("Invalid curve", "DataError");
+      }
+
+      // 5.
+      if (jwk.alg !== undefined && jwk.alg !== "EdDSA") {
+        throw new DOMException("Invalid algorithm", "DataError");
+      }
+
+      // 6.
+      if (
+        keyUsages.length > 0 && jwk.use !== undefined && jwk.use !== "sig"
+      ) {
+        throw new DOMException("Invalid key usage", "DataError");
+      }
+
+      // 7.
+      if (jwk.key_ops !== undefined) {
+        if (
+          ArrayPrototypeFind(
+            jwk.key_ops,
+            (u) => !ArrayPrototypeIncludes(recognisedUsages, u),
+          ) !== undefined
+        ) {
+          throw new DOMException(
+            "'key_ops' property of JsonWebKey is invalid",
+            "DataError",
+          );
+        }
+
+        if (
+          !ArrayPrototypeEvery(
+            jwk.key_ops,
+            (u) => ArrayPrototypeIncludes(keyUsages, u),
+          )
+        ) {
+          throw new DOMException(
+            "'key_ops' property of JsonWebKey is invalid",
+            "DataError",
+          );
+        }
+      }
+
+      // 8.
+      if (jwk.ext !== undefined && jwk.ext === false && extractable) {
+        throw new DOMException("Invalid key extractability", "DataError");
+      }
+
+      // 9.
+      if (jwk.d !== undefined) {
+        // https://www.rfc-editor.org/rfc/rfc8037#section-2
+        let privateKeyData;
+        try {
+          privateKeyData = ops.op_crypto_base64url_decode(jwk.d);
+        } catch (_) {
+          throw new DOMException("invalid private key data", "DataError");
+        }
+
+        const handle = {};
+        WeakMapPrototypeSet(KEY_STORE, handle, storeKey({
+          type: "private",
+          data: privateKeyData,
+        }, extractable));
+
+        const algorithm = {
+          name: "Ed448",
+        };
+
+        return constructKey(
+          "private",
+          extractable,
+          usageIntersection(keyUsages, recognisedUsages),
+          algorithm,
+          handle,
+        );
+      } else {
+        // https://www.rfc-editor.org/rfc/rfc8037#section-2
+        let publicKeyData;
+        try {
+          publicKeyData = ops.op_crypto_base64url_decode(jwk.x);
+        } catch (_) {
+          throw new DOMException("invalid public key data", "DataError");
+        }
+
+        const handle = {};
+        WeakMapPrototypeSet(KEY_STORE, handle, storeKey({
+          type: "public",
+          data: publicKeyData,
+        }, extractable));
+
+        const algorithm = {
+          name: "Ed448",
+        };
+
+        return constructKey(
+          "public",
+          extractable,
+          usageIntersection(keyUsages, recognisedUsages),
+          algorithm,
+          handle,
+        );
+      }
+    }
+    default:
+      throw new DOMException("Not implemented", "NotSupportedError");
+  }
+}
+
+function importKeyX448(
+  format,
+  keyData,
+  extractable,
+  keyUsages,
+) {
+  switch (format) {
+    case "raw": {
+      // 1.
+      if (keyUsages.length > 0) {
+        throw new DOMException("Invalid key usages", "SyntaxError");
+      }
+
+      const handle = {};
+      WeakMapPrototypeSet(KEY_STORE, handle, storeKey({
+        type: "public",
+        data: keyData,
+      }, extractable));
+
+      // 2-3.
+      const algorithm = {
+        name: "X448",
+      };
+
+      // 4-6.
+      return constructKey(
+        "public",
+        extractable,
+        [],
+        algorithm,
+        handle,
+      );
+    }
+    case "spki": {
+      // 1.
+      if (keyUsages.length > 0) {
+        throw new DOMException("Invalid key usages", "SyntaxError");
+      }
+
+      const publicKeyData = new Uint8Array(56);
+      if (!ops.op_crypto_import_spki_x448(keyData, publicKeyData)) {
+        throw new DOMException("Invalid key data", "DataError");
+      }
+
+      const handle = {};
+      WeakMapPrototypeSet(KEY_STORE, handle, storeKey({
+        type: "public",
+        data: publicKeyData,
+      }, extractable));
+
+      const algorithm = {
+        name: "X448",
+      };
+
+      return constructKey(
+        "public",
+        extractable,
+        [],
+        algorithm,
+        handle,
+      );
+    }
+    case "pkcs8": {
+      // 1.
+      if (
+        ArrayPrototypeFind(
+          keyUsages,
+          (u) => !ArrayPrototypeIncludes(["deriveKey", "deriveBits"], u),
+        ) !== undefined
+      ) {
+        throw new DOMException("Invalid key usages", "SyntaxError");
+      }
+
+      const privateKeyData = new Uint8Array(56);
+      if (!ops.op_crypto_import_pkcs8_x448(keyData, privateKeyData)) {
+        throw new DOMException("Invalid key data", "DataError");
+      }
+
+      const handle = {};
+      WeakMapPrototypeSet(KEY_STORE, handle, storeKey({
+        type: "private",
+        data: privateKeyData,
+      }, extractable));
+
+      const algorithm = {
+        name: "X448",
+      };
+
+      return constructKey(
+        "private",
+        extractable,
+        usageIntersection(keyUsages, recognisedUsages),
+        algorithm,
+        handle,
+      );
+    }
+    case "jwk": {
+      // 1.
+      const jwk = keyData;
+
+      // 2.
+      if (jwk.d !== undefined) {
+        if (
+          ArrayPrototypeFind(
+            keyUsages,
+            (u) =>
+              !ArrayPrototypeIncludes(
+                ["deriveKey", "deriveBits"],
+                u,
+              ),
+          ) !== undefined
+        ) {
+          throw new DOMException("Invalid key usages", "SyntaxError");
+        }
+      }
+
+      // 3.
+      if (jwk.d === undefined && keyUsages.length > 0) {
+        throw new DOMException("Invalid key usages", "SyntaxError");
+      }
+
+      // 4.
+      if (jwk.kty !== "OKP") {
+        throw new DOMException("Invalid key type", "DataError");
+      }
+
+      // 5.
+      if (jwk.crv !== "X448") {
+        throw new DOMException("Invalid curve", "DataError");
+      }
+
+      // 6.
+      if (keyUsages.length > 0 && jwk.use !== undefined) {
+        if (jwk.use !== "enc") {
+          throw new DOMException("Invalid key use", "DataError");
+        }
+      }
+
+      // 7.
+      if (jwk.key_ops !== undefined) {
+        if (
+          ArrayPrototypeFind(
+            jwk.key_ops,
+            (u) => !ArrayPrototypeIncludes(recognisedUsages, u),
+          ) !== undefined
+        ) {
+          throw new DOMException(
+            "'key_ops' property of JsonWebKey is invalid",
+            "DataError",
+          );
+        }
+
+        if (
+          !ArrayPrototypeEvery(
+            jwk.key_ops,
+            (u) => ArrayPrototypeIncludes(keyUsages, u),
+          )
+        ) {
+          throw new DOMException(
+            "'key_ops' property of JsonWebKey is invalid",
+            "DataError",
+          );
+        }
+      }
+
+      // 8.
+      if (jwk.ext !== undefined && jwk.ext === false && extractable) {
+        throw new DOMException("Invalid key extractability", "DataError");
+      }
+
+      // 9.
+      if (jwk.d !== undefined) {
+        // https://www.rfc-editor.org/rfc/rfc8037#section-2
+        const privateKeyData = ops.op_crypto_base64url_decode(jwk.d);
+
+        const handle = {};
+        WeakMapPrototypeSet(KEY_STORE, handle, storeKey({
+          type: "private",
+          data: privateKeyData,
+        }, extractable));
+
+        const algorithm = {
+          name: "X448",
+        };
+
+        return constructKey(
+          "private",
+          extractable,
+          usageIntersection(keyUsages, ["deriveKey", "deriveBits"]),
+          algorithm,
+          handle,
+        );
+      } else {
+        // https://www.rfc-editor.org/rfc/rfc8037#section-2
+        const publicKeyData = ops.op_crypto_base64url_decode(jwk.x);
+
+        const handle = {};
+        WeakMapPrototypeSet(KEY_STORE, handle, storeKey({
+          type: "public",
+          data: publicKeyData,
+        }, extractable));
+
+        const algorithm = {
+          name: "X448",
+        };
+
+        return constructKey(
+          "public",
+          extractable,
+          [],
+          algorithm,
+          handle,
+        );
+      }
+    }
+    default:
+      throw new DOMException("Not implemented", "NotSupportedError");
+  }
+}
+
+function exportKeyAES(
+  format,
+  key,
+  innerKey,
+) {
+  switch (format) {
+    // 2.
+    case "raw": {
+      // 1.
+      const data = ops.op_crypto_export_key({
+        format: "raw",
+        algorithm: "AES",
+      }, innerKey);
+      // 2.
+      return TypedArrayPrototypeGetBuffer(data);
+    }
+    case "jwk": {
+      // 1-2.
+      const jwk = {
+        kty: "oct",
+      };
+
+      // 3.
+      const data = ops.op_crypto_export_key({
+        format: "jwksecret",
+        algorithm: "AES",
+      }, innerKey);
+      ObjectAssign(jwk, data);
+
+      // 4.
+      const algorithm = key[_algorithm];
+      switch (algorithm.length) {
+        case 128:
+          jwk.alg = aesJwkAlg[algorithm.name][128];
+          break;
+        case 192:
+          jwk.alg = aesJwkAlg[algorithm.name][192];
+          break;
+        case 256:
+          jwk.alg = aesJwkAlg[algorithm.name][256];
+          break;
+        default:
+          throw new DOMException(
+            "Invalid key length",
+            "NotSupportedError",
+          );
+      }
+
+      // 5.
+      jwk.key_ops = key.usages;
+
+      // 6.
+      jwk.ext = key[_extractable];
+
+      // 7.
+      return jwk;
+    }
+    default:
+      throw new DOMException("Not implemented", "NotSupportedError");
+  }
+}
+
+function importKeyAES(
+  format,
+  normalizedAlgorithm,
+  keyData,
+  extractable,
+  keyUsages,
+  supportedKeyUsages,
+) {
+  // 1.
+  if (
+    ArrayPrototypeFind(
+      keyUsages,
+      (u) => !ArrayPrototypeIncludes(supportedKeyUsages, u),
+    ) !== undefined
+  ) {
+    throw new DOMException("Invalid key usages", "SyntaxError");
+  }
+
+  const algorithmName = normalizedAlgorithm.name;
+
+  // 2.
+  let data = keyData;
+
+  switch (format) {
+    case "raw": {
+      // 2.
+      if (
+        !ArrayPrototypeIncludes(
+          [128, 192, 256],
+          TypedArrayPrototypeGetByteLength(keyData) * 8,
+        )
+      ) {
+        throw new DOMException("Invalid key length", "DataError");
+      }
+
+      break;
+    }
+    case "jwk": {
       // 1.
       const jwk = keyData;
 
@@ -2698,47 +3854,203 @@ function importKeyAES(
       );
       data = rawData.data;
 
-      // 5.
-      switch (TypedArrayPrototypeGetByteLength(data) * 8) {
-        case 128:
-          if (
-            jwk.alg !== undefined &&
-            jwk.alg !== aesJwkAlg[algorithmName][128]
-          ) {
-            throw new DOMException("Invalid algorithm", "DataError");
-          }
-          break;
-        case 192:
-          if (
-            jwk.alg !== undefined &&
-            jwk.alg !== aesJwkAlg[algorithmName][192]
-          ) {
-            throw new DOMException("Invalid algorithm", "DataError");
-          }
-          break;
-        case 256:
-          if (
-            jwk.alg !== undefined &&
-            jwk.alg !== aesJwkAlg[algorithmName][256]
-          ) {
-            throw new DOMException("Invalid algorithm", "DataError");
-          }
-          break;
-        default:
-          throw new DOMException(
-            "Invalid key length",
-            "DataError",
-          );
+      // 5.
+      switch (TypedArrayPrototypeGetByteLength(data) * 8) {
+        case 128:
+          if (
+            jwk.alg !== undefined &&
+            jwk.alg !== aesJwkAlg[algorithmName][128]
+          ) {
+            throw new DOMException("Invalid algorithm", "DataError");
+          }
+          break;
+        case 192:
+          if (
+            jwk.alg !== undefined &&
+            jwk.alg !== aesJwkAlg[algorithmName][192]
+          ) {
+            throw new DOMException("Invalid algorithm", "DataError");
+          }
+          break;
+        case 256:
+          if (
+            jwk.alg !== undefined &&
+            jwk.alg !== aesJwkAlg[algorithmName][256]
+          ) {
+            throw new DOMException("Invalid algorithm", "DataError");
+          }
+          break;
+        default:
+          throw new DOMException(
+            "Invalid key length",
+            "DataError",
+          );
+      }
+
+      // 6.
+      if (
+        keyUsages.length > 0 && jwk.use !== undefined && jwk.use !== "enc"
+      ) {
+        throw new DOMException("Invalid key usages", "DataError");
+      }
+
+      // 7.
+      // Section 4.3 of RFC7517
+      if (jwk.key_ops !== undefined) {
+        if (
+          ArrayPrototypeFind(
+            jwk.key_ops,
+            (u) => !ArrayPrototypeIncludes(recognisedUsages, u),
+          ) !== undefined
+        ) {
+          throw new DOMException(
+            "'key_ops' property of JsonWebKey is invalid",
+            "DataError",
+          );
+        }
+
+        if (
+          !ArrayPrototypeEvery(
+            jwk.key_ops,
+            (u) => ArrayPrototypeIncludes(keyUsages, u),
+          )
+        ) {
+          throw new DOMException(
+            "'key_ops' property of JsonWebKey is invalid",
+            "DataError",
+          );
+        }
+      }
+
+      // 8.
+      if (jwk.ext === false && extractable === true) {
+        throw new DOMException(
+          "'ext' property of JsonWebKey must not be false if extractable is true",
+          "DataError",
+        );
+      }
+
+      break;
+    }
+    default:
+      throw new DOMException("Not implemented", "NotSupportedError");
+  }
+
+  const handle = {};
+  WeakMapPrototypeSet(KEY_STORE, handle, storeKey({
+    type: "secret",
+    data,
+  }, extractable));
+
+  // 4-7.
+  const algorithm = {
+    name: algorithmName,
+    length: TypedArrayPrototypeGetByteLength(data) * 8,
+  };
+
+  const key = constructKey(
+    "secret",
+    extractable,
+    usageIntersection(keyUsages, recognisedUsages),
+    algorithm,
+    handle,
+  );
+
+  // 8.
+  return key;
+}
+
+function exportKeyChaCha20(format, key, innerKey) {
+  switch (format) {
+    case "raw": {
+      const data = ops.op_crypto_export_key({
+        format: "raw",
+        algorithm: "CHACHA20",
+      }, innerKey);
+      return TypedArrayPrototypeGetBuffer(data);
+    }
+    case "jwk": {
+      const jwk = {
+        kty: "oct",
+      };
+
+      const data = ops.op_crypto_export_key({
+        format: "jwksecret",
+        algorithm: "CHACHA20",
+      }, innerKey);
+      ObjectAssign(jwk, data);
+
+      const alg = chachaJwkAlg[key[_algorithm].name];
+      if (alg !== undefined) {
+        jwk.alg = alg;
+      }
+      jwk.key_ops = key.usages;
+      jwk.ext = key[_extractable];
+
+      return jwk;
+    }
+    default:
+      throw new DOMException("Not implemented", "NotSupportedError");
+  }
+}
+
+function importKeyChaCha20(
+  format,
+  normalizedAlgorithm,
+  keyData,
+  extractable,
+  keyUsages,
+) {
+  const supportedKeyUsages = ["encrypt", "decrypt", "wrapKey", "unwrapKey"];
+  if (
+    ArrayPrototypeFind(
+      keyUsages,
+      (u) => !ArrayPrototypeIncludes(supportedKeyUsages, u),
+    ) !== undefined
+  ) {
+    throw new DOMException("Invalid key usages", "SyntaxError");
+  }
+
+  const algorithmName = normalizedAlgorithm.name;
+
+  let data;
+  switch (format) {
+    case "raw": {
+      // ChaCha20 and AES-256-GCM-SIV keys are always 256 bits.
+      const { rawData } = ops.op_crypto_import_key(
+        { algorithm: "CHACHA20" },
+        { raw: keyData },
+      );
+      data = rawData.data;
+      break;
+    }
+    case "jwk": {
+      const jwk = keyData;
+
+      if (jwk.kty !== "oct") {
+        throw new DOMException(
+          "'kty' property of JsonWebKey must be 'oct'",
+          "DataError",
+        );
+      }
+
+      if (jwk.k === undefined) {
+        throw new DOMException(
+          "'k' property of JsonWebKey must be present",
+          "DataError",
+        );
+      }
+
+      if (jwk.alg !== undefined && jwk.alg !== chachaJwkAlg[algorithmName]) {
+        throw new DOMException("Invalid algorithm", "DataError");
       }
 
-      // 6.
       if (
         keyUsages.length > 0 && jwk.use !== undefined && jwk.use !== "enc"
       ) {
         throw new DOMException("Invalid key usages", "DataError");
       }
 
-      // 7.
       // Section 4.3 of RFC7517
       if (jwk.key_ops !== undefined) {
         if (
@@ -2766,7 +4078,6 @@ function importKeyAES(
         }
       }
 
-      // 8.
       if (jwk.ext === false && extractable === true) {
         throw new DOMException(
           "'ext' property of JsonWebKey must not be false if extractable is true",
@@ -2774,6 +4085,11 @@ function importKeyAES(
         );
       }
 
+      const { rawData } = ops.op_crypto_import_key(
+        { algorithm: "CHACHA20" },
+        { jwkSecret: jwk },
+      );
+      data = rawData.data;
       break;
     }
     default:
@@ -2781,27 +4097,22 @@ function importKeyAES(
   }
 
   const handle = {};
-  WeakMapPrototypeSet(KEY_STORE, handle, {
+  WeakMapPrototypeSet(KEY_STORE, handle, storeKey({
     type: "secret",
     data,
-  });
+  }, extractable));
 
-  // 4-7.
   const algorithm = {
     name: algorithmName,
-    length: TypedArrayPrototypeGetByteLength(data) * 8,
   };
 
-  const key = constructKey(
+  return constructKey(
     "secret",
     extractable,
     usageIntersection(keyUsages, recognisedUsages),
     algorithm,
     handle,
   );
-
-  // 8.
-  return key;
 }
 
 function importKeyHMAC(
@@ -2976,10 +4287,10 @@ function importKeyHMAC(
   }
 
   const handle = {};
-  WeakMapPrototypeSet(KEY_STORE, handle, {
+  WeakMapPrototypeSet(KEY_STORE, handle, storeKey({
     type: "secret",
     data,
-  });
+  }, extractable));
 
   const algorithm = {
     name: "HMAC",
@@ -2998,6 +4309,136 @@ function importKeyHMAC(
   return key;
 }
 
+function importKeyKMAC(
+  format,
+  normalizedAlgorithm,
+  keyData,
+  extractable,
+  keyUsages,
+) {
+  if (
+    ArrayPrototypeFind(
+      keyUsages,
+      (u) => !ArrayPrototypeIncludes(["sign", "verify"], u),
+    ) !== undefined
+  ) {
+    throw new DOMException("Invalid key usages", "SyntaxError");
+  }
+
+  const algorithmName = normalizedAlgorithm.name;
+
+  let data;
+  switch (format) {
+    case "raw": {
+      data = keyData;
+      break;
+    }
+    case "jwk": {
+      const jwk = keyData;
+
+      if (jwk.kty !== "oct") {
+        throw new DOMException(
+          "'kty' property of JsonWebKey must be 'oct'",
+          "DataError",
+        );
+      }
+
+      if (jwk.k === undefined) {
+        throw new DOMException(
+          "'k' property of JsonWebKey must be present",
+          "DataError",
+        );
+      }
+
+      const { rawData } = ops.op_crypto_import_key(
+        { algorithm: "HMAC" },
+        { jwkSecret: jwk },
+      );
+      data = rawData.data;
+
+      if (jwk.alg !== undefined && jwk.alg !== kmacJwkAlg[algorithmName]) {
+        throw new DOMException(
+          `'alg' property of JsonWebKey must be '${
+            kmacJwkAlg[algorithmName]
+          }'`,
+          "DataError",
+        );
+      }
+
+      if (
+        keyUsages.length > 0 && jwk.use !== undefined && jwk.use !== "sig"
+      ) {
+        throw new DOMException(
+          "'use' property of JsonWebKey must be 'sig'",
+          "DataError",
+        );
+      }
+
+      // Section 4.3 of RFC7517
+      if (jwk.key_ops !== undefined) {
+        if (
+          ArrayPrototypeFind(
+            jwk.key_ops,
+            (u) => !ArrayPrototypeIncludes(recognisedUsages, u),
+          ) !== undefined
+        ) {
+          throw new DOMException(
+            "'key_ops' property of JsonWebKey is invalid",
+            "DataError",
+          );
+        }
+
+        if (
+          !ArrayPrototypeEvery(
+            jwk.key_ops,
+            (u) => ArrayPrototypeIncludes(keyUsages, u),
+          )
+        ) {
+          throw new DOMException(
+            "'key_ops' property of JsonWebKey is invalid",
+            "DataError",
+          );
+        }
+      }
+
+      if (jwk.ext === false && extractable === true) {
+        throw new DOMException(
+          "'ext' property of JsonWebKey must not be false if extractable is true",
+          "DataError",
+        );
+      }
+
+      break;
+    }
+    default:
+      throw new DOMException("Not implemented", "NotSupportedError");
+  }
+
+  const length = TypedArrayPrototypeGetByteLength(data) * 8;
+  if (length === 0) {
+    throw new DOMException("Key length is zero", "DataError");
+  }
+
+  const handle = {};
+  WeakMapPrototypeSet(KEY_STORE, handle, storeKey({
+    type: "secret",
+    data,
+  }, extractable));
+
+  const algorithm = {
+    name: algorithmName,
+    length,
+  };
+
+  return constructKey(
+    "secret",
+    extractable,
+    usageIntersection(keyUsages, recognisedUsages),
+    algorithm,
+    handle,
+  );
+}
+
 function importKeyEC(
   format,
   normalizedAlgorithm,
@@ -3043,7 +4484,7 @@ function importKeyEC(
       }, { raw: keyData });
 
       const handle = {};
-      WeakMapPrototypeSet(KEY_STORE, handle, rawData);
+      WeakMapPrototypeSet(KEY_STORE, handle, storeKey(rawData, extractable));
 
       // 4-5.
       const algorithm = {
@@ -3084,7 +4525,7 @@ function importKeyEC(
       }, { pkcs8: keyData });
 
       const handle = {};
-      WeakMapPrototypeSet(KEY_STORE, handle, rawData);
+      WeakMapPrototypeSet(KEY_STORE, handle, storeKey(rawData, extractable));
 
       const algorithm = {
         name: normalizedAlgorithm.name,
@@ -3127,7 +4568,7 @@ function importKeyEC(
       }, { spki: keyData });
 
       const handle = {};
-      WeakMapPrototypeSet(KEY_STORE, handle, rawData);
+      WeakMapPrototypeSet(KEY_STORE, handle, storeKey(rawData, extractable));
 
       const algorithm = {
         name: normalizedAlgorithm.name,
@@ -3232,6 +4673,10 @@ function importKeyEC(
             algNamedCurve = "P-521";
             break;
           }
+          case "ES256K": {
+            algNamedCurve = "secp256k1";
+            break;
+          }
           default:
             throw new DOMException(
               "Curve algorithm not supported",
@@ -3271,7 +4716,7 @@ function importKeyEC(
         }, { jwkPrivateEc: jwk });
 
         const handle = {};
-        WeakMapPrototypeSet(KEY_STORE, handle, rawData);
+        WeakMapPrototypeSet(KEY_STORE, handle, storeKey(rawData, extractable));
 
         const algorithm = {
           name: normalizedAlgorithm.name,
@@ -3294,7 +4739,7 @@ function importKeyEC(
         }, { jwkPublicEc: jwk });
 
         const handle = {};
-        WeakMapPrototypeSet(KEY_STORE, handle, rawData);
+        WeakMapPrototypeSet(KEY_STORE, handle, storeKey(rawData, extractable));
 
         const algorithm = {
           name: normalizedAlgorithm.name,
@@ -3380,7 +4825,7 @@ function importKeyRSA(
         );
 
       const handle = {};
-      WeakMapPrototypeSet(KEY_STORE, handle, rawData);
+      WeakMapPrototypeSet(KEY_STORE, handle, storeKey(rawData, extractable));
 
       const algorithm = {
         name: normalizedAlgorithm.name,
@@ -3426,7 +4871,7 @@ function importKeyRSA(
         );
 
       const handle = {};
-      WeakMapPrototypeSet(KEY_STORE, handle, rawData);
+      WeakMapPrototypeSet(KEY_STORE, handle, storeKey(rawData, extractable));
 
       const algorithm = {
         name: normalizedAlgorithm.name,
@@ -3675,7 +5120,7 @@ function importKeyRSA(
           );
 
         const handle = {};
-        WeakMapPrototypeSet(KEY_STORE, handle, rawData);
+        WeakMapPrototypeSet(KEY_STORE, handle, storeKey(rawData, extractable));
 
         const algorithm = {
           name: normalizedAlgorithm.name,
@@ -3718,7 +5163,7 @@ function importKeyRSA(
           );
 
         const handle = {};
-        WeakMapPrototypeSet(KEY_STORE, handle, rawData);
+        WeakMapPrototypeSet(KEY_STORE, handle, storeKey(rawData, extractable));
 
         const algorithm = {
           name: normalizedAlgorithm.name,
@@ -3773,10 +5218,10 @@ function importKeyHKDF(
 
   // 3.
   const handle = {};
-  WeakMapPrototypeSet(KEY_STORE, handle, {
+  WeakMapPrototypeSet(KEY_STORE, handle, storeKey({
     type: "secret",
     data: keyData,
-  });
+  }, extractable));
 
   // 4-8.
   const algorithm = {
@@ -3825,25 +5270,113 @@ function importKeyPBKDF2(
 
   // 4.
   const handle = {};
-  WeakMapPrototypeSet(KEY_STORE, handle, {
+  WeakMapPrototypeSet(KEY_STORE, handle, storeKey({
     type: "secret",
     data: keyData,
-  });
+  }, extractable));
 
   // 5-9.
   const algorithm = {
-    name: "PBKDF2",
+    name: "PBKDF2",
+  };
+  const key = constructKey(
+    "secret",
+    false,
+    usageIntersection(keyUsages, recognisedUsages),
+    algorithm,
+    handle,
+  );
+
+  // 10.
+  return key;
+}
+
+function importKeyArgon2id(
+  format,
+  keyData,
+  extractable,
+  keyUsages,
+) {
+  if (format !== "raw") {
+    throw new DOMException("Format not supported", "NotSupportedError");
+  }
+
+  if (
+    ArrayPrototypeFind(
+      keyUsages,
+      (u) => !ArrayPrototypeIncludes(["deriveKey", "deriveBits"], u),
+    ) !== undefined
+  ) {
+    throw new DOMException("Invalid key usages", "SyntaxError");
+  }
+
+  if (extractable !== false) {
+    throw new DOMException(
+      "Key must not be extractable",
+      "SyntaxError",
+    );
+  }
+
+  const handle = {};
+  WeakMapPrototypeSet(KEY_STORE, handle, storeKey({
+    type: "secret",
+    data: keyData,
+  }, extractable));
+
+  const algorithm = {
+    name: "Argon2id",
+  };
+  return constructKey(
+    "secret",
+    false,
+    usageIntersection(keyUsages, recognisedUsages),
+    algorithm,
+    handle,
+  );
+}
+
+function importKeyScrypt(
+  format,
+  keyData,
+  extractable,
+  keyUsages,
+) {
+  if (format !== "raw") {
+    throw new DOMException("Format not supported", "NotSupportedError");
+  }
+
+  if (
+    ArrayPrototypeFind(
+      keyUsages,
+      (u) => !ArrayPrototypeIncludes(["deriveKey", "deriveBits"], u),
+    ) !== undefined
+  ) {
+    throw new DOMException("Invalid key usages", "SyntaxError");
+  }
+
+  if (extractable !== false) {
+    throw new DOMException(
+      "Key must not be extractable",
+      "SyntaxError",
+    );
+  }
+
+  const handle = {};
+  WeakMapPrototypeSet(KEY_STORE, handle, storeKey({
+    type: "secret",
+    data: keyData,
+  }, extractable));
+
+  const algorithm = {
+    name: "scrypt",
   };
-  const key = constructKey(
+  return constructKey(
     "secret",
     false,
     usageIntersection(keyUsages, recognisedUsages),
     algorithm,
     handle,
   );
-
-  // 10.
-  return key;
 }
 
 function exportKeyHMAC(format, key, innerKey) {
@@ -3855,7 +5388,10 @@ function exportKeyHMAC(format, key, innerKey) {
   switch (format) {
     // 3.
     case "raw": {
-      const bits = innerKey.data;
+      const bits = ops.op_crypto_export_key({
+        format: "raw",
+        algorithm: "HMAC",
+      }, innerKey);
       // TODO(petamoriken): Uint8Array doesn't have push method
       // for (let _i = 7 & (8 - bits.length % 8); _i > 0; _i--) {
       //   bits.push(0);
@@ -3912,6 +5448,41 @@ function exportKeyHMAC(format, key, innerKey) {
   }
 }
 
+function exportKeyKMAC(format, key, innerKey) {
+  if (innerKey == null) {
+    throw new DOMException("Key is not available", "OperationError");
+  }
+
+  switch (format) {
+    case "raw": {
+      const data = ops.op_crypto_export_key({
+        format: "raw",
+        algorithm: "HMAC",
+      }, innerKey);
+      return TypedArrayPrototypeGetBuffer(data);
+    }
+    case "jwk": {
+      const jwk = {
+        kty: "oct",
+      };
+
+      const data = ops.op_crypto_export_key({
+        format: "jwksecret",
+        algorithm: "HMAC",
+      }, innerKey);
+      jwk.k = data.k;
+
+      jwk.alg = kmacJwkAlg[key[_algorithm].name];
+      jwk.key_ops = key.usages;
+      jwk.ext = key[_extractable];
+
+      return jwk;
+    }
+    default:
+      throw new DOMException("Not implemented", "NotSupportedError");
+  }
+}
+
 function exportKeyRSA(format, key, innerKey) {
   switch (format) {
     case "pkcs8": {
@@ -4054,7 +5625,11 @@ function exportKeyEd25519(format, key, innerKey) {
       }
 
       // 2-3.
-      return TypedArrayPrototypeGetBuffer(innerKey);
+      const data = ops.op_crypto_export_key({
+        format: "raw",
+        algorithm: "Ed25519",
+      }, innerKey);
+      return TypedArrayPrototypeGetBuffer(data);
     }
     case "spki": {
       // 1.
@@ -4065,7 +5640,10 @@ function exportKeyEd25519(format, key, innerKey) {
         );
       }
 
-      const spkiDer = ops.op_crypto_export_spki_ed25519(innerKey);
+      const spkiDer = ops.op_crypto_export_key({
+        format: "spki",
+        algorithm: "Ed25519",
+      }, innerKey);
       return TypedArrayPrototypeGetBuffer(spkiDer);
     }
     case "pkcs8": {
@@ -4077,16 +5655,17 @@ function exportKeyEd25519(format, key, innerKey) {
         );
       }
 
-      const pkcs8Der = ops.op_crypto_export_pkcs8_ed25519(
-        new Uint8Array([0x04, 0x22, ...new SafeArrayIterator(innerKey)]),
-      );
-      pkcs8Der[15] = 0x20;
+      const pkcs8Der = ops.op_crypto_export_key({
+        format: "pkcs8",
+        algorithm: "Ed25519",
+      }, innerKey);
       return TypedArrayPrototypeGetBuffer(pkcs8Der);
     }
     case "jwk": {
-      const x = key[_type] === "private"
-        ? ops.op_crypto_jwk_x_ed25519(innerKey)
-        : ops.op_crypto_base64url_encode(innerKey);
+      const { x, d } = ops.op_crypto_export_key({
+        format: key[_type] === "private" ? "jwkprivate" : "jwkpublic",
+        algorithm: "Ed25519",
+      }, innerKey);
       const jwk = {
         kty: "OKP",
         alg: "EdDSA",
@@ -4096,7 +5675,7 @@ function exportKeyEd25519(format, key, innerKey) {
         ext: key[_extractable],
       };
       if (key[_type] === "private") {
-        jwk.d = ops.op_crypto_base64url_encode(innerKey);
+        jwk.d = d;
       }
       return jwk;
     }
@@ -4117,7 +5696,11 @@ function exportKeyX25519(format, key, innerKey) {
       }
 
       // 2-3.
-      return TypedArrayPrototypeGetBuffer(innerKey);
+      const data = ops.op_crypto_export_key({
+        format: "raw",
+        algorithm: "X25519",
+      }, innerKey);
+      return TypedArrayPrototypeGetBuffer(data);
     }
     case "spki": {
       // 1.
@@ -4128,7 +5711,10 @@ function exportKeyX25519(format, key, innerKey) {
         );
       }
 
-      const spkiDer = ops.op_crypto_export_spki_x25519(innerKey);
+      const spkiDer = ops.op_crypto_export_key({
+        format: "spki",
+        algorithm: "X25519",
+      }, innerKey);
       return TypedArrayPrototypeGetBuffer(spkiDer);
     }
     case "pkcs8": {
@@ -4140,17 +5726,20 @@ function exportKeyX25519(format, key, innerKey) {
         );
       }
 
-      const pkcs8Der = ops.op_crypto_export_pkcs8_x25519(
-        new Uint8Array([0x04, 0x22, ...new SafeArrayIterator(innerKey)]),
-      );
-      pkcs8Der[15] = 0x20;
+      const pkcs8Der = ops.op_crypto_export_key({
+        format: "pkcs8",
+        algorithm: "X25519",
+      }, innerKey);
       return TypedArrayPrototypeGetBuffer(pkcs8Der);
     }
     case "jwk": {
       if (key[_type] === "private") {
         throw new DOMException("Not implemented", "NotSupportedError");
       }
-      const x = ops.op_crypto_base64url_encode(innerKey);
+      const { x } = ops.op_crypto_export_key({
+        format: "jwkpublic",
+        algorithm: "X25519",
+      }, innerKey);
       const jwk = {
         kty: "OKP",
         crv: "X25519",
@@ -4165,6 +5754,147 @@ function exportKeyX25519(format, key, innerKey) {
   }
 }
 
+function exportKeyEd448(format, key, innerKey) {
+  switch (format) {
+    case "raw": {
+      // 1.
+      if (key[_type] !== "public") {
+        throw new DOMException(
+          "Key is not a public key",
+          "InvalidAccessError",
+        );
+      }
+
+      // 2-3.
+      const data = ops.op_crypto_export_key({
+        format: "raw",
+        algorithm: "Ed448",
+      }, innerKey);
+      return TypedArrayPrototypeGetBuffer(data);
+    }
+    case "spki": {
+      // 1.
+      if (key[_type] !== "public") {
+        throw new DOMException(
+          "Key is not a public key",
+          "InvalidAccessError",
+        );
+      }
+
+      const spkiDer = ops.op_crypto_export_key({
+        format: "spki",
+        algorithm: "Ed448",
+      }, innerKey);
+      return TypedArrayPrototypeGetBuffer(spkiDer);
+    }
+    case "pkcs8": {
+      // 1.
+      if (key[_type] !== "private") {
+        throw new DOMException(
+          "Key is not a public key",
+          "InvalidAccessError",
+        );
+      }
+
+      const pkcs8Der = ops.op_crypto_export_key({
+        format: "pkcs8",
+        algorithm: "Ed448",
+      }, innerKey);
+      return TypedArrayPrototypeGetBuffer(pkcs8Der);
+    }
+    case "jwk": {
+      const { x, d } = ops.op_crypto_export_key({
+        format: key[_type] === "private" ? "jwkprivate" : "jwkpublic",
+        algorithm: "Ed448",
+      }, innerKey);
+      const jwk = {
+        kty: "OKP",
+        alg: "EdDSA",
+        crv: "Ed448",
+        x,
+        "key_ops": key.usages,
+        ext: key[_extractable],
+      };
+      if (key[_type] === "private") {
+        jwk.d = d;
+      }
+      return jwk;
+    }
+    default:
+      throw new DOMException("Not implemented", "NotSupportedError");
+  }
+}
+
+function exportKeyX448(format, key, innerKey) {
+  switch (format) {
+    case "raw": {
+      // 1.
+      if (key[_type] !== "public") {
+        throw new DOMException(
+          "Key is not a public key",
+          "InvalidAccessError",
+        );
+      }
+
+      // 2-3.
+      const data = ops.op_crypto_export_key({
+        format: "raw",
+        algorithm: "X448",
+      }, innerKey);
+      return TypedArrayPrototypeGetBuffer(data);
+    }
+    case "spki": {
+      // 1.
+      if (key[_type] !== "public") {
+        throw new DOMException(
+          "Key is not a public key",
+          "InvalidAccessError",
+        );
+      }
+
+      const spkiDer = ops.op_crypto_export_key({
+        format: "spki",
+        algorithm: "X448",
+      }, innerKey);
+      return TypedArrayPrototypeGetBuffer(spkiDer);
+    }
+    case "pkcs8": {
+      // 1.
+      if (key[_type] !== "private") {
+        throw new DOMException(
+          "Key is not a public key",
+          "InvalidAccessError",
+        );
+      }
+
+      const pkcs8Der = ops.op_crypto_export_key({
+        format: "pkcs8",
+        algorithm: "X448",
+      }, innerKey);
+      return TypedArrayPrototypeGetBuffer(pkcs8Der);
+    }
+    case "jwk": {
+      const { x, d } = ops.op_crypto_export_key({
+        format: key[_type] === "private" ? "jwkprivate" : "jwkpublic",
+        algorithm: "X448",
+      }, innerKey);
+      const jwk = {
+        kty: "OKP",
+        crv: "X448",
+        x,
+        "key_ops": key.usages,
+        ext: key[_extractable],
+      };
+      if (key[_type] === "private") {
+        jwk.d = d;
+      }
+      return jwk;
+    }
+    default:
+      throw new DOMException("Not implemented", "NotSupportedError");
+  }
+}
+
 function exportKeyEC(format, key, innerKey) {
   switch (format) {
     case "raw": {
@@ -4247,6 +5977,10 @@ function exportKeyEC(format, key, innerKey) {
             algNamedCurve = "ES512";
             break;
           }
+          case "secp256k1": {
+            algNamedCurve = "ES256K";
+            break;
+          }
           default:
             throw new DOMException(
               "Curve algorithm not supported",
@@ -4319,10 +6053,10 @@ async function generateKeyAES(normalizedAlgorithm, extractable, usages) {
     length: normalizedAlgorithm.length,
   });
   const handle = {};
-  WeakMapPrototypeSet(KEY_STORE, handle, {
+  WeakMapPrototypeSet(KEY_STORE, handle, storeKey({
     type: "secret",
     data: keyData,
-  });
+  }, extractable));
 
   // 6-8.
   const algorithm = {
@@ -4343,17 +6077,138 @@ async function generateKeyAES(normalizedAlgorithm, extractable, usages) {
   return key;
 }
 
-async function deriveBits(normalizedAlgorithm, baseKey, length) {
-  switch (normalizedAlgorithm.name) {
-    case "PBKDF2": {
-      // 1.
+async function generateKeyChaCha20(normalizedAlgorithm, extractable, usages) {
+  const keyData = await core.opAsync("op_crypto_generate_key", {
+    algorithm: "CHACHA20",
+  });
+  const handle = {};
+  WeakMapPrototypeSet(KEY_STORE, handle, storeKey({
+    type: "secret",
+    data: keyData,
+  }, extractable));
+
+  const algorithm = {
+    name: normalizedAlgorithm.name,
+  };
+
+  return constructKey(
+    "secret",
+    extractable,
+    usages,
+    algorithm,
+    handle,
+  );
+}
+
+async function deriveBits(normalizedAlgorithm, baseKey, length, options) {
+  switch (normalizedAlgorithm.name) {
+    case "PBKDF2": {
+      // 1.
+      if (length == null || length == 0 || length % 8 !== 0) {
+        throw new DOMException("Invalid length", "OperationError");
+      }
+
+      if (normalizedAlgorithm.iterations == 0) {
+        throw new DOMException(
+          "iterations must not be zero",
+          "OperationError",
+        );
+      }
+
+      const handle = baseKey[_handle];
+      const keyData = WeakMapPrototypeGet(KEY_STORE, handle);
+
+      normalizedAlgorithm.salt = copyBuffer(normalizedAlgorithm.salt);
+
+      // Non-standard: progress is reported in iterations, over all blocks of
+      // the output.
+      const blocks = MathCeil(
+        length / 8 / hashOutputLength[normalizedAlgorithm.hash.name],
+      );
+      const buf = await runLongOperation(
+        options,
+        normalizedAlgorithm.iterations * blocks,
+        (progressRid, cancelRid) =>
+          core.opAsync(
+            "op_crypto_derive_bits",
+            {
+              key: keyData,
+              algorithm: "PBKDF2",
+              hash: normalizedAlgorithm.hash.name,
+              iterations: normalizedAlgorithm.iterations,
+              length,
+            },
+            normalizedAlgorithm.salt,
+            progressRid,
+            cancelRid,
+          ),
+      );
+
+      return TypedArrayPrototypeGetBuffer(buf);
+    }
+    case "Argon2id": {
+      if (length == null || length == 0 || length % 8 !== 0) {
+        throw new DOMException("Invalid length", "OperationError");
+      }
+
+      if (normalizedAlgorithm.iterations == 0) {
+        throw new DOMException(
+          "iterations must not be zero",
+          "OperationError",
+        );
+      }
+
+      if (normalizedAlgorithm.parallelism == 0) {
+        throw new DOMException(
+          "parallelism must not be zero",
+          "OperationError",
+        );
+      }
+
+      // RFC 9106 requires at least 8 KiB of memory per lane.
+      if (normalizedAlgorithm.memory < 8 * normalizedAlgorithm.parallelism) {
+        throw new DOMException(
+          "memory must be at least 8 times parallelism",
+          "OperationError",
+        );
+      }
+
+      const handle = baseKey[_handle];
+      const keyData = WeakMapPrototypeGet(KEY_STORE, handle);
+
+      normalizedAlgorithm.salt = copyBuffer(normalizedAlgorithm.salt);
+
+      const buf = await core.opAsync("op_crypto_derive_bits", {
+        key: keyData,
+        algorithm: "Argon2id",
+        memory: normalizedAlgorithm.memory,
+        parallelism: normalizedAlgorithm.parallelism,
+        iterations: normalizedAlgorithm.iterations,
+        length,
+      }, normalizedAlgorithm.salt);
+
+      return TypedArrayPrototypeGetBuffer(buf);
+    }
+    case "scrypt": {
       if (length == null || length == 0 || length % 8 !== 0) {
         throw new DOMException("Invalid length", "OperationError");
       }
 
-      if (normalizedAlgorithm.iterations == 0) {
+      // N must be a power of two greater than 1.
+      const cost = normalizedAlgorithm.cost;
+      if (cost < 2 || (cost & (cost - 1)) !== 0) {
         throw new DOMException(
-          "iterations must not be zero",
+          "cost must be a power of two greater than 1",
+          "OperationError",
+        );
+      }
+
+      if (
+        normalizedAlgorithm.blockSize == 0 ||
+        normalizedAlgorithm.parallelism == 0
+      ) {
+        throw new DOMException(
+          "blockSize and parallelism must not be zero",
           "OperationError",
         );
       }
@@ -4365,9 +6220,10 @@ async function deriveBits(normalizedAlgorithm, baseKey, length) {
 
       const buf = await core.opAsync("op_crypto_derive_bits", {
         key: keyData,
-        algorithm: "PBKDF2",
-        hash: normalizedAlgorithm.hash.name,
-        iterations: normalizedAlgorithm.iterations,
+        algorithm: "scrypt",
+        cost,
+        blockSize: normalizedAlgorithm.blockSize,
+        parallelism: normalizedAlgorithm.parallelism,
         length,
       }, normalizedAlgorithm.salt);
 
@@ -4420,18 +6276,16 @@ async function deriveBits(normalizedAlgorithm, baseKey, length) {
           length: length ?? 0,
         });
 
-        // 8.
-        if (length === null) {
-          return TypedArrayPrototypeGetBuffer(buf);
-        } else if (TypedArrayPrototypeGetByteLength(buf) * 8 < length) {
-          throw new DOMException("Invalid length", "OperationError");
-        } else {
+        // 8. The secret is truncated to `length` bits in Rust, where a null
+        // length is sent as 0.
+        if (length === 0) {
           return ArrayBufferPrototypeSlice(
             TypedArrayPrototypeGetBuffer(buf),
             0,
-            MathCeil(length / 8),
+            0,
           );
         }
+        return TypedArrayPrototypeGetBuffer(buf);
       } else {
         throw new DOMException("Not implemented", "NotSupportedError");
       }
@@ -4508,11 +6362,88 @@ async function deriveBits(normalizedAlgorithm, baseKey, length) {
         );
       }
     }
+    case "X448": {
+      // 1.
+      if (baseKey[_type] !== "private") {
+        throw new DOMException("Invalid key type", "InvalidAccessError");
+      }
+      // 2.
+      const publicKey = normalizedAlgorithm.public;
+      // 3.
+      if (publicKey[_type] !== "public") {
+        throw new DOMException("Invalid key type", "InvalidAccessError");
+      }
+      // 4.
+      if (publicKey[_algorithm].name !== baseKey[_algorithm].name) {
+        throw new DOMException(
+          "Algorithm mismatch",
+          "InvalidAccessError",
+        );
+      }
+
+      // 5.
+      const kHandle = baseKey[_handle];
+      const k = WeakMapPrototypeGet(KEY_STORE, kHandle);
+
+      const uHandle = publicKey[_handle];
+      const u = WeakMapPrototypeGet(KEY_STORE, uHandle);
+
+      const secret = new Uint8Array(56);
+      // 6.
+      if (!ops.op_crypto_derive_bits_x448(k, u, secret)) {
+        throw new DOMException("Invalid key", "OperationError");
+      }
+
+      // 7.
+      if (length === null) {
+        return TypedArrayPrototypeGetBuffer(secret);
+      } else if (
+        TypedArrayPrototypeGetByteLength(secret) * 8 < length
+      ) {
+        throw new DOMException("Invalid length", "OperationError");
+      } else {
+        return ArrayBufferPrototypeSlice(
+          TypedArrayPrototypeGetBuffer(secret),
+          0,
+          MathCeil(length / 8),
+        );
+      }
+    }
     default:
       throw new DOMException("Not implemented", "NotSupportedError");
   }
 }
 
+/**
+ * Validates AES-GCM wrapping parameters the same way encrypt/decrypt do.
+ * @param {object} normalizedAlgorithm
+ * @returns {{ iv: Uint8Array, additionalData: Uint8Array | null, tagLength: number }}
+ */
+function aesGcmWrapParams(normalizedAlgorithm) {
+  const iv = copyBuffer(normalizedAlgorithm.iv);
+  // We only support 96-bit and 128-bit nonce.
+  if (
+    !ArrayPrototypeIncludes([12, 16], TypedArrayPrototypeGetByteLength(iv))
+  ) {
+    throw new DOMException(
+      "Initialization vector length not supported",
+      "NotSupportedError",
+    );
+  }
+
+  // The `aes_gcm` crate only supports verifying 128-bit tags.
+  const tagLength = normalizedAlgorithm.tagLength ?? 128;
+  if (tagLength !== 128) {
+    throw new DOMException("Invalid tag length", "NotSupportedError");
+  }
+
+  const additionalData = normalizedAlgorithm.additionalData
+    ? copyBuffer(normalizedAlgorithm.additionalData)
+    : null;
+
+  return { iv, additionalData, tagLength };
+}
+
 async function encrypt(normalizedAlgorithm, key, data) {
   const handle = key[_handle];
   const keyData = WeakMapPrototypeGet(KEY_STORE, handle);
@@ -4672,6 +6603,40 @@ async function encrypt(normalizedAlgorithm, key, data) {
       // 8.
       return TypedArrayPrototypeGetBuffer(cipherText);
     }
+    case "ChaCha20-Poly1305":
+    case "XChaCha20-Poly1305":
+    case "AES-256-GCM-SIV": {
+      normalizedAlgorithm.iv = copyBuffer(normalizedAlgorithm.iv);
+
+      // 1.
+      if (
+        TypedArrayPrototypeGetByteLength(normalizedAlgorithm.iv) !==
+          chachaNonceLength[normalizedAlgorithm.name]
+      ) {
+        throw new DOMException(
+          "Initialization vector length not supported",
+          "OperationError",
+        );
+      }
+
+      // 2.
+      if (normalizedAlgorithm.additionalData !== undefined) {
+        normalizedAlgorithm.additionalData = copyBuffer(
+          normalizedAlgorithm.additionalData,
+        );
+      }
+
+      // 3-4.
+      const cipherText = await core.opAsync("op_crypto_encrypt", {
+        key: keyData,
+        algorithm: normalizedAlgorithm.name,
+        iv: normalizedAlgorithm.iv,
+        additionalData: normalizedAlgorithm.additionalData || null,
+      }, data);
+
+      // 5.
+      return TypedArrayPrototypeGetBuffer(cipherText);
+    }
     default:
       throw new DOMException("Not implemented", "NotSupportedError");
   }
@@ -4745,6 +6710,65 @@ const CryptoPrototype = Crypto.prototype;
 
 const crypto = webidl.createBranded(Crypto);
 
+/**
+ * Writes `key` to a file, encrypted with a key derived from `passphrase`.
+ * @param {string | URL} path
+ * @param {CryptoKey} key
+ * @param {string} passphrase
+ * @returns {Promise<void>}
+ */
+async function saveCryptoKey(path, key, passphrase) {
+  const prefix = "Failed to execute 'saveCryptoKey' on 'Deno'";
+  webidl.requiredArguments(arguments.length, 3, prefix);
+  key = webidl.converters.CryptoKey(key, prefix, "Argument 2");
+  passphrase = webidl.converters.DOMString(passphrase, prefix, "Argument 3");
+
+  if (!key[_extractable]) {
+    throw new DOMException("Key is not extractable", "InvalidAccessError");
+  }
+
+  const algorithm = ObjectAssign({}, key[_algorithm]);
+  if (algorithm.publicExponent !== undefined) {
+    algorithm.publicExponent = ArrayPrototypeSlice(algorithm.publicExponent);
+  }
+
+  await core.opAsync("op_crypto_keystore_save", {
+    path: pathFromURL(path),
+    key: WeakMapPrototypeGet(KEY_STORE, key[_handle]),
+    passphrase,
+    metadata: JSONStringify({ algorithm, usages: key[_usages] }),
+  });
+}
+
+/**
+ * Reads a key written by `saveCryptoKey`. The key keeps the algorithm,
+ * usages and extractable flag it was saved with.
+ * @param {string | URL} path
+ * @param {string} passphrase
+ * @returns {Promise<CryptoKey>}
+ */
+async function loadCryptoKey(path, passphrase) {
+  const prefix = "Failed to execute 'loadCryptoKey' on 'Deno'";
+  webidl.requiredArguments(arguments.length, 2, prefix);
+  passphrase = webidl.converters.DOMString(passphrase, prefix, "Argument 2");
+
+  const { type, handle, extractable, metadata } = await core.opAsync(
+    "op_crypto_keystore_load",
+    { path: pathFromURL(path), passphrase },
+  );
+  const storedKey = { type, handle };
+  KEY_HANDLE_REGISTRY.register(storedKey, handle);
+
+  const { algorithm, usages } = JSONParse(metadata);
+  if (algorithm.publicExponent !== undefined) {
+    algorithm.publicExponent = new Uint8Array(algorithm.publicExponent);
+  }
+
+  const keyHandle = {};
+  WeakMapPrototypeSet(KEY_STORE, keyHandle, storedKey);
+  return constructKey(type, extractable, usages, algorithm, keyHandle);
+}
+
 webidl.converters.AlgorithmIdentifier = (V, prefix, context, opts) => {
   // Union for (object or DOMString)
   if (webidl.type(V) == "Object") {
@@ -4926,6 +6950,65 @@ const dictHmacKeyGenParams = [
 webidl.converters.HmacKeyGenParams = webidl
   .createDictionaryConverter("HmacKeyGenParams", dictHmacKeyGenParams);
 
+const dictKmacKeyGenParams = [
+  ...new SafeArrayIterator(dictAlgorithm),
+  {
+    key: "length",
+    converter: (V, prefix, context, opts) =>
+      webidl.converters["unsigned long"](V, prefix, context, {
+        ...opts,
+        enforceRange: true,
+      }),
+  },
+];
+
+webidl.converters.KmacKeyGenParams = webidl
+  .createDictionaryConverter("KmacKeyGenParams", dictKmacKeyGenParams);
+
+const dictKmacParams = [
+  ...new SafeArrayIterator(dictAlgorithm),
+  {
+    key: "length",
+    converter: (V, prefix, context, opts) =>
+      webidl.converters["unsigned long"](V, prefix, context, {
+        ...opts,
+        enforceRange: true,
+      }),
+    required: true,
+  },
+  {
+    key: "customization",
+    converter: webidl.converters["BufferSource"],
+  },
+];
+
+webidl.converters.KmacParams = webidl
+  .createDictionaryConverter("KmacParams", dictKmacParams);
+
+const dictCShakeParams = [
+  ...new SafeArrayIterator(dictAlgorithm),
+  {
+    key: "length",
+    converter: (V, prefix, context, opts) =>
+      webidl.converters["unsigned long"](V, prefix, context, {
+        ...opts,
+        enforceRange: true,
+      }),
+    required: true,
+  },
+  {
+    key: "functionName",
+    converter: webidl.converters["BufferSource"],
+  },
+  {
+    key: "customization",
+    converter: webidl.converters["BufferSource"],
+  },
+];
+
+webidl.converters.CShakeParams = webidl
+  .createDictionaryConverter("CShakeParams", dictCShakeParams);
+
 const dictRsaPssParams = [
   ...new SafeArrayIterator(dictAlgorithm),
   {
@@ -5142,6 +7225,84 @@ const dictPbkdf2Params = [
 webidl.converters.Pbkdf2Params = webidl
   .createDictionaryConverter("Pbkdf2Params", dictPbkdf2Params);
 
+const dictArgon2Params = [
+  ...new SafeArrayIterator(dictAlgorithm),
+  {
+    key: "salt",
+    converter: webidl.converters["BufferSource"],
+    required: true,
+  },
+  {
+    key: "memory",
+    converter: (V, prefix, context, opts) =>
+      webidl.converters["unsigned long"](V, prefix, context, {
+        ...opts,
+        enforceRange: true,
+      }),
+    required: true,
+  },
+  {
+    key: "parallelism",
+    converter: (V, prefix, context, opts) =>
+      webidl.converters["unsigned long"](V, prefix, context, {
+        ...opts,
+        enforceRange: true,
+      }),
+    required: true,
+  },
+  {
+    key: "iterations",
+    converter: (V, prefix, context, opts) =>
+      webidl.converters["unsigned long"](V, prefix, context, {
+        ...opts,
+        enforceRange: true,
+      }),
+    required: true,
+  },
+];
+
+webidl.converters.Argon2Params = webidl
+  .createDictionaryConverter("Argon2Params", dictArgon2Params);
+
+const dictScryptParams = [
+  ...new SafeArrayIterator(dictAlgorithm),
+  {
+    key: "salt",
+    converter: webidl.converters["BufferSource"],
+    required: true,
+  },
+  {
+    key: "cost",
+    converter: (V, prefix, context, opts) =>
+      webidl.converters["unsigned long"](V, prefix, context, {
+        ...opts,
+        enforceRange: true,
+      }),
+    required: true,
+  },
+  {
+    key: "blockSize",
+    converter: (V, prefix, context, opts) =>
+      webidl.converters["unsigned long"](V, prefix, context, {
+        ...opts,
+        enforceRange: true,
+      }),
+    required: true,
+  },
+  {
+    key: "parallelism",
+    converter: (V, prefix, context, opts) =>
+      webidl.converters["unsigned long"](V, prefix, context, {
+        ...opts,
+        enforceRange: true,
+      }),
+    required: true,
+  },
+];
+
+webidl.converters.ScryptParams = webidl
+  .createDictionaryConverter("ScryptParams", dictScryptParams);
+
 const dictAesDerivedKeyParams = [
   ...new SafeArrayIterator(dictAlgorithm),
   {
@@ -5185,6 +7346,19 @@ const dictAesGcmParams = [
   },
 ];
 
+const dictChaCha20Poly1305Params = [
+  ...new SafeArrayIterator(dictAlgorithm),
+  {
+    key: "iv",
+    converter: webidl.converters["BufferSource"],
+    required: true,
+  },
+  {
+    key: "additionalData",
+    converter: webidl.converters["BufferSource"],
+  },
+];
+
 const dictAesCtrParams = [
   ...new SafeArrayIterator(dictAlgorithm),
   {
@@ -5215,6 +7389,12 @@ webidl.converters.AesGcmParams = webidl
 webidl.converters.AesCtrParams = webidl
   .createDictionaryConverter("AesCtrParams", dictAesCtrParams);
 
+webidl.converters.ChaCha20Poly1305Params = webidl
+  .createDictionaryConverter(
+    "ChaCha20Poly1305Params",
+    dictChaCha20Poly1305Params,
+  );
+
 webidl.converters.CryptoKey = webidl.createInterfaceConverter(
   "CryptoKey",
   CryptoKey.prototype,
@@ -5246,4 +7426,11 @@ const dictEcdhKeyDeriveParams = [
 webidl.converters.EcdhKeyDeriveParams = webidl
   .createDictionaryConverter("EcdhKeyDeriveParams", dictEcdhKeyDeriveParams);
 
-export { Crypto, crypto, CryptoKey, SubtleCrypto };
+export {
+  Crypto,
+  crypto,
+  CryptoKey,
+  loadCryptoKey,
+  saveCryptoKey,
+  SubtleCrypto,
+};
@@ -0,0 +1,220 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! Selects the library used for RSA private key operations.
+//!
+//! The default backend is ring together with the RustCrypto crates. When the
+//! extension is compiled with the `aws-lc-rs` feature, aws-lc-rs can be
+//! selected instead: its RSA implementation is constant-time and it has a
+//! FIPS 140-3 validated mode. With aws-lc-rs selected, these operations
+//! run in aws-lc-rs:
+//!
+//! - RSASSA-PKCS1-v1_5 signing with SHA-256, SHA-384 and SHA-512
+//! - RSA-PSS signing with SHA-256, SHA-384 and SHA-512, when the salt length
+//!   equals the hash length
+//! - RSA-OAEP decryption and key unwrapping with SHA-1, SHA-256, SHA-384 and
+//!   SHA-512, with or without a label
+//!
+//! Other parameters for these operations, like SHA-1 signatures or other PSS
+//! salt lengths, are rejected with a `NotSupportedError` instead of silently
+//! using the default backend. Public key operations always use the default
+//! backend.
+
+use deno_core::error::type_error;
+use deno_core::error::AnyError;
+use deno_core::OpState;
+use std::str::FromStr;
+
+use crate::key::CryptoHash;
+use crate::shared::ShaHash;
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum CryptoBackend {
+  #[default]
+  Ring,
+  #[cfg(feature = "aws-lc-rs")]
+  AwsLc,
+}
+
+impl FromStr for CryptoBackend {
+  type Err = AnyError;
+
+  fn from_str(s: &str) -> Result<Self, AnyError> {
+    match s {
+      "ring" => Ok(CryptoBackend::Ring),
+      #[cfg(feature = "aws-lc-rs")]
+      "aws-lc-rs" => Ok(CryptoBackend::AwsLc),
+      _ => Err(type_error(format!("Unsupported crypto backend: {s}"))),
+    }
+  }
+}
+
+pub fn get(state: &OpState) -> CryptoBackend {
+  state
+    .try_borrow::<CryptoBackend>()
+    .copied()
+    .unwrap_or_default()
+}
+
+#[derive(Clone, Copy)]
+pub enum RsaSignPadding {
+  Pkcs1v15,
+  Pss { salt_len: usize },
+}
+
+/// Signs with the selected backend, or returns `None` if the default
+/// backend is selected.
+#[cfg_attr(not(feature = "aws-lc-rs"), allow(unused_variables))]
+pub fn rsa_sign(
+  backend: CryptoBackend,
+  pkcs1_der: &[u8],
+  padding: RsaSignPadding,
+  hash: CryptoHash,
+  data: &[u8],
+) -> Option<Result<Vec<u8>, AnyError>> {
+  match backend {
+    CryptoBackend::Ring => None,
+    #[cfg(feature = "aws-lc-rs")]
+    CryptoBackend::AwsLc => {
+      Some(aws_lc::rsa_sign(pkcs1_der, padding, hash, data))
+    }
+  }
+}
+
+/// Decrypts with the selected backend, or returns `None` if the default
+/// backend is selected.
+#[cfg_attr(not(feature = "aws-lc-rs"), allow(unused_variables))]
+pub fn rsa_oaep_decrypt(
+  backend: CryptoBackend,
+  pkcs1_der: &[u8],
+  hash: ShaHash,
+  label: &[u8],
+  data: &[u8],
+) -> Option<Result<Vec<u8>, AnyError>> {
+  match backend {
+    CryptoBackend::Ring => None,
+    #[cfg(feature = "aws-lc-rs")]
+    CryptoBackend::AwsLc => {
+      Some(aws_lc::rsa_oaep_decrypt(pkcs1_der, hash, label, data))
+    }
+  }
+}
+
+#[cfg(feature = "aws-lc-rs")]
+mod aws_lc {
+  use ::rsa::pkcs1::DecodeRsaPrivateKey;
+  use ::rsa::pkcs8::EncodePrivateKey;
+  use aws_lc_rs::rand::SystemRandom;
+  use aws_lc_rs::rsa;
+  use aws_lc_rs::signature;
+  use aws_lc_rs::signature::RsaKeyPair;
+  use deno_core::error::AnyError;
+
+  use super::RsaSignPadding;
+  use crate::key::CryptoHash;
+  use crate::shared::data_error;
+  use crate::shared::not_supported_error;
+  use crate::shared::operation_error;
+  use crate::shared::ShaHash;
+
+  pub fn rsa_sign(
+    pkcs1_der: &[u8],
+    padding: RsaSignPadding,
+    hash: CryptoHash,
+    data: &[u8],
+  ) -> Result<Vec<u8>, AnyError> {
+    let encoding: &'static dyn signature::RsaEncoding = match (padding, hash) {
+      (RsaSignPadding::Pkcs1v15, CryptoHash::Sha256) => {
+        &signature::RSA_PKCS1_SHA256
+      }
+      (RsaSignPadding::Pkcs1v15, CryptoHash::Sha384) => {
+        &signature::RSA_PKCS1_SHA384
+      }
+      (RsaSignPadding::Pkcs1v15, CryptoHash::Sha512) => {
+        &signature::RSA_PKCS1_SHA512
+      }
+      // aws-lc-rs always uses a salt as long as the hash output.
+      (RsaSignPadding::Pss { salt_len: 32 }, CryptoHash::Sha256) => {
+        &signature::RSA_PSS_SHA256
+      }
+      (RsaSignPadding::Pss { salt_len: 48 }, CryptoHash::Sha384) => {
+        &signature::RSA_PSS_SHA384
+      }
+      (RsaSignPadding::Pss { salt_len: 64 }, CryptoHash::Sha512) => {
+        &signature::RSA_PSS_SHA512
+      }
+      (RsaSignPadding::Pkcs1v15, _) => {
+        return Err(not_supported_error(
+          "RSASSA-PKCS1-v1_5 with SHA-1 is not supported by the aws-lc-rs backend",
+        ))
+      }
+      (RsaSignPadding::Pss { .. }, _) => {
+        return Err(not_supported_error(
+          "RSA-PSS is only supported by the aws-lc-rs backend with SHA-256, SHA-384 or SHA-512 and a salt length equal to the hash length",
+        ))
+      }
+    };
+    let key_pair = RsaKeyPair::from_der(pkcs1_der)
+      .map_err(|_| data_error("invalid RSA private key"))?;
+    let mut signature = vec![0; key_pair.public_modulus_len()];
+    key_pair
+      .sign(encoding, &SystemRandom::new(), data, &mut signature)
+      .map_err(|_| operation_error("Sign failed"))?;
+    Ok(signature)
+  }
+
+  pub fn rsa_oaep_decrypt(
+    pkcs1_der: &[u8],
+    hash: ShaHash,
+    label: &[u8],
+    data: &[u8],
+  ) -> Result<Vec<u8>, AnyError> {
+    let algorithm = match hash {
+      ShaHash::Sha1 => &rsa::OAEP_SHA1_MGF1SHA1,
+      ShaHash::Sha256 => &rsa::OAEP_SHA256_MGF1SHA256,
+      ShaHash::Sha384 => &rsa::OAEP_SHA384_MGF1SHA384,
+      ShaHash::Sha512 => &rsa::OAEP_SHA512_MGF1SHA512,
+    };
+    let pkcs8 =
+      ::rsa::RsaPrivateKey::from_pkcs1_der(pkcs1_der)?.to_pkcs8_der()?;
+    let key = rsa::PrivateDecryptingKey::from_pkcs8(pkcs8.as_bytes())
+      .ok()
+      .and_then(|key| rsa::OaepPrivateDecryptingKey::new(key).ok())
+      .ok_or_else(|| data_error("invalid RSA private key"))?;
+    let label = (!label.is_empty()).then_some(label);
+    let mut output = vec![0; key.min_output_size()];
+    let len = key
+      .decrypt(algorithm, data, &mut output, label)
+      .map_err(|_| operation_error("Decryption failed"))?
+      .len();
+    output.truncate(len);
+    Ok(output)
+  }
+}
+
+#[cfg(all(test, feature = "aws-lc-rs"))]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_aws_lc_rejects_unsupported_parameters() {
+    for (padding, hash) in [
+      (RsaSignPadding::Pkcs1v15, CryptoHash::Sha1),
+      (RsaSignPadding::Pss { salt_len: 20 }, CryptoHash::Sha256),
+    ] {
+      let result =
+        rsa_sign(CryptoBackend::AwsLc, &[], padding, hash, b"data").unwrap();
+      assert_eq!(
+        deno_core::error::get_custom_error_class(&result.unwrap_err()),
+        Some("DOMExceptionNotSupportedError")
+      );
+    }
+    assert!(rsa_sign(
+      CryptoBackend::Ring,
+      &[],
+      RsaSignPadding::Pkcs1v15,
+      CryptoHash::Sha1,
+      b"data"
+    )
+    .is_none());
+  }
+}
@@ -0,0 +1,188 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! Signing and verification of many small messages in a single blocking
+//! task, instead of one async op round-trip per message.
+
+use deno_core::error::type_error;
+use deno_core::error::AnyError;
+use deno_core::op;
+use deno_core::task::spawn_blocking;
+use deno_core::JsBuffer;
+use deno_core::OpState;
+use deno_core::ToJsBuffer;
+use ring::hmac::Algorithm as HmacAlgorithm;
+use ring::hmac::Key as HmacKey;
+use ring::signature::Ed25519KeyPair;
+use serde::Deserialize;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::fips;
+use crate::key::CryptoHash;
+use crate::KeyData;
+use crate::KeyType;
+
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "camelCase", tag = "algorithm")]
+pub enum BatchAlgorithm {
+  #[serde(rename = "Ed25519")]
+  Ed25519,
+  #[serde(rename = "HMAC")]
+  Hmac { hash: CryptoHash },
+}
+
+impl BatchAlgorithm {
+  fn check_key(self, key: &KeyData, signing: bool) -> Result<(), AnyError> {
+    let valid = match self {
+      BatchAlgorithm::Ed25519 if signing => {
+        matches!(key.r#type, KeyType::Private)
+      }
+      BatchAlgorithm::Ed25519 => matches!(key.r#type, KeyType::Public),
+      BatchAlgorithm::Hmac { .. } => matches!(key.r#type, KeyType::Secret),
+    };
+    if !valid {
+      return Err(type_error("Invalid key type"));
+    }
+    Ok(())
+  }
+}
+
+#[derive(Deserialize)]
+pub struct BatchSignItem {
+  key: KeyData,
+  data: JsBuffer,
+}
+
+#[derive(Deserialize)]
+pub struct BatchVerifyItem {
+  key: KeyData,
+  data: JsBuffer,
+  signature: JsBuffer,
+}
+
+fn resolve_keys<'a>(
+  state: &OpState,
+  algorithm: BatchAlgorithm,
+  keys: impl Iterator<Item = &'a mut KeyData>,
+  signing: bool,
+) -> Result<(), AnyError> {
+  if let BatchAlgorithm::Ed25519 = algorithm {
+    fips::check_unapproved(state, "Ed25519")?;
+  }
+  for key in keys {
+    key.resolve(state)?;
+    algorithm.check_key(key, signing)?;
+  }
+  Ok(())
+}
+
+#[op]
+pub async fn op_crypto_sign_batch(
+  state: Rc<RefCell<OpState>>,
+  algorithm: BatchAlgorithm,
+  mut items: Vec<BatchSignItem>,
+) -> Result<Vec<ToJsBuffer>, AnyError> {
+  resolve_keys(
+    &state.borrow(),
+    algorithm,
+    items.iter_mut().map(|item| &mut item.key),
+    true,
+  )?;
+  spawn_blocking(move || {
+    items
+      .iter()
+      .map(|item| sign(algorithm, &item.key, &item.data))
+      .collect()
+  })
+  .await
+  .unwrap()
+}
+
+fn sign(
+  algorithm: BatchAlgorithm,
+  key: &KeyData,
+  data: &[u8],
+) -> Result<ToJsBuffer, AnyError> {
+  let signature = match algorithm {
+    BatchAlgorithm::Ed25519 => Ed25519KeyPair::from_seed_unchecked(&key.data)
+      .map_err(|_| type_error("Invalid Ed25519 private key"))?
+      .sign(data)
+      .as_ref()
+      .to_vec(),
+    BatchAlgorithm::Hmac { hash } => {
+      let hash: HmacAlgorithm = hash.into();
+      ring::hmac::sign(&HmacKey::new(hash, &key.data), data)
+        .as_ref()
+        .to_vec()
+    }
+  };
+  Ok(signature.into())
+}
+
+/// Returns whether each signature is valid, in order.
+#[op]
+pub async fn op_crypto_verify_batch(
+  state: Rc<RefCell<OpState>>,
+  algorithm: BatchAlgorithm,
+  mut items: Vec<BatchVerifyItem>,
+) -> Result<Vec<bool>, AnyError> {
+  resolve_keys(
+    &state.borrow(),
+    algorithm,
+    items.iter_mut().map(|item| &mut item.key),
+    false,
+  )?;
+  let results = spawn_blocking(move || match algorithm {
+    BatchAlgorithm::Ed25519 if verify_ed25519_batch(&items) => {
+      vec![true; items.len()]
+    }
+    _ => items.iter().map(|item| verify(algorithm, item)).collect(),
+  })
+  .await
+  .unwrap();
+  Ok(results)
+}
+
+fn verify(algorithm: BatchAlgorithm, item: &BatchVerifyItem) -> bool {
+  match algorithm {
+    BatchAlgorithm::Ed25519 => ring::signature::UnparsedPublicKey::new(
+      &ring::signature::ED25519,
+      &*item.key.data,
+    )
+    .verify(&item.data, &item.signature)
+    .is_ok(),
+    BatchAlgorithm::Hmac { hash } => {
+      let hash: HmacAlgorithm = hash.into();
+      let key = HmacKey::new(hash, &item.key.data);
+      ring::hmac::verify(&key, &item.data, &item.signature).is_ok()
+    }
+  }
+}
+
+/// Checks all signatures at once, which is considerably faster than one at a
+/// time. Returns false if any item is invalid, in which case the caller falls
+/// back to verifying each signature on its own to find out which.
+///
+/// Batch verification uses the cofactored verification equation, so weak
+/// (small order) public keys are always left to individual verification.
+fn verify_ed25519_batch(items: &[BatchVerifyItem]) -> bool {
+  let mut messages = Vec::with_capacity(items.len());
+  let mut signatures = Vec::with_capacity(items.len());
+  let mut verifying_keys = Vec::with_capacity(items.len());
+  for item in items {
+    let Ok(key) = ed25519_dalek::VerifyingKey::try_from(&*item.key.data) else {
+      return false;
+    };
+    let Ok(signature) = ed25519_dalek::Signature::from_slice(&item.signature)
+    else {
+      return false;
+    };
+    if key.is_weak() {
+      return false;
+    }
+    messages.push(&*item.data);
+    signatures.push(signature);
+    verifying_keys.push(key);
+  }
+  ed25519_dalek::verify_batch(&messages, &signatures, &verifying_keys).is_ok()
+}
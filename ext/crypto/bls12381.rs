@@ -0,0 +1,163 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! BLS signatures over BLS12-381 using the "minimal-pubkey-size" variant
+//! (48 byte public keys in G1, 96 byte signatures in G2) with the proof of
+//! possession ciphersuite, as used by Ethereum.
+
+use blst::min_pk::AggregatePublicKey;
+use blst::min_pk::AggregateSignature;
+use blst::min_pk::PublicKey;
+use blst::min_pk::SecretKey;
+use blst::min_pk::Signature;
+use blst::BLST_ERROR;
+use deno_core::error::AnyError;
+use deno_core::op;
+use deno_core::OpState;
+use rand::rngs::OsRng;
+use rand::RngCore;
+
+use crate::fips;
+
+// https://datatracker.ietf.org/doc/html/draft-irtf-cfrg-bls-signature-05#section-4.2.3
+const DST: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+
+const PUBLIC_KEY_LEN: usize = 48;
+const SIGNATURE_LEN: usize = 96;
+
+#[op(fast)]
+pub fn op_crypto_generate_bls12381_keypair(
+  state: &mut OpState,
+  pkey: &mut [u8],
+  pubkey: &mut [u8],
+) -> Result<bool, AnyError> {
+  fips::check_unapproved(state, "BLS12-381")?;
+  let mut ikm = [0; 32];
+  OsRng.fill_bytes(&mut ikm);
+
+  let sk = match SecretKey::key_gen(&ikm, &[]) {
+    Ok(sk) => sk,
+    Err(_) => return Ok(false),
+  };
+  pkey.copy_from_slice(&sk.to_bytes());
+  pubkey.copy_from_slice(&sk.sk_to_pk().to_bytes());
+  Ok(true)
+}
+
+#[op(fast)]
+pub fn op_crypto_sign_bls12381(
+  state: &mut OpState,
+  key: &[u8],
+  data: &[u8],
+  signature: &mut [u8],
+) -> Result<bool, AnyError> {
+  fips::check_unapproved(state, "BLS12-381")?;
+  let sk = match SecretKey::from_bytes(key) {
+    Ok(sk) => sk,
+    Err(_) => return Ok(false),
+  };
+  signature.copy_from_slice(&sk.sign(data, DST, &[]).to_bytes());
+  Ok(true)
+}
+
+#[op(fast)]
+pub fn op_crypto_verify_bls12381(
+  state: &mut OpState,
+  pubkey: &[u8],
+  data: &[u8],
+  signature: &[u8],
+) -> Result<bool, AnyError> {
+  fips::check_unapproved(state, "BLS12-381")?;
+  let (Ok(pk), Ok(sig)) =
+    (PublicKey::from_bytes(pubkey), Signature::from_bytes(signature))
+  else {
+    return Ok(false);
+  };
+  Ok(sig.verify(true, data, DST, &[], &pk, true) == BLST_ERROR::BLST_SUCCESS)
+}
+
+/// `signatures` is the concatenation of the compressed signatures to
+/// aggregate.
+#[op(fast)]
+pub fn op_crypto_aggregate_bls12381_signatures(
+  state: &mut OpState,
+  signatures: &[u8],
+  out: &mut [u8],
+) -> Result<bool, AnyError> {
+  fips::check_unapproved(state, "BLS12-381")?;
+  let Some(sigs) = parse_concatenated(signatures, SIGNATURE_LEN, |s| {
+    Signature::from_bytes(s)
+  }) else {
+    return Ok(false);
+  };
+  let sigs = sigs.iter().collect::<Vec<_>>();
+  match AggregateSignature::aggregate(&sigs, true) {
+    Ok(agg) => {
+      out.copy_from_slice(&agg.to_signature().to_bytes());
+      Ok(true)
+    }
+    Err(_) => Ok(false),
+  }
+}
+
+/// `pubkeys` is the concatenation of the compressed public keys to
+/// aggregate.
+#[op(fast)]
+pub fn op_crypto_aggregate_bls12381_public_keys(
+  state: &mut OpState,
+  pubkeys: &[u8],
+  out: &mut [u8],
+) -> Result<bool, AnyError> {
+  fips::check_unapproved(state, "BLS12-381")?;
+  let Some(pks) = parse_public_keys(pubkeys) else {
+    return Ok(false);
+  };
+  let pks = pks.iter().collect::<Vec<_>>();
+  match AggregatePublicKey::aggregate(&pks, true) {
+    Ok(agg) => {
+      out.copy_from_slice(&agg.to_public_key().to_bytes());
+      Ok(true)
+    }
+    Err(_) => Ok(false),
+  }
+}
+
+/// Verifies an aggregate signature by several signers over the same message.
+#[op(fast)]
+pub fn op_crypto_verify_bls12381_aggregate(
+  state: &mut OpState,
+  pubkeys: &[u8],
+  data: &[u8],
+  signature: &[u8],
+) -> Result<bool, AnyError> {
+  fips::check_unapproved(state, "BLS12-381")?;
+  let (Some(pks), Ok(sig)) =
+    (parse_public_keys(pubkeys), Signature::from_bytes(signature))
+  else {
+    return Ok(false);
+  };
+  // Public keys are validated individually here, since
+  // `fast_aggregate_verify` assumes they carry a proof of possession.
+  if pks.iter().any(|pk| pk.validate().is_err()) {
+    return Ok(false);
+  }
+  let pks = pks.iter().collect::<Vec<_>>();
+  Ok(
+    sig.fast_aggregate_verify(true, data, DST, &pks)
+      == BLST_ERROR::BLST_SUCCESS,
+  )
+}
+
+fn parse_public_keys(pubkeys: &[u8]) -> Option<Vec<PublicKey>> {
+  parse_concatenated(pubkeys, PUBLIC_KEY_LEN, PublicKey::from_bytes)
+}
+
+fn parse_concatenated<T>(
+  data: &[u8],
+  len: usize,
+  parse: impl Fn(&[u8]) -> Result<T, BLST_ERROR>,
+) -> Option<Vec<T>> {
+  if data.is_empty() || data.len() % len != 0 {
+    return None;
+  }
+  data.chunks_exact(len).map(|c| parse(c).ok()).collect()
+}
@@ -0,0 +1,215 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! Describes what this build of the extension supports, so that libraries
+//! can feature-detect algorithms instead of probing with try/catch. The
+//! table mirrors `supportedAlgorithms` in `00_crypto.js`.
+
+use deno_core::op;
+use deno_core::OpState;
+use serde::Serialize;
+
+use crate::backend;
+use crate::backend::CryptoBackend;
+use crate::fips;
+use crate::key::KeyUsage;
+use crate::shared::RSA_MAX_MODULUS_LENGTH;
+
+const NAMED_CURVES: &[&str] = &["P-256", "P-384", "P-521", "secp256k1"];
+/// Curves that are not approved by FIPS 140-3.
+const NON_FIPS_CURVES: &[&str] = &["secp256k1"];
+
+const AES_KEY_LENGTHS: &[usize] = &[128, 192, 256];
+const LENGTH_256: &[usize] = &[256];
+
+const SIGN: &[KeyUsage] = &[KeyUsage::Sign, KeyUsage::Verify];
+const DERIVE: &[KeyUsage] = &[KeyUsage::DeriveKey, KeyUsage::DeriveBits];
+const CIPHER: &[KeyUsage] = &[
+  KeyUsage::Encrypt,
+  KeyUsage::Decrypt,
+  KeyUsage::WrapKey,
+  KeyUsage::UnwrapKey,
+];
+const WRAP: &[KeyUsage] = &[KeyUsage::WrapKey, KeyUsage::UnwrapKey];
+
+struct AlgorithmEntry {
+  name: &'static str,
+  operations: &'static [&'static str],
+  usages: &'static [KeyUsage],
+  named_curves: bool,
+  key_lengths: Option<&'static [usize]>,
+  max_modulus_length: Option<usize>,
+  fips_approved: bool,
+}
+
+const fn entry(
+  name: &'static str,
+  operations: &'static [&'static str],
+  usages: &'static [KeyUsage],
+  fips_approved: bool,
+) -> AlgorithmEntry {
+  AlgorithmEntry {
+    name,
+    operations,
+    usages,
+    named_curves: false,
+    key_lengths: None,
+    max_modulus_length: None,
+    fips_approved,
+  }
+}
+
+const fn rsa(
+  name: &'static str,
+  operations: &'static [&'static str],
+  usages: &'static [KeyUsage],
+) -> AlgorithmEntry {
+  AlgorithmEntry {
+    max_modulus_length: Some(RSA_MAX_MODULUS_LENGTH),
+    ..entry(name, operations, usages, true)
+  }
+}
+
+const fn ec(
+  name: &'static str,
+  operations: &'static [&'static str],
+  usages: &'static [KeyUsage],
+) -> AlgorithmEntry {
+  AlgorithmEntry {
+    named_curves: true,
+    ..entry(name, operations, usages, true)
+  }
+}
+
+const fn symmetric(
+  name: &'static str,
+  operations: &'static [&'static str],
+  usages: &'static [KeyUsage],
+  key_lengths: &'static [usize],
+  fips_approved: bool,
+) -> AlgorithmEntry {
+  AlgorithmEntry {
+    key_lengths: Some(key_lengths),
+    ..entry(name, operations, usages, fips_approved)
+  }
+}
+
+const DIGEST: &[&str] = &["digest"];
+const SIGN_OPS: &[&str] = &["generateKey", "importKey", "sign", "verify"];
+const CIPHER_OPS: &[&str] = &[
+  "generateKey",
+  "importKey",
+  "encrypt",
+  "decrypt",
+  "wrapKey",
+  "unwrapKey",
+];
+const AEAD_OPS: &[&str] = &["generateKey", "importKey", "encrypt", "decrypt"];
+const WRAP_OPS: &[&str] = &["generateKey", "importKey", "wrapKey", "unwrapKey"];
+const KDF_OPS: &[&str] = &["importKey", "deriveBits", "deriveKey"];
+const AGREEMENT_OPS: &[&str] =
+  &["generateKey", "importKey", "deriveBits", "deriveKey"];
+
+const ALGORITHMS: &[AlgorithmEntry] = &[
+  entry("SHA-1", DIGEST, &[], true),
+  entry("SHA-256", DIGEST, &[], true),
+  entry("SHA-384", DIGEST, &[], true),
+  entry("SHA-512", DIGEST, &[], true),
+  entry("cSHAKE128", DIGEST, &[], true),
+  entry("cSHAKE256", DIGEST, &[], true),
+  rsa("RSASSA-PKCS1-v1_5", SIGN_OPS, SIGN),
+  rsa("RSA-PSS", SIGN_OPS, SIGN),
+  rsa("RSA-OAEP", CIPHER_OPS, CIPHER),
+  ec("ECDSA", SIGN_OPS, SIGN),
+  ec("ECDH", AGREEMENT_OPS, DERIVE),
+  symmetric("AES-CTR", CIPHER_OPS, CIPHER, AES_KEY_LENGTHS, true),
+  symmetric("AES-CBC", CIPHER_OPS, CIPHER, AES_KEY_LENGTHS, true),
+  symmetric("AES-GCM", CIPHER_OPS, CIPHER, AES_KEY_LENGTHS, true),
+  symmetric("AES-KW", WRAP_OPS, WRAP, AES_KEY_LENGTHS, true),
+  symmetric("AES-KWP", WRAP_OPS, WRAP, AES_KEY_LENGTHS, true),
+  symmetric("ChaCha20-Poly1305", AEAD_OPS, CIPHER, LENGTH_256, false),
+  symmetric("XChaCha20-Poly1305", AEAD_OPS, CIPHER, LENGTH_256, false),
+  symmetric("AES-256-GCM-SIV", AEAD_OPS, CIPHER, LENGTH_256, false),
+  entry("HMAC", SIGN_OPS, SIGN, true),
+  entry("KMAC128", SIGN_OPS, SIGN, true),
+  entry("KMAC256", SIGN_OPS, SIGN, true),
+  entry("HKDF", KDF_OPS, DERIVE, true),
+  entry("PBKDF2", KDF_OPS, DERIVE, true),
+  entry("Argon2id", KDF_OPS, DERIVE, false),
+  entry("scrypt", KDF_OPS, DERIVE, false),
+  entry("Ed25519", SIGN_OPS, SIGN, false),
+  entry("Ed448", SIGN_OPS, SIGN, false),
+  entry("X25519", AGREEMENT_OPS, DERIVE, false),
+  entry("X448", AGREEMENT_OPS, DERIVE, false),
+];
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlgorithmCapabilities {
+  name: &'static str,
+  operations: &'static [&'static str],
+  usages: &'static [KeyUsage],
+  #[serde(skip_serializing_if = "Option::is_none")]
+  named_curves: Option<Vec<&'static str>>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  key_lengths: Option<&'static [usize]>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  max_modulus_length: Option<usize>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SupportedAlgorithms {
+  fips: bool,
+  /// The backend used for RSA private key operations.
+  backend: &'static str,
+  /// All backends compiled into this build.
+  backends: Vec<&'static str>,
+  algorithms: Vec<AlgorithmCapabilities>,
+}
+
+fn backend_name(backend: CryptoBackend) -> &'static str {
+  match backend {
+    CryptoBackend::Ring => "ring",
+    #[cfg(feature = "aws-lc-rs")]
+    CryptoBackend::AwsLc => "aws-lc-rs",
+  }
+}
+
+/// Only algorithms and curves that can actually be used are listed, so in
+/// FIPS mode the ones that are not approved are left out.
+#[op]
+pub fn op_crypto_supported_algorithms(
+  state: &mut OpState,
+) -> SupportedAlgorithms {
+  let fips = fips::is_enabled(state);
+  let named_curves: Vec<&'static str> = NAMED_CURVES
+    .iter()
+    .copied()
+    .filter(|curve| !fips || !NON_FIPS_CURVES.contains(curve))
+    .collect();
+
+  let algorithms = ALGORITHMS
+    .iter()
+    .filter(|algorithm| !fips || algorithm.fips_approved)
+    .map(|algorithm| AlgorithmCapabilities {
+      name: algorithm.name,
+      operations: algorithm.operations,
+      usages: algorithm.usages,
+      named_curves: algorithm.named_curves.then(|| named_curves.clone()),
+      key_lengths: algorithm.key_lengths,
+      max_modulus_length: algorithm.max_modulus_length,
+    })
+    .collect();
+
+  #[allow(unused_mut)]
+  let mut backends = vec![backend_name(CryptoBackend::Ring)];
+  #[cfg(feature = "aws-lc-rs")]
+  backends.push(backend_name(CryptoBackend::AwsLc));
+
+  SupportedAlgorithms {
+    fips,
+    backend: backend_name(backend::get(state)),
+    backends,
+    algorithms,
+  }
+}
@@ -0,0 +1,23 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use deno_core::error::AnyError;
+use deno_core::op;
+
+use crate::export_key::export_key;
+use crate::export_key::ExportKeyOptions;
+use crate::export_key::ExportKeyResult;
+use crate::import_key::import_key;
+use crate::import_key::ImportKeyOptions;
+use crate::import_key::KeyData;
+
+/// Translates key material between encodings (e.g. PKCS#8 to JWK) by running
+/// it through the import and export steps, without creating a `CryptoKey`.
+#[op]
+pub fn op_crypto_convert_key(
+  import_opts: ImportKeyOptions,
+  export_opts: ExportKeyOptions,
+  key_data: KeyData,
+) -> Result<ExportKeyResult, AnyError> {
+  let raw_data = import_key(import_opts, key_data)?.into_raw_data();
+  export_key(export_opts, raw_data.into())
+}
@@ -0,0 +1,154 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use deno_core::error::type_error;
+use deno_core::error::AnyError;
+use deno_core::op;
+use deno_core::task::spawn_blocking;
+use deno_core::OpState;
+use deno_core::ToJsBuffer;
+use rcgen::Certificate;
+use rcgen::CertificateParams;
+use rcgen::DistinguishedName;
+use rcgen::DnType;
+use rcgen::KeyPair;
+use rcgen::SanType;
+use rcgen::SignatureAlgorithm;
+use rsa::pkcs1::DecodeRsaPrivateKey;
+use rsa::pkcs8::EncodePrivateKey;
+use rsa::RsaPrivateKey;
+use serde::Deserialize;
+use std::cell::RefCell;
+use std::net::IpAddr;
+use std::rc::Rc;
+
+use crate::key::Algorithm;
+use crate::key::CryptoHash;
+use crate::key::CryptoNamedCurve;
+use crate::shared::data_error;
+use crate::KeyData;
+use crate::KeyType;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CsrArg {
+  key: KeyData,
+  algorithm: Algorithm,
+  hash: Option<CryptoHash>,
+  named_curve: Option<CryptoNamedCurve>,
+  /// `[attribute, value]` pairs, where the attribute is one of "C", "ST",
+  /// "L", "O", "OU", "CN" or a dotted OID.
+  subject: Vec<(String, String)>,
+  #[serde(default)]
+  subject_alt_names: Vec<SubjectAltName>,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "lowercase")]
+pub enum SubjectAltName {
+  Dns(String),
+  Email(String),
+  Uri(String),
+  Ip(IpAddr),
+}
+
+impl From<SubjectAltName> for SanType {
+  fn from(name: SubjectAltName) -> Self {
+    match name {
+      SubjectAltName::Dns(name) => SanType::DnsName(name),
+      SubjectAltName::Email(name) => SanType::Rfc822Name(name),
+      SubjectAltName::Uri(name) => SanType::URI(name),
+      SubjectAltName::Ip(addr) => SanType::IpAddress(addr),
+    }
+  }
+}
+
+fn dn_type(attribute: &str) -> Result<DnType, AnyError> {
+  Ok(match attribute {
+    "C" => DnType::CountryName,
+    "ST" => DnType::StateOrProvinceName,
+    "L" => DnType::LocalityName,
+    "O" => DnType::OrganizationName,
+    "OU" => DnType::OrganizationalUnitName,
+    "CN" => DnType::CommonName,
+    oid => {
+      let arcs = oid
+        .split('.')
+        .map(|arc| arc.parse::<u64>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| {
+          type_error(format!("Unknown subject attribute: {attribute}"))
+        })?;
+      DnType::from_oid(&arcs)
+    }
+  })
+}
+
+/// Returns the PKCS#8 encoding of the private key along with the matching
+/// signature algorithm.
+fn signing_key(
+  args: &CsrArg,
+) -> Result<(Vec<u8>, &'static SignatureAlgorithm), AnyError> {
+  if !matches!(args.key.r#type, KeyType::Private) {
+    return Err(type_error("Expected a private key"));
+  }
+
+  match args.algorithm {
+    Algorithm::RsassaPkcs1v15 => {
+      let alg = match args.hash {
+        Some(CryptoHash::Sha256) => &rcgen::PKCS_RSA_SHA256,
+        Some(CryptoHash::Sha384) => &rcgen::PKCS_RSA_SHA384,
+        Some(CryptoHash::Sha512) => &rcgen::PKCS_RSA_SHA512,
+        _ => return Err(type_error("Unsupported algorithm")),
+      };
+      // RSA private keys are kept as PKCS#1.
+      let pkcs8 = RsaPrivateKey::from_pkcs1_der(&args.key.data)
+        .map_err(|_| data_error("invalid RSA private key"))?
+        .to_pkcs8_der()
+        .map_err(|_| data_error("invalid RSA private key"))?;
+      Ok((pkcs8.as_bytes().to_vec(), alg))
+    }
+    Algorithm::Ecdsa => {
+      let alg = match args.named_curve {
+        Some(CryptoNamedCurve::P256) => &rcgen::PKCS_ECDSA_P256_SHA256,
+        Some(CryptoNamedCurve::P384) => &rcgen::PKCS_ECDSA_P384_SHA384,
+        _ => return Err(type_error("Unsupported algorithm")),
+      };
+      Ok((args.key.data.to_vec(), alg))
+    }
+    _ => Err(type_error("Unsupported algorithm")),
+  }
+}
+
+fn generate_csr(args: CsrArg) -> Result<Vec<u8>, AnyError> {
+  let (pkcs8, alg) = signing_key(&args)?;
+  let key_pair = KeyPair::from_der_and_sign_algo(&pkcs8, alg)
+    .map_err(|e| data_error(e.to_string()))?;
+
+  let mut distinguished_name = DistinguishedName::new();
+  for (attribute, value) in args.subject {
+    distinguished_name.push(dn_type(&attribute)?, value);
+  }
+
+  let mut params = CertificateParams::default();
+  params.alg = alg;
+  params.key_pair = Some(key_pair);
+  params.distinguished_name = distinguished_name;
+  params.subject_alt_names =
+    args.subject_alt_names.into_iter().map(Into::into).collect();
+
+  let request = Certificate::from_params(params)
+    .and_then(|cert| cert.serialize_request_der())
+    .map_err(|e| type_error(e.to_string()))?;
+  Ok(request)
+}
+
+/// Generates a DER encoded PKCS#10 certificate signing request.
+#[op]
+pub async fn op_crypto_generate_csr(
+  state: Rc<RefCell<OpState>>,
+  mut args: CsrArg,
+) -> Result<ToJsBuffer, AnyError> {
+  args.key.resolve(&state.borrow())?;
+  let request = spawn_blocking(move || generate_csr(args)).await.unwrap()?;
+  Ok(request.into())
+}
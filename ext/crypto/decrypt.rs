@@ -3,6 +3,7 @@
 use aes::cipher::block_padding::Pkcs7;
 use aes::cipher::BlockDecryptMut;
 use aes::cipher::KeyIvInit;
+use aes_gcm::aead::generic_array::typenum::Unsigned;
 use aes_gcm::aead::generic_array::typenum::U12;
 use aes_gcm::aead::generic_array::typenum::U16;
 use aes_gcm::aead::generic_array::ArrayLength;
@@ -12,6 +13,9 @@ use aes_gcm::aes::Aes256;
 use aes_gcm::AeadInPlace;
 use aes_gcm::KeyInit;
 use aes_gcm::Nonce;
+use aes_gcm_siv::Aes256GcmSiv;
+use chacha20poly1305::ChaCha20Poly1305;
+use chacha20poly1305::XChaCha20Poly1305;
 use ctr::cipher::StreamCipher;
 use ctr::Ctr128BE;
 use ctr::Ctr32BE;
@@ -22,6 +26,7 @@ use deno_core::error::AnyError;
 use deno_core::op;
 use deno_core::task::spawn_blocking;
 use deno_core::JsBuffer;
+use deno_core::OpState;
 use deno_core::ToJsBuffer;
 use rsa::pkcs1::DecodeRsaPrivateKey;
 use rsa::PaddingScheme;
@@ -31,13 +36,20 @@ use sha1::Sha1;
 use sha2::Sha256;
 use sha2::Sha384;
 use sha2::Sha512;
+use std::cell::RefCell;
+use std::rc::Rc;
+use zeroize::Zeroize;
 
+use crate::backend;
+use crate::backend::CryptoBackend;
+use crate::fips;
 use crate::shared::*;
+use crate::KeyData;
 
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DecryptOptions {
-  key: V8RawKeyData,
+  key: KeyData,
   #[serde(flatten)]
   algorithm: DecryptAlgorithm,
 }
@@ -73,17 +85,56 @@ pub enum DecryptAlgorithm {
     length: usize,
     tag_length: usize,
   },
+  #[serde(rename = "ChaCha20-Poly1305", rename_all = "camelCase")]
+  ChaCha20Poly1305 {
+    #[serde(with = "serde_bytes")]
+    iv: Vec<u8>,
+    #[serde(with = "serde_bytes")]
+    additional_data: Option<Vec<u8>>,
+  },
+  #[serde(rename = "XChaCha20-Poly1305", rename_all = "camelCase")]
+  XChaCha20Poly1305 {
+    #[serde(with = "serde_bytes")]
+    iv: Vec<u8>,
+    #[serde(with = "serde_bytes")]
+    additional_data: Option<Vec<u8>>,
+  },
+  #[serde(rename = "AES-256-GCM-SIV", rename_all = "camelCase")]
+  Aes256GcmSiv {
+    #[serde(with = "serde_bytes")]
+    iv: Vec<u8>,
+    #[serde(with = "serde_bytes")]
+    additional_data: Option<Vec<u8>>,
+  },
+}
+
+impl DecryptAlgorithm {
+  /// The name of the algorithm, if it is not approved in FIPS mode.
+  fn unapproved_name(&self) -> Option<&'static str> {
+    match self {
+      DecryptAlgorithm::ChaCha20Poly1305 { .. } => Some("ChaCha20-Poly1305"),
+      DecryptAlgorithm::XChaCha20Poly1305 { .. } => Some("XChaCha20-Poly1305"),
+      DecryptAlgorithm::Aes256GcmSiv { .. } => Some("AES-256-GCM-SIV"),
+      _ => None,
+    }
+  }
 }
 
 #[op]
 pub async fn op_crypto_decrypt(
-  opts: DecryptOptions,
+  state: Rc<RefCell<OpState>>,
+  mut opts: DecryptOptions,
   data: JsBuffer,
 ) -> Result<ToJsBuffer, AnyError> {
-  let key = opts.key;
+  if let Some(name) = opts.algorithm.unapproved_name() {
+    fips::check_unapproved(&state.borrow(), name)?;
+  }
+  opts.key.resolve(&state.borrow())?;
+  let key = V8RawKeyData::from(opts.key);
+  let backend = backend::get(&state.borrow());
   let fun = move || match opts.algorithm {
     DecryptAlgorithm::RsaOaep { hash, label } => {
-      decrypt_rsa_oaep(key, hash, label, &data)
+      decrypt_rsa_oaep(key, backend, hash, label, &data)
     }
     DecryptAlgorithm::AesCbc { iv, length } => {
       decrypt_aes_cbc(key, length, iv, &data)
@@ -99,18 +150,36 @@ pub async fn op_crypto_decrypt(
       length,
       tag_length,
     } => decrypt_aes_gcm(key, length, tag_length, iv, additional_data, &data),
+    DecryptAlgorithm::ChaCha20Poly1305 {
+      iv,
+      additional_data,
+    } => decrypt_aead::<ChaCha20Poly1305>(key, iv, additional_data, &data),
+    DecryptAlgorithm::XChaCha20Poly1305 {
+      iv,
+      additional_data,
+    } => decrypt_aead::<XChaCha20Poly1305>(key, iv, additional_data, &data),
+    DecryptAlgorithm::Aes256GcmSiv {
+      iv,
+      additional_data,
+    } => decrypt_aead::<Aes256GcmSiv>(key, iv, additional_data, &data),
   };
   let buf = spawn_blocking(fun).await.unwrap()?;
   Ok(buf.into())
 }
 
-fn decrypt_rsa_oaep(
+pub(crate) fn decrypt_rsa_oaep(
   key: V8RawKeyData,
+  backend: CryptoBackend,
   hash: ShaHash,
   label: Vec<u8>,
   data: &[u8],
 ) -> Result<Vec<u8>, deno_core::anyhow::Error> {
   let key = key.as_rsa_private_key()?;
+  if let Some(plaintext) =
+    backend::rsa_oaep_decrypt(backend, key, hash, &label, data)
+  {
+    return plaintext;
+  }
 
   let private_key = rsa::RsaPrivateKey::from_pkcs1_der(key)?;
   let label = Some(String::from_utf8_lossy(&label).to_string());
@@ -315,7 +384,7 @@ fn decrypt_aes_ctr(
   }
 }
 
-fn decrypt_aes_gcm(
+pub(crate) fn decrypt_aes_gcm(
   key: V8RawKeyData,
   length: usize,
   tag_length: usize,
@@ -341,7 +410,7 @@ fn decrypt_aes_gcm(
   let mut plaintext = data[..sep].to_vec();
 
   // Fixed 96-bit or 128-bit nonce
-  match iv.len() {
+  let result = match iv.len() {
     12 => decrypt_aes_gcm_gen::<U12>(
       key,
       tag.into(),
@@ -349,7 +418,7 @@ fn decrypt_aes_gcm(
       length,
       additional_data,
       &mut plaintext,
-    )?,
+    ),
     16 => decrypt_aes_gcm_gen::<U16>(
       key,
       tag.into(),
@@ -357,9 +426,63 @@ fn decrypt_aes_gcm(
       length,
       additional_data,
       &mut plaintext,
-    )?,
+    ),
     _ => return Err(type_error("iv length not equal to 12 or 16")),
+  };
+
+  // `aes_gcm` decrypts before checking the tag, so on failure the buffer
+  // holds unauthenticated plaintext.
+  if let Err(err) = result {
+    plaintext.zeroize();
+    return Err(err);
   }
 
   Ok(plaintext)
 }
+
+fn decrypt_aead<C>(
+  key: V8RawKeyData,
+  iv: Vec<u8>,
+  additional_data: Option<Vec<u8>>,
+  data: &[u8],
+) -> Result<Vec<u8>, AnyError>
+where
+  C: KeyInit + AeadInPlace,
+{
+  let key = key.as_secret_key()?;
+  let additional_data = additional_data.unwrap_or_default();
+
+  // 96-bit nonce for ChaCha20-Poly1305 and AES-GCM-SIV, 192-bit nonce for
+  // XChaCha20-Poly1305.
+  if iv.len() != C::NonceSize::USIZE {
+    return Err(type_error(format!(
+      "iv length not equal to {}",
+      C::NonceSize::USIZE
+    )));
+  }
+
+  // Poly1305 and POLYVAL based AEADs always produce a 128-bit tag.
+  let tag_length = C::TagSize::USIZE;
+  if data.len() < tag_length {
+    return Err(operation_error("Decryption failed"));
+  }
+
+  let sep = data.len() - tag_length;
+  let tag = &data[sep..];
+
+  // The actual ciphertext, called plaintext because it is reused in place.
+  let mut plaintext = data[..sep].to_vec();
+
+  let cipher =
+    C::new_from_slice(key).map_err(|_| operation_error("Decryption failed"))?;
+  cipher
+    .decrypt_in_place_detached(
+      aes_gcm::aead::Nonce::<C>::from_slice(&iv),
+      &additional_data,
+      &mut plaintext,
+      aes_gcm::aead::Tag::<C>::from_slice(tag),
+    )
+    .map_err(|_| operation_error("Decryption failed"))?;
+
+  Ok(plaintext)
+}
@@ -0,0 +1,82 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use deno_core::error::type_error;
+use deno_core::error::AnyError;
+use deno_core::op;
+use deno_core::OpState;
+use deno_core::Resource;
+use deno_core::ResourceId;
+use deno_core::ToJsBuffer;
+use ring::digest;
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::key::CryptoHash;
+
+/// Incremental digest state, so that large inputs can be hashed chunk by
+/// chunk instead of being copied into a single buffer first.
+pub struct DigestContext(RefCell<digest::Context>);
+
+impl Resource for DigestContext {
+  fn name(&self) -> Cow<str> {
+    "cryptoDigestContext".into()
+  }
+}
+
+#[op]
+pub fn op_crypto_digest_create(
+  state: &mut OpState,
+  algorithm: CryptoHash,
+) -> ResourceId {
+  let context = digest::Context::new(algorithm.into());
+  state
+    .resource_table
+    .add(DigestContext(RefCell::new(context)))
+}
+
+#[op]
+pub fn op_crypto_digest_update(
+  state: &mut OpState,
+  rid: ResourceId,
+  data: &[u8],
+) -> Result<(), AnyError> {
+  let context = state.resource_table.get::<DigestContext>(rid)?;
+  context.0.borrow_mut().update(data);
+  Ok(())
+}
+
+#[op]
+pub fn op_crypto_digest_finalize(
+  state: &mut OpState,
+  rid: ResourceId,
+) -> Result<ToJsBuffer, AnyError> {
+  let context = state.resource_table.take::<DigestContext>(rid)?;
+  let context = Rc::try_unwrap(context)
+    .map_err(|_| type_error("Digest context is already in use"))?;
+  let digest = context.0.into_inner().finish();
+  Ok(digest.as_ref().to_vec().into())
+}
+
+/// Size of the chunks read by `op_crypto_digest_resource`.
+const READ_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Digests everything that can be read from a resource, like an open file or
+/// a readable stream, without passing the data through JS.
+#[op]
+pub async fn op_crypto_digest_resource(
+  state: Rc<RefCell<OpState>>,
+  rid: ResourceId,
+  algorithm: CryptoHash,
+) -> Result<ToJsBuffer, AnyError> {
+  let resource = state.borrow().resource_table.get_any(rid)?;
+  let mut context = digest::Context::new(algorithm.into());
+  loop {
+    let view = resource.clone().read(READ_CHUNK_SIZE).await?;
+    if view.is_empty() {
+      break;
+    }
+    context.update(&view);
+  }
+  Ok(context.finish().as_ref().to_vec().into())
+}
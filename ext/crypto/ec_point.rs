@@ -0,0 +1,56 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! Strict validation of peer supplied EC public keys, so that servers can
+//! reject malformed points before using them for ECDH.
+
+use deno_core::error::AnyError;
+use deno_core::op;
+use deno_core::ToJsBuffer;
+use serde::Serialize;
+
+use crate::key::CryptoNamedCurve;
+use crate::shared::data_error;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EcPublicKeyEncodings {
+  uncompressed: ToJsBuffer,
+  compressed: ToJsBuffer,
+}
+
+// Decodes a SEC1 point, which checks its length, that it is on the curve and
+// that it is not the point at infinity. Every supported curve has cofactor 1,
+// so any such point is in the prime order subgroup and no further check is
+// needed.
+macro_rules! validate_point {
+  ($curve:ident, $point:expr) => {{
+    use $curve::elliptic_curve::sec1::ToEncodedPoint;
+    let key = $curve::PublicKey::from_sec1_bytes($point)
+      .map_err(|_| data_error("invalid EC public key"))?;
+    EcPublicKeyEncodings {
+      uncompressed: key.to_encoded_point(false).as_bytes().to_vec().into(),
+      compressed: key.to_encoded_point(true).as_bytes().to_vec().into(),
+    }
+  }};
+}
+
+/// Validates a SEC1 encoded public key and returns both its uncompressed and
+/// compressed encodings.
+#[op]
+pub fn op_crypto_validate_ec_public_key(
+  point: &[u8],
+  named_curve: CryptoNamedCurve,
+) -> Result<EcPublicKeyEncodings, AnyError> {
+  // Only the compressed (0x02, 0x03) and uncompressed (0x04) forms are
+  // accepted; the identity (0x00), compact and hybrid forms are not.
+  if !matches!(point.first(), Some(0x02..=0x04)) {
+    return Err(data_error("invalid EC point encoding"));
+  }
+  let encodings = match named_curve {
+    CryptoNamedCurve::P256 => validate_point!(p256, point),
+    CryptoNamedCurve::P384 => validate_point!(p384, point),
+    CryptoNamedCurve::P521 => validate_point!(p521, point),
+    CryptoNamedCurve::Secp256k1 => validate_point!(k256, point),
+  };
+  Ok(encodings)
+}
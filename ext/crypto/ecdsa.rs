@@ -0,0 +1,105 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! Conversion between the fixed-size `r || s` ECDSA signatures produced by
+//! WebCrypto (IEEE P1363) and the ASN.1 DER `Ecdsa-Sig-Value` used by most
+//! PKI tooling.
+
+use deno_core::error::AnyError;
+use deno_core::op;
+use deno_core::ToJsBuffer;
+use spki::der;
+use spki::der::asn1::UIntRef;
+use spki::der::Decode;
+use spki::der::DecodeValue;
+use spki::der::Encode;
+use spki::der::Header;
+use spki::der::Reader;
+use spki::der::Sequence;
+
+use crate::key::CryptoNamedCurve;
+use crate::shared::data_error;
+
+// Ecdsa-Sig-Value ::= SEQUENCE { r INTEGER, s INTEGER }
+struct EcdsaSigValue<'a> {
+  r: UIntRef<'a>,
+  s: UIntRef<'a>,
+}
+
+impl<'a> DecodeValue<'a> for EcdsaSigValue<'a> {
+  fn decode_value<R: Reader<'a>>(
+    reader: &mut R,
+    header: Header,
+  ) -> der::Result<Self> {
+    reader.read_nested(header.length, |reader| {
+      Ok(Self {
+        r: reader.decode()?,
+        s: reader.decode()?,
+      })
+    })
+  }
+}
+
+impl<'a> Sequence<'a> for EcdsaSigValue<'a> {
+  fn fields<F, T>(&self, f: F) -> der::Result<T>
+  where
+    F: FnOnce(&[&dyn Encode]) -> der::Result<T>,
+  {
+    f(&[&self.r, &self.s])
+  }
+}
+
+/// Size in bytes of the curve order, i.e. of each of `r` and `s`.
+fn scalar_len(named_curve: CryptoNamedCurve) -> usize {
+  match named_curve {
+    CryptoNamedCurve::P256 | CryptoNamedCurve::Secp256k1 => 32,
+    CryptoNamedCurve::P384 => 48,
+    CryptoNamedCurve::P521 => 66,
+  }
+}
+
+fn is_zero(n: &UIntRef) -> bool {
+  n.as_bytes() == [0]
+}
+
+#[op]
+pub fn op_crypto_ecdsa_signature_to_der(
+  signature: &[u8],
+  named_curve: CryptoNamedCurve,
+) -> Result<ToJsBuffer, AnyError> {
+  let len = scalar_len(named_curve);
+  if signature.len() != 2 * len {
+    return Err(data_error("invalid signature length for curve"));
+  }
+  let (r, s) = signature.split_at(len);
+  let value = EcdsaSigValue {
+    r: UIntRef::new(r).map_err(|_| data_error("invalid signature"))?,
+    s: UIntRef::new(s).map_err(|_| data_error("invalid signature"))?,
+  };
+  if is_zero(&value.r) || is_zero(&value.s) {
+    return Err(data_error("invalid signature"));
+  }
+  let der = value
+    .to_vec()
+    .map_err(|_| data_error("invalid signature"))?;
+  Ok(der.into())
+}
+
+#[op]
+pub fn op_crypto_ecdsa_signature_from_der(
+  signature: &[u8],
+  named_curve: CryptoNamedCurve,
+) -> Result<ToJsBuffer, AnyError> {
+  let value = EcdsaSigValue::from_der(signature)
+    .map_err(|_| data_error("invalid DER encoded signature"))?;
+  let len = scalar_len(named_curve);
+
+  let mut raw = vec![0; 2 * len];
+  for (n, out) in [value.r, value.s].iter().zip(raw.chunks_exact_mut(len)) {
+    let bytes = n.as_bytes();
+    if bytes.len() > len || is_zero(n) {
+      return Err(data_error("invalid signature for curve"));
+    }
+    out[len - bytes.len()..].copy_from_slice(bytes);
+  }
+  Ok(raw.into())
+}
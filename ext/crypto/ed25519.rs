@@ -2,54 +2,70 @@
 
 use deno_core::error::AnyError;
 use deno_core::op;
-use deno_core::ToJsBuffer;
-use elliptic_curve::pkcs8::PrivateKeyInfo;
+use deno_core::OpState;
 use rand::rngs::OsRng;
 use rand::RngCore;
 use ring::signature::Ed25519KeyPair;
 use ring::signature::KeyPair;
+use rsa::pkcs8::PrivateKeyInfo;
 use spki::der::Decode;
 use spki::der::Encode;
 
+use crate::fips;
+use crate::KeyData;
+
 #[op(fast)]
 pub fn op_crypto_generate_ed25519_keypair(
+  state: &mut OpState,
   pkey: &mut [u8],
   pubkey: &mut [u8],
-) -> bool {
+) -> Result<bool, AnyError> {
+  fips::check_unapproved(state, "Ed25519")?;
   let mut rng = OsRng;
   rng.fill_bytes(pkey);
 
   let pair = match Ed25519KeyPair::from_seed_unchecked(pkey) {
     Ok(p) => p,
-    Err(_) => return false,
+    Err(_) => return Ok(false),
   };
   pubkey.copy_from_slice(pair.public_key().as_ref());
-  true
+  Ok(true)
 }
 
-#[op(fast)]
+#[op]
 pub fn op_crypto_sign_ed25519(
-  key: &[u8],
+  state: &mut OpState,
+  mut key: KeyData,
   data: &[u8],
   signature: &mut [u8],
-) -> bool {
-  let pair = match Ed25519KeyPair::from_seed_unchecked(key) {
+) -> Result<bool, AnyError> {
+  fips::check_unapproved(state, "Ed25519")?;
+  key.resolve(state)?;
+  let pair = match Ed25519KeyPair::from_seed_unchecked(&key.data) {
     Ok(p) => p,
-    Err(_) => return false,
+    Err(_) => return Ok(false),
   };
   signature.copy_from_slice(pair.sign(data).as_ref());
-  true
+  Ok(true)
 }
 
-#[op(fast)]
+#[op]
 pub fn op_crypto_verify_ed25519(
-  pubkey: &[u8],
+  state: &mut OpState,
+  mut key: KeyData,
   data: &[u8],
   signature: &[u8],
-) -> bool {
-  ring::signature::UnparsedPublicKey::new(&ring::signature::ED25519, pubkey)
+) -> Result<bool, AnyError> {
+  fips::check_unapproved(state, "Ed25519")?;
+  key.resolve(state)?;
+  Ok(
+    ring::signature::UnparsedPublicKey::new(
+      &ring::signature::ED25519,
+      &key.data,
+    )
     .verify(data, signature)
-    .is_ok()
+    .is_ok(),
+  )
 }
 
 // id-Ed25519 OBJECT IDENTIFIER ::= { 1 3 101 112 }
@@ -102,10 +118,7 @@ pub fn op_crypto_import_pkcs8_ed25519(key_data: &[u8], out: &mut [u8]) -> bool {
   true
 }
 
-#[op]
-pub fn op_crypto_export_spki_ed25519(
-  pubkey: &[u8],
-) -> Result<ToJsBuffer, AnyError> {
+pub fn export_spki(pubkey: &[u8]) -> Result<Vec<u8>, AnyError> {
   let key_info = spki::SubjectPublicKeyInfo {
     algorithm: spki::AlgorithmIdentifier {
       // id-Ed25519
@@ -114,13 +127,16 @@ pub fn op_crypto_export_spki_ed25519(
     },
     subject_public_key: pubkey,
   };
-  Ok(key_info.to_vec()?.into())
+  Ok(key_info.to_vec()?)
 }
 
-#[op]
-pub fn op_crypto_export_pkcs8_ed25519(
-  pkey: &[u8],
-) -> Result<ToJsBuffer, AnyError> {
+pub fn export_pkcs8(pkey: &[u8]) -> Result<Vec<u8>, AnyError> {
+  // CurvePrivateKey ::= OCTET STRING
+  let mut private_key = Vec::with_capacity(pkey.len() + 2);
+  private_key.push(0x04);
+  private_key.push(pkey.len() as u8);
+  private_key.extend_from_slice(pkey);
+
   // This should probably use OneAsymmetricKey instead
   let pk_info = rsa::pkcs8::PrivateKeyInfo {
     public_key: None,
@@ -129,19 +145,38 @@ pub fn op_crypto_export_pkcs8_ed25519(
       oid: ED25519_OID,
       parameters: None,
     },
-    private_key: pkey, // OCTET STRING
+    private_key: &private_key,
   };
 
-  Ok(pk_info.to_vec()?.into())
+  Ok(pk_info.to_vec()?)
 }
 
 // 'x' from Section 2 of RFC 8037
 // https://www.rfc-editor.org/rfc/rfc8037#section-2
-#[op]
-pub fn op_crypto_jwk_x_ed25519(pkey: &[u8]) -> Result<String, AnyError> {
+pub fn jwk_x(pkey: &[u8]) -> Result<String, AnyError> {
   let pair = Ed25519KeyPair::from_seed_unchecked(pkey)?;
   Ok(base64::encode_config(
     pair.public_key().as_ref(),
     base64::URL_SAFE_NO_PAD,
   ))
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_export_pkcs8() {
+    let seed = [7; 32];
+    let pkcs8 = export_pkcs8(&seed).unwrap();
+    // RFC 8410 Section 10.3
+    assert_eq!(
+      &pkcs8[..16],
+      &[
+        0x30, 0x2e, 0x02, 0x01, 0x00, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70,
+        0x04, 0x22, 0x04, 0x20
+      ]
+    );
+    assert_eq!(&pkcs8[16..], &seed);
+  }
+}
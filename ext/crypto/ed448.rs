@@ -0,0 +1,340 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! Ed448 signatures (RFC 8032 Section 5.2), built on the curve arithmetic of
+//! `ed448-goldilocks`.
+
+use deno_core::error::AnyError;
+use deno_core::op;
+use deno_core::OpState;
+use ed448_goldilocks::curve::edwards::CompressedEdwardsY;
+use ed448_goldilocks::curve::ExtendedPoint;
+use ed448_goldilocks::Scalar;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use rsa::pkcs8::PrivateKeyInfo;
+use sha3::digest::ExtendableOutput;
+use sha3::digest::Update;
+use sha3::digest::XofReader;
+use sha3::Shake256;
+use spki::der::Decode;
+use spki::der::Encode;
+use zeroize::Zeroizing;
+
+use crate::fips;
+use crate::KeyData;
+
+// https://www.rfc-editor.org/rfc/rfc8032#section-5.2
+const ED448_KEY_LEN: usize = 57;
+const ED448_SIGNATURE_LEN: usize = 2 * ED448_KEY_LEN;
+
+// dom4(0, "") for pure Ed448 with an empty context string.
+const DOM4: &[u8] = b"SigEd448\x00\x00";
+
+fn shake256(parts: &[&[u8]]) -> Zeroizing<[u8; 114]> {
+  let mut hasher = Shake256::default();
+  for part in parts {
+    hasher.update(part);
+  }
+  let mut out = Zeroizing::new([0; 114]);
+  hasher.finalize_xof().read(&mut *out);
+  out
+}
+
+fn scalar_from_hash(hash: &[u8; 114]) -> Scalar {
+  Scalar::from_bytes_mod_order_wide(hash)
+}
+
+/// The secret scalar and the prefix used to derive nonces, expanded from a
+/// private key as in RFC 8032 Section 5.2.5.
+struct ExpandedKey {
+  scalar: Scalar,
+  prefix: Zeroizing<[u8; ED448_KEY_LEN]>,
+  public_key: [u8; ED448_KEY_LEN],
+}
+
+fn expand_private_key(seed: &[u8]) -> Option<ExpandedKey> {
+  if seed.len() != ED448_KEY_LEN {
+    return None;
+  }
+  let hash = shake256(&[seed]);
+
+  let mut scalar_bytes = Zeroizing::new([0; 114]);
+  scalar_bytes[..ED448_KEY_LEN].copy_from_slice(&hash[..ED448_KEY_LEN]);
+  scalar_bytes[0] &= 0xfc;
+  scalar_bytes[55] |= 0x80;
+  scalar_bytes[56] = 0;
+  let scalar = scalar_from_hash(&scalar_bytes);
+
+  let mut prefix = Zeroizing::new([0; ED448_KEY_LEN]);
+  prefix.copy_from_slice(&hash[ED448_KEY_LEN..]);
+
+  let public_key = ExtendedPoint::generator().scalar_mul(&scalar).compress().0;
+  Some(ExpandedKey {
+    scalar,
+    prefix,
+    public_key,
+  })
+}
+
+fn sign(seed: &[u8], data: &[u8]) -> Option<[u8; ED448_SIGNATURE_LEN]> {
+  let key = expand_private_key(seed)?;
+  let r = scalar_from_hash(&shake256(&[DOM4, &*key.prefix, data]));
+  let big_r = ExtendedPoint::generator().scalar_mul(&r).compress().0;
+  let k = scalar_from_hash(&shake256(&[DOM4, &big_r, &key.public_key, data]));
+  let s = r + k * key.scalar;
+
+  let mut signature = [0; ED448_SIGNATURE_LEN];
+  signature[..ED448_KEY_LEN].copy_from_slice(&big_r);
+  signature[ED448_KEY_LEN..].copy_from_slice(&s.to_bytes_rfc_8032());
+  Some(signature)
+}
+
+fn verify(public_key: &[u8], data: &[u8], signature: &[u8]) -> bool {
+  if public_key.len() != ED448_KEY_LEN || signature.len() != ED448_SIGNATURE_LEN
+  {
+    return false;
+  }
+  let public_key: [u8; ED448_KEY_LEN] = public_key.try_into().unwrap();
+  let big_r: [u8; ED448_KEY_LEN] =
+    signature[..ED448_KEY_LEN].try_into().unwrap();
+  let s: [u8; ED448_KEY_LEN] = signature[ED448_KEY_LEN..].try_into().unwrap();
+
+  let Some(a) = CompressedEdwardsY(public_key).decompress() else {
+    return false;
+  };
+  let Some(r) = CompressedEdwardsY(big_r).decompress() else {
+    return false;
+  };
+  let Some(s) = Scalar::from_canonical_bytes(s) else {
+    return false;
+  };
+  let k = scalar_from_hash(&shake256(&[DOM4, &big_r, &public_key, data]));
+
+  // [4][S]B = [4]R + [4][k]A
+  let lhs = ExtendedPoint::generator().scalar_mul(&s);
+  let rhs = r.add(&a.scalar_mul(&k));
+  lhs.double().double() == rhs.double().double()
+}
+
+#[op(fast)]
+pub fn op_crypto_generate_ed448_keypair(
+  state: &mut OpState,
+  pkey: &mut [u8],
+  pubkey: &mut [u8],
+) -> Result<bool, AnyError> {
+  fips::check_unapproved(state, "Ed448")?;
+  let mut rng = OsRng;
+  rng.fill_bytes(pkey);
+
+  let key = match expand_private_key(pkey) {
+    Some(key) => key,
+    None => return Ok(false),
+  };
+  pubkey.copy_from_slice(&key.public_key);
+  Ok(true)
+}
+
+#[op]
+pub fn op_crypto_sign_ed448(
+  state: &mut OpState,
+  mut key: KeyData,
+  data: &[u8],
+  signature: &mut [u8],
+) -> Result<bool, AnyError> {
+  fips::check_unapproved(state, "Ed448")?;
+  key.resolve(state)?;
+  if signature.len() != ED448_SIGNATURE_LEN {
+    return Ok(false);
+  }
+  match sign(&key.data, data) {
+    Some(sig) => {
+      signature.copy_from_slice(&sig);
+      Ok(true)
+    }
+    None => Ok(false),
+  }
+}
+
+#[op]
+pub fn op_crypto_verify_ed448(
+  state: &mut OpState,
+  mut key: KeyData,
+  data: &[u8],
+  signature: &[u8],
+) -> Result<bool, AnyError> {
+  fips::check_unapproved(state, "Ed448")?;
+  key.resolve(state)?;
+  Ok(verify(&key.data, data, signature))
+}
+
+// id-Ed448 OBJECT IDENTIFIER ::= { 1 3 101 113 }
+pub const ED448_OID: const_oid::ObjectIdentifier =
+  const_oid::ObjectIdentifier::new_unwrap("1.3.101.113");
+
+#[op(fast)]
+pub fn op_crypto_import_spki_ed448(key_data: &[u8], out: &mut [u8]) -> bool {
+  // 2-3.
+  let pk_info = match spki::SubjectPublicKeyInfo::from_der(key_data) {
+    Ok(pk_info) => pk_info,
+    Err(_) => return false,
+  };
+  // 4.
+  let alg = pk_info.algorithm.oid;
+  if alg != ED448_OID {
+    return false;
+  }
+  // 5.
+  if pk_info.algorithm.parameters.is_some() {
+    return false;
+  }
+  if pk_info.subject_public_key.len() != ED448_KEY_LEN {
+    return false;
+  }
+  out.copy_from_slice(pk_info.subject_public_key);
+  true
+}
+
+#[op(fast)]
+pub fn op_crypto_import_pkcs8_ed448(key_data: &[u8], out: &mut [u8]) -> bool {
+  // 2-3.
+  // This should probably use OneAsymmetricKey instead
+  let pk_info = match PrivateKeyInfo::from_der(key_data) {
+    Ok(pk_info) => pk_info,
+    Err(_) => return false,
+  };
+  // 4.
+  let alg = pk_info.algorithm.oid;
+  if alg != ED448_OID {
+    return false;
+  }
+  // 5.
+  if pk_info.algorithm.parameters.is_some() {
+    return false;
+  }
+  // 6.
+  // CurvePrivateKey ::= OCTET STRING
+  if pk_info.private_key.len() != ED448_KEY_LEN + 2 {
+    return false;
+  }
+  out.copy_from_slice(&pk_info.private_key[2..]);
+  true
+}
+
+pub fn export_spki(pubkey: &[u8]) -> Result<Vec<u8>, AnyError> {
+  let key_info = spki::SubjectPublicKeyInfo {
+    algorithm: spki::AlgorithmIdentifier {
+      // id-Ed448
+      oid: ED448_OID,
+      parameters: None,
+    },
+    subject_public_key: pubkey,
+  };
+  Ok(key_info.to_vec()?)
+}
+
+pub fn export_pkcs8(pkey: &[u8]) -> Result<Vec<u8>, AnyError> {
+  // CurvePrivateKey ::= OCTET STRING
+  let mut private_key = Vec::with_capacity(pkey.len() + 2);
+  private_key.push(0x04);
+  private_key.push(pkey.len() as u8);
+  private_key.extend_from_slice(pkey);
+
+  // This should probably use OneAsymmetricKey instead
+  let pk_info = rsa::pkcs8::PrivateKeyInfo {
+    public_key: None,
+    algorithm: rsa::pkcs8::AlgorithmIdentifier {
+      // id-Ed448
+      oid: ED448_OID,
+      parameters: None,
+    },
+    private_key: &private_key,
+  };
+
+  Ok(pk_info.to_vec()?)
+}
+
+// 'x' from Section 2 of RFC 8037
+// https://www.rfc-editor.org/rfc/rfc8037#section-2
+pub fn jwk_x(pkey: &[u8]) -> Result<String, AnyError> {
+  let key = expand_private_key(pkey).ok_or_else(|| {
+    crate::shared::data_error("invalid Ed448 private key length")
+  })?;
+  Ok(base64::encode_config(
+    key.public_key,
+    base64::URL_SAFE_NO_PAD,
+  ))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn hex(s: &str) -> Vec<u8> {
+    (0..s.len())
+      .step_by(2)
+      .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+      .collect()
+  }
+
+  // RFC 8032 Section 7.4
+  const VECTORS: &[(&str, &str, &str, &str)] = &[
+    (
+      "6c82a562cb808d10d632be89c8513ebf6c929f34ddfa8c9f63c9960ef6e348a3\
+       528c8a3fcc2f044e39a3fc5b94492f8f032e7549a20098f95b",
+      "5fd7449b59b461fd2ce787ec616ad46a1da1342485a70e1f8a0ea75d80e96778\
+       edf124769b46c7061bd6783df1e50f6cd1fa1abeafe8256180",
+      "",
+      "533a37f6bbe457251f023c0d88f976ae2dfb504a843e34d2074fd823d41a591f\
+       2b233f034f628281f2fd7a22ddd47d7828c59bd0a21bfd3980ff0d2028d4b18a\
+       9df63e006c5d1c2d345b925d8dc00b4104852db99ac5c7cdda8530a113a0f4db\
+       b61149f05a7363268c71d95808ff2e652600",
+    ),
+    (
+      "c4eab05d357007c632f3dbb48489924d552b08fe0c353a0d4a1f00acda2c463a\
+       fbea67c5e8d2877c5e3bc397a659949ef8021e954e0a12274e",
+      "43ba28f430cdff456ae531545f7ecd0ac834a55d9358c0372bfa0c6c6798c086\
+       6aea01eb00742802b8438ea4cb82169c235160627b4c3a9480",
+      "03",
+      "26b8f91727bd62897af15e41eb43c377efb9c610d48f2335cb0bd0087810f435\
+       2541b143c4b981b7e18f62de8ccdf633fc1bf037ab7cd779805e0dbcc0aae1cb\
+       cee1afb2e027df36bc04dcecbf154336c19f0af7e0a6472905e799f1953d2a0f\
+       f3348ab21aa4adafd1d234441cf807c03a00",
+    ),
+  ];
+
+  #[test]
+  fn test_rfc8032_vectors() {
+    for (seed, public_key, data, signature) in VECTORS {
+      let (seed, data) = (hex(seed), hex(data));
+      let (public_key, signature) = (hex(public_key), hex(signature));
+      assert_eq!(&expand_private_key(&seed).unwrap().public_key, &*public_key);
+      assert_eq!(&sign(&seed, &data).unwrap(), &*signature);
+      assert!(verify(&public_key, &data, &signature));
+    }
+  }
+
+  #[test]
+  fn test_verify_rejects_invalid_signatures() {
+    let (_, public_key, data, signature) = VECTORS[1];
+    let (public_key, data) = (hex(public_key), hex(data));
+    let signature = hex(signature);
+
+    assert!(!verify(&public_key, b"other", &signature));
+    assert!(!verify(&public_key, &data, &signature[..113]));
+    assert!(!verify(
+      &public_key,
+      &data,
+      &[signature.clone(), vec![0]].concat()
+    ));
+    assert!(!verify(&public_key[..56], &data, &signature));
+
+    let mut tampered = signature.clone();
+    tampered[0] ^= 1;
+    assert!(!verify(&public_key, &data, &tampered));
+
+    // S must be reduced modulo the group order.
+    let mut unreduced = signature;
+    unreduced[113] = 0x01;
+    assert!(!verify(&public_key, &data, &unreduced));
+  }
+}
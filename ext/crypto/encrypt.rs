@@ -1,9 +1,13 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
 use aes::cipher::block_padding::Pkcs7;
+use aes::cipher::BlockCipher;
+use aes::cipher::BlockEncrypt;
 use aes::cipher::BlockEncryptMut;
+use aes::cipher::BlockSizeUser;
 use aes::cipher::KeyIvInit;
 use aes::cipher::StreamCipher;
+use aes_gcm::aead::generic_array::typenum::Unsigned;
 use aes_gcm::aead::generic_array::typenum::U12;
 use aes_gcm::aead::generic_array::typenum::U16;
 use aes_gcm::aead::generic_array::ArrayLength;
@@ -13,6 +17,9 @@ use aes_gcm::aes::Aes256;
 use aes_gcm::AeadInPlace;
 use aes_gcm::KeyInit;
 use aes_gcm::Nonce;
+use aes_gcm_siv::Aes256GcmSiv;
+use chacha20poly1305::ChaCha20Poly1305;
+use chacha20poly1305::XChaCha20Poly1305;
 use ctr::Ctr128BE;
 use ctr::Ctr32BE;
 use ctr::Ctr64BE;
@@ -21,9 +28,13 @@ use deno_core::error::AnyError;
 use deno_core::op;
 use deno_core::task::spawn_blocking;
 use deno_core::JsBuffer;
+use deno_core::OpState;
+use deno_core::Resource;
+use deno_core::ResourceId;
 use deno_core::ToJsBuffer;
+use ghash::universal_hash::UniversalHash;
+use ghash::GHash;
 use rand::rngs::OsRng;
-use rsa::pkcs1::DecodeRsaPublicKey;
 use rsa::PaddingScheme;
 use rsa::PublicKey;
 use serde::Deserialize;
@@ -32,13 +43,19 @@ use sha1::Sha1;
 use sha2::Sha256;
 use sha2::Sha384;
 use sha2::Sha512;
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::rc::Rc;
+use zeroize::Zeroize;
 
+use crate::fips;
 use crate::shared::*;
+use crate::KeyData;
 
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct EncryptOptions {
-  key: V8RawKeyData,
+  key: KeyData,
   #[serde(flatten)]
   algorithm: EncryptAlgorithm,
 }
@@ -74,14 +91,52 @@ pub enum EncryptAlgorithm {
     ctr_length: usize,
     key_length: usize,
   },
+  #[serde(rename = "ChaCha20-Poly1305", rename_all = "camelCase")]
+  ChaCha20Poly1305 {
+    #[serde(with = "serde_bytes")]
+    iv: Vec<u8>,
+    #[serde(with = "serde_bytes")]
+    additional_data: Option<Vec<u8>>,
+  },
+  #[serde(rename = "XChaCha20-Poly1305", rename_all = "camelCase")]
+  XChaCha20Poly1305 {
+    #[serde(with = "serde_bytes")]
+    iv: Vec<u8>,
+    #[serde(with = "serde_bytes")]
+    additional_data: Option<Vec<u8>>,
+  },
+  #[serde(rename = "AES-256-GCM-SIV", rename_all = "camelCase")]
+  Aes256GcmSiv {
+    #[serde(with = "serde_bytes")]
+    iv: Vec<u8>,
+    #[serde(with = "serde_bytes")]
+    additional_data: Option<Vec<u8>>,
+  },
+}
+
+impl EncryptAlgorithm {
+  /// The name of the algorithm, if it is not approved in FIPS mode.
+  fn unapproved_name(&self) -> Option<&'static str> {
+    match self {
+      EncryptAlgorithm::ChaCha20Poly1305 { .. } => Some("ChaCha20-Poly1305"),
+      EncryptAlgorithm::XChaCha20Poly1305 { .. } => Some("XChaCha20-Poly1305"),
+      EncryptAlgorithm::Aes256GcmSiv { .. } => Some("AES-256-GCM-SIV"),
+      _ => None,
+    }
+  }
 }
 
 #[op]
 pub async fn op_crypto_encrypt(
-  opts: EncryptOptions,
+  state: Rc<RefCell<OpState>>,
+  mut opts: EncryptOptions,
   data: JsBuffer,
 ) -> Result<ToJsBuffer, AnyError> {
-  let key = opts.key;
+  if let Some(name) = opts.algorithm.unapproved_name() {
+    fips::check_unapproved(&state.borrow(), name)?;
+  }
+  opts.key.resolve(&state.borrow())?;
+  let key = V8RawKeyData::from(opts.key);
   let fun = move || match opts.algorithm {
     EncryptAlgorithm::RsaOaep { hash, label } => {
       encrypt_rsa_oaep(key, hash, label, &data)
@@ -100,12 +155,24 @@ pub async fn op_crypto_encrypt(
       ctr_length,
       key_length,
     } => encrypt_aes_ctr(key, key_length, &counter, ctr_length, &data),
+    EncryptAlgorithm::ChaCha20Poly1305 {
+      iv,
+      additional_data,
+    } => encrypt_aead::<ChaCha20Poly1305>(key, iv, additional_data, &data),
+    EncryptAlgorithm::XChaCha20Poly1305 {
+      iv,
+      additional_data,
+    } => encrypt_aead::<XChaCha20Poly1305>(key, iv, additional_data, &data),
+    EncryptAlgorithm::Aes256GcmSiv {
+      iv,
+      additional_data,
+    } => encrypt_aead::<Aes256GcmSiv>(key, iv, additional_data, &data),
   };
   let buf = spawn_blocking(fun).await.unwrap()?;
   Ok(buf.into())
 }
 
-fn encrypt_rsa_oaep(
+pub(crate) fn encrypt_rsa_oaep(
   key: V8RawKeyData,
   hash: ShaHash,
   label: Vec<u8>,
@@ -114,7 +181,7 @@ fn encrypt_rsa_oaep(
   let label = String::from_utf8_lossy(&label).to_string();
 
   let public_key = key.as_rsa_public_key()?;
-  let public_key = rsa::RsaPublicKey::from_pkcs1_der(&public_key)
+  let public_key = rsa_public_key_from_pkcs1_der(&public_key)
     .map_err(|_| operation_error("failed to decode public key"))?;
   let mut rng = OsRng;
   let padding = match hash {
@@ -218,7 +285,7 @@ fn encrypt_aes_gcm_general<N: ArrayLength<u8>>(
   Ok(tag)
 }
 
-fn encrypt_aes_gcm(
+pub(crate) fn encrypt_aes_gcm(
   key: V8RawKeyData,
   length: usize,
   tag_length: usize,
@@ -231,22 +298,32 @@ fn encrypt_aes_gcm(
 
   let mut ciphertext = data.to_vec();
   // Fixed 96-bit OR 128-bit nonce
-  let tag = match iv.len() {
+  let result = match iv.len() {
     12 => encrypt_aes_gcm_general::<U12>(
       key,
       iv,
       length,
       &mut ciphertext,
       additional_data,
-    )?,
+    ),
     16 => encrypt_aes_gcm_general::<U16>(
       key,
       iv,
       length,
       &mut ciphertext,
       additional_data,
-    )?,
-    _ => return Err(type_error("iv length not equal to 12 or 16")),
+    ),
+    _ => Err(type_error("iv length not equal to 12 or 16")),
+  };
+
+  // On failure the buffer still holds a copy of the plaintext, which may be
+  // a key being wrapped.
+  let tag = match result {
+    Ok(tag) => tag,
+    Err(err) => {
+      ciphertext.zeroize();
+      return Err(err);
+    }
   };
 
   // Truncated tag to the specified tag length.
@@ -310,3 +387,439 @@ fn encrypt_aes_ctr(
     )),
   }
 }
+
+fn encrypt_aead<C>(
+  key: V8RawKeyData,
+  iv: Vec<u8>,
+  additional_data: Option<Vec<u8>>,
+  data: &[u8],
+) -> Result<Vec<u8>, AnyError>
+where
+  C: KeyInit + AeadInPlace,
+{
+  let key = key.as_secret_key()?;
+  let additional_data = additional_data.unwrap_or_default();
+
+  // 96-bit nonce for ChaCha20-Poly1305 and AES-GCM-SIV, 192-bit nonce for
+  // XChaCha20-Poly1305.
+  if iv.len() != C::NonceSize::USIZE {
+    return Err(type_error(format!(
+      "iv length not equal to {}",
+      C::NonceSize::USIZE
+    )));
+  }
+
+  let cipher =
+    C::new_from_slice(key).map_err(|_| operation_error("Encryption failed"))?;
+
+  let mut ciphertext = data.to_vec();
+  let tag = cipher
+    .encrypt_in_place_detached(
+      aes_gcm::aead::Nonce::<C>::from_slice(&iv),
+      &additional_data,
+      &mut ciphertext,
+    )
+    .map_err(|_| operation_error("Encryption failed"))?;
+
+  // C | T
+  ciphertext.extend_from_slice(&tag);
+
+  Ok(ciphertext)
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncryptStreamOptions {
+  key: KeyData,
+  #[serde(flatten)]
+  algorithm: EncryptStreamAlgorithm,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", tag = "algorithm")]
+pub enum EncryptStreamAlgorithm {
+  #[serde(rename = "AES-GCM", rename_all = "camelCase")]
+  AesGcm {
+    #[serde(with = "serde_bytes")]
+    iv: Vec<u8>,
+    #[serde(with = "serde_bytes")]
+    additional_data: Option<Vec<u8>>,
+    length: usize,
+    tag_length: usize,
+  },
+  #[serde(rename = "AES-CTR", rename_all = "camelCase")]
+  AesCtr {
+    #[serde(with = "serde_bytes")]
+    counter: Vec<u8>,
+    ctr_length: usize,
+    key_length: usize,
+  },
+}
+
+/// Incremental AES-GCM (NIST SP 800-38D) built from AES-CTR and GHASH, as
+/// `aes_gcm` only supports encrypting the whole message at once.
+pub struct AesGcmStream {
+  ctr: Box<dyn StreamCipher>,
+  ghash: GHash,
+  // Trailing ciphertext bytes which don't fill a whole GHASH block yet.
+  pending: Vec<u8>,
+  // E(K, J0)
+  tag_mask: ghash::Block,
+  aad_len: u64,
+  ct_len: u64,
+  tag_length: usize,
+}
+
+impl AesGcmStream {
+  fn new<C>(
+    key: &[u8],
+    iv: &[u8],
+    additional_data: &[u8],
+    tag_length: usize,
+  ) -> Result<Self, AnyError>
+  where
+    C: BlockCipher
+      + BlockEncrypt
+      + BlockSizeUser<BlockSize = U16>
+      + KeyInit
+      + 'static,
+    Ctr32BE<C>: KeyIvInit + StreamCipher,
+  {
+    let cipher = C::new_from_slice(key)
+      .map_err(|_| operation_error("invalid key length"))?;
+
+    // H = E(K, 0^128)
+    let mut h = ghash::Block::default();
+    cipher.encrypt_block(&mut h);
+
+    let j0 = if iv.len() == 12 {
+      // J0 = IV || 0^31 || 1
+      let mut j0 = ghash::Block::default();
+      j0[..12].copy_from_slice(iv);
+      j0[15] = 1;
+      j0
+    } else {
+      // J0 = GHASH(IV || 0^s+64 || [len(IV)]64)
+      let mut ghash = GHash::new(&h);
+      ghash.update_padded(iv);
+      let mut len_block = ghash::Block::default();
+      len_block[8..].copy_from_slice(&(iv.len() as u64 * 8).to_be_bytes());
+      ghash.update(&[len_block]);
+      ghash.finalize()
+    };
+
+    let mut tag_mask = j0;
+    cipher.encrypt_block(&mut tag_mask);
+
+    // The keystream starts at inc32(J0).
+    let mut counter = j0;
+    let low = u32::from_be_bytes([j0[12], j0[13], j0[14], j0[15]]);
+    counter[12..].copy_from_slice(&low.wrapping_add(1).to_be_bytes());
+    let ctr = Ctr32BE::<C>::new_from_slices(key, &counter)
+      .map_err(|_| operation_error("invalid key or iv"))?;
+
+    let mut ghash = GHash::new(&h);
+    ghash.update_padded(additional_data);
+
+    Ok(Self {
+      ctr: Box::new(ctr),
+      ghash,
+      pending: Vec::with_capacity(16),
+      tag_mask,
+      aad_len: additional_data.len() as u64,
+      ct_len: 0,
+      tag_length,
+    })
+  }
+
+  fn update(&mut self, data: &mut [u8]) -> Result<(), AnyError> {
+    self
+      .ctr
+      .try_apply_keystream(data)
+      .map_err(|_| operation_error("tried to encrypt too much data"))?;
+    self.ct_len += data.len() as u64;
+
+    let mut data: &[u8] = data;
+    if !self.pending.is_empty() {
+      let n = (16 - self.pending.len()).min(data.len());
+      self.pending.extend_from_slice(&data[..n]);
+      data = &data[n..];
+      if self.pending.len() < 16 {
+        return Ok(());
+      }
+      self.ghash.update_padded(&self.pending);
+      self.pending.clear();
+    }
+
+    let full = data.len() - data.len() % 16;
+    self.ghash.update_padded(&data[..full]);
+    self.pending.extend_from_slice(&data[full..]);
+    Ok(())
+  }
+
+  fn finalize(mut self) -> Vec<u8> {
+    self.ghash.update_padded(&self.pending);
+    // [len(A)]64 || [len(C)]64
+    let mut len_block = ghash::Block::default();
+    len_block[..8].copy_from_slice(&(self.aad_len * 8).to_be_bytes());
+    len_block[8..].copy_from_slice(&(self.ct_len * 8).to_be_bytes());
+    self.ghash.update(&[len_block]);
+
+    let mut tag = self.ghash.finalize();
+    for (t, m) in tag.iter_mut().zip(self.tag_mask.iter()) {
+      *t ^= m;
+    }
+    tag[..self.tag_length / 8].to_vec()
+  }
+}
+
+pub enum EncryptStream {
+  AesCtr(Box<dyn StreamCipher>),
+  AesGcm(Box<AesGcmStream>),
+}
+
+pub struct EncryptStreamResource(RefCell<EncryptStream>);
+
+impl Resource for EncryptStreamResource {
+  fn name(&self) -> Cow<str> {
+    "cryptoEncryptStream".into()
+  }
+}
+
+fn new_aes_ctr_stream<B>(
+  key: &[u8],
+  counter: &[u8],
+) -> Result<Box<dyn StreamCipher>, AnyError>
+where
+  B: KeyIvInit + StreamCipher + 'static,
+{
+  let cipher = B::new_from_slices(key, counter)
+    .map_err(|_| operation_error("invalid key or counter"))?;
+  Ok(Box::new(cipher))
+}
+
+#[op]
+pub fn op_crypto_encrypt_init(
+  state: &mut OpState,
+  mut opts: EncryptStreamOptions,
+) -> Result<ResourceId, AnyError> {
+  opts.key.resolve(state)?;
+  let key = V8RawKeyData::from(opts.key);
+  let key = key.as_secret_key()?;
+  let stream = match opts.algorithm {
+    EncryptStreamAlgorithm::AesGcm {
+      iv,
+      additional_data,
+      length,
+      tag_length,
+    } => {
+      if iv.len() != 12 && iv.len() != 16 {
+        return Err(type_error("iv length not equal to 12 or 16"));
+      }
+      // NIST SP 800-38D Section 5.2.1.2
+      if !matches!(tag_length, 32 | 64 | 96 | 104 | 112 | 120 | 128) {
+        return Err(type_error("invalid tag length"));
+      }
+      let additional_data = additional_data.unwrap_or_default();
+      let stream = match length {
+        128 => {
+          AesGcmStream::new::<Aes128>(key, &iv, &additional_data, tag_length)?
+        }
+        192 => {
+          AesGcmStream::new::<Aes192>(key, &iv, &additional_data, tag_length)?
+        }
+        256 => {
+          AesGcmStream::new::<Aes256>(key, &iv, &additional_data, tag_length)?
+        }
+        _ => return Err(type_error("invalid length")),
+      };
+      EncryptStream::AesGcm(Box::new(stream))
+    }
+    EncryptStreamAlgorithm::AesCtr {
+      counter,
+      ctr_length,
+      key_length,
+    } => {
+      let cipher = match (ctr_length, key_length) {
+        (32, 128) => new_aes_ctr_stream::<Ctr32BE<Aes128>>(key, &counter),
+        (32, 192) => new_aes_ctr_stream::<Ctr32BE<Aes192>>(key, &counter),
+        (32, 256) => new_aes_ctr_stream::<Ctr32BE<Aes256>>(key, &counter),
+        (64, 128) => new_aes_ctr_stream::<Ctr64BE<Aes128>>(key, &counter),
+        (64, 192) => new_aes_ctr_stream::<Ctr64BE<Aes192>>(key, &counter),
+        (64, 256) => new_aes_ctr_stream::<Ctr64BE<Aes256>>(key, &counter),
+        (128, 128) => new_aes_ctr_stream::<Ctr128BE<Aes128>>(key, &counter),
+        (128, 192) => new_aes_ctr_stream::<Ctr128BE<Aes192>>(key, &counter),
+        (128, 256) => new_aes_ctr_stream::<Ctr128BE<Aes256>>(key, &counter),
+        (32 | 64 | 128, _) => Err(type_error("invalid length")),
+        _ => Err(type_error(
+          "invalid counter length. Currently supported 32/64/128 bits",
+        )),
+      }?;
+      EncryptStream::AesCtr(cipher)
+    }
+  };
+
+  Ok(
+    state
+      .resource_table
+      .add(EncryptStreamResource(RefCell::new(stream))),
+  )
+}
+
+#[op]
+pub fn op_crypto_encrypt_update(
+  state: &mut OpState,
+  rid: ResourceId,
+  data: JsBuffer,
+) -> Result<ToJsBuffer, AnyError> {
+  let resource = state.resource_table.get::<EncryptStreamResource>(rid)?;
+  let mut ciphertext = data.to_vec();
+  match &mut *resource.0.borrow_mut() {
+    EncryptStream::AesCtr(cipher) => cipher
+      .try_apply_keystream(&mut ciphertext)
+      .map_err(|_| operation_error("tried to encrypt too much data"))?,
+    EncryptStream::AesGcm(stream) => stream.update(&mut ciphertext)?,
+  };
+  Ok(ciphertext.into())
+}
+
+/// Releases the stream. For AES-GCM this returns the authentication tag,
+/// which the caller appends after the last ciphertext chunk.
+#[op]
+pub fn op_crypto_encrypt_finalize(
+  state: &mut OpState,
+  rid: ResourceId,
+) -> Result<ToJsBuffer, AnyError> {
+  let resource = state.resource_table.take::<EncryptStreamResource>(rid)?;
+  let resource = Rc::try_unwrap(resource)
+    .map_err(|_| type_error("Encrypt stream is already in use"))?;
+  let tail = match resource.0.into_inner() {
+    EncryptStream::AesCtr(_) => vec![],
+    EncryptStream::AesGcm(stream) => stream.finalize(),
+  };
+  Ok(tail.into())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::decrypt::decrypt_aes_gcm;
+
+  fn hex(s: &str) -> Vec<u8> {
+    (0..s.len())
+      .step_by(2)
+      .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+      .collect()
+  }
+
+  // Test Case 4 of "The Galois/Counter Mode of Operation (GCM)", with the
+  // key repeated to the longer key sizes as in Test Cases 10 and 16.
+  const KEY: &str = "feffe9928665731c6d6a8f9467308308";
+  const PLAINTEXT: &str = "d9313225f88406e5a55909c5aff5269a86a7a9531534f7da\
+    2e4c303d8a318a721c3c0c95956809532fcf0e2449a6b525b16aedf5aa0de657ba637b39";
+  const AAD: &str = "feedfacedeadbeeffeedfacedeadbeefabaddad2";
+
+  // Encrypts in chunks that don't line up with the GHASH blocks and returns
+  // C || T.
+  fn stream_encrypt<C>(key: &[u8], iv: &str, tag_length: usize) -> Vec<u8>
+  where
+    C: BlockCipher
+      + BlockEncrypt
+      + BlockSizeUser<BlockSize = U16>
+      + KeyInit
+      + 'static,
+    Ctr32BE<C>: KeyIvInit + StreamCipher,
+  {
+    let mut stream =
+      AesGcmStream::new::<C>(key, &hex(iv), &hex(AAD), tag_length).unwrap();
+    let mut out = vec![];
+    for chunk in hex(PLAINTEXT).chunks(7) {
+      let mut chunk = chunk.to_vec();
+      stream.update(&mut chunk).unwrap();
+      out.extend_from_slice(&chunk);
+    }
+    out.extend_from_slice(&stream.finalize());
+    out
+  }
+
+  #[test]
+  fn test_aes_gcm_stream_key_sizes() {
+    let key = hex(KEY);
+    let iv = "cafebabefacedbaddecaf888";
+    assert_eq!(
+      stream_encrypt::<Aes128>(&key, iv, 128),
+      hex(
+        "42831ec2217774244b7221b784d0d49ce3aa212f2c02a4e035c17e2329aca12e\
+         21d514b25466931c7d8f6a5aac84aa051ba30b396a0aac973d58e091\
+         5bc94fbc3221a5db94fae95ae7121a47"
+      )
+    );
+    assert_eq!(
+      stream_encrypt::<Aes192>(&[&key[..], &key[..8]].concat(), iv, 128),
+      hex(
+        "3980ca0b3c00e841eb06fac4872a2757859e1ceaa6efd984628593b40ca1e19c\
+         7d773d00c144c525ac619d18c84a3f4718e2448b2fe324d9ccda2710\
+         2519498e80f1478f37ba55bd6d27618c"
+      )
+    );
+    assert_eq!(
+      stream_encrypt::<Aes256>(&[&key[..], &key[..]].concat(), iv, 128),
+      hex(
+        "522dc1f099567d07f47f37a32a84427d643a8cdcbfe5c0c97598a2bd2555d1aa\
+         8cb08e48590dbb3da7b08b1056828838c5f61e6393ba7a0abcc9f662\
+         76fc6ece0f4e1768cddf8853bb2d551b"
+      )
+    );
+  }
+
+  #[test]
+  fn test_aes_gcm_stream_16_byte_iv() {
+    // J0 is derived with GHASH when the IV isn't 96 bits.
+    assert_eq!(
+      stream_encrypt::<Aes128>(
+        &hex(KEY),
+        "cafebabefacedbaddecaf88800010203",
+        128
+      ),
+      hex(
+        "9b5f747b9309412b963b3ddfa6c704659ab8b10b58e5f8160cf09d96f9f640cf\
+         90a7f2b83603761d85bf6b569121339f0bbde447bfd0aad950ea347a\
+         deb20c3627355e1fb963d77bcb849fb1"
+      )
+    );
+  }
+
+  #[test]
+  fn test_aes_gcm_stream_truncated_tag() {
+    let key = hex(KEY);
+    let iv = "cafebabefacedbaddecaf888";
+    let full = stream_encrypt::<Aes128>(&key, iv, 128);
+    let plaintext_len = hex(PLAINTEXT).len();
+    for tag_length in [32, 64, 96, 104, 112, 120] {
+      let truncated = stream_encrypt::<Aes128>(&key, iv, tag_length);
+      assert_eq!(truncated.len(), plaintext_len + tag_length / 8);
+      assert_eq!(truncated, full[..truncated.len()]);
+    }
+  }
+
+  #[test]
+  fn test_aes_gcm_stream_decrypt() {
+    let key = hex(KEY);
+    let iv = "cafebabefacedbaddecaf88800010203";
+    let mut ciphertext = stream_encrypt::<Aes128>(&key, iv, 128);
+    let decrypt = |data: &[u8]| {
+      decrypt_aes_gcm(
+        V8RawKeyData::Secret(key.clone().into()),
+        128,
+        128,
+        hex(iv),
+        Some(hex(AAD)),
+        data,
+      )
+    };
+    assert_eq!(decrypt(&ciphertext).unwrap(), hex(PLAINTEXT));
+
+    *ciphertext.last_mut().unwrap() ^= 1;
+    assert!(decrypt(&ciphertext).is_err());
+  }
+}
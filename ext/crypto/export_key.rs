@@ -5,6 +5,7 @@ use const_oid::ObjectIdentifier;
 use deno_core::error::custom_error;
 use deno_core::error::AnyError;
 use deno_core::op;
+use deno_core::OpState;
 use deno_core::ToJsBuffer;
 use elliptic_curve::sec1::ToEncodedPoint;
 use p256::pkcs8::DecodePrivateKey;
@@ -16,7 +17,13 @@ use spki::der::Decode;
 use spki::der::Encode;
 use spki::AlgorithmIdentifier;
 
+use crate::ed25519;
+use crate::ed448;
 use crate::shared::*;
+use crate::x25519;
+use crate::x448;
+use crate::KeyData;
+use crate::KeyType;
 
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -52,8 +59,26 @@ pub enum ExportKeyAlgorithm {
   Ecdh { named_curve: EcNamedCurve },
   #[serde(rename = "AES")]
   Aes {},
+  #[serde(rename = "CHACHA20")]
+  ChaCha20 {},
   #[serde(rename = "HMAC")]
   Hmac {},
+  #[serde(rename = "Ed25519")]
+  Ed25519 {},
+  #[serde(rename = "X25519")]
+  X25519 {},
+  #[serde(rename = "Ed448")]
+  Ed448 {},
+  #[serde(rename = "X448")]
+  X448 {},
+}
+
+#[derive(Clone, Copy)]
+enum OkpCurve {
+  Ed25519,
+  X25519,
+  Ed448,
+  X448,
 }
 
 #[derive(Serialize)]
@@ -88,10 +113,39 @@ pub enum ExportKeyResult {
     y: String,
     d: String,
   },
+  JwkPublicOkp {
+    x: String,
+  },
+  JwkPrivateOkp {
+    x: String,
+    d: String,
+  },
 }
 
 #[op]
 pub fn op_crypto_export_key(
+  state: &mut OpState,
+  opts: ExportKeyOptions,
+  mut key_data: KeyData,
+) -> Result<ExportKeyResult, AnyError> {
+  key_data.resolve(state)?;
+  // A public key can share its stored material with a non-extractable
+  // private key, exporting its public part is always allowed.
+  let public_only = match opts.format {
+    ExportKeyFormat::Spki | ExportKeyFormat::JwkPublic => true,
+    ExportKeyFormat::Raw => !matches!(key_data.r#type, KeyType::Secret),
+    _ => false,
+  };
+  if !key_data.extractable && !public_only {
+    return Err(custom_error(
+      "DOMExceptionInvalidAccessError",
+      "Key is not extractable",
+    ));
+  }
+  export_key(opts, key_data.into())
+}
+
+pub(crate) fn export_key(
   opts: ExportKeyOptions,
   key_data: V8RawKeyData,
 ) -> Result<ExportKeyResult, AnyError> {
@@ -103,9 +157,23 @@ pub fn op_crypto_export_key(
     | ExportKeyAlgorithm::Ecdsa { named_curve } => {
       export_key_ec(opts.format, key_data, opts.algorithm, named_curve)
     }
-    ExportKeyAlgorithm::Aes {} | ExportKeyAlgorithm::Hmac {} => {
+    ExportKeyAlgorithm::Aes {}
+    | ExportKeyAlgorithm::ChaCha20 {}
+    | ExportKeyAlgorithm::Hmac {} => {
       export_key_symmetric(opts.format, key_data)
     }
+    ExportKeyAlgorithm::Ed25519 {} => {
+      export_key_okp(opts.format, key_data, OkpCurve::Ed25519)
+    }
+    ExportKeyAlgorithm::X25519 {} => {
+      export_key_okp(opts.format, key_data, OkpCurve::X25519)
+    }
+    ExportKeyAlgorithm::Ed448 {} => {
+      export_key_okp(opts.format, key_data, OkpCurve::Ed448)
+    }
+    ExportKeyAlgorithm::X448 {} => {
+      export_key_okp(opts.format, key_data, OkpCurve::X448)
+    }
   }
 }
 
@@ -215,6 +283,11 @@ fn export_key_symmetric(
   key_data: V8RawKeyData,
 ) -> Result<ExportKeyResult, deno_core::anyhow::Error> {
   match format {
+    ExportKeyFormat::Raw => {
+      let bytes = key_data.as_secret_key()?;
+
+      Ok(ExportKeyResult::Raw(bytes.to_vec().into()))
+    }
     ExportKeyFormat::JwkSecret => {
       let bytes = key_data.as_secret_key()?;
 
@@ -226,6 +299,65 @@ fn export_key_symmetric(
   }
 }
 
+fn export_key_okp(
+  format: ExportKeyFormat,
+  key_data: V8RawKeyData,
+  curve: OkpCurve,
+) -> Result<ExportKeyResult, deno_core::anyhow::Error> {
+  match format {
+    ExportKeyFormat::Raw => {
+      let public_key = key_data.as_okp_public_key()?;
+
+      Ok(ExportKeyResult::Raw(public_key.to_vec().into()))
+    }
+    ExportKeyFormat::Spki => {
+      let public_key = key_data.as_okp_public_key()?;
+      let spki_der = match curve {
+        OkpCurve::Ed25519 => ed25519::export_spki(public_key)?,
+        OkpCurve::X25519 => x25519::export_spki(public_key)?,
+        OkpCurve::Ed448 => ed448::export_spki(public_key)?,
+        OkpCurve::X448 => x448::export_spki(public_key)?,
+      };
+
+      Ok(ExportKeyResult::Spki(spki_der.into()))
+    }
+    ExportKeyFormat::Pkcs8 => {
+      let private_key = key_data.as_okp_private_key()?;
+      let pkcs8_der = match curve {
+        OkpCurve::Ed25519 => ed25519::export_pkcs8(private_key)?,
+        OkpCurve::X25519 => x25519::export_pkcs8(private_key)?,
+        OkpCurve::Ed448 => ed448::export_pkcs8(private_key)?,
+        OkpCurve::X448 => x448::export_pkcs8(private_key)?,
+      };
+
+      Ok(ExportKeyResult::Pkcs8(pkcs8_der.into()))
+    }
+    ExportKeyFormat::JwkPublic => {
+      let public_key = key_data.as_okp_public_key()?;
+
+      Ok(ExportKeyResult::JwkPublicOkp {
+        x: bytes_to_b64(public_key),
+      })
+    }
+    ExportKeyFormat::JwkPrivate => {
+      let private_key = key_data.as_okp_private_key()?;
+      // 'x' from Section 2 of RFC 8037
+      let x = match curve {
+        OkpCurve::Ed25519 => ed25519::jwk_x(private_key)?,
+        OkpCurve::Ed448 => ed448::jwk_x(private_key)?,
+        OkpCurve::X448 => x448::jwk_x(private_key)?,
+        OkpCurve::X25519 => return Err(unsupported_format()),
+      };
+
+      Ok(ExportKeyResult::JwkPrivateOkp {
+        x,
+        d: bytes_to_b64(private_key),
+      })
+    }
+    ExportKeyFormat::JwkSecret => Err(unsupported_format()),
+  }
+}
+
 fn export_key_ec(
   format: ExportKeyFormat,
   key_data: V8RawKeyData,
@@ -245,8 +377,15 @@ fn export_key_ec(
 
           point.as_ref().to_vec()
         }
+        EcNamedCurve::Secp256k1 => {
+          let point = key_data.as_ec_public_key_k256()?;
+
+          point.as_ref().to_vec()
+        }
         EcNamedCurve::P521 => {
-          return Err(data_error("Unsupported named curve"))
+          let point = key_data.as_ec_public_key_p521()?;
+
+          point.as_ref().to_vec()
         }
       };
       Ok(ExportKeyResult::Raw(subject_public_key.into()))
@@ -263,8 +402,15 @@ fn export_key_ec(
 
           point.as_ref().to_vec()
         }
+        EcNamedCurve::Secp256k1 => {
+          let point = key_data.as_ec_public_key_k256()?;
+
+          point.as_ref().to_vec()
+        }
         EcNamedCurve::P521 => {
-          return Err(data_error("Unsupported named curve"))
+          let point = key_data.as_ec_public_key_p521()?;
+
+          point.as_ref().to_vec()
         }
       };
 
@@ -277,9 +423,14 @@ fn export_key_ec(
           oid: elliptic_curve::ALGORITHM_OID,
           parameters: Some((&p384::NistP384::OID).into()),
         },
-        EcNamedCurve::P521 => {
-          return Err(data_error("Unsupported named curve"))
-        }
+        EcNamedCurve::Secp256k1 => AlgorithmIdentifier {
+          oid: elliptic_curve::ALGORITHM_OID,
+          parameters: Some((&k256::Secp256k1::OID).into()),
+        },
+        EcNamedCurve::P521 => AlgorithmIdentifier {
+          oid: elliptic_curve::ALGORITHM_OID,
+          parameters: Some((&ID_SECP521R1_OID).into()),
+        },
       };
 
       let alg_id = match algorithm {
@@ -343,7 +494,42 @@ fn export_key_ec(
           ))
         }
       }
-      EcNamedCurve::P521 => Err(data_error("Unsupported named curve")),
+      EcNamedCurve::Secp256k1 => {
+        let point = key_data.as_ec_public_key_k256()?;
+        let coords = point.coordinates();
+
+        if let k256::elliptic_curve::sec1::Coordinates::Uncompressed { x, y } =
+          coords
+        {
+          Ok(ExportKeyResult::JwkPublicEc {
+            x: bytes_to_b64(x),
+            y: bytes_to_b64(y),
+          })
+        } else {
+          Err(custom_error(
+            "DOMExceptionOperationError",
+            "failed to decode public key",
+          ))
+        }
+      }
+      EcNamedCurve::P521 => {
+        let point = key_data.as_ec_public_key_p521()?;
+        let coords = point.coordinates();
+
+        if let p521::elliptic_curve::sec1::Coordinates::Uncompressed { x, y } =
+          coords
+        {
+          Ok(ExportKeyResult::JwkPublicEc {
+            x: bytes_to_b64(x),
+            y: bytes_to_b64(y),
+          })
+        } else {
+          Err(custom_error(
+            "DOMExceptionOperationError",
+            "failed to decode public key",
+          ))
+        }
+      }
     },
     ExportKeyFormat::JwkPrivate => {
       let private_key = key_data.as_ec_private_key()?;
@@ -365,7 +551,7 @@ fn export_key_ec(
             Ok(ExportKeyResult::JwkPrivateEc {
               x: bytes_to_b64(x),
               y: bytes_to_b64(y),
-              d: bytes_to_b64(&ec_key.to_be_bytes()),
+              d: bytes_to_b64(&ec_key.to_bytes()),
             })
           } else {
             Err(data_error("expected valid public EC key"))
@@ -388,13 +574,59 @@ fn export_key_ec(
             Ok(ExportKeyResult::JwkPrivateEc {
               x: bytes_to_b64(x),
               y: bytes_to_b64(y),
-              d: bytes_to_b64(&ec_key.to_be_bytes()),
+              d: bytes_to_b64(&ec_key.to_bytes()),
+            })
+          } else {
+            Err(data_error("expected valid public EC key"))
+          }
+        }
+
+        EcNamedCurve::Secp256k1 => {
+          let ec_key =
+            k256::SecretKey::from_pkcs8_der(private_key).map_err(|_| {
+              custom_error(
+                "DOMExceptionOperationError",
+                "failed to decode private key",
+              )
+            })?;
+
+          let point = ec_key.public_key().to_encoded_point(false);
+          if let elliptic_curve::sec1::Coordinates::Uncompressed { x, y } =
+            point.coordinates()
+          {
+            Ok(ExportKeyResult::JwkPrivateEc {
+              x: bytes_to_b64(x),
+              y: bytes_to_b64(y),
+              d: bytes_to_b64(&ec_key.to_bytes()),
+            })
+          } else {
+            Err(data_error("expected valid public EC key"))
+          }
+        }
+        EcNamedCurve::P521 => {
+          let ec_key =
+            p521::SecretKey::from_pkcs8_der(private_key).map_err(|_| {
+              custom_error(
+                "DOMExceptionOperationError",
+                "failed to decode private key",
+              )
+            })?;
+
+          let point = key_data.as_ec_public_key_p521()?;
+          if let p521::elliptic_curve::sec1::Coordinates::Uncompressed {
+            x,
+            y,
+          } = point.coordinates()
+          {
+            Ok(ExportKeyResult::JwkPrivateEc {
+              x: bytes_to_b64(x),
+              y: bytes_to_b64(y),
+              d: bytes_to_b64(&ec_key.to_bytes()),
             })
           } else {
             Err(data_error("expected valid public EC key"))
           }
         }
-        _ => Err(not_supported_error("Unsupported namedCurve")),
       }
     }
     ExportKeyFormat::JwkSecret => Err(unsupported_format()),
@@ -0,0 +1,134 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! FIPS mode restricts the extension to algorithms approved by FIPS 140-3
+//! (SP 800-140C). `00_crypto.js` rejects other algorithms while normalizing
+//! them, and the ops check again here so that calling them directly, or
+//! through APIs outside of `crypto.subtle` like bcrypt, BLS12-381 or JWS, does
+//! not bypass FIPS mode.
+//!
+//! FIPS mode also requires RSA private key operations to run in the FIPS
+//! validated AWS-LC module, see `check_backend`.
+
+use deno_core::error::custom_error;
+use deno_core::error::generic_error;
+use deno_core::error::AnyError;
+use deno_core::op;
+use deno_core::OpState;
+
+use crate::backend::CryptoBackend;
+use crate::key::Algorithm;
+use crate::key::CryptoHash;
+use crate::key::CryptoNamedCurve;
+
+pub struct FipsMode(pub bool);
+
+pub fn is_enabled(state: &OpState) -> bool {
+  state.try_borrow::<FipsMode>().is_some_and(|mode| mode.0)
+}
+
+fn not_approved(algorithm: &str) -> AnyError {
+  custom_error(
+    "DOMExceptionNotSupportedError",
+    format!("{algorithm} is not approved in FIPS mode"),
+  )
+}
+
+/// Mirrors `fipsApprovedAlgorithms` in `00_crypto.js`.
+fn is_approved(algorithm: Algorithm) -> bool {
+  matches!(
+    algorithm,
+    Algorithm::RsassaPkcs1v15
+      | Algorithm::RsaPss
+      | Algorithm::RsaOaep
+      | Algorithm::Ecdsa
+      | Algorithm::Ecdh
+      | Algorithm::AesCtr
+      | Algorithm::AesCbc
+      | Algorithm::AesGcm
+      | Algorithm::AesKw
+      | Algorithm::AesKwp
+      | Algorithm::Hmac
+      | Algorithm::Kmac128
+      | Algorithm::Kmac256
+      | Algorithm::Pbkdf2
+      | Algorithm::Hkdf
+  )
+}
+
+pub fn check_algorithm(
+  state: &OpState,
+  algorithm: Algorithm,
+  named_curve: Option<CryptoNamedCurve>,
+) -> Result<(), AnyError> {
+  if !is_enabled(state) {
+    return Ok(());
+  }
+  // Same errors as `normalizeAlgorithm` in `00_crypto.js`.
+  if !is_approved(algorithm) {
+    return Err(custom_error(
+      "DOMExceptionNotSupportedError",
+      "Algorithm is not approved in FIPS mode",
+    ));
+  }
+  if matches!(named_curve, Some(CryptoNamedCurve::Secp256k1)) {
+    return Err(custom_error(
+      "DOMExceptionNotSupportedError",
+      "Curve is not approved in FIPS mode",
+    ));
+  }
+  Ok(())
+}
+
+/// For ops implementing an algorithm which is never approved, like bcrypt or
+/// Ed448.
+pub fn check_unapproved(
+  state: &OpState,
+  algorithm: &str,
+) -> Result<(), AnyError> {
+  if is_enabled(state) {
+    return Err(not_approved(algorithm));
+  }
+  Ok(())
+}
+
+/// FIPS mode needs the aws-lc-rs backend, built against the FIPS validated
+/// AWS-LC module with the `fips` feature. Called when the runtime is
+/// configured, so that a misconfigured process fails on startup rather than
+/// on the first RSA operation.
+pub fn check_backend(backend: CryptoBackend) -> Result<(), AnyError> {
+  match backend {
+    #[cfg(feature = "fips")]
+    CryptoBackend::AwsLc => aws_lc_rs::try_fips_mode()
+      .map_err(|err| generic_error(format!("FIPS mode is unavailable: {err}"))),
+    #[cfg(all(feature = "aws-lc-rs", not(feature = "fips")))]
+    CryptoBackend::AwsLc => Err(generic_error(
+      "FIPS mode requires a build with the `fips` feature",
+    )),
+    CryptoBackend::Ring => Err(generic_error(
+      "FIPS mode requires the aws-lc-rs crypto backend",
+    )),
+  }
+}
+
+/// SHA-1 is only allowed for legacy signature verification and HMAC.
+pub fn check_signing_hash(
+  state: &OpState,
+  algorithm: Algorithm,
+  hash: Option<CryptoHash>,
+) -> Result<(), AnyError> {
+  if is_enabled(state)
+    && !matches!(algorithm, Algorithm::Hmac)
+    && matches!(hash, Some(CryptoHash::Sha1))
+  {
+    return Err(custom_error(
+      "DOMExceptionNotSupportedError",
+      "SHA-1 signatures are not allowed in FIPS mode",
+    ));
+  }
+  Ok(())
+}
+
+#[op]
+pub fn op_crypto_fips_enabled(state: &mut OpState) -> bool {
+  is_enabled(state)
+}
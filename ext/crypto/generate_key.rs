@@ -2,25 +2,39 @@
 
 use deno_core::error::AnyError;
 use deno_core::op;
-use deno_core::task::spawn_blocking;
+use deno_core::CancelFuture;
+use deno_core::CancelHandle;
+use deno_core::OpState;
+use deno_core::ResourceId;
 use deno_core::ToJsBuffer;
 use elliptic_curve::rand_core::OsRng;
-use num_traits::FromPrimitive;
-use once_cell::sync::Lazy;
+use k256::pkcs8::EncodePrivateKey;
+use num_bigint_dig::traits::ModInverse;
+use num_bigint_dig::RandPrime;
+use num_traits::One;
+use num_traits::ToPrimitive;
 use ring::rand::SecureRandom;
 use ring::signature::EcdsaKeyPair;
 use rsa::pkcs1::EncodeRsaPrivateKey;
 use rsa::BigUint;
 use rsa::RsaPrivateKey;
+use rsa::RsaPublicKey;
 use serde::Deserialize;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use tokio::sync::OwnedSemaphorePermit;
+use tokio::sync::Semaphore;
 
+use crate::fips;
+use crate::progress::spawn_cancelable;
 use crate::shared::*;
 
-// Allowlist for RSA public exponents.
-static PUB_EXPONENT_1: Lazy<BigUint> =
-  Lazy::new(|| BigUint::from_u64(3).unwrap());
-static PUB_EXPONENT_2: Lazy<BigUint> =
-  Lazy::new(|| BigUint::from_u64(65537).unwrap());
+// RSA public exponents must be odd and at least 3. The upper bound is the
+// largest exponent the `rsa` crate accepts.
+const MIN_PUB_EXPONENT: u64 = 3;
 
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase", tag = "algorithm")]
@@ -35,46 +49,110 @@ pub enum GenerateKeyOptions {
   Ec { named_curve: EcNamedCurve },
   #[serde(rename = "AES", rename_all = "camelCase")]
   Aes { length: usize },
+  #[serde(rename = "CHACHA20", rename_all = "camelCase")]
+  ChaCha20 {},
   #[serde(rename = "HMAC", rename_all = "camelCase")]
   Hmac {
     hash: ShaHash,
     length: Option<usize>,
   },
+  #[serde(rename = "KMAC", rename_all = "camelCase")]
+  Kmac { length: usize },
 }
 
 #[op]
 pub async fn op_crypto_generate_key(
+  state: Rc<RefCell<OpState>>,
   opts: GenerateKeyOptions,
+  cancel_rid: Option<ResourceId>,
 ) -> Result<ToJsBuffer, AnyError> {
-  let fun = || match opts {
-    GenerateKeyOptions::Rsa {
-      modulus_length,
-      public_exponent,
-    } => generate_key_rsa(modulus_length, &public_exponent),
-    GenerateKeyOptions::Ec { named_curve } => generate_key_ec(named_curve),
-    GenerateKeyOptions::Aes { length } => generate_key_aes(length),
-    GenerateKeyOptions::Hmac { hash, length } => {
-      generate_key_hmac(hash, length)
+  if let GenerateKeyOptions::ChaCha20 {} = opts {
+    fips::check_unapproved(&state.borrow(), "ChaCha20-Poly1305")?;
+  }
+  // Only RSA key generation takes long enough to be worth canceling, or
+  // limiting.
+  let permit = match opts {
+    GenerateKeyOptions::Rsa { .. } => {
+      Some(KeygenLimit::acquire(&state, cancel_rid).await?)
     }
+    _ => None,
   };
-  let buf = spawn_blocking(fun).await.unwrap()?;
+  let buf = spawn_cancelable(&state, cancel_rid, move |canceled| {
+    // Held until the blocking task ends, even if the op was canceled.
+    let _permit = permit;
+    match opts {
+      GenerateKeyOptions::Rsa {
+        modulus_length,
+        public_exponent,
+      } => generate_key_rsa(modulus_length, &public_exponent, canceled),
+      GenerateKeyOptions::Ec { named_curve } => generate_key_ec(named_curve),
+      GenerateKeyOptions::Aes { length } => generate_key_aes(length),
+      GenerateKeyOptions::ChaCha20 {} => generate_key_chacha20(),
+      GenerateKeyOptions::Hmac { hash, length } => {
+        generate_key_hmac(hash, length)
+      }
+      GenerateKeyOptions::Kmac { length } => generate_key_kmac(length),
+    }
+  })
+  .await?;
   Ok(buf.into())
 }
 
+/// Bounds the number of RSA key generations running on the blocking pool at
+/// once, so that a burst of `generateKey` calls cannot starve file I/O.
+pub struct KeygenLimit(Arc<Semaphore>);
+
+impl KeygenLimit {
+  /// Defaults to the available parallelism.
+  pub fn new(max_tasks: Option<usize>) -> Self {
+    let max_tasks = max_tasks.unwrap_or_else(|| {
+      std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+    });
+    Self(Arc::new(Semaphore::new(max_tasks.max(1))))
+  }
+
+  async fn acquire(
+    state: &Rc<RefCell<OpState>>,
+    cancel_rid: Option<ResourceId>,
+  ) -> Result<OwnedSemaphorePermit, AnyError> {
+    let (semaphore, cancel_handle) = {
+      let state = state.borrow();
+      let cancel_handle = cancel_rid
+        .and_then(|rid| state.resource_table.get::<CancelHandle>(rid).ok());
+      (state.borrow::<KeygenLimit>().0.clone(), cancel_handle)
+    };
+    let acquire = semaphore.acquire_owned();
+    let permit = match cancel_handle {
+      Some(cancel_handle) => acquire.or_cancel(cancel_handle).await?,
+      None => acquire.await,
+    };
+    // The semaphore is never closed.
+    Ok(permit.unwrap())
+  }
+}
+
 fn generate_key_rsa(
   modulus_length: u32,
   public_exponent: &[u8],
+  canceled: &AtomicBool,
 ) -> Result<Vec<u8>, AnyError> {
+  if modulus_length as usize > RSA_MAX_MODULUS_LENGTH {
+    return Err(operation_error("Unsupported modulus length"));
+  }
+
   let exponent = BigUint::from_bytes_be(public_exponent);
-  if exponent != *PUB_EXPONENT_1 && exponent != *PUB_EXPONENT_2 {
+  let is_valid_exponent = exponent.to_u64().is_some_and(|e| {
+    e % 2 == 1
+      && (MIN_PUB_EXPONENT..=RsaPublicKey::MAX_PUB_EXPONENT).contains(&e)
+  });
+  if !is_valid_exponent {
     return Err(operation_error("Bad public exponent"));
   }
 
-  let mut rng = OsRng;
-
   let private_key =
-    RsaPrivateKey::new_with_exp(&mut rng, modulus_length as usize, &exponent)
-      .map_err(|_| operation_error("Failed to generate RSA key"))?;
+    generate_rsa_private_key(modulus_length as usize, &exponent, canceled)?;
 
   let private_key = private_key
     .to_pkcs1_der()
@@ -83,11 +161,59 @@ fn generate_key_rsa(
   Ok(private_key.as_bytes().to_vec())
 }
 
+// Two-prime version of `rsa::algorithms::generate_multi_prime_key_with_exp`
+// that checks `canceled` before each prime search, as a single prime can take
+// seconds to find for large moduli.
+fn generate_rsa_private_key(
+  bit_size: usize,
+  exponent: &BigUint,
+  canceled: &AtomicBool,
+) -> Result<RsaPrivateKey, AnyError> {
+  let mut rng = OsRng;
+
+  // Small moduli are quick to generate, and need the `rsa` crate's checks
+  // that there are enough primes of the requested size.
+  if bit_size < 64 {
+    return RsaPrivateKey::new_with_exp(&mut rng, bit_size, exponent)
+      .map_err(|_| operation_error("Failed to generate RSA key"));
+  }
+
+  loop {
+    let mut primes = Vec::with_capacity(2);
+    let mut todo = bit_size;
+    for i in 0..2 {
+      if canceled.load(Ordering::Relaxed) {
+        return Err(operation_error("RSA key generation was canceled"));
+      }
+      let prime = rng.gen_prime(todo / (2 - i));
+      todo -= prime.bits();
+      primes.push(prime);
+    }
+
+    if primes[0] == primes[1] {
+      continue;
+    }
+
+    let n = &primes[0] * &primes[1];
+    if n.bits() != bit_size {
+      continue;
+    }
+
+    let totient = (&primes[0] - BigUint::one()) * (&primes[1] - BigUint::one());
+    if let Some(d) = exponent.mod_inverse(totient) {
+      let d = d.to_biguint().unwrap();
+      return RsaPrivateKey::from_components(n, exponent.clone(), d, primes)
+        .map_err(|_| operation_error("Failed to generate RSA key"));
+    }
+  }
+}
+
 fn generate_key_ec(named_curve: EcNamedCurve) -> Result<Vec<u8>, AnyError> {
   let curve = match named_curve {
     EcNamedCurve::P256 => &ring::signature::ECDSA_P256_SHA256_FIXED_SIGNING,
     EcNamedCurve::P384 => &ring::signature::ECDSA_P384_SHA384_FIXED_SIGNING,
-    _ => return Err(not_supported_error("Unsupported named curve")),
+    EcNamedCurve::P521 => return generate_key_p521(),
+    EcNamedCurve::Secp256k1 => return generate_key_secp256k1(),
   };
 
   let rng = ring::rand::SystemRandom::new();
@@ -98,6 +224,30 @@ fn generate_key_ec(named_curve: EcNamedCurve) -> Result<Vec<u8>, AnyError> {
   Ok(pkcs8.as_ref().to_vec())
 }
 
+// ring does not implement P-521, so the key is generated with the RustCrypto
+// `p521` crate and serialized to the same PKCS#8 format.
+fn generate_key_p521() -> Result<Vec<u8>, AnyError> {
+  let secret_key = p521::SecretKey::random(&mut OsRng);
+
+  let pkcs8 = secret_key
+    .to_pkcs8_der()
+    .map_err(|_| operation_error("Failed to generate EC key"))?;
+
+  Ok(pkcs8.as_bytes().to_vec())
+}
+
+// ring does not implement secp256k1, so the key is generated with the
+// RustCrypto `k256` crate and serialized to the same PKCS#8 format.
+fn generate_key_secp256k1() -> Result<Vec<u8>, AnyError> {
+  let secret_key = k256::SecretKey::random(&mut OsRng);
+
+  let pkcs8 = secret_key
+    .to_pkcs8_der()
+    .map_err(|_| operation_error("Failed to generate EC key"))?;
+
+  Ok(pkcs8.as_bytes().to_vec())
+}
+
 fn generate_key_aes(length: usize) -> Result<Vec<u8>, AnyError> {
   if length % 8 != 0 || length > 256 {
     return Err(operation_error("Invalid AES key length"));
@@ -112,6 +262,31 @@ fn generate_key_aes(length: usize) -> Result<Vec<u8>, AnyError> {
   Ok(key)
 }
 
+// ChaCha20-Poly1305 and XChaCha20-Poly1305 both use a 256-bit key.
+fn generate_key_chacha20() -> Result<Vec<u8>, AnyError> {
+  let mut key = vec![0u8; 32];
+  let rng = ring::rand::SystemRandom::new();
+  rng
+    .fill(&mut key)
+    .map_err(|_| operation_error("Failed to generate key"))?;
+
+  Ok(key)
+}
+
+fn generate_key_kmac(length: usize) -> Result<Vec<u8>, AnyError> {
+  if length == 0 || length % 8 != 0 {
+    return Err(operation_error("Invalid KMAC key length"));
+  }
+
+  let mut key = vec![0u8; length / 8];
+  let rng = ring::rand::SystemRandom::new();
+  rng
+    .fill(&mut key)
+    .map_err(|_| operation_error("Failed to generate key"))?;
+
+  Ok(key)
+}
+
 fn generate_key_hmac(
   hash: ShaHash,
   length: Option<usize>,
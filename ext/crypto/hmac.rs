@@ -0,0 +1,89 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use deno_core::error::type_error;
+use deno_core::error::AnyError;
+use deno_core::op;
+use deno_core::OpState;
+use deno_core::Resource;
+use deno_core::ResourceId;
+use deno_core::ToJsBuffer;
+use ring::hmac;
+use serde::Deserialize;
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::key::CryptoHash;
+use crate::shared::V8RawKeyData;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HmacCreateArgs {
+  key: V8RawKeyData,
+  hash: CryptoHash,
+}
+
+/// Incremental HMAC state, so that signatures can be computed over streamed
+/// bodies without buffering the whole message.
+pub struct HmacContext(RefCell<hmac::Context>);
+
+impl Resource for HmacContext {
+  fn name(&self) -> Cow<str> {
+    "cryptoHmacContext".into()
+  }
+}
+
+impl HmacContext {
+  fn take(state: &mut OpState, rid: ResourceId) -> Result<Self, AnyError> {
+    let context = state.resource_table.take::<HmacContext>(rid)?;
+    Rc::try_unwrap(context)
+      .map_err(|_| type_error("HMAC context is already in use"))
+  }
+
+  fn sign(self) -> hmac::Tag {
+    self.0.into_inner().sign()
+  }
+}
+
+#[op]
+pub fn op_crypto_hmac_create(
+  state: &mut OpState,
+  args: HmacCreateArgs,
+) -> Result<ResourceId, AnyError> {
+  let key = hmac::Key::new(args.hash.into(), args.key.as_secret_key()?);
+  let context = hmac::Context::with_key(&key);
+  Ok(state.resource_table.add(HmacContext(RefCell::new(context))))
+}
+
+#[op]
+pub fn op_crypto_hmac_update(
+  state: &mut OpState,
+  rid: ResourceId,
+  data: &[u8],
+) -> Result<(), AnyError> {
+  let context = state.resource_table.get::<HmacContext>(rid)?;
+  context.0.borrow_mut().update(data);
+  Ok(())
+}
+
+#[op]
+pub fn op_crypto_hmac_sign(
+  state: &mut OpState,
+  rid: ResourceId,
+) -> Result<ToJsBuffer, AnyError> {
+  let tag = HmacContext::take(state, rid)?.sign();
+  Ok(tag.as_ref().to_vec().into())
+}
+
+#[op]
+pub fn op_crypto_hmac_verify(
+  state: &mut OpState,
+  rid: ResourceId,
+  signature: &[u8],
+) -> Result<bool, AnyError> {
+  let tag = HmacContext::take(state, rid)?.sign();
+  Ok(
+    ring::constant_time::verify_slices_are_equal(tag.as_ref(), signature)
+      .is_ok(),
+  )
+}
@@ -0,0 +1,485 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! Hybrid Public Key Encryption (HPKE), base mode only.
+//! https://www.rfc-editor.org/rfc/rfc9180
+
+use aes_gcm::aead::Payload;
+use aes_gcm::Aes128Gcm;
+use aes_gcm::Aes256Gcm;
+use aes_gcm::KeyInit;
+use chacha20poly1305::ChaCha20Poly1305;
+use deno_core::error::type_error;
+use deno_core::error::AnyError;
+use deno_core::op;
+use deno_core::JsBuffer;
+use deno_core::OpState;
+use deno_core::Resource;
+use deno_core::ResourceId;
+use deno_core::ToJsBuffer;
+use hkdf::Hkdf;
+use p256::elliptic_curve::sec1::ToEncodedPoint;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::Deserialize;
+use serde::Serialize;
+use sha2::Sha256;
+use std::borrow::Cow;
+use std::cell::Cell;
+
+use crate::fips;
+use crate::shared::data_error;
+use crate::shared::operation_error;
+
+const HPKE_VERSION: &[u8] = b"HPKE-v1";
+const MODE_BASE: u8 = 0x00;
+
+#[derive(Deserialize, Clone, Copy)]
+pub enum HpkeKem {
+  #[serde(rename = "DHKEM-X25519-HKDF-SHA256")]
+  X25519HkdfSha256,
+  #[serde(rename = "DHKEM-P256-HKDF-SHA256")]
+  P256HkdfSha256,
+}
+
+#[derive(Deserialize, Clone, Copy)]
+pub enum HpkeKdf {
+  #[serde(rename = "HKDF-SHA256")]
+  HkdfSha256,
+}
+
+#[derive(Deserialize, Clone, Copy)]
+pub enum HpkeAead {
+  #[serde(rename = "AES-128-GCM")]
+  Aes128Gcm,
+  #[serde(rename = "AES-256-GCM")]
+  Aes256Gcm,
+  #[serde(rename = "ChaCha20Poly1305")]
+  ChaCha20Poly1305,
+}
+
+impl HpkeKem {
+  // Section 7.1
+  fn id(self) -> u16 {
+    match self {
+      HpkeKem::P256HkdfSha256 => 0x0010,
+      HpkeKem::X25519HkdfSha256 => 0x0020,
+    }
+  }
+
+  fn suite_id(self) -> Vec<u8> {
+    [&b"KEM"[..], &self.id().to_be_bytes()].concat()
+  }
+
+  fn generate_secret_key(self) -> Vec<u8> {
+    match self {
+      HpkeKem::X25519HkdfSha256 => {
+        let mut sk = vec![0; 32];
+        OsRng.fill_bytes(&mut sk);
+        sk
+      }
+      HpkeKem::P256HkdfSha256 => {
+        p256::SecretKey::random(&mut OsRng).to_bytes().to_vec()
+      }
+    }
+  }
+
+  fn public_key(self, sk: &[u8]) -> Result<Vec<u8>, AnyError> {
+    match self {
+      HpkeKem::X25519HkdfSha256 => {
+        let sk: [u8; 32] = sk
+          .try_into()
+          .map_err(|_| data_error("invalid X25519 private key"))?;
+        Ok(
+          x25519_dalek::x25519(sk, x25519_dalek::X25519_BASEPOINT_BYTES)
+            .to_vec(),
+        )
+      }
+      HpkeKem::P256HkdfSha256 => {
+        let sk = p256::SecretKey::from_slice(sk)
+          .map_err(|_| data_error("invalid P-256 private key"))?;
+        // SerializePublicKey uses the uncompressed SEC1 encoding.
+        Ok(sk.public_key().to_encoded_point(false).as_bytes().to_vec())
+      }
+    }
+  }
+
+  fn dh(self, sk: &[u8], pk: &[u8]) -> Result<Vec<u8>, AnyError> {
+    match self {
+      HpkeKem::X25519HkdfSha256 => {
+        let sk: [u8; 32] = sk
+          .try_into()
+          .map_err(|_| data_error("invalid X25519 private key"))?;
+        let pk: [u8; 32] = pk
+          .try_into()
+          .map_err(|_| data_error("invalid X25519 public key"))?;
+        let dh = x25519_dalek::x25519(sk, pk);
+        // Section 7.1.4: reject the all-zero shared secret.
+        if dh == [0; 32] {
+          return Err(operation_error("invalid X25519 public key"));
+        }
+        Ok(dh.to_vec())
+      }
+      HpkeKem::P256HkdfSha256 => {
+        let sk = p256::SecretKey::from_slice(sk)
+          .map_err(|_| data_error("invalid P-256 private key"))?;
+        let pk = p256::PublicKey::from_sec1_bytes(pk)
+          .map_err(|_| data_error("invalid P-256 public key"))?;
+        let dh =
+          p256::ecdh::diffie_hellman(sk.to_nonzero_scalar(), pk.as_affine());
+        Ok(dh.raw_secret_bytes().to_vec())
+      }
+    }
+  }
+
+  // Section 4.1
+  fn extract_and_expand(
+    self,
+    dh: &[u8],
+    kem_context: &[u8],
+  ) -> Result<Vec<u8>, AnyError> {
+    let suite_id = self.suite_id();
+    let eae_prk = labeled_extract(&suite_id, b"", b"eae_prk", dh);
+    labeled_expand(&suite_id, &eae_prk, b"shared_secret", kem_context, 32)
+  }
+
+  /// Returns `(shared_secret, enc)`.
+  fn encap(self, pk_r: &[u8]) -> Result<(Vec<u8>, Vec<u8>), AnyError> {
+    let sk_e = self.generate_secret_key();
+    let enc = self.public_key(&sk_e)?;
+    let dh = self.dh(&sk_e, pk_r)?;
+    let kem_context = [&enc[..], pk_r].concat();
+    Ok((self.extract_and_expand(&dh, &kem_context)?, enc))
+  }
+
+  fn decap(self, enc: &[u8], sk_r: &[u8]) -> Result<Vec<u8>, AnyError> {
+    let dh = self.dh(sk_r, enc)?;
+    let pk_rm = self.public_key(sk_r)?;
+    let kem_context = [enc, &pk_rm[..]].concat();
+    self.extract_and_expand(&dh, &kem_context)
+  }
+}
+
+impl HpkeKdf {
+  // Section 7.2
+  fn id(self) -> u16 {
+    match self {
+      HpkeKdf::HkdfSha256 => 0x0001,
+    }
+  }
+}
+
+impl HpkeAead {
+  // Section 7.3
+  fn id(self) -> u16 {
+    match self {
+      HpkeAead::Aes128Gcm => 0x0001,
+      HpkeAead::Aes256Gcm => 0x0002,
+      HpkeAead::ChaCha20Poly1305 => 0x0003,
+    }
+  }
+
+  fn key_len(self) -> usize {
+    match self {
+      HpkeAead::Aes128Gcm => 16,
+      HpkeAead::Aes256Gcm | HpkeAead::ChaCha20Poly1305 => 32,
+    }
+  }
+
+  fn seal(
+    self,
+    key: &[u8],
+    nonce: &[u8],
+    aad: &[u8],
+    msg: &[u8],
+  ) -> Result<Vec<u8>, AnyError> {
+    let payload = Payload { msg, aad };
+    match self {
+      HpkeAead::Aes128Gcm => aead_seal::<Aes128Gcm>(key, nonce, payload),
+      HpkeAead::Aes256Gcm => aead_seal::<Aes256Gcm>(key, nonce, payload),
+      HpkeAead::ChaCha20Poly1305 => {
+        aead_seal::<ChaCha20Poly1305>(key, nonce, payload)
+      }
+    }
+  }
+
+  fn open(
+    self,
+    key: &[u8],
+    nonce: &[u8],
+    aad: &[u8],
+    msg: &[u8],
+  ) -> Result<Vec<u8>, AnyError> {
+    let payload = Payload { msg, aad };
+    match self {
+      HpkeAead::Aes128Gcm => aead_open::<Aes128Gcm>(key, nonce, payload),
+      HpkeAead::Aes256Gcm => aead_open::<Aes256Gcm>(key, nonce, payload),
+      HpkeAead::ChaCha20Poly1305 => {
+        aead_open::<ChaCha20Poly1305>(key, nonce, payload)
+      }
+    }
+  }
+}
+
+// All supported AEADs use a 96-bit nonce.
+const HPKE_NONCE_LEN: usize = 12;
+
+fn aead_seal<C>(
+  key: &[u8],
+  nonce: &[u8],
+  payload: Payload,
+) -> Result<Vec<u8>, AnyError>
+where
+  C: KeyInit + aes_gcm::aead::Aead,
+{
+  let cipher =
+    C::new_from_slice(key).map_err(|_| operation_error("Encryption failed"))?;
+  cipher
+    .encrypt(nonce.into(), payload)
+    .map_err(|_| operation_error("Encryption failed"))
+}
+
+fn aead_open<C>(
+  key: &[u8],
+  nonce: &[u8],
+  payload: Payload,
+) -> Result<Vec<u8>, AnyError>
+where
+  C: KeyInit + aes_gcm::aead::Aead,
+{
+  let cipher =
+    C::new_from_slice(key).map_err(|_| operation_error("Decryption failed"))?;
+  cipher
+    .decrypt(nonce.into(), payload)
+    .map_err(|_| operation_error("Decryption failed"))
+}
+
+// Section 4
+fn labeled_extract(
+  suite_id: &[u8],
+  salt: &[u8],
+  label: &[u8],
+  ikm: &[u8],
+) -> Vec<u8> {
+  let labeled_ikm = [HPKE_VERSION, suite_id, label, ikm].concat();
+  let (prk, _) = Hkdf::<Sha256>::extract(Some(salt), &labeled_ikm);
+  prk.to_vec()
+}
+
+fn labeled_expand(
+  suite_id: &[u8],
+  prk: &[u8],
+  label: &[u8],
+  info: &[u8],
+  len: usize,
+) -> Result<Vec<u8>, AnyError> {
+  let labeled_info = [
+    &(len as u16).to_be_bytes()[..],
+    HPKE_VERSION,
+    suite_id,
+    label,
+    info,
+  ]
+  .concat();
+  let hkdf = Hkdf::<Sha256>::from_prk(prk)
+    .map_err(|_| operation_error("invalid HKDF pseudorandom key"))?;
+  let mut okm = vec![0; len];
+  hkdf
+    .expand(&labeled_info, &mut okm)
+    .map_err(|_| operation_error("invalid HKDF output length"))?;
+  Ok(okm)
+}
+
+/// Encryption context established by `op_crypto_hpke_setup_base_s` or
+/// `op_crypto_hpke_setup_base_r`.
+pub struct HpkeContext {
+  aead: HpkeAead,
+  key: Vec<u8>,
+  base_nonce: Vec<u8>,
+  seq: Cell<u64>,
+  is_sender: bool,
+}
+
+impl Resource for HpkeContext {
+  fn name(&self) -> Cow<str> {
+    "cryptoHpkeContext".into()
+  }
+}
+
+impl HpkeContext {
+  // Section 5.1
+  fn new(
+    kem: HpkeKem,
+    kdf: HpkeKdf,
+    aead: HpkeAead,
+    shared_secret: &[u8],
+    info: &[u8],
+    is_sender: bool,
+  ) -> Result<Self, AnyError> {
+    let suite_id = [
+      &b"HPKE"[..],
+      &kem.id().to_be_bytes(),
+      &kdf.id().to_be_bytes(),
+      &aead.id().to_be_bytes(),
+    ]
+    .concat();
+
+    // The base mode has no PSK.
+    let psk_id_hash = labeled_extract(&suite_id, b"", b"psk_id_hash", b"");
+    let info_hash = labeled_extract(&suite_id, b"", b"info_hash", info);
+    let key_schedule_context =
+      [&[MODE_BASE][..], &psk_id_hash, &info_hash].concat();
+
+    let secret = labeled_extract(&suite_id, shared_secret, b"secret", b"");
+    let key = labeled_expand(
+      &suite_id,
+      &secret,
+      b"key",
+      &key_schedule_context,
+      aead.key_len(),
+    )?;
+    let base_nonce = labeled_expand(
+      &suite_id,
+      &secret,
+      b"base_nonce",
+      &key_schedule_context,
+      HPKE_NONCE_LEN,
+    )?;
+
+    Ok(Self {
+      aead,
+      key,
+      base_nonce,
+      seq: Cell::new(0),
+      is_sender,
+    })
+  }
+
+  // Section 5.2
+  fn compute_nonce(&self) -> Result<Vec<u8>, AnyError> {
+    let seq = self.seq.get();
+    if seq == u64::MAX {
+      return Err(operation_error("Message limit reached"));
+    }
+    let mut nonce = self.base_nonce.clone();
+    let offset = HPKE_NONCE_LEN - 8;
+    for (n, s) in nonce[offset..].iter_mut().zip(seq.to_be_bytes()) {
+      *n ^= s;
+    }
+    Ok(nonce)
+  }
+
+  fn seal(&self, aad: &[u8], pt: &[u8]) -> Result<Vec<u8>, AnyError> {
+    if !self.is_sender {
+      return Err(type_error("Only the sender context can seal"));
+    }
+    let nonce = self.compute_nonce()?;
+    let ct = self.aead.seal(&self.key, &nonce, aad, pt)?;
+    self.seq.set(self.seq.get() + 1);
+    Ok(ct)
+  }
+
+  fn open(&self, aad: &[u8], ct: &[u8]) -> Result<Vec<u8>, AnyError> {
+    if self.is_sender {
+      return Err(type_error("Only the recipient context can open"));
+    }
+    let nonce = self.compute_nonce()?;
+    let pt = self.aead.open(&self.key, &nonce, aad, ct)?;
+    self.seq.set(self.seq.get() + 1);
+    Ok(pt)
+  }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HpkeSetupArgs {
+  kem: HpkeKem,
+  kdf: HpkeKdf,
+  aead: HpkeAead,
+  info: Option<JsBuffer>,
+}
+
+impl HpkeSetupArgs {
+  fn check_fips(&self, state: &OpState) -> Result<(), AnyError> {
+    if let HpkeKem::X25519HkdfSha256 = self.kem {
+      fips::check_unapproved(state, "DHKEM(X25519, HKDF-SHA256)")?;
+    }
+    if let HpkeAead::ChaCha20Poly1305 = self.aead {
+      fips::check_unapproved(state, "ChaCha20Poly1305")?;
+    }
+    Ok(())
+  }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HpkeSenderContext {
+  rid: ResourceId,
+  enc: ToJsBuffer,
+}
+
+#[op]
+pub fn op_crypto_hpke_setup_base_s(
+  state: &mut OpState,
+  args: HpkeSetupArgs,
+  pk_r: JsBuffer,
+) -> Result<HpkeSenderContext, AnyError> {
+  args.check_fips(state)?;
+  let (shared_secret, enc) = args.kem.encap(&pk_r)?;
+  let info = args.info.as_deref().unwrap_or_default();
+  let context = HpkeContext::new(
+    args.kem,
+    args.kdf,
+    args.aead,
+    &shared_secret,
+    info,
+    true,
+  )?;
+  let rid = state.resource_table.add(context);
+  Ok(HpkeSenderContext {
+    rid,
+    enc: enc.into(),
+  })
+}
+
+#[op]
+pub fn op_crypto_hpke_setup_base_r(
+  state: &mut OpState,
+  args: HpkeSetupArgs,
+  enc: JsBuffer,
+  sk_r: JsBuffer,
+) -> Result<ResourceId, AnyError> {
+  args.check_fips(state)?;
+  let shared_secret = args.kem.decap(&enc, &sk_r)?;
+  let info = args.info.as_deref().unwrap_or_default();
+  let context = HpkeContext::new(
+    args.kem,
+    args.kdf,
+    args.aead,
+    &shared_secret,
+    info,
+    false,
+  )?;
+  Ok(state.resource_table.add(context))
+}
+
+#[op]
+pub fn op_crypto_hpke_seal(
+  state: &mut OpState,
+  rid: ResourceId,
+  aad: JsBuffer,
+  pt: JsBuffer,
+) -> Result<ToJsBuffer, AnyError> {
+  let context = state.resource_table.get::<HpkeContext>(rid)?;
+  Ok(context.seal(&aad, &pt)?.into())
+}
+
+#[op]
+pub fn op_crypto_hpke_open(
+  state: &mut OpState,
+  rid: ResourceId,
+  aad: JsBuffer,
+  ct: JsBuffer,
+) -> Result<ToJsBuffer, AnyError> {
+  let context = state.resource_table.get::<HpkeContext>(rid)?;
+  Ok(context.open(&aad, &ct)?.into())
+}
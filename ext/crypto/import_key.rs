@@ -4,10 +4,11 @@ use deno_core::error::AnyError;
 use deno_core::op;
 use deno_core::JsBuffer;
 use deno_core::ToJsBuffer;
-use elliptic_curve::pkcs8::PrivateKeyInfo;
+use p256::pkcs8::DecodePrivateKey;
 use p256::pkcs8::EncodePrivateKey;
 use ring::signature::EcdsaKeyPair;
 use rsa::pkcs1::UIntRef;
+use rsa::pkcs8::PrivateKeyInfo;
 use serde::Deserialize;
 use serde::Serialize;
 use spki::der::Decode;
@@ -65,6 +66,8 @@ pub enum ImportKeyOptions {
   Ecdh { named_curve: EcNamedCurve },
   #[serde(rename = "AES", rename_all = "camelCase")]
   Aes {},
+  #[serde(rename = "CHACHA20", rename_all = "camelCase")]
+  ChaCha20 {},
   #[serde(rename = "HMAC", rename_all = "camelCase")]
   Hmac {},
 }
@@ -87,10 +90,28 @@ pub enum ImportKeyResult {
   Hmac { raw_data: RustRawKeyData },
 }
 
+impl ImportKeyResult {
+  pub(crate) fn into_raw_data(self) -> RustRawKeyData {
+    match self {
+      ImportKeyResult::Rsa { raw_data, .. }
+      | ImportKeyResult::Ec { raw_data }
+      | ImportKeyResult::Aes { raw_data }
+      | ImportKeyResult::Hmac { raw_data } => raw_data,
+    }
+  }
+}
+
 #[op]
 pub fn op_crypto_import_key(
   opts: ImportKeyOptions,
   key_data: KeyData,
+) -> Result<ImportKeyResult, AnyError> {
+  import_key(opts, key_data)
+}
+
+pub(crate) fn import_key(
+  opts: ImportKeyOptions,
+  key_data: KeyData,
 ) -> Result<ImportKeyResult, AnyError> {
   match opts {
     ImportKeyOptions::RsassaPkcs1v15 {} => import_key_rsassa(key_data),
@@ -101,6 +122,7 @@ pub fn op_crypto_import_key(
       import_key_ec(key_data, named_curve)
     }
     ImportKeyOptions::Aes {} => import_key_aes(key_data),
+    ImportKeyOptions::ChaCha20 {} => import_key_chacha20(key_data),
     ImportKeyOptions::Hmac {} => import_key_hmac(key_data),
   }
 }
@@ -499,7 +521,18 @@ fn import_key_ec_jwk_to_point(
 
       p384::EncodedPoint::from_affine_coordinates(&x, &y, false).to_bytes()
     }
-    _ => return Err(not_supported_error("Unsupported named curve")),
+    EcNamedCurve::Secp256k1 => {
+      let x = decode_b64url_to_field_bytes::<k256::Secp256k1>(&x)?;
+      let y = decode_b64url_to_field_bytes::<k256::Secp256k1>(&y)?;
+
+      k256::EncodedPoint::from_affine_coordinates(&x, &y, false).to_bytes()
+    }
+    EcNamedCurve::P521 => {
+      let x = decode_b64url_to_field_bytes::<p521::NistP521>(&x)?;
+      let y = decode_b64url_to_field_bytes::<p521::NistP521>(&y)?;
+
+      p521::EncodedPoint::from_affine_coordinates(&x, &y, false).to_bytes()
+    }
   };
 
   Ok(point_bytes.to_vec())
@@ -523,38 +556,49 @@ fn import_key_ec_jwk(
       let pkcs8_der = match named_curve {
         EcNamedCurve::P256 => {
           let d = decode_b64url_to_field_bytes::<p256::NistP256>(&d)?;
-          let pk = p256::SecretKey::from_be_bytes(&d)?;
+          let pk = p256::SecretKey::from_bytes(&d)?;
 
-          pk.to_pkcs8_der()?
+          pk.to_pkcs8_der()?.as_bytes().to_vec()
         }
         EcNamedCurve::P384 => {
           let d = decode_b64url_to_field_bytes::<p384::NistP384>(&d)?;
-          let pk = p384::SecretKey::from_be_bytes(&d)?;
+          let pk = p384::SecretKey::from_bytes(&d)?;
+
+          pk.to_pkcs8_der()?.as_bytes().to_vec()
+        }
+        EcNamedCurve::Secp256k1 => {
+          let d = decode_b64url_to_field_bytes::<k256::Secp256k1>(&d)?;
+          let pk = k256::SecretKey::from_bytes(&d)?;
 
-          pk.to_pkcs8_der()?
+          pk.to_pkcs8_der()?.as_bytes().to_vec()
         }
         EcNamedCurve::P521 => {
-          return Err(data_error("Unsupported named curve"))
+          let d = decode_b64url_to_field_bytes::<p521::NistP521>(&d)?;
+          let pk = p521::SecretKey::from_bytes(&d)
+            .map_err(|_| data_error("invalid JWK private key"))?;
+
+          pk.to_pkcs8_der()?.as_bytes().to_vec()
         }
       };
 
       // Import using ring, to validate key
       let key_alg = match named_curve {
-        EcNamedCurve::P256 => CryptoNamedCurve::P256.try_into()?,
-        EcNamedCurve::P384 => CryptoNamedCurve::P256.try_into()?,
-        EcNamedCurve::P521 => {
-          return Err(data_error("Unsupported named curve"))
-        }
+        EcNamedCurve::P256 => Some(CryptoNamedCurve::P256.try_into()?),
+        EcNamedCurve::P384 => Some(CryptoNamedCurve::P256.try_into()?),
+        // Already validated by `SecretKey::from_bytes`.
+        EcNamedCurve::P521 | EcNamedCurve::Secp256k1 => None,
       };
 
-      let _key_pair = EcdsaKeyPair::from_private_key_and_public_key(
-        key_alg,
-        private_d.as_bytes(),
-        point_bytes.as_ref(),
-      );
+      if let Some(key_alg) = key_alg {
+        let _key_pair = EcdsaKeyPair::from_private_key_and_public_key(
+          key_alg,
+          private_d.as_bytes(),
+          point_bytes.as_ref(),
+        );
+      }
 
       Ok(ImportKeyResult::Ec {
-        raw_data: RustRawKeyData::Private(pkcs8_der.as_bytes().to_vec().into()),
+        raw_data: RustRawKeyData::Private(pkcs8_der.into()),
       })
     }
     _ => unreachable!(),
@@ -604,7 +648,25 @@ fn import_key_ec(
             return Err(data_error("invalid P-384 elliptic curve point"));
           }
         }
-        _ => return Err(not_supported_error("Unsupported named curve")),
+        EcNamedCurve::P521 => {
+          // 1-2.
+          let point = p521::EncodedPoint::from_bytes(&data)
+            .map_err(|_| data_error("invalid P-521 elliptic curve point"))?;
+          // 3.
+          if point.is_identity() {
+            return Err(data_error("invalid P-521 elliptic curve point"));
+          }
+        }
+        EcNamedCurve::Secp256k1 => {
+          // 1-2.
+          let point = k256::EncodedPoint::from_bytes(&data).map_err(|_| {
+            data_error("invalid secp256k1 elliptic curve point")
+          })?;
+          // 3.
+          if point.is_identity() {
+            return Err(data_error("invalid secp256k1 elliptic curve point"));
+          }
+        }
       };
       Ok(ImportKeyResult::Ec {
         raw_data: RustRawKeyData::Public(data.to_vec().into()),
@@ -613,19 +675,14 @@ fn import_key_ec(
     KeyData::Pkcs8(data) => {
       // 2-7
       // Deserialize PKCS8 - validate structure, extracts named_curve
-      let named_curve_alg = match named_curve {
-        EcNamedCurve::P256 | EcNamedCurve::P384 => {
-          let pk = PrivateKeyInfo::from_der(data.as_ref())
-            .map_err(|_| data_error("expected valid PKCS#8 data"))?;
-          pk.algorithm
-            .parameters
-            .ok_or_else(|| data_error("malformed parameters"))?
-            .oid()
-            .unwrap()
-        }
-        EcNamedCurve::P521 => {
-          return Err(data_error("Unsupported named curve"))
-        }
+      let named_curve_alg = {
+        let pk = PrivateKeyInfo::from_der(data.as_ref())
+          .map_err(|_| data_error("expected valid PKCS#8 data"))?;
+        pk.algorithm
+          .parameters
+          .ok_or_else(|| data_error("malformed parameters"))?
+          .oid()
+          .unwrap()
       };
 
       // 8-9.
@@ -636,21 +693,33 @@ fn import_key_ec(
         ID_SECP384R1_OID => Some(EcNamedCurve::P384),
         // id-secp521r1
         ID_SECP521R1_OID => Some(EcNamedCurve::P521),
+        // secp256k1
+        ID_SECP256K1_OID => Some(EcNamedCurve::Secp256k1),
         _ => None,
       };
 
       // 10.
       if let Some(pk_named_curve) = pk_named_curve {
-        let signing_alg = match pk_named_curve {
-          EcNamedCurve::P256 => CryptoNamedCurve::P256.try_into()?,
-          EcNamedCurve::P384 => CryptoNamedCurve::P384.try_into()?,
+        match pk_named_curve {
+          EcNamedCurve::P256 | EcNamedCurve::P384 => {
+            let signing_alg = match pk_named_curve {
+              EcNamedCurve::P256 => CryptoNamedCurve::P256.try_into()?,
+              _ => CryptoNamedCurve::P384.try_into()?,
+            };
+            // deserialize pkcs8 using ring crate, to VALIDATE public key
+            let _private_key = EcdsaKeyPair::from_pkcs8(signing_alg, &data)?;
+          }
+          // ring has no P-521 or secp256k1 support, validate with the
+          // RustCrypto crates instead
           EcNamedCurve::P521 => {
-            return Err(data_error("Unsupported named curve"))
+            let _private_key = p521::SecretKey::from_pkcs8_der(&data)
+              .map_err(|_| data_error("invalid P-521 private key"))?;
           }
-        };
-
-        // deserialize pkcs8 using ring crate, to VALIDATE public key
-        let _private_key = EcdsaKeyPair::from_pkcs8(signing_alg, &data)?;
+          EcNamedCurve::Secp256k1 => {
+            let _private_key = k256::SecretKey::from_pkcs8_der(&data)
+              .map_err(|_| data_error("invalid secp256k1 private key"))?;
+          }
+        }
 
         // 11.
         if named_curve != pk_named_curve {
@@ -694,6 +763,8 @@ fn import_key_ec(
         ID_SECP384R1_OID => Some(EcNamedCurve::P384),
         // id-secp521r1
         ID_SECP521R1_OID => Some(EcNamedCurve::P521),
+        // secp256k1
+        ID_SECP256K1_OID => Some(EcNamedCurve::Secp256k1),
         _ => None,
       };
 
@@ -729,7 +800,30 @@ fn import_key_ec(
 
             point.as_bytes().len()
           }
-          _ => return Err(not_supported_error("Unsupported named curve")),
+          EcNamedCurve::P521 => {
+            let point =
+              p521::EncodedPoint::from_bytes(&*encoded_key).map_err(|_| {
+                data_error("invalid P-521 elliptic curve SPKI data")
+              })?;
+
+            if point.is_identity() {
+              return Err(data_error("invalid P-521 elliptic curve point"));
+            }
+
+            point.as_bytes().len()
+          }
+          EcNamedCurve::Secp256k1 => {
+            let point =
+              k256::EncodedPoint::from_bytes(&*encoded_key).map_err(|_| {
+                data_error("invalid secp256k1 elliptic curve SPKI data")
+              })?;
+
+            if point.is_identity() {
+              return Err(data_error("invalid secp256k1 elliptic curve point"));
+            }
+
+            point.as_bytes().len()
+          }
         };
 
         if bytes_consumed != pk_info.subject_public_key.len() {
@@ -768,6 +862,24 @@ fn import_key_aes(key_data: KeyData) -> Result<ImportKeyResult, AnyError> {
   })
 }
 
+fn import_key_chacha20(key_data: KeyData) -> Result<ImportKeyResult, AnyError> {
+  let data = match key_data {
+    KeyData::Raw(data) => data.to_vec(),
+    KeyData::JwkSecret { k } => base64::decode_config(k, URL_SAFE_FORGIVING)
+      .map_err(|_| data_error("invalid key data"))?,
+    _ => return Err(unsupported_format()),
+  };
+
+  // ChaCha20-Poly1305 and XChaCha20-Poly1305 both use a 256-bit key.
+  if data.len() != 32 {
+    return Err(data_error("invalid key length"));
+  }
+
+  Ok(ImportKeyResult::Hmac {
+    raw_data: RustRawKeyData::Secret(data.into()),
+  })
+}
+
 fn import_key_hmac(key_data: KeyData) -> Result<ImportKeyResult, AnyError> {
   Ok(match key_data {
     KeyData::JwkSecret { k } => {
@@ -0,0 +1,259 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! Compact JWS (RFC 7515) signing and verification in a single op, for
+//! servers that issue and check many tokens. Only the JOSE algorithms that
+//! are widely used for JWTs are supported.
+
+use deno_core::error::type_error;
+use deno_core::error::AnyError;
+use deno_core::op;
+use deno_core::serde_json;
+use deno_core::serde_json::Map;
+use deno_core::serde_json::Value;
+use deno_core::task::spawn_blocking;
+use deno_core::JsBuffer;
+use deno_core::OpState;
+use deno_core::ToJsBuffer;
+use ring::signature::EcdsaKeyPair;
+use ring::signature::Ed25519KeyPair;
+use ring::signature::KeyPair;
+use serde::Deserialize;
+use serde::Serialize;
+use signature::Verifier;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::backend;
+use crate::fips;
+use crate::key::Algorithm;
+use crate::key::CryptoHash;
+use crate::key::CryptoNamedCurve;
+use crate::shared::data_error;
+use crate::shared::not_supported_error;
+use crate::KeyData;
+use crate::KeyType;
+use crate::SignArg;
+
+#[derive(Deserialize, PartialEq, Eq, Clone, Copy)]
+pub enum JwsAlgorithm {
+  #[serde(rename = "ES256")]
+  Es256,
+  #[serde(rename = "EdDSA")]
+  EdDsa,
+  #[serde(rename = "RS256")]
+  Rs256,
+  #[serde(rename = "HS256")]
+  Hs256,
+}
+
+impl JwsAlgorithm {
+  fn from_header(header: &Map<String, Value>) -> Result<Self, AnyError> {
+    let alg = header
+      .get("alg")
+      .ok_or_else(|| type_error("JWS header is missing \"alg\""))?;
+    serde_json::from_value(alg.clone())
+      .map_err(|_| not_supported_error("Unsupported JWS algorithm"))
+  }
+
+  fn check_fips(self, state: &OpState) -> Result<(), AnyError> {
+    if let JwsAlgorithm::EdDsa = self {
+      fips::check_unapproved(state, "EdDSA")?;
+    }
+    Ok(())
+  }
+
+  fn check_key(self, key: &KeyData) -> Result<(), AnyError> {
+    let valid = match self {
+      JwsAlgorithm::Hs256 => matches!(key.r#type, KeyType::Secret),
+      _ => !matches!(key.r#type, KeyType::Secret),
+    };
+    if !valid {
+      return Err(type_error("Invalid key type"));
+    }
+    Ok(())
+  }
+}
+
+fn encode(data: &[u8]) -> String {
+  base64::encode_config(data, base64::URL_SAFE_NO_PAD)
+}
+
+fn decode(data: &str) -> Result<Vec<u8>, AnyError> {
+  base64::decode_config(data, base64::URL_SAFE_NO_PAD)
+    .map_err(|_| data_error("invalid JWS"))
+}
+
+#[derive(Deserialize)]
+pub struct JwsSignArg {
+  key: KeyData,
+  header: Map<String, Value>,
+}
+
+/// Returns `BASE64URL(header) || '.' || BASE64URL(payload) || '.' ||
+/// BASE64URL(signature)`. The algorithm is taken from the header's `alg`.
+#[op]
+pub async fn op_crypto_jws_sign(
+  state: Rc<RefCell<OpState>>,
+  mut args: JwsSignArg,
+  payload: JsBuffer,
+) -> Result<String, AnyError> {
+  let algorithm = JwsAlgorithm::from_header(&args.header)?;
+  let backend = {
+    let state = state.borrow();
+    algorithm.check_fips(&state)?;
+    args.key.resolve(&state)?;
+    backend::get(&state)
+  };
+  algorithm.check_key(&args.key)?;
+  if matches!(args.key.r#type, KeyType::Public) {
+    return Err(type_error("Invalid key type"));
+  }
+
+  let signing_input = format!(
+    "{}.{}",
+    encode(&serde_json::to_vec(&args.header)?),
+    encode(&payload)
+  );
+  spawn_blocking(move || {
+    let signature = match algorithm {
+      JwsAlgorithm::EdDsa => {
+        Ed25519KeyPair::from_seed_unchecked(&args.key.data)
+          .map_err(|_| type_error("Invalid Ed25519 private key"))?
+          .sign(signing_input.as_bytes())
+          .as_ref()
+          .to_vec()
+      }
+      _ => {
+        let (algorithm, named_curve) = match algorithm {
+          JwsAlgorithm::Es256 => {
+            (Algorithm::Ecdsa, Some(CryptoNamedCurve::P256))
+          }
+          JwsAlgorithm::Rs256 => (Algorithm::RsassaPkcs1v15, None),
+          _ => (Algorithm::Hmac, None),
+        };
+        let args = SignArg {
+          key: args.key,
+          algorithm,
+          salt_length: None,
+          hash: Some(CryptoHash::Sha256),
+          named_curve,
+          length: None,
+          customization: None,
+        };
+        crate::sign_key(args, backend, signing_input.as_bytes())?
+      }
+    };
+    Ok(format!("{signing_input}.{}", encode(&signature)))
+  })
+  .await
+  .unwrap()
+}
+
+#[derive(Serialize)]
+pub struct JwsVerifyResult {
+  header: Map<String, Value>,
+  payload: ToJsBuffer,
+}
+
+/// Verifies a compact JWS whose header `alg` must be `algorithm`, so that a
+/// token can not choose how it is checked. Returns `null` if the signature is
+/// invalid, or the decoded header and payload otherwise.
+#[op]
+pub async fn op_crypto_jws_verify(
+  state: Rc<RefCell<OpState>>,
+  mut key: KeyData,
+  algorithm: JwsAlgorithm,
+  token: String,
+) -> Result<Option<JwsVerifyResult>, AnyError> {
+  {
+    let state = state.borrow();
+    algorithm.check_fips(&state)?;
+    key.resolve(&state)?;
+  }
+  algorithm.check_key(&key)?;
+
+  let mut parts = token.split('.');
+  let (Some(header), Some(payload), Some(signature), None) =
+    (parts.next(), parts.next(), parts.next(), parts.next())
+  else {
+    return Err(data_error("invalid JWS"));
+  };
+  let header: Map<String, Value> = serde_json::from_slice(&decode(header)?)
+    .map_err(|_| data_error("invalid JWS header"))?;
+  if JwsAlgorithm::from_header(&header).ok() != Some(algorithm) {
+    return Ok(None);
+  }
+  let payload = decode(payload)?;
+  let signature = decode(signature)?;
+
+  spawn_blocking(move || {
+    let signing_input = &token.as_bytes()[..token.rfind('.').unwrap()];
+    let valid = verify(algorithm, key, signing_input, &signature)?;
+    Ok(valid.then(|| JwsVerifyResult {
+      header,
+      payload: payload.into(),
+    }))
+  })
+  .await
+  .unwrap()
+}
+
+fn verify(
+  algorithm: JwsAlgorithm,
+  key: KeyData,
+  data: &[u8],
+  signature: &[u8],
+) -> Result<bool, AnyError> {
+  let valid = match algorithm {
+    JwsAlgorithm::Es256 => {
+      let private_key;
+      let public_key = match key.r#type {
+        KeyType::Private => {
+          private_key = EcdsaKeyPair::from_pkcs8(
+            &ring::signature::ECDSA_P256_SHA256_FIXED_SIGNING,
+            &key.data,
+          )?;
+          private_key.public_key().as_ref()
+        }
+        _ => &*key.data,
+      };
+      ring::signature::UnparsedPublicKey::new(
+        &ring::signature::ECDSA_P256_SHA256_FIXED,
+        public_key,
+      )
+      .verify(data, signature)
+      .is_ok()
+    }
+    JwsAlgorithm::EdDsa => {
+      let private_key;
+      let public_key = match key.r#type {
+        KeyType::Private => {
+          private_key = Ed25519KeyPair::from_seed_unchecked(&key.data)
+            .map_err(|_| type_error("Invalid Ed25519 private key"))?;
+          private_key.public_key().as_ref()
+        }
+        _ => &*key.data,
+      };
+      ring::signature::UnparsedPublicKey::new(
+        &ring::signature::ED25519,
+        public_key,
+      )
+      .verify(data, signature)
+      .is_ok()
+    }
+    JwsAlgorithm::Rs256 => {
+      use rsa::pkcs1v15::Signature;
+      use rsa::pkcs1v15::VerifyingKey;
+      let public_key = crate::read_rsa_public_key(key)?;
+      let signature: Signature = signature.to_vec().into();
+      VerifyingKey::<sha2::Sha256>::new_with_prefix(public_key)
+        .verify(data, &signature)
+        .is_ok()
+    }
+    JwsAlgorithm::Hs256 => {
+      let key = ring::hmac::Key::new(ring::hmac::HMAC_SHA256, &key.data);
+      ring::hmac::verify(&key, data, signature).is_ok()
+    }
+  };
+  Ok(valid)
+}
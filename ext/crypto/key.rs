@@ -1,5 +1,6 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
+use deno_core::error::AnyError;
 use ring::agreement::Algorithm as RingAlgorithm;
 use ring::digest;
 use ring::hkdf;
@@ -35,39 +36,70 @@ pub enum CryptoNamedCurve {
   P256,
   #[serde(rename = "P-384")]
   P384,
+  #[serde(rename = "P-521")]
+  P521,
+  #[serde(rename = "secp256k1")]
+  Secp256k1,
 }
 
-impl From<CryptoNamedCurve> for &RingAlgorithm {
-  fn from(curve: CryptoNamedCurve) -> &'static RingAlgorithm {
+// ring only implements P-256 and P-384; P-521 and secp256k1 are handled by
+// the `p521` and `k256` crates instead, so these conversions are fallible.
+impl TryFrom<CryptoNamedCurve> for &RingAlgorithm {
+  type Error = AnyError;
+
+  fn try_from(
+    curve: CryptoNamedCurve,
+  ) -> Result<&'static RingAlgorithm, AnyError> {
     match curve {
-      CryptoNamedCurve::P256 => &ring::agreement::ECDH_P256,
-      CryptoNamedCurve::P384 => &ring::agreement::ECDH_P384,
+      CryptoNamedCurve::P256 => Ok(&ring::agreement::ECDH_P256),
+      CryptoNamedCurve::P384 => Ok(&ring::agreement::ECDH_P384),
+      CryptoNamedCurve::P521 | CryptoNamedCurve::Secp256k1 => {
+        Err(not_supported_curve())
+      }
     }
   }
 }
 
-impl From<CryptoNamedCurve> for &EcdsaSigningAlgorithm {
-  fn from(curve: CryptoNamedCurve) -> &'static EcdsaSigningAlgorithm {
+impl TryFrom<CryptoNamedCurve> for &EcdsaSigningAlgorithm {
+  type Error = AnyError;
+
+  fn try_from(
+    curve: CryptoNamedCurve,
+  ) -> Result<&'static EcdsaSigningAlgorithm, AnyError> {
     match curve {
       CryptoNamedCurve::P256 => {
-        &ring::signature::ECDSA_P256_SHA256_FIXED_SIGNING
+        Ok(&ring::signature::ECDSA_P256_SHA256_FIXED_SIGNING)
       }
       CryptoNamedCurve::P384 => {
-        &ring::signature::ECDSA_P384_SHA384_FIXED_SIGNING
+        Ok(&ring::signature::ECDSA_P384_SHA384_FIXED_SIGNING)
+      }
+      CryptoNamedCurve::P521 | CryptoNamedCurve::Secp256k1 => {
+        Err(not_supported_curve())
       }
     }
   }
 }
 
-impl From<CryptoNamedCurve> for &EcdsaVerificationAlgorithm {
-  fn from(curve: CryptoNamedCurve) -> &'static EcdsaVerificationAlgorithm {
+impl TryFrom<CryptoNamedCurve> for &EcdsaVerificationAlgorithm {
+  type Error = AnyError;
+
+  fn try_from(
+    curve: CryptoNamedCurve,
+  ) -> Result<&'static EcdsaVerificationAlgorithm, AnyError> {
     match curve {
-      CryptoNamedCurve::P256 => &ring::signature::ECDSA_P256_SHA256_FIXED,
-      CryptoNamedCurve::P384 => &ring::signature::ECDSA_P384_SHA384_FIXED,
+      CryptoNamedCurve::P256 => Ok(&ring::signature::ECDSA_P256_SHA256_FIXED),
+      CryptoNamedCurve::P384 => Ok(&ring::signature::ECDSA_P384_SHA384_FIXED),
+      CryptoNamedCurve::P521 | CryptoNamedCurve::Secp256k1 => {
+        Err(not_supported_curve())
+      }
     }
   }
 }
 
+fn not_supported_curve() -> AnyError {
+  crate::shared::not_supported_error("Unsupported named curve")
+}
+
 impl From<CryptoHash> for HmacAlgorithm {
   fn from(hash: CryptoHash) -> HmacAlgorithm {
     match hash {
@@ -90,6 +122,17 @@ impl From<CryptoHash> for &'static digest::Algorithm {
   }
 }
 
+impl From<CryptoHash> for hkdf::Algorithm {
+  fn from(hash: CryptoHash) -> hkdf::Algorithm {
+    match hash {
+      CryptoHash::Sha1 => hkdf::HKDF_SHA1_FOR_LEGACY_USE_ONLY,
+      CryptoHash::Sha256 => hkdf::HKDF_SHA256,
+      CryptoHash::Sha384 => hkdf::HKDF_SHA384,
+      CryptoHash::Sha512 => hkdf::HKDF_SHA512,
+    }
+  }
+}
+
 pub struct HkdfOutput<T>(pub T);
 
 impl hkdf::KeyType for HkdfOutput<usize> {
@@ -131,10 +174,30 @@ pub enum Algorithm {
   AesGcm,
   #[serde(rename = "AES-KW")]
   AesKw,
+  #[serde(rename = "AES-KWP")]
+  AesKwp,
+  #[serde(rename = "ChaCha20-Poly1305")]
+  ChaCha20Poly1305,
+  #[serde(rename = "XChaCha20-Poly1305")]
+  XChaCha20Poly1305,
+  #[serde(rename = "AES-256-GCM-SIV")]
+  Aes256GcmSiv,
   #[serde(rename = "HMAC")]
   Hmac,
+  #[serde(rename = "KMAC128")]
+  Kmac128,
+  #[serde(rename = "KMAC256")]
+  Kmac256,
   #[serde(rename = "PBKDF2")]
   Pbkdf2,
   #[serde(rename = "HKDF")]
   Hkdf,
+  #[serde(rename = "Argon2id")]
+  Argon2id,
+  #[serde(rename = "scrypt")]
+  Scrypt,
+  #[serde(rename = "ConcatKDF")]
+  ConcatKdf,
+  #[serde(rename = "X963KDF")]
+  X963Kdf,
 }
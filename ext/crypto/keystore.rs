@@ -0,0 +1,391 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! Passphrase protected key files, so that CLI tools can keep keys on disk
+//! without each of them rolling their own format.
+//!
+//! A key file is laid out as follows, with integers in big endian:
+//!
+//! ```text
+//! magic    "DENOKEY\0"
+//! version  u8
+//! m_cost   u32   Argon2id memory cost in KiB
+//! t_cost   u32   Argon2id iterations
+//! p_cost   u32   Argon2id parallelism
+//! salt     [u8; 16]
+//! nonce    [u8; 12]
+//! sealed   AES-256-GCM(plaintext), with the header above as AAD
+//! ```
+//!
+//! and the sealed plaintext as follows:
+//!
+//! ```text
+//! type          u8    0 = secret, 1 = private, 2 = public
+//! extractable   u8
+//! metadata_len  u32
+//! metadata      [u8; metadata_len]   JSON written by `Deno.saveCryptoKey`
+//! key data
+//! ```
+
+use aes_gcm::aead::Aead;
+use aes_gcm::aead::Payload;
+use aes_gcm::Aes256Gcm;
+use aes_gcm::KeyInit;
+use aes_gcm::Nonce;
+use deno_core::error::custom_error;
+use deno_core::error::AnyError;
+use deno_core::op;
+use deno_core::task::spawn_blocking;
+use deno_core::OpState;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::Deserialize;
+use serde::Serialize;
+use std::cell::RefCell;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::sync::Arc;
+use zeroize::Zeroizing;
+
+use crate::shared::data_error;
+use crate::shared::operation_error;
+use crate::CryptoKeyStore;
+use crate::KeyData;
+use crate::KeyType;
+use crate::StoredKey;
+
+pub trait CryptoPermissions {
+  fn check_read(&mut self, p: &Path, api_name: &str) -> Result<(), AnyError>;
+  fn check_write(&mut self, p: &Path, api_name: &str) -> Result<(), AnyError>;
+}
+
+const MAGIC: &[u8; 8] = b"DENOKEY\0";
+const VERSION: u8 = 1;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const HEADER_LEN: usize = MAGIC.len() + 1 + 3 * 4 + SALT_LEN + NONCE_LEN;
+
+// OWASP recommended minimum for Argon2id. These are stored in each file, so
+// they can be raised later without breaking existing files.
+const M_COST: u32 = 19 * 1024;
+const T_COST: u32 = 2;
+const P_COST: u32 = 1;
+
+// Upper bounds for the costs read back from a key file header. Without them a
+// crafted file could make `open` allocate gigabytes or spin for hours before
+// the passphrase is even checked.
+const MAX_M_COST: u32 = 256 * 1024;
+const MAX_T_COST: u32 = 16;
+const MAX_P_COST: u32 = 8;
+
+fn derive_kek(
+  passphrase: &[u8],
+  salt: &[u8],
+  m_cost: u32,
+  t_cost: u32,
+  p_cost: u32,
+) -> Result<Zeroizing<[u8; 32]>, AnyError> {
+  let params = argon2::Params::new(m_cost, t_cost, p_cost, Some(32))
+    .map_err(|_| data_error("Invalid key file parameters"))?;
+  let argon2 = argon2::Argon2::new(
+    argon2::Algorithm::Argon2id,
+    argon2::Version::V0x13,
+    params,
+  );
+  let mut kek = Zeroizing::new([0u8; 32]);
+  argon2
+    .hash_password_into(passphrase, salt, &mut *kek)
+    .map_err(|_| operation_error("Key derivation failed"))?;
+  Ok(kek)
+}
+
+/// A key read back from a key file.
+struct KeyFile {
+  r#type: KeyType,
+  extractable: bool,
+  metadata: String,
+  data: Zeroizing<Vec<u8>>,
+}
+
+fn seal(key: &KeyFile, passphrase: &[u8]) -> Result<Vec<u8>, AnyError> {
+  let mut salt = [0u8; SALT_LEN];
+  let mut nonce = [0u8; NONCE_LEN];
+  OsRng.fill_bytes(&mut salt);
+  OsRng.fill_bytes(&mut nonce);
+
+  let mut header = Vec::with_capacity(HEADER_LEN);
+  header.extend_from_slice(MAGIC);
+  header.push(VERSION);
+  header.extend_from_slice(&M_COST.to_be_bytes());
+  header.extend_from_slice(&T_COST.to_be_bytes());
+  header.extend_from_slice(&P_COST.to_be_bytes());
+  header.extend_from_slice(&salt);
+  header.extend_from_slice(&nonce);
+
+  let metadata_len = u32::try_from(key.metadata.len())
+    .map_err(|_| data_error("Key metadata is too large"))?;
+  let mut plaintext = Zeroizing::new(Vec::with_capacity(
+    2 + 4 + key.metadata.len() + key.data.len(),
+  ));
+  plaintext.push(match key.r#type {
+    KeyType::Secret => 0,
+    KeyType::Private => 1,
+    KeyType::Public => 2,
+  });
+  plaintext.push(key.extractable as u8);
+  plaintext.extend_from_slice(&metadata_len.to_be_bytes());
+  plaintext.extend_from_slice(key.metadata.as_bytes());
+  plaintext.extend_from_slice(&key.data);
+
+  let kek = derive_kek(passphrase, &salt, M_COST, T_COST, P_COST)?;
+  let sealed = Aes256Gcm::new((&*kek).into())
+    .encrypt(
+      Nonce::from_slice(&nonce),
+      Payload {
+        msg: &plaintext,
+        aad: &header,
+      },
+    )
+    .map_err(|_| operation_error("Encryption failed"))?;
+  header.extend_from_slice(&sealed);
+  Ok(header)
+}
+
+fn open(file: &[u8], passphrase: &[u8]) -> Result<KeyFile, AnyError> {
+  if file.len() < HEADER_LEN || &file[..MAGIC.len()] != MAGIC {
+    return Err(data_error("Not a key file"));
+  }
+  let (header, sealed) = file.split_at(HEADER_LEN);
+  if header[MAGIC.len()] != VERSION {
+    return Err(data_error("Unsupported key file version"));
+  }
+  let u32_at = |offset: usize| {
+    let offset = MAGIC.len() + 1 + offset * 4;
+    u32::from_be_bytes(header[offset..offset + 4].try_into().unwrap())
+  };
+  let salt = &header[HEADER_LEN - NONCE_LEN - SALT_LEN..][..SALT_LEN];
+  let nonce = &header[HEADER_LEN - NONCE_LEN..];
+
+  let (m_cost, t_cost, p_cost) = (u32_at(0), u32_at(1), u32_at(2));
+  if m_cost > MAX_M_COST || t_cost > MAX_T_COST || p_cost > MAX_P_COST {
+    return Err(data_error(
+      "Key file parameters exceed the supported limits",
+    ));
+  }
+
+  let kek = derive_kek(passphrase, salt, m_cost, t_cost, p_cost)?;
+  let plaintext = Zeroizing::new(
+    Aes256Gcm::new((&*kek).into())
+      .decrypt(
+        Nonce::from_slice(nonce),
+        Payload {
+          msg: sealed,
+          aad: header,
+        },
+      )
+      .map_err(|_| operation_error("Invalid passphrase or key file"))?,
+  );
+  if plaintext.len() < 6 {
+    return Err(data_error("Invalid key file"));
+  }
+  let r#type = match plaintext[0] {
+    0 => KeyType::Secret,
+    1 => KeyType::Private,
+    2 => KeyType::Public,
+    _ => return Err(data_error("Invalid key type")),
+  };
+  let extractable = match plaintext[1] {
+    0 => false,
+    1 => true,
+    _ => return Err(data_error("Invalid key file")),
+  };
+  let metadata_len =
+    u32::from_be_bytes(plaintext[2..6].try_into().unwrap()) as usize;
+  let rest = &plaintext[6..];
+  if rest.len() < metadata_len {
+    return Err(data_error("Invalid key file"));
+  }
+  let (metadata, data) = rest.split_at(metadata_len);
+  let metadata = String::from_utf8(metadata.to_vec())
+    .map_err(|_| data_error("Invalid key file"))?;
+  Ok(KeyFile {
+    r#type,
+    extractable,
+    metadata,
+    data: Zeroizing::new(data.to_vec()),
+  })
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeystoreSaveArg {
+  path: String,
+  key: KeyData,
+  passphrase: String,
+  metadata: String,
+}
+
+/// Writes `key` to `path`, encrypted with a key derived from `passphrase`.
+/// Like `wrapKey`, this requires the key to be extractable.
+#[op]
+pub async fn op_crypto_keystore_save<P>(
+  state: Rc<RefCell<OpState>>,
+  mut args: KeystoreSaveArg,
+) -> Result<(), AnyError>
+where
+  P: CryptoPermissions + 'static,
+{
+  let path = PathBuf::from(args.path);
+  {
+    let mut state = state.borrow_mut();
+    state
+      .borrow_mut::<P>()
+      .check_write(&path, "Deno.saveCryptoKey()")?;
+    args.key.resolve(&state)?;
+  }
+  if !args.key.extractable {
+    return Err(custom_error(
+      "DOMExceptionInvalidAccessError",
+      "Key is not extractable",
+    ));
+  }
+  let key = KeyFile {
+    r#type: args.key.r#type,
+    extractable: args.key.extractable,
+    metadata: args.metadata,
+    data: Zeroizing::new(args.key.data.to_vec()),
+  };
+  let passphrase = Zeroizing::new(args.passphrase);
+
+  spawn_blocking(move || {
+    let file = seal(&key, passphrase.as_bytes())?;
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+      use std::os::unix::fs::OpenOptionsExt;
+      options.mode(0o600);
+    }
+    let mut out = options.open(&path)?;
+    // The mode above only applies when the file is created.
+    #[cfg(unix)]
+    {
+      use std::os::unix::fs::PermissionsExt;
+      out.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+    }
+    out.write_all(&file)?;
+    Ok(())
+  })
+  .await
+  .unwrap()
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeystoreLoadArg {
+  path: String,
+  passphrase: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeystoreLoadResult {
+  handle: u32,
+  r#type: &'static str,
+  extractable: bool,
+  metadata: String,
+}
+
+/// Reads a key written by `op_crypto_keystore_save` into the key store. The
+/// key keeps the extractable flag it was saved with.
+#[op]
+pub async fn op_crypto_keystore_load<P>(
+  state: Rc<RefCell<OpState>>,
+  args: KeystoreLoadArg,
+) -> Result<KeystoreLoadResult, AnyError>
+where
+  P: CryptoPermissions + 'static,
+{
+  let path = PathBuf::from(args.path);
+  state
+    .borrow_mut()
+    .borrow_mut::<P>()
+    .check_read(&path, "Deno.loadCryptoKey()")?;
+  let passphrase = Zeroizing::new(args.passphrase);
+
+  let key = spawn_blocking(move || {
+    let file = std::fs::read(&path)?;
+    open(&file, passphrase.as_bytes())
+  })
+  .await
+  .unwrap()?;
+
+  let mut state = state.borrow_mut();
+  let handle = state.borrow_mut::<CryptoKeyStore>().insert(StoredKey {
+    r#type: key.r#type,
+    data: Arc::new(key.data),
+    extractable: key.extractable,
+  });
+  Ok(KeystoreLoadResult {
+    handle,
+    r#type: match key.r#type {
+      KeyType::Secret => "secret",
+      KeyType::Private => "private",
+      KeyType::Public => "public",
+    },
+    extractable: key.extractable,
+    metadata: key.metadata,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn set_u32(file: &mut [u8], index: usize, value: u32) {
+    let offset = MAGIC.len() + 1 + index * 4;
+    file[offset..offset + 4].copy_from_slice(&value.to_be_bytes());
+  }
+
+  fn secret_key(extractable: bool) -> KeyFile {
+    KeyFile {
+      r#type: KeyType::Secret,
+      extractable,
+      metadata: r#"{"usages":["sign"]}"#.to_string(),
+      data: Zeroizing::new(b"secret".to_vec()),
+    }
+  }
+
+  #[test]
+  fn test_open_roundtrip() {
+    for extractable in [true, false] {
+      let file = seal(&secret_key(extractable), b"passphrase").unwrap();
+      let key = open(&file, b"passphrase").unwrap();
+      assert!(matches!(key.r#type, KeyType::Secret));
+      assert_eq!(key.extractable, extractable);
+      assert_eq!(key.metadata, r#"{"usages":["sign"]}"#);
+      assert_eq!(&**key.data, b"secret");
+      assert!(open(&file, b"wrong").is_err());
+    }
+  }
+
+  #[test]
+  fn test_open_rejects_oversized_costs() {
+    let file = seal(&secret_key(true), b"passphrase").unwrap();
+    for (index, value) in [
+      (0, MAX_M_COST + 1),
+      (0, u32::MAX),
+      (1, MAX_T_COST + 1),
+      (2, u32::MAX),
+    ] {
+      let mut file = file.clone();
+      set_u32(&mut file, index, value);
+      let err = open(&file, b"passphrase").err().unwrap();
+      assert_eq!(
+        err.to_string(),
+        "Key file parameters exceed the supported limits"
+      );
+    }
+  }
+}
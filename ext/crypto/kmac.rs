@@ -0,0 +1,161 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! cSHAKE and KMAC as specified in NIST SP 800-185.
+
+use deno_core::error::type_error;
+use deno_core::error::AnyError;
+use deno_core::op;
+use deno_core::task::spawn_blocking;
+use deno_core::JsBuffer;
+use deno_core::ToJsBuffer;
+use serde::Deserialize;
+use sha3::digest::core_api::CoreWrapper;
+use sha3::digest::ExtendableOutput;
+use sha3::digest::Update;
+use sha3::CShake128Core;
+use sha3::CShake256Core;
+use zeroize::Zeroizing;
+
+use crate::key::Algorithm;
+use crate::shared::operation_error;
+
+#[derive(Deserialize, Clone, Copy)]
+pub enum CShakeAlgorithm {
+  #[serde(rename = "cSHAKE128")]
+  CShake128,
+  #[serde(rename = "cSHAKE256")]
+  CShake256,
+}
+
+impl CShakeAlgorithm {
+  /// The sponge rate in bytes.
+  fn rate(self) -> usize {
+    match self {
+      CShakeAlgorithm::CShake128 => 168,
+      CShakeAlgorithm::CShake256 => 136,
+    }
+  }
+}
+
+impl TryFrom<Algorithm> for CShakeAlgorithm {
+  type Error = AnyError;
+
+  /// The cSHAKE variant underlying a KMAC algorithm.
+  fn try_from(algorithm: Algorithm) -> Result<Self, AnyError> {
+    match algorithm {
+      Algorithm::Kmac128 => Ok(CShakeAlgorithm::CShake128),
+      Algorithm::Kmac256 => Ok(CShakeAlgorithm::CShake256),
+      _ => Err(type_error("Unsupported algorithm")),
+    }
+  }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CShakeArg {
+  algorithm: CShakeAlgorithm,
+  /// Output length in bits.
+  length: usize,
+  #[serde(default, with = "serde_bytes")]
+  function_name: Option<Vec<u8>>,
+  #[serde(default, with = "serde_bytes")]
+  customization: Option<Vec<u8>>,
+}
+
+fn output_len(length: usize) -> Result<usize, AnyError> {
+  if length == 0 || length % 8 != 0 {
+    return Err(operation_error("Invalid length"));
+  }
+  Ok(length / 8)
+}
+
+fn cshake(
+  algorithm: CShakeAlgorithm,
+  function_name: &[u8],
+  customization: &[u8],
+  input: &[&[u8]],
+  output: &mut [u8],
+) {
+  match algorithm {
+    CShakeAlgorithm::CShake128 => {
+      let mut hasher = CoreWrapper::from_core(
+        CShake128Core::new_with_function_name(function_name, customization),
+      );
+      input.iter().for_each(|data| hasher.update(data));
+      hasher.finalize_xof_into(output);
+    }
+    CShakeAlgorithm::CShake256 => {
+      let mut hasher = CoreWrapper::from_core(
+        CShake256Core::new_with_function_name(function_name, customization),
+      );
+      input.iter().for_each(|data| hasher.update(data));
+      hasher.finalize_xof_into(output);
+    }
+  }
+}
+
+#[op]
+pub async fn op_crypto_cshake(
+  args: CShakeArg,
+  data: JsBuffer,
+) -> Result<ToJsBuffer, AnyError> {
+  let mut output = vec![0; output_len(args.length)?];
+  let output = spawn_blocking(move || {
+    cshake(
+      args.algorithm,
+      args.function_name.as_deref().unwrap_or_default(),
+      args.customization.as_deref().unwrap_or_default(),
+      &[&data],
+      &mut output,
+    );
+    output
+  })
+  .await?;
+  Ok(output.into())
+}
+
+fn left_encode(value: usize) -> Vec<u8> {
+  let bytes = (value as u64).to_be_bytes();
+  let skip = bytes.iter().take_while(|b| **b == 0).count().min(7);
+  let mut encoded = vec![(8 - skip) as u8];
+  encoded.extend_from_slice(&bytes[skip..]);
+  encoded
+}
+
+fn right_encode(value: usize) -> Vec<u8> {
+  let bytes = (value as u64).to_be_bytes();
+  let skip = bytes.iter().take_while(|b| **b == 0).count().min(7);
+  let mut encoded = bytes[skip..].to_vec();
+  encoded.push((8 - skip) as u8);
+  encoded
+}
+
+/// KMAC128 is built on cSHAKE128 and KMAC256 on cSHAKE256. `length` is the
+/// output length in bits.
+pub fn kmac(
+  algorithm: CShakeAlgorithm,
+  key: &[u8],
+  data: &[u8],
+  customization: &[u8],
+  length: usize,
+) -> Result<Vec<u8>, AnyError> {
+  let mut output = vec![0; output_len(length)?];
+
+  // bytepad(encode_string(K), rate)
+  let rate = algorithm.rate();
+  let header = [left_encode(rate), left_encode(key.len() * 8)].concat();
+  let padded_len = (header.len() + key.len() + rate - 1) / rate * rate;
+  let mut padded_key = Zeroizing::new(Vec::with_capacity(padded_len));
+  padded_key.extend_from_slice(&header);
+  padded_key.extend_from_slice(key);
+  padded_key.resize(padded_len, 0);
+
+  cshake(
+    algorithm,
+    b"KMAC",
+    customization,
+    &[padded_key.as_slice(), data, &right_encode(length)],
+    &mut output,
+  );
+  Ok(output)
+}
@@ -80,6 +80,15 @@ declare interface AesGcmParams extends Algorithm {
   tagLength?: number;
 }
 
+/** Parameters for ChaCha20-Poly1305, XChaCha20-Poly1305 and AES-256-GCM-SIV.
+ *
+ * @category Web Crypto API
+ */
+declare interface ChaCha20Poly1305Params extends Algorithm {
+  iv: BufferSource;
+  additionalData?: BufferSource;
+}
+
 /** @category Web Crypto API */
 declare interface AesCtrParams extends Algorithm {
   counter: BufferSource;
@@ -92,6 +101,26 @@ declare interface HmacKeyGenParams extends Algorithm {
   length?: number;
 }
 
+/** @category Web Crypto API */
+declare interface KmacKeyGenParams extends Algorithm {
+  length?: number;
+}
+
+/** @category Web Crypto API */
+declare interface KmacParams extends Algorithm {
+  /** Output length in bits. */
+  length: number;
+  customization?: BufferSource;
+}
+
+/** @category Web Crypto API */
+declare interface CShakeParams extends Algorithm {
+  /** Output length in bits. */
+  length: number;
+  functionName?: BufferSource;
+  customization?: BufferSource;
+}
+
 /** @category Web Crypto API */
 declare interface EcKeyGenParams extends Algorithm {
   namedCurve: NamedCurve;
@@ -175,6 +204,22 @@ declare interface Pbkdf2Params extends Algorithm {
   salt: BufferSource;
 }
 
+/** @category Web Crypto API */
+declare interface Argon2Params extends Algorithm {
+  salt: BufferSource;
+  memory: number;
+  parallelism: number;
+  iterations: number;
+}
+
+/** @category Web Crypto API */
+declare interface ScryptParams extends Algorithm {
+  salt: BufferSource;
+  cost: number;
+  blockSize: number;
+  parallelism: number;
+}
+
 /** @category Web Crypto API */
 declare interface AesDerivedKeyParams extends Algorithm {
   length: number;
@@ -229,6 +274,25 @@ declare var CryptoKeyPair: {
   new (): never;
 };
 
+/** Deno specific options for `SubtleCrypto.generateKey()`.
+ *
+ * @category Web Crypto API
+ */
+declare interface GenerateKeyOptions {
+  /** Aborts RSA key generation, which can take several seconds for large
+   * moduli. */
+  signal?: AbortSignal;
+}
+
+/** @category Web Crypto API */
+declare interface DeriveBitsOptions {
+  /** Aborts PBKDF2 derivation. */
+  signal?: AbortSignal;
+  /** Called periodically during PBKDF2 derivation with the number of
+   * iterations done so far, out of `total`. */
+  onProgress?: (completed: number, total: number) => void;
+}
+
 /** This Web Crypto API interface provides a number of low-level cryptographic
  * functions. It is accessed via the Crypto.subtle properties available in a
  * window context (via Window.crypto).
@@ -240,9 +304,10 @@ declare interface SubtleCrypto {
     algorithm: RsaHashedKeyGenParams | EcKeyGenParams,
     extractable: boolean,
     keyUsages: KeyUsage[],
+    options?: GenerateKeyOptions,
   ): Promise<CryptoKeyPair>;
   generateKey(
-    algorithm: AesKeyGenParams | HmacKeyGenParams,
+    algorithm: AesKeyGenParams | HmacKeyGenParams | KmacKeyGenParams,
     extractable: boolean,
     keyUsages: KeyUsage[],
   ): Promise<CryptoKey>;
@@ -250,6 +315,7 @@ declare interface SubtleCrypto {
     algorithm: AlgorithmIdentifier,
     extractable: boolean,
     keyUsages: KeyUsage[],
+    options?: GenerateKeyOptions,
   ): Promise<CryptoKeyPair | CryptoKey>;
   importKey(
     format: "jwk",
@@ -279,18 +345,18 @@ declare interface SubtleCrypto {
     key: CryptoKey,
   ): Promise<ArrayBuffer>;
   sign(
-    algorithm: AlgorithmIdentifier | RsaPssParams | EcdsaParams,
+    algorithm: AlgorithmIdentifier | RsaPssParams | EcdsaParams | KmacParams,
     key: CryptoKey,
     data: BufferSource,
   ): Promise<ArrayBuffer>;
   verify(
-    algorithm: AlgorithmIdentifier | RsaPssParams | EcdsaParams,
+    algorithm: AlgorithmIdentifier | RsaPssParams | EcdsaParams | KmacParams,
     key: CryptoKey,
     signature: BufferSource,
     data: BufferSource,
   ): Promise<boolean>;
   digest(
-    algorithm: AlgorithmIdentifier,
+    algorithm: AlgorithmIdentifier | CShakeParams,
     data: BufferSource,
   ): Promise<ArrayBuffer>;
   encrypt(
@@ -299,7 +365,8 @@ declare interface SubtleCrypto {
       | RsaOaepParams
       | AesCbcParams
       | AesGcmParams
-      | AesCtrParams,
+      | AesCtrParams
+      | ChaCha20Poly1305Params,
     key: CryptoKey,
     data: BufferSource,
   ): Promise<ArrayBuffer>;
@@ -309,7 +376,8 @@ declare interface SubtleCrypto {
       | RsaOaepParams
       | AesCbcParams
       | AesGcmParams
-      | AesCtrParams,
+      | AesCtrParams
+      | ChaCha20Poly1305Params,
     key: CryptoKey,
     data: BufferSource,
   ): Promise<ArrayBuffer>;
@@ -318,15 +386,20 @@ declare interface SubtleCrypto {
       | AlgorithmIdentifier
       | HkdfParams
       | Pbkdf2Params
+      | Argon2Params
+      | ScryptParams
       | EcdhKeyDeriveParams,
     baseKey: CryptoKey,
     length: number,
+    options?: DeriveBitsOptions,
   ): Promise<ArrayBuffer>;
   deriveKey(
     algorithm:
       | AlgorithmIdentifier
       | HkdfParams
       | Pbkdf2Params
+      | Argon2Params
+      | ScryptParams
       | EcdhKeyDeriveParams,
     baseKey: CryptoKey,
     derivedKeyType:
@@ -14,7 +14,11 @@ use deno_core::ToJsBuffer;
 use deno_core::task::spawn_blocking;
 use deno_core::JsBuffer;
 use deno_core::OpState;
+use deno_core::ResourceId;
 use serde::Deserialize;
+use serde::Deserializer;
+use serde::Serialize;
+use serde::Serializer;
 use shared::operation_error;
 
 use p256::elliptic_curve::sec1::FromEncodedPoint;
@@ -24,18 +28,15 @@ use rand::rngs::StdRng;
 use rand::thread_rng;
 use rand::Rng;
 use rand::SeedableRng;
-use ring::digest;
 use ring::hkdf;
 use ring::hmac::Algorithm as HmacAlgorithm;
 use ring::hmac::Key as HmacKey;
-use ring::pbkdf2;
 use ring::rand as RingRand;
 use ring::signature::EcdsaKeyPair;
 use ring::signature::EcdsaSigningAlgorithm;
 use ring::signature::EcdsaVerificationAlgorithm;
 use ring::signature::KeyPair;
 use rsa::pkcs1::DecodeRsaPrivateKey;
-use rsa::pkcs1::DecodeRsaPublicKey;
 use rsa::RsaPrivateKey;
 use rsa::RsaPublicKey;
 use sha1::Sha1;
@@ -45,53 +46,144 @@ use sha2::Sha512;
 use signature::RandomizedSigner;
 use signature::Signer;
 use signature::Verifier;
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::num::NonZeroU32;
+use std::ops::Deref;
 use std::path::PathBuf;
+use std::rc::Rc;
+use std::sync::Arc;
+use zeroize::Zeroizing;
 
 pub use rand; // Re-export rand
 
+mod backend;
+mod batch;
+mod bls12381;
+mod capabilities;
+mod convert_key;
+mod csr;
 mod decrypt;
+mod digest;
+mod ec_point;
+mod ecdsa;
 mod ed25519;
+mod ed448;
 mod encrypt;
 mod export_key;
+mod fips;
 mod generate_key;
+mod hmac;
+mod hpke;
 mod import_key;
+mod jws;
 mod key;
+mod keystore;
+mod kmac;
+mod openssh;
+mod password;
+mod pem;
+mod pkcs12;
+mod progress;
 mod shared;
+mod transfer;
 mod x25519;
+mod x448;
+mod x509;
 
+pub use crate::backend::CryptoBackend;
+use crate::decrypt::decrypt_aes_gcm;
+use crate::decrypt::decrypt_rsa_oaep;
 pub use crate::decrypt::op_crypto_decrypt;
+use crate::encrypt::encrypt_aes_gcm;
+use crate::encrypt::encrypt_rsa_oaep;
 pub use crate::encrypt::op_crypto_encrypt;
+pub use crate::encrypt::op_crypto_encrypt_finalize;
+pub use crate::encrypt::op_crypto_encrypt_init;
+pub use crate::encrypt::op_crypto_encrypt_update;
 pub use crate::export_key::op_crypto_export_key;
+pub use crate::fips::check_backend as check_fips_backend;
 pub use crate::generate_key::op_crypto_generate_key;
 pub use crate::import_key::op_crypto_import_key;
 use crate::key::Algorithm;
 use crate::key::CryptoHash;
 use crate::key::CryptoNamedCurve;
 use crate::key::HkdfOutput;
+pub use crate::keystore::CryptoPermissions;
+use crate::shared::rsa_public_key_from_pkcs1_der;
+use crate::shared::ShaHash;
 use crate::shared::V8RawKeyData;
 
 deno_core::extension!(deno_crypto,
   deps = [ deno_webidl, deno_web ],
+  parameters = [P: CryptoPermissions],
   ops = [
     op_crypto_get_random_values,
+    op_crypto_fill_random,
+    fips::op_crypto_fips_enabled,
+    capabilities::op_crypto_supported_algorithms,
+    op_crypto_key_store,
+    op_crypto_key_release,
+    transfer::op_crypto_key_transfer_out,
+    transfer::op_crypto_key_transfer_in,
+    keystore::op_crypto_keystore_save<P>,
+    keystore::op_crypto_keystore_load<P>,
     op_crypto_generate_key,
     op_crypto_sign_key,
     op_crypto_verify_key,
+    jws::op_crypto_jws_sign,
+    jws::op_crypto_jws_verify,
+    batch::op_crypto_sign_batch,
+    batch::op_crypto_verify_batch,
     op_crypto_derive_bits,
+    op_crypto_hkdf_extract,
+    op_crypto_hkdf_expand,
+    progress::op_crypto_progress_create,
+    progress::op_crypto_progress,
     op_crypto_import_key,
     op_crypto_export_key,
     op_crypto_encrypt,
+    op_crypto_encrypt_init,
+    op_crypto_encrypt_update,
+    op_crypto_encrypt_finalize,
     op_crypto_decrypt,
     op_crypto_subtle_digest,
+    kmac::op_crypto_cshake,
+    password::op_crypto_bcrypt_hash,
+    password::op_crypto_bcrypt_verify,
+    digest::op_crypto_digest_create,
+    digest::op_crypto_digest_update,
+    digest::op_crypto_digest_finalize,
+    digest::op_crypto_digest_resource,
+    hmac::op_crypto_hmac_create,
+    hmac::op_crypto_hmac_update,
+    hmac::op_crypto_hmac_sign,
+    hmac::op_crypto_hmac_verify,
+    hpke::op_crypto_hpke_setup_base_s,
+    hpke::op_crypto_hpke_setup_base_r,
+    hpke::op_crypto_hpke_seal,
+    hpke::op_crypto_hpke_open,
     op_crypto_random_uuid,
+    op_crypto_random_uuids,
     op_crypto_wrap_key,
     op_crypto_unwrap_key,
     op_crypto_base64url_decode,
     op_crypto_base64url_encode,
+    pem::op_crypto_pem_decode,
+    pem::op_crypto_pem_encode,
+    openssh::op_crypto_import_openssh_private_key,
+    openssh::op_crypto_export_openssh_public_key,
+    pkcs12::op_crypto_import_pkcs12,
+    x509::op_crypto_x509_parse,
+    csr::op_crypto_generate_csr,
+    ec_point::op_crypto_validate_ec_public_key,
+    ecdsa::op_crypto_ecdsa_signature_to_der,
+    ecdsa::op_crypto_ecdsa_signature_from_der,
+    convert_key::op_crypto_convert_key,
     x25519::op_crypto_generate_x25519_keypair,
     x25519::op_crypto_derive_bits_x25519,
+    x25519::op_crypto_derive_key_x25519_hkdf,
     x25519::op_crypto_import_spki_x25519,
     x25519::op_crypto_import_pkcs8_x25519,
     ed25519::op_crypto_generate_ed25519_keypair,
@@ -99,17 +191,35 @@ deno_core::extension!(deno_crypto,
     ed25519::op_crypto_import_pkcs8_ed25519,
     ed25519::op_crypto_sign_ed25519,
     ed25519::op_crypto_verify_ed25519,
-    ed25519::op_crypto_export_spki_ed25519,
-    ed25519::op_crypto_export_pkcs8_ed25519,
-    ed25519::op_crypto_jwk_x_ed25519,
-    x25519::op_crypto_export_spki_x25519,
-    x25519::op_crypto_export_pkcs8_x25519,
+    bls12381::op_crypto_generate_bls12381_keypair,
+    bls12381::op_crypto_sign_bls12381,
+    bls12381::op_crypto_verify_bls12381,
+    bls12381::op_crypto_aggregate_bls12381_signatures,
+    bls12381::op_crypto_aggregate_bls12381_public_keys,
+    bls12381::op_crypto_verify_bls12381_aggregate,
+    ed448::op_crypto_generate_ed448_keypair,
+    ed448::op_crypto_import_spki_ed448,
+    ed448::op_crypto_import_pkcs8_ed448,
+    ed448::op_crypto_sign_ed448,
+    ed448::op_crypto_verify_ed448,
+    x448::op_crypto_generate_x448_keypair,
+    x448::op_crypto_derive_bits_x448,
+    x448::op_crypto_import_spki_x448,
+    x448::op_crypto_import_pkcs8_x448,
   ],
   esm = [ "00_crypto.js" ],
   options = {
     maybe_seed: Option<u64>,
+    fips: bool,
+    backend: CryptoBackend,
+    max_keygen_tasks: Option<usize>,
   },
   state = |state, options| {
+    state.put(CryptoKeyStore::default());
+    state.put(transfer::IssuedTransfers::default());
+    state.put(fips::FipsMode(options.fips));
+    state.put(options.backend);
+    state.put(generate_key::KeygenLimit::new(options.max_keygen_tasks));
     if let Some(seed) = options.maybe_seed {
       state.put(StdRng::seed_from_u64(seed));
     }
@@ -142,6 +252,29 @@ pub fn op_crypto_get_random_values(
     );
   }
 
+  fill_random(state, out);
+
+  Ok(())
+}
+
+/// Like `op_crypto_get_random_values`, but without the 65536 byte limit
+/// imposed by the Web Crypto API.
+#[op(fast)]
+pub fn op_crypto_fill_random(state: &mut OpState, out: &mut [u8]) {
+  fill_random(state, out);
+}
+
+/// Seed for the random number generator of a worker spawned from this realm,
+/// when running with `--seed`. It is drawn from the realm's own generator, so
+/// every worker gets an independent stream that is still reproducible as long
+/// as workers are created in the same order.
+pub fn worker_seed(state: &mut OpState) -> Option<u64> {
+  state
+    .try_borrow_mut::<StdRng>()
+    .map(|seeded_rng| seeded_rng.gen())
+}
+
+fn fill_random(state: &mut OpState, out: &mut [u8]) {
   let maybe_seeded_rng = state.try_borrow_mut::<StdRng>();
   if let Some(seeded_rng) = maybe_seeded_rng {
     seeded_rng.fill(out);
@@ -149,8 +282,6 @@ pub fn op_crypto_get_random_values(
     let mut rng = thread_rng();
     rng.fill(out);
   }
-
-  Ok(())
 }
 
 #[derive(Deserialize)]
@@ -161,7 +292,7 @@ pub enum KeyFormat {
   Spki,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Clone, Copy)]
 #[serde(rename_all = "lowercase")]
 pub enum KeyType {
   Secret,
@@ -169,11 +300,145 @@ pub enum KeyType {
   Public,
 }
 
+/// Key material passed inline from JS, or owned on the Rust side (shared with
+/// a stored key handle, or produced by an import). Rust owned bytes are wiped
+/// once the last reference is dropped.
+pub enum KeyBytes {
+  Inline(JsBuffer),
+  Stored(Arc<Zeroizing<Vec<u8>>>),
+}
+
+impl Default for KeyBytes {
+  fn default() -> Self {
+    KeyBytes::Stored(Arc::default())
+  }
+}
+
+impl<'de> Deserialize<'de> for KeyBytes {
+  fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+  where
+    D: Deserializer<'de>,
+  {
+    JsBuffer::deserialize(deserializer).map(KeyBytes::Inline)
+  }
+}
+
+impl Deref for KeyBytes {
+  type Target = [u8];
+
+  fn deref(&self) -> &[u8] {
+    match self {
+      KeyBytes::Inline(data) => data,
+      KeyBytes::Stored(data) => data,
+    }
+  }
+}
+
+impl AsRef<[u8]> for KeyBytes {
+  fn as_ref(&self) -> &[u8] {
+    self
+  }
+}
+
+impl From<Vec<u8>> for KeyBytes {
+  fn from(data: Vec<u8>) -> Self {
+    KeyBytes::Stored(Arc::new(Zeroizing::new(data)))
+  }
+}
+
+impl Serialize for KeyBytes {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: Serializer,
+  {
+    ToJsBuffer::from(self.to_vec()).serialize(serializer)
+  }
+}
+
 #[derive(Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub struct KeyData {
   r#type: KeyType,
-  data: JsBuffer,
+  #[serde(default)]
+  data: KeyBytes,
+  // Returned by `op_crypto_key_store`, used instead of `data`.
+  handle: Option<u32>,
+  // Only set from the key store. Inline key bytes are never exported, so
+  // passing a key's bytes does not bypass its extractable flag.
+  #[serde(skip)]
+  extractable: bool,
+}
+
+impl KeyData {
+  fn resolve(&mut self, state: &OpState) -> Result<(), AnyError> {
+    if let Some(handle) = self.handle {
+      let key = state
+        .try_borrow::<CryptoKeyStore>()
+        .and_then(|store| store.keys.get(&handle))
+        .ok_or_else(|| type_error("Invalid key handle"))?;
+      self.r#type = key.r#type;
+      self.data = KeyBytes::Stored(key.data.clone());
+      self.extractable = key.extractable;
+    }
+    Ok(())
+  }
+}
+
+impl From<KeyData> for V8RawKeyData {
+  fn from(key: KeyData) -> Self {
+    match key.r#type {
+      KeyType::Secret => V8RawKeyData::Secret(key.data),
+      KeyType::Private => V8RawKeyData::Private(key.data),
+      KeyType::Public => V8RawKeyData::Public(key.data),
+    }
+  }
+}
+
+struct StoredKey {
+  r#type: KeyType,
+  data: Arc<Zeroizing<Vec<u8>>>,
+  extractable: bool,
+}
+
+/// Key material registered once with `op_crypto_key_store`, so that sign,
+/// verify, derive and export calls only need to pass a handle. This is kept
+/// out of the resource table as key lifetimes are tied to JS garbage
+/// collection.
+#[derive(Default)]
+struct CryptoKeyStore {
+  next_handle: u32,
+  keys: HashMap<u32, StoredKey>,
+}
+
+impl CryptoKeyStore {
+  fn insert(&mut self, key: StoredKey) -> u32 {
+    let handle = self.next_handle;
+    self.next_handle = handle.wrapping_add(1);
+    self.keys.insert(handle, key);
+    handle
+  }
+}
+
+#[op]
+pub fn op_crypto_key_store(
+  state: &mut OpState,
+  key: KeyData,
+  extractable: bool,
+) -> Result<u32, AnyError> {
+  if key.handle.is_some() {
+    return Err(type_error("Key is already stored"));
+  }
+  let handle = state.borrow_mut::<CryptoKeyStore>().insert(StoredKey {
+    r#type: key.r#type,
+    data: Arc::new(Zeroizing::new(key.data.to_vec())),
+    extractable,
+  });
+  Ok(handle)
+}
+
+#[op(fast)]
+pub fn op_crypto_key_release(state: &mut OpState, handle: u32) {
+  state.borrow_mut::<CryptoKeyStore>().keys.remove(&handle);
 }
 
 #[derive(Deserialize)]
@@ -184,24 +449,56 @@ pub struct SignArg {
   salt_length: Option<u32>,
   hash: Option<CryptoHash>,
   named_curve: Option<CryptoNamedCurve>,
+  /// KMAC output length in bits.
+  length: Option<usize>,
+  #[serde(default, with = "serde_bytes")]
+  customization: Option<Vec<u8>>,
 }
 
 #[op]
 pub async fn op_crypto_sign_key(
-  args: SignArg,
+  state: Rc<RefCell<OpState>>,
+  mut args: SignArg,
   zero_copy: JsBuffer,
 ) -> Result<ToJsBuffer, AnyError> {
-  let data = &*zero_copy;
+  let backend = {
+    let state = state.borrow();
+    fips::check_algorithm(&state, args.algorithm, args.named_curve)?;
+    fips::check_signing_hash(&state, args.algorithm, args.hash)?;
+    args.key.resolve(&state)?;
+    backend::get(&state)
+  };
+  // RSA and ECDSA signing is expensive enough to stall the event loop.
+  let signature = spawn_blocking(move || sign_key(args, backend, &zero_copy))
+    .await
+    .unwrap()?;
+  Ok(signature.into())
+}
+
+fn sign_key(
+  args: SignArg,
+  backend: CryptoBackend,
+  data: &[u8],
+) -> Result<Vec<u8>, AnyError> {
   let algorithm = args.algorithm;
 
   let signature = match algorithm {
     Algorithm::RsassaPkcs1v15 => {
       use rsa::pkcs1v15::SigningKey;
-      let private_key = RsaPrivateKey::from_pkcs1_der(&args.key.data)?;
-      match args
+      let hash = args
         .hash
-        .ok_or_else(|| type_error("Missing argument hash".to_string()))?
-      {
+        .ok_or_else(|| type_error("Missing argument hash".to_string()))?;
+      if let Some(signature) = backend::rsa_sign(
+        backend,
+        &args.key.data,
+        backend::RsaSignPadding::Pkcs1v15,
+        hash,
+        data,
+      ) {
+        return signature;
+      }
+      let private_key = RsaPrivateKey::from_pkcs1_der(&args.key.data)?;
+      match hash {
         CryptoHash::Sha1 => {
           let signing_key = SigningKey::<Sha1>::new_with_prefix(private_key);
           signing_key.sign(data)
@@ -229,12 +526,21 @@ pub async fn op_crypto_sign_key(
         .salt_length
         .ok_or_else(|| type_error("Missing argument saltLength".to_string()))?
         as usize;
+      let hash = args
+        .hash
+        .ok_or_else(|| type_error("Missing argument hash".to_string()))?;
+      if let Some(signature) = backend::rsa_sign(
+        backend,
+        &args.key.data,
+        backend::RsaSignPadding::Pss { salt_len },
+        hash,
+        data,
+      ) {
+        return signature;
+      }
 
       let rng = OsRng;
-      match args
-        .hash
-        .ok_or_else(|| type_error("Missing argument hash".to_string()))?
-      {
+      match hash {
         CryptoHash::Sha1 => {
           let signing_key =
             SigningKey::<Sha1>::new_with_salt_len(private_key, salt_len);
@@ -259,24 +565,51 @@ pub async fn op_crypto_sign_key(
       .to_vec()
     }
     Algorithm::Ecdsa => {
-      let curve: &EcdsaSigningAlgorithm =
-        args.named_curve.ok_or_else(not_supported)?.try_into()?;
-
-      let key_pair = EcdsaKeyPair::from_pkcs8(curve, &args.key.data)?;
-      // We only support P256-SHA256 & P384-SHA384. These are recommended signature pairs.
+      let named_curve = args.named_curve.ok_or_else(not_supported)?;
+      // We only support P256-SHA256, P384-SHA384, P521-SHA512 &
+      // secp256k1-SHA256. These are recommended signature pairs.
       // https://briansmith.org/rustdoc/ring/signature/index.html#statics
       if let Some(hash) = args.hash {
         match hash {
-          CryptoHash::Sha256 | CryptoHash::Sha384 => (),
+          CryptoHash::Sha256 | CryptoHash::Sha384 | CryptoHash::Sha512 => (),
           _ => return Err(type_error("Unsupported algorithm")),
         }
       };
 
-      let rng = RingRand::SystemRandom::new();
-      let signature = key_pair.sign(&rng, data)?;
+      match named_curve {
+        CryptoNamedCurve::P521 => {
+          let secret_key = p521::SecretKey::from_pkcs8_der(&args.key.data)
+            .map_err(|_| type_error("expected valid private EC key"))?;
+          let secret_bytes = Zeroizing::new(secret_key.to_bytes());
+          let signing_key = p521::ecdsa::SigningKey::from_bytes(&secret_bytes)
+            .map_err(|_| type_error("Unexpected error decoding private key"))?;
+          let signature: p521::ecdsa::Signature =
+            p521::ecdsa::signature::Signer::sign(&signing_key, data);
+
+          // Fixed-size (r || s) signature, as for the other NIST curves.
+          signature.to_bytes().to_vec()
+        }
+        CryptoNamedCurve::Secp256k1 => {
+          let secret_key = k256::SecretKey::from_pkcs8_der(&args.key.data)
+            .map_err(|_| type_error("Unexpected error decoding private key"))?;
+          let signing_key = k256::ecdsa::SigningKey::from(secret_key);
+          let signature: k256::ecdsa::Signature =
+            k256::ecdsa::signature::Signer::sign(&signing_key, data);
 
-      // Signature data as buffer.
-      signature.as_ref().to_vec()
+          // Fixed-size (r || s) signature, as for the NIST curves.
+          signature.to_bytes().to_vec()
+        }
+        _ => {
+          let curve: &EcdsaSigningAlgorithm = named_curve.try_into()?;
+          let key_pair = EcdsaKeyPair::from_pkcs8(curve, &args.key.data)?;
+
+          let rng = RingRand::SystemRandom::new();
+          let signature = key_pair.sign(&rng, data)?;
+
+          // Signature data as buffer.
+          signature.as_ref().to_vec()
+        }
+      }
     }
     Algorithm::Hmac => {
       let hash: HmacAlgorithm = args.hash.ok_or_else(not_supported)?.into();
@@ -286,10 +619,17 @@ pub async fn op_crypto_sign_key(
       let signature = ring::hmac::sign(&key, data);
       signature.as_ref().to_vec()
     }
+    Algorithm::Kmac128 | Algorithm::Kmac256 => kmac::kmac(
+      algorithm.try_into()?,
+      &args.key.data,
+      data,
+      args.customization.as_deref().unwrap_or_default(),
+      args.length.ok_or_else(not_supported)?,
+    )?,
     _ => return Err(type_error("Unsupported algorithm".to_string())),
   };
 
-  Ok(signature.into())
+  Ok(signature)
 }
 
 #[derive(Deserialize)]
@@ -300,14 +640,29 @@ pub struct VerifyArg {
   hash: Option<CryptoHash>,
   signature: JsBuffer,
   named_curve: Option<CryptoNamedCurve>,
+  /// KMAC output length in bits.
+  length: Option<usize>,
+  #[serde(default, with = "serde_bytes")]
+  customization: Option<Vec<u8>>,
 }
 
 #[op]
 pub async fn op_crypto_verify_key(
-  args: VerifyArg,
+  state: Rc<RefCell<OpState>>,
+  mut args: VerifyArg,
   zero_copy: JsBuffer,
 ) -> Result<bool, AnyError> {
-  let data = &*zero_copy;
+  {
+    let state = state.borrow();
+    fips::check_algorithm(&state, args.algorithm, args.named_curve)?;
+    args.key.resolve(&state)?;
+  }
+  spawn_blocking(move || verify_key(args, &zero_copy))
+    .await
+    .unwrap()
+}
+
+fn verify_key(args: VerifyArg, data: &[u8]) -> Result<bool, AnyError> {
   let algorithm = args.algorithm;
 
   let verification = match algorithm {
@@ -374,6 +729,60 @@ pub async fn op_crypto_verify_key(
       let key = HmacKey::new(hash, &args.key.data);
       ring::hmac::verify(&key, data, &args.signature).is_ok()
     }
+    Algorithm::Kmac128 | Algorithm::Kmac256 => {
+      let expected = kmac::kmac(
+        algorithm.try_into()?,
+        &args.key.data,
+        data,
+        args.customization.as_deref().unwrap_or_default(),
+        args.length.ok_or_else(not_supported)?,
+      )?;
+      ring::constant_time::verify_slices_are_equal(&expected, &args.signature)
+        .is_ok()
+    }
+    Algorithm::Ecdsa
+      if matches!(args.named_curve, Some(CryptoNamedCurve::P521)) =>
+    {
+      let public_key = match args.key.r#type {
+        KeyType::Private => p521::SecretKey::from_pkcs8_der(&args.key.data)
+          .map_err(|_| type_error("expected valid private EC key"))?
+          .public_key(),
+        KeyType::Public => p521::PublicKey::from_sec1_bytes(&args.key.data)
+          .map_err(|_| type_error("Unexpected error decoding public key"))?,
+        _ => return Err(type_error("Invalid Key format".to_string())),
+      };
+      let verifying_key =
+        p521::ecdsa::VerifyingKey::from_sec1_bytes(&public_key.to_sec1_bytes())
+          .map_err(|_| type_error("Unexpected error decoding public key"))?;
+
+      match p521::ecdsa::Signature::from_slice(&args.signature) {
+        Ok(signature) => p521::ecdsa::signature::Verifier::verify(
+          &verifying_key,
+          data,
+          &signature,
+        )
+        .is_ok(),
+        Err(_) => false,
+      }
+    }
+    Algorithm::Ecdsa
+      if matches!(args.named_curve, Some(CryptoNamedCurve::Secp256k1)) =>
+    {
+      let verifying_key = match args.key.r#type {
+        KeyType::Private => {
+          let secret_key = k256::SecretKey::from_pkcs8_der(&args.key.data)
+            .map_err(|_| type_error("Unexpected error decoding private key"))?;
+          *k256::ecdsa::SigningKey::from(secret_key).verifying_key()
+        }
+        KeyType::Public => {
+          k256::ecdsa::VerifyingKey::from_sec1_bytes(&args.key.data)
+            .map_err(|_| type_error("Unexpected error decoding public key"))?
+        }
+        _ => return Err(type_error("Invalid Key format".to_string())),
+      };
+
+      verify_secp256k1(&verifying_key, data, &args.signature)
+    }
     Algorithm::Ecdsa => {
       let signing_alg: &EcdsaSigningAlgorithm =
         args.named_curve.ok_or_else(not_supported)?.try_into()?;
@@ -403,6 +812,22 @@ pub async fn op_crypto_verify_key(
   Ok(verification)
 }
 
+/// Verifies a fixed-size (r || s) secp256k1 signature. k256 only accepts
+/// low-S signatures, but signatures produced elsewhere may carry a high S
+/// value, which is equally valid, so S is normalized before verifying.
+fn verify_secp256k1(
+  verifying_key: &k256::ecdsa::VerifyingKey,
+  data: &[u8],
+  signature: &[u8],
+) -> bool {
+  let Ok(signature) = k256::ecdsa::Signature::try_from(signature) else {
+    return false;
+  };
+  let signature = signature.normalize_s().unwrap_or(signature);
+  k256::ecdsa::signature::Verifier::verify(verifying_key, data, &signature)
+    .is_ok()
+}
+
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DeriveKeyArg {
@@ -414,37 +839,60 @@ pub struct DeriveKeyArg {
   // ECDH
   public_key: Option<KeyData>,
   named_curve: Option<CryptoNamedCurve>,
-  // HKDF
+  // HKDF, ConcatKDF and X9.63
   info: Option<JsBuffer>,
+  // Argon2id
+  memory: Option<u32>,
+  // Argon2id and scrypt
+  parallelism: Option<u32>,
+  // scrypt
+  cost: Option<u64>,
+  block_size: Option<u32>,
 }
 
 #[op]
 pub async fn op_crypto_derive_bits(
-  args: DeriveKeyArg,
+  state: Rc<RefCell<OpState>>,
+  mut args: DeriveKeyArg,
   zero_copy: Option<JsBuffer>,
+  progress_rid: Option<ResourceId>,
+  cancel_rid: Option<ResourceId>,
 ) -> Result<ToJsBuffer, AnyError> {
+  let progress = {
+    let state = state.borrow();
+    fips::check_algorithm(&state, args.algorithm, args.named_curve)?;
+    args.key.resolve(&state)?;
+    if let Some(public_key) = &mut args.public_key {
+      public_key.resolve(&state)?;
+    }
+    progress::progress_counter(&state, progress_rid)?.unwrap_or_default()
+  };
   let algorithm = args.algorithm;
   match algorithm {
     Algorithm::Pbkdf2 => {
-      let zero_copy = zero_copy.ok_or_else(not_supported)?;
-      let salt = &*zero_copy;
+      let salt = zero_copy.ok_or_else(not_supported)?;
       // The caller must validate these cases.
       assert!(args.length > 0);
       assert!(args.length % 8 == 0);
 
-      let algorithm = match args.hash.ok_or_else(not_supported)? {
-        CryptoHash::Sha1 => pbkdf2::PBKDF2_HMAC_SHA1,
-        CryptoHash::Sha256 => pbkdf2::PBKDF2_HMAC_SHA256,
-        CryptoHash::Sha384 => pbkdf2::PBKDF2_HMAC_SHA384,
-        CryptoHash::Sha512 => pbkdf2::PBKDF2_HMAC_SHA512,
-      };
-
+      let hash = args.hash.ok_or_else(not_supported)?;
       // This will never panic. We have already checked length earlier.
       let iterations =
         NonZeroU32::new(args.iterations.ok_or_else(not_supported)?).unwrap();
       let secret = args.key.data;
-      let mut out = vec![0; args.length / 8];
-      pbkdf2::derive(algorithm, iterations, salt, &secret, &mut out);
+      let length = args.length / 8;
+
+      // Hundreds of thousands of iterations take long enough to stall the
+      // event loop, and to be worth reporting progress for.
+      let out =
+        progress::spawn_cancelable(&state, cancel_rid, move |canceled| {
+          let mut out = vec![0; length];
+          progress::pbkdf2_derive(
+            hash, iterations, &salt, &secret, &mut out, &progress, canceled,
+          )?;
+          Ok(out)
+        })
+        .await?;
       Ok(out.into())
     }
     Algorithm::Ecdh => {
@@ -494,7 +942,9 @@ pub async fn op_crypto_derive_bits(
           );
 
           // raw serialized x-coordinate of the computed point
-          Ok(shared_secret.raw_secret_bytes().to_vec().into())
+          let bits =
+            ecdh_secret_bits(shared_secret.raw_secret_bytes(), args.length)?;
+          Ok(bits.into())
         }
         CryptoNamedCurve::P384 => {
           let secret_key = p384::SecretKey::from_pkcs8_der(&args.key.data)
@@ -533,19 +983,86 @@ pub async fn op_crypto_derive_bits(
           );
 
           // raw serialized x-coordinate of the computed point
-          Ok(shared_secret.raw_secret_bytes().to_vec().into())
+          let bits =
+            ecdh_secret_bits(shared_secret.raw_secret_bytes(), args.length)?;
+          Ok(bits.into())
+        }
+        CryptoNamedCurve::P521 => {
+          let secret_key = p521::SecretKey::from_pkcs8_der(&args.key.data)
+            .map_err(|_| type_error("Unexpected error decoding private key"))?;
+
+          let public_key = match public_key.r#type {
+            KeyType::Private => {
+              p521::SecretKey::from_pkcs8_der(&public_key.data)
+                .map_err(|_| {
+                  type_error("Unexpected error decoding private key")
+                })?
+                .public_key()
+            }
+            KeyType::Public => p521::PublicKey::from_sec1_bytes(
+              &public_key.data,
+            )
+            .map_err(|_| type_error("Unexpected error decoding private key"))?,
+            _ => unreachable!(),
+          };
+
+          let shared_secret = p521::elliptic_curve::ecdh::diffie_hellman(
+            secret_key.to_nonzero_scalar(),
+            public_key.as_affine(),
+          );
+
+          // raw serialized x-coordinate of the computed point
+          let bits =
+            ecdh_secret_bits(shared_secret.raw_secret_bytes(), args.length)?;
+          Ok(bits.into())
+        }
+        CryptoNamedCurve::Secp256k1 => {
+          let secret_key = k256::SecretKey::from_pkcs8_der(&args.key.data)
+            .map_err(|_| type_error("Unexpected error decoding private key"))?;
+
+          let public_key = match public_key.r#type {
+            KeyType::Private => {
+              k256::SecretKey::from_pkcs8_der(&public_key.data)
+                .map_err(|_| {
+                  type_error("Unexpected error decoding private key")
+                })?
+                .public_key()
+            }
+            KeyType::Public => {
+              let point = k256::EncodedPoint::from_bytes(public_key.data)
+                .map_err(|_| {
+                  type_error("Unexpected error decoding private key")
+                })?;
+
+              let pk = k256::PublicKey::from_encoded_point(&point);
+              // pk is a constant time Option.
+              if pk.is_some().into() {
+                pk.unwrap()
+              } else {
+                return Err(type_error(
+                  "Unexpected error decoding private key",
+                ));
+              }
+            }
+            _ => unreachable!(),
+          };
+
+          let shared_secret = k256::elliptic_curve::ecdh::diffie_hellman(
+            secret_key.to_nonzero_scalar(),
+            public_key.as_affine(),
+          );
+
+          // raw serialized x-coordinate of the computed point
+          let bits =
+            ecdh_secret_bits(shared_secret.raw_secret_bytes(), args.length)?;
+          Ok(bits.into())
         }
       }
     }
     Algorithm::Hkdf => {
       let zero_copy = zero_copy.ok_or_else(not_supported)?;
       let salt = &*zero_copy;
-      let algorithm = match args.hash.ok_or_else(not_supported)? {
-        CryptoHash::Sha1 => hkdf::HKDF_SHA1_FOR_LEGACY_USE_ONLY,
-        CryptoHash::Sha256 => hkdf::HKDF_SHA256,
-        CryptoHash::Sha384 => hkdf::HKDF_SHA384,
-        CryptoHash::Sha512 => hkdf::HKDF_SHA512,
-      };
+      let hash = args.hash.ok_or_else(not_supported)?;
 
       let info = args
         .info
@@ -555,38 +1072,251 @@ pub async fn op_crypto_derive_bits(
       // L
       let length = args.length / 8;
 
-      let salt = hkdf::Salt::new(algorithm, salt);
-      let prk = salt.extract(&secret);
-      let info = &[&*info];
-      let okm = prk.expand(info, HkdfOutput(length)).map_err(|_e| {
-        custom_error(
-          "DOMExceptionOperationError",
-          "The length provided for HKDF is too large",
-        )
-      })?;
-      let mut r = vec![0u8; length];
-      okm.fill(&mut r)?;
+      let r = hkdf_derive(hash, &secret, salt, &info, length)?;
       Ok(r.into())
     }
+    Algorithm::Argon2id => {
+      let salt = zero_copy.ok_or_else(not_supported)?;
+      // The caller must validate these cases.
+      assert!(args.length > 0);
+      assert!(args.length % 8 == 0);
+
+      let length = args.length / 8;
+      let params = argon2::Params::new(
+        args.memory.ok_or_else(not_supported)?,
+        args.iterations.ok_or_else(not_supported)?,
+        args.parallelism.ok_or_else(not_supported)?,
+        Some(length),
+      )
+      .map_err(|e| operation_error(e.to_string()))?;
+      let secret = args.key.data;
+
+      // Argon2 is memory-hard by design, so run it off the event loop.
+      let out = spawn_blocking(move || {
+        let argon2 = argon2::Argon2::new(
+          argon2::Algorithm::Argon2id,
+          argon2::Version::V0x13,
+          params,
+        );
+        let mut out = vec![0; length];
+        argon2
+          .hash_password_into(&secret, &salt, &mut out)
+          .map_err(|e| operation_error(e.to_string()))?;
+        Ok::<_, AnyError>(out)
+      })
+      .await
+      .unwrap()?;
+      Ok(out.into())
+    }
+    Algorithm::Scrypt => {
+      let salt = zero_copy.ok_or_else(not_supported)?;
+      // The caller must validate these cases.
+      assert!(args.length > 0);
+      assert!(args.length % 8 == 0);
+
+      // N must be a power of two greater than 1.
+      let cost = args.cost.ok_or_else(not_supported)?;
+      if cost < 2 || !cost.is_power_of_two() {
+        return Err(operation_error("cost must be a power of two"));
+      }
+
+      let length = args.length / 8;
+      let params = scrypt::Params::new(
+        cost.trailing_zeros() as u8,
+        args.block_size.ok_or_else(not_supported)?,
+        args.parallelism.ok_or_else(not_supported)?,
+        length,
+      )
+      .map_err(|_| operation_error("Invalid scrypt parameters"))?;
+      let secret = args.key.data;
+
+      // scrypt is memory-hard by design, so run it off the event loop.
+      let out = spawn_blocking(move || {
+        let mut out = vec![0; length];
+        scrypt::scrypt(&secret, &salt, &params, &mut out)
+          .map_err(|_| operation_error("Invalid scrypt output length"))?;
+        Ok::<_, AnyError>(out)
+      })
+      .await
+      .unwrap()?;
+      Ok(out.into())
+    }
+    Algorithm::ConcatKdf | Algorithm::X963Kdf => {
+      let hash = args.hash.ok_or_else(not_supported)?;
+      let info = args
+        .info
+        .ok_or_else(|| type_error("Missing argument info".to_string()))?;
+      if args.length == 0 || args.length % 8 != 0 {
+        return Err(operation_error("Invalid length"));
+      }
+
+      let out = one_step_kdf(
+        hash,
+        &args.key.data,
+        &info,
+        args.length / 8,
+        matches!(algorithm, Algorithm::ConcatKdf),
+      );
+      Ok(out.into())
+    }
     _ => Err(type_error("Unsupported algorithm".to_string())),
   }
 }
 
+/// The hash based one-step KDFs of NIST SP 800-56A (ConcatKDF) and ANSI X9.63,
+/// which differ only in the position of the 32-bit block counter:
+/// `H(counter || Z || info)` and `H(Z || counter || info)` respectively.
+fn one_step_kdf(
+  hash: CryptoHash,
+  secret: &[u8],
+  info: &[u8],
+  length: usize,
+  counter_first: bool,
+) -> Vec<u8> {
+  let algorithm: &'static ring::digest::Algorithm = hash.into();
+  let mut out = Vec::with_capacity(length);
+  let mut counter: u32 = 1;
+  while out.len() < length {
+    let mut context = ring::digest::Context::new(algorithm);
+    if counter_first {
+      context.update(&counter.to_be_bytes());
+      context.update(secret);
+    } else {
+      context.update(secret);
+      context.update(&counter.to_be_bytes());
+    }
+    context.update(info);
+    let block = context.finish();
+    let take = block.as_ref().len().min(length - out.len());
+    out.extend_from_slice(&block.as_ref()[..take]);
+    counter += 1;
+  }
+  out
+}
+
+/// Returns the first `length` bits of an ECDH shared secret, or all of it for
+/// a `length` of 0, which is what JS sends for a null length.
+fn ecdh_secret_bits(secret: &[u8], length: usize) -> Result<Vec<u8>, AnyError> {
+  if length == 0 {
+    return Ok(secret.to_vec());
+  }
+  if length > secret.len() * 8 {
+    return Err(operation_error("Invalid length"));
+  }
+  let mut bits = secret[..(length + 7) / 8].to_vec();
+  if length % 8 != 0 {
+    // Clear the bits past `length` in the last byte.
+    let last = bits.len() - 1;
+    bits[last] &= 0xff << (8 - length % 8);
+  }
+  Ok(bits)
+}
+
+/// HKDF (RFC 5869) extract and expand, producing `length` bytes.
+fn hkdf_derive(
+  hash: CryptoHash,
+  ikm: &[u8],
+  salt: &[u8],
+  info: &[u8],
+  length: usize,
+) -> Result<Vec<u8>, AnyError> {
+  let salt = hkdf::Salt::new(hash.into(), salt);
+  let prk = salt.extract(ikm);
+  hkdf_expand(&prk, info, length)
+}
+
+fn hkdf_expand(
+  prk: &hkdf::Prk,
+  info: &[u8],
+  length: usize,
+) -> Result<Vec<u8>, AnyError> {
+  let info = &[info];
+  let okm = prk.expand(info, HkdfOutput(length)).map_err(|_e| {
+    custom_error(
+      "DOMExceptionOperationError",
+      "The length provided for HKDF is too large",
+    )
+  })?;
+  let mut r = vec![0u8; length];
+  okm.fill(&mut r)?;
+  Ok(r)
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HkdfExtractArg {
+  /// The input keying material.
+  key: KeyData,
+  hash: CryptoHash,
+  salt: JsBuffer,
+}
+
+/// HKDF-Extract, returning the pseudorandom key. Together with
+/// `op_crypto_hkdf_expand` this allows key schedules that reuse a PRK, like
+/// the one in TLS 1.3, which `deriveBits` cannot express.
+#[op]
+pub fn op_crypto_hkdf_extract(
+  state: &mut OpState,
+  mut args: HkdfExtractArg,
+) -> Result<ToJsBuffer, AnyError> {
+  args.key.resolve(state)?;
+  if !matches!(args.key.r#type, KeyType::Secret) {
+    return Err(type_error("Invalid key type"));
+  }
+  let hash: HmacAlgorithm = args.hash.into();
+  let prk = ring::hmac::sign(&HmacKey::new(hash, &args.salt), &args.key.data);
+  Ok(prk.as_ref().to_vec().into())
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HkdfExpandArg {
+  /// The pseudorandom key, usually the output of `op_crypto_hkdf_extract`.
+  key: KeyData,
+  hash: CryptoHash,
+  info: JsBuffer,
+  /// Length of the output in bits.
+  length: usize,
+}
+
+/// HKDF-Expand of a pseudorandom key that was not necessarily produced by
+/// HKDF-Extract.
+#[op]
+pub fn op_crypto_hkdf_expand(
+  state: &mut OpState,
+  mut args: HkdfExpandArg,
+) -> Result<ToJsBuffer, AnyError> {
+  args.key.resolve(state)?;
+  if !matches!(args.key.r#type, KeyType::Secret) {
+    return Err(type_error("Invalid key type"));
+  }
+  if args.length == 0 || args.length % 8 != 0 {
+    return Err(type_error("Length must be a non-zero multiple of 8"));
+  }
+  let hash: HmacAlgorithm = args.hash.into();
+  // RFC 5869 section 2.3: PRK must be at least HashLen octets.
+  if args.key.data.len() < hash.digest_algorithm().output_len {
+    return Err(operation_error("The pseudorandom key is too short"));
+  }
+  let prk = hkdf::Prk::new_less_safe(args.hash.into(), &args.key.data);
+  let okm = hkdf_expand(&prk, &args.info, args.length / 8)?;
+  Ok(okm.into())
+}
+
 fn read_rsa_public_key(key_data: KeyData) -> Result<RsaPublicKey, AnyError> {
   let public_key = match key_data.r#type {
     KeyType::Private => {
       RsaPrivateKey::from_pkcs1_der(&key_data.data)?.to_public_key()
     }
-    KeyType::Public => RsaPublicKey::from_pkcs1_der(&key_data.data)?,
+    KeyType::Public => rsa_public_key_from_pkcs1_der(&key_data.data)?,
     KeyType::Secret => unreachable!("unexpected KeyType::Secret"),
   };
   Ok(public_key)
 }
 
-#[op]
-pub fn op_crypto_random_uuid(state: &mut OpState) -> Result<String, AnyError> {
+fn random_uuid(state: &mut OpState) -> uuid::Uuid {
   let maybe_seeded_rng = state.try_borrow_mut::<StdRng>();
-  let uuid = if let Some(seeded_rng) = maybe_seeded_rng {
+  if let Some(seeded_rng) = maybe_seeded_rng {
     let mut bytes = [0u8; 16];
     seeded_rng.fill(&mut bytes);
     uuid::Builder::from_bytes(bytes)
@@ -594,9 +1324,38 @@ pub fn op_crypto_random_uuid(state: &mut OpState) -> Result<String, AnyError> {
       .into_uuid()
   } else {
     uuid::Uuid::new_v4()
-  };
+  }
+}
 
-  Ok(uuid.to_string())
+#[op]
+pub fn op_crypto_random_uuid(state: &mut OpState) -> Result<String, AnyError> {
+  Ok(random_uuid(state).to_string())
+}
+
+/// The most UUIDs `op_crypto_random_uuids` generates in one call, which draws
+/// as much entropy as a single `getRandomValues` call may.
+const MAX_RANDOM_UUIDS: u32 = 65536 / 16;
+
+/// Generates `count` v4 UUIDs in a single call, packed as 16 bytes each.
+#[op]
+pub fn op_crypto_random_uuids(
+  state: &mut OpState,
+  count: u32,
+) -> Result<ToJsBuffer, AnyError> {
+  if count > MAX_RANDOM_UUIDS {
+    return Err(
+      deno_web::DomExceptionQuotaExceededError::new(&format!(
+        "The number of UUIDs ({count}) exceeds the number that can be generated at once ({MAX_RANDOM_UUIDS})"
+      ))
+      .into(),
+    );
+  }
+
+  let mut out = Vec::with_capacity(count as usize * 16);
+  for _ in 0..count {
+    out.extend_from_slice(random_uuid(state).as_bytes());
+  }
+  Ok(out.into())
 }
 
 #[op]
@@ -605,7 +1364,7 @@ pub async fn op_crypto_subtle_digest(
   data: JsBuffer,
 ) -> Result<ToJsBuffer, AnyError> {
   let output = spawn_blocking(move || {
-    digest::digest(algorithm.into(), &data)
+    ring::digest::digest(algorithm.into(), &data)
       .as_ref()
       .to_vec()
       .into()
@@ -618,20 +1377,46 @@ pub async fn op_crypto_subtle_digest(
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct WrapUnwrapKeyArg {
-  key: V8RawKeyData,
-  algorithm: Algorithm,
+  key: KeyData,
+  #[serde(flatten)]
+  algorithm: WrapAlgorithm,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", tag = "algorithm")]
+pub enum WrapAlgorithm {
+  #[serde(rename = "AES-KW")]
+  AesKw,
+  #[serde(rename = "AES-KWP")]
+  AesKwp,
+  #[serde(rename = "RSA-OAEP")]
+  RsaOaep {
+    hash: ShaHash,
+    #[serde(with = "serde_bytes")]
+    label: Vec<u8>,
+  },
+  #[serde(rename = "AES-GCM", rename_all = "camelCase")]
+  AesGcm {
+    #[serde(with = "serde_bytes")]
+    iv: Vec<u8>,
+    #[serde(with = "serde_bytes")]
+    additional_data: Option<Vec<u8>>,
+    length: usize,
+    tag_length: usize,
+  },
 }
 
 #[op]
 pub fn op_crypto_wrap_key(
-  args: WrapUnwrapKeyArg,
+  state: &mut OpState,
+  mut args: WrapUnwrapKeyArg,
   data: JsBuffer,
 ) -> Result<ToJsBuffer, AnyError> {
-  let algorithm = args.algorithm;
-
-  match algorithm {
-    Algorithm::AesKw => {
-      let key = args.key.as_secret_key()?;
+  args.key.resolve(state)?;
+  let key = V8RawKeyData::from(args.key);
+  match args.algorithm {
+    WrapAlgorithm::AesKw => {
+      let key = key.as_secret_key()?;
 
       if data.len() % 8 != 0 {
         return Err(type_error("Data must be multiple of 8 bytes"));
@@ -647,19 +1432,54 @@ pub fn op_crypto_wrap_key(
 
       Ok(wrapped_key.into())
     }
-    _ => Err(type_error("Unsupported algorithm")),
+    // RFC 5649 allows wrapping data of any length.
+    WrapAlgorithm::AesKwp => {
+      let key = key.as_secret_key()?;
+
+      if data.is_empty() {
+        return Err(type_error("Data must not be empty"));
+      }
+
+      let wrapped_key = match key.len() {
+        16 => KekAes128::new(key.into()).wrap_with_padding_vec(&data),
+        24 => KekAes192::new(key.into()).wrap_with_padding_vec(&data),
+        32 => KekAes256::new(key.into()).wrap_with_padding_vec(&data),
+        _ => return Err(type_error("Invalid key length")),
+      }
+      .map_err(|_| operation_error("encryption error"))?;
+
+      Ok(wrapped_key.into())
+    }
+    WrapAlgorithm::RsaOaep { hash, label } => {
+      // Fails if the exported key is too long for the modulus and hash.
+      let wrapped_key = encrypt_rsa_oaep(key, hash, label, &data)
+        .map_err(|_| operation_error("encryption error"))?;
+      Ok(wrapped_key.into())
+    }
+    WrapAlgorithm::AesGcm {
+      iv,
+      additional_data,
+      length,
+      tag_length,
+    } => {
+      let wrapped_key =
+        encrypt_aes_gcm(key, length, tag_length, iv, additional_data, &data)?;
+      Ok(wrapped_key.into())
+    }
   }
 }
 
 #[op]
 pub fn op_crypto_unwrap_key(
-  args: WrapUnwrapKeyArg,
+  state: &mut OpState,
+  mut args: WrapUnwrapKeyArg,
   data: JsBuffer,
 ) -> Result<ToJsBuffer, AnyError> {
-  let algorithm = args.algorithm;
-  match algorithm {
-    Algorithm::AesKw => {
-      let key = args.key.as_secret_key()?;
+  args.key.resolve(state)?;
+  let key = V8RawKeyData::from(args.key);
+  match args.algorithm {
+    WrapAlgorithm::AesKw => {
+      let key = key.as_secret_key()?;
 
       if data.len() % 8 != 0 {
         return Err(type_error("Data must be multiple of 8 bytes"));
@@ -677,10 +1497,85 @@ pub fn op_crypto_unwrap_key(
 
       Ok(unwrapped_key.into())
     }
-    _ => Err(type_error("Unsupported algorithm")),
+    WrapAlgorithm::AesKwp => {
+      let key = key.as_secret_key()?;
+
+      if data.len() < 16 || data.len() % 8 != 0 {
+        return Err(operation_error("Invalid wrapped key length"));
+      }
+
+      let unwrapped_key = match key.len() {
+        16 => KekAes128::new(key.into()).unwrap_with_padding_vec(&data),
+        24 => KekAes192::new(key.into()).unwrap_with_padding_vec(&data),
+        32 => KekAes256::new(key.into()).unwrap_with_padding_vec(&data),
+        _ => return Err(type_error("Invalid key length")),
+      }
+      .map_err(|_| {
+        operation_error("decryption error - integrity check failed")
+      })?;
+
+      Ok(unwrapped_key.into())
+    }
+    WrapAlgorithm::RsaOaep { hash, label } => {
+      let backend = backend::get(state);
+      let unwrapped_key = decrypt_rsa_oaep(key, backend, hash, label, &data)
+        .map_err(|_| {
+          operation_error("decryption error - integrity check failed")
+        })?;
+      Ok(unwrapped_key.into())
+    }
+    WrapAlgorithm::AesGcm {
+      iv,
+      additional_data,
+      length,
+      tag_length,
+    } => {
+      if data.len() < tag_length / 8 {
+        return Err(operation_error("Wrapped key is shorter than the tag"));
+      }
+      let unwrapped_key =
+        decrypt_aes_gcm(key, length, tag_length, iv, additional_data, &data)?;
+      Ok(unwrapped_key.into())
+    }
   }
 }
 
 pub fn get_declaration() -> PathBuf {
   PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("lib.deno_crypto.d.ts")
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_verify_secp256k1_high_s() {
+    let signing_key = k256::ecdsa::SigningKey::from_slice(&[0x42; 32]).unwrap();
+    let verifying_key = *signing_key.verifying_key();
+    let data = b"sample";
+
+    let signature: k256::ecdsa::Signature =
+      k256::ecdsa::signature::Signer::sign(&signing_key, data);
+    assert!(signature.normalize_s().is_none());
+    assert!(verify_secp256k1(
+      &verifying_key,
+      data,
+      &signature.to_bytes()
+    ));
+
+    // The same signature with S replaced by n - S.
+    let (r, s) = signature.split_scalars();
+    let high_s =
+      k256::ecdsa::Signature::from_scalars(r.to_bytes(), (-*s).to_bytes())
+        .unwrap();
+    assert!(high_s.normalize_s().is_some());
+    assert!(verify_secp256k1(&verifying_key, data, &high_s.to_bytes()));
+
+    assert!(!verify_secp256k1(
+      &verifying_key,
+      b"other",
+      &high_s.to_bytes()
+    ));
+    assert!(!verify_secp256k1(&verifying_key, data, &[0; 63]));
+  }
+}
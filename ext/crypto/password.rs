@@ -0,0 +1,75 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! bcrypt password hashing, for services that need to verify existing bcrypt
+//! hashes or produce new ones.
+
+use deno_core::error::type_error;
+use deno_core::error::AnyError;
+use deno_core::op;
+use deno_core::task::spawn_blocking;
+use deno_core::JsBuffer;
+use deno_core::OpState;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::fips;
+use crate::shared::data_error;
+
+/// bcrypt only uses the first 72 bytes of a password. Longer passwords are
+/// rejected rather than silently truncated.
+const MAX_PASSWORD_LEN: usize = 72;
+
+/// Cost bounds accepted by the `bcrypt` crate.
+const MIN_COST: u32 = 4;
+const MAX_COST: u32 = 31;
+
+fn check_password(password: &[u8]) -> Result<(), AnyError> {
+  if password.len() > MAX_PASSWORD_LEN {
+    return Err(type_error("Password must be at most 72 bytes"));
+  }
+  Ok(())
+}
+
+/// Returns the hash in the modular crypt format, e.g. `$2b$12$...`.
+#[op]
+pub async fn op_crypto_bcrypt_hash(
+  state: Rc<RefCell<OpState>>,
+  password: JsBuffer,
+  cost: u32,
+) -> Result<String, AnyError> {
+  fips::check_unapproved(&state.borrow(), "bcrypt")?;
+  check_password(&password)?;
+  if !(MIN_COST..=MAX_COST).contains(&cost) {
+    return Err(type_error(format!(
+      "Cost must be between {MIN_COST} and {MAX_COST}"
+    )));
+  }
+  let mut salt = [0u8; 16];
+  OsRng.fill_bytes(&mut salt);
+
+  spawn_blocking(move || {
+    let parts = bcrypt::hash_with_salt(&*password, cost, salt)
+      .map_err(|err| type_error(err.to_string()))?;
+    Ok(parts.format_for_version(bcrypt::Version::TwoB))
+  })
+  .await
+  .unwrap()
+}
+
+#[op]
+pub async fn op_crypto_bcrypt_verify(
+  state: Rc<RefCell<OpState>>,
+  password: JsBuffer,
+  hash: String,
+) -> Result<bool, AnyError> {
+  fips::check_unapproved(&state.borrow(), "bcrypt")?;
+  check_password(&password)?;
+  spawn_blocking(move || {
+    bcrypt::verify(&*password, &hash)
+      .map_err(|_| data_error("Invalid bcrypt hash"))
+  })
+  .await
+  .unwrap()
+}
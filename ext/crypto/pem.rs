@@ -0,0 +1,65 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use deno_core::error::AnyError;
+use deno_core::op;
+use deno_core::ToJsBuffer;
+use pem_rfc7468::LineEnding;
+use serde::Deserialize;
+
+use crate::shared::data_error;
+
+/// The DER structures that can be armored, each of which maps to exactly one
+/// PEM type label.
+#[derive(Deserialize, Clone, Copy)]
+pub enum PemFormat {
+  #[serde(rename = "pkcs8")]
+  Pkcs8,
+  #[serde(rename = "spki")]
+  Spki,
+  #[serde(rename = "pkcs1")]
+  Pkcs1Private,
+  #[serde(rename = "pkcs1-public")]
+  Pkcs1Public,
+  #[serde(rename = "sec1")]
+  Sec1,
+  #[serde(rename = "pkcs10")]
+  Pkcs10,
+}
+
+impl PemFormat {
+  fn label(self) -> &'static str {
+    match self {
+      PemFormat::Pkcs8 => "PRIVATE KEY",
+      PemFormat::Spki => "PUBLIC KEY",
+      PemFormat::Pkcs1Private => "RSA PRIVATE KEY",
+      PemFormat::Pkcs1Public => "RSA PUBLIC KEY",
+      PemFormat::Sec1 => "EC PRIVATE KEY",
+      PemFormat::Pkcs10 => "CERTIFICATE REQUEST",
+    }
+  }
+}
+
+#[op]
+pub fn op_crypto_pem_decode(
+  pem: String,
+  format: PemFormat,
+) -> Result<ToJsBuffer, AnyError> {
+  let (label, der) = pem_rfc7468::decode_vec(pem.as_bytes())
+    .map_err(|e| data_error(format!("invalid PEM document: {e}")))?;
+  if label != format.label() {
+    return Err(data_error(format!(
+      "expected PEM label \"{}\", found \"{label}\"",
+      format.label()
+    )));
+  }
+  Ok(der.into())
+}
+
+#[op]
+pub fn op_crypto_pem_encode(
+  der: &[u8],
+  format: PemFormat,
+) -> Result<String, AnyError> {
+  pem_rfc7468::encode_string(format.label(), LineEnding::LF, der)
+    .map_err(|e| data_error(format!("failed to encode PEM document: {e}")))
+}
@@ -0,0 +1,39 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use deno_core::error::AnyError;
+use deno_core::op;
+use deno_core::ToJsBuffer;
+use p12_keystore::KeyStore;
+use serde::Serialize;
+
+use crate::shared::data_error;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Pkcs12Identity {
+  /// PKCS#8 `PrivateKeyInfo`.
+  private_key: ToJsBuffer,
+  /// DER encoded certificates, leaf first.
+  certificates: Vec<ToJsBuffer>,
+}
+
+#[op]
+pub fn op_crypto_import_pkcs12(
+  data: &[u8],
+  password: String,
+) -> Result<Pkcs12Identity, AnyError> {
+  let store = KeyStore::from_pkcs12(data, &password)
+    .map_err(|e| data_error(format!("invalid PKCS#12 archive: {e}")))?;
+  let (_, chain) = store
+    .private_key_chain()
+    .ok_or_else(|| data_error("PKCS#12 archive contains no private key"))?;
+
+  Ok(Pkcs12Identity {
+    private_key: chain.key().to_vec().into(),
+    certificates: chain
+      .chain()
+      .iter()
+      .map(|cert| cert.as_der().to_vec().into())
+      .collect(),
+  })
+}
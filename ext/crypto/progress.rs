@@ -0,0 +1,146 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! Progress reporting and cancellation for long running operations, like
+//! RSA key generation or PBKDF2 with a high iteration count.
+
+use deno_core::error::AnyError;
+use deno_core::op;
+use deno_core::task::spawn_blocking;
+use deno_core::CancelFuture;
+use deno_core::CancelHandle;
+use deno_core::OpState;
+use deno_core::Resource;
+use deno_core::ResourceId;
+use ring::hmac;
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::num::NonZeroU32;
+use std::rc::Rc;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+use crate::key::CryptoHash;
+use crate::shared::operation_error;
+
+/// Number of units of work done so far, updated from the blocking pool and
+/// polled from JS.
+pub struct Progress(Arc<AtomicU64>);
+
+impl Resource for Progress {
+  fn name(&self) -> Cow<str> {
+    "cryptoProgress".into()
+  }
+}
+
+#[op(fast)]
+pub fn op_crypto_progress_create(state: &mut OpState) -> ResourceId {
+  state
+    .resource_table
+    .add(Progress(Arc::new(AtomicU64::new(0))))
+}
+
+#[op(fast)]
+pub fn op_crypto_progress(
+  state: &mut OpState,
+  rid: ResourceId,
+) -> Result<f64, AnyError> {
+  let progress = state.resource_table.get::<Progress>(rid)?;
+  Ok(progress.0.load(Ordering::Relaxed) as f64)
+}
+
+/// Looks up the counter shared with JS through `op_crypto_progress`.
+pub fn progress_counter(
+  state: &OpState,
+  rid: Option<ResourceId>,
+) -> Result<Option<Arc<AtomicU64>>, AnyError> {
+  rid
+    .map(|rid| {
+      let progress = state.resource_table.get::<Progress>(rid)?;
+      Ok(progress.0.clone())
+    })
+    .transpose()
+}
+
+/// Runs `f` on the blocking pool. If the `CancelHandle` resource `cancel_rid`
+/// is closed before `f` returns, the op fails right away. Dropping the
+/// blocking task does not stop it, so `f` is expected to poll the flag it is
+/// given and give up early once it is set.
+pub async fn spawn_cancelable<T, F>(
+  state: &Rc<RefCell<OpState>>,
+  cancel_rid: Option<ResourceId>,
+  f: F,
+) -> Result<T, AnyError>
+where
+  T: Send + 'static,
+  F: FnOnce(&AtomicBool) -> Result<T, AnyError> + Send + 'static,
+{
+  let cancel_handle = cancel_rid.and_then(|rid| {
+    state.borrow().resource_table.get::<CancelHandle>(rid).ok()
+  });
+  let canceled = Arc::new(AtomicBool::new(false));
+
+  let task = {
+    let canceled = canceled.clone();
+    spawn_blocking(move || f(&canceled))
+  };
+
+  let Some(cancel_handle) = cancel_handle else {
+    return task.await.unwrap();
+  };
+  let res = task.or_cancel(cancel_handle).await;
+  if let Some(cancel_rid) = cancel_rid {
+    state.borrow_mut().resource_table.close(cancel_rid).ok();
+  }
+  match res {
+    Ok(res) => res.unwrap(),
+    Err(err) => {
+      canceled.store(true, Ordering::Relaxed);
+      Err(err.into())
+    }
+  }
+}
+
+/// Number of PBKDF2 iterations between progress updates and cancellation
+/// checks.
+const PBKDF2_CHUNK: u32 = 1 << 12;
+
+/// PBKDF2 (RFC 8018) as implemented by `ring::pbkdf2::derive`, but reporting
+/// the total number of iterations done so far across all output blocks.
+pub fn pbkdf2_derive(
+  hash: CryptoHash,
+  iterations: NonZeroU32,
+  salt: &[u8],
+  secret: &[u8],
+  out: &mut [u8],
+  progress: &AtomicU64,
+  canceled: &AtomicBool,
+) -> Result<(), AnyError> {
+  let key = hmac::Key::new(hash.into(), secret);
+  let output_len = key.algorithm().digest_algorithm().output_len;
+  let iterations = iterations.get();
+
+  for (index, block) in out.chunks_mut(output_len).enumerate() {
+    let mut context = hmac::Context::with_key(&key);
+    context.update(salt);
+    context.update(&(index as u32 + 1).to_be_bytes());
+    let mut u = context.sign();
+    block.copy_from_slice(&u.as_ref()[..block.len()]);
+
+    let done = index as u64 * iterations as u64;
+    for i in 1..iterations {
+      u = hmac::sign(&key, u.as_ref());
+      block.iter_mut().zip(u.as_ref()).for_each(|(t, u)| *t ^= u);
+
+      if i % PBKDF2_CHUNK == 0 {
+        if canceled.load(Ordering::Relaxed) {
+          return Err(operation_error("Key derivation was canceled"));
+        }
+        progress.store(done + i as u64, Ordering::Relaxed);
+      }
+    }
+    progress.store(done + iterations as u64, Ordering::Relaxed);
+  }
+  Ok(())
+}
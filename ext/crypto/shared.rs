@@ -5,25 +5,34 @@ use std::borrow::Cow;
 use deno_core::error::custom_error;
 use deno_core::error::type_error;
 use deno_core::error::AnyError;
-use deno_core::JsBuffer;
-use deno_core::ToJsBuffer;
 use elliptic_curve::sec1::ToEncodedPoint;
+use p256::pkcs8::DecodePrivateKey;
 use rsa::pkcs1::DecodeRsaPrivateKey;
 use rsa::pkcs1::EncodeRsaPublicKey;
-use rsa::pkcs8::DecodePrivateKey;
+use rsa::BigUint;
 use rsa::RsaPrivateKey;
+use rsa::RsaPublicKey;
 use serde::Deserialize;
 use serde::Serialize;
+use spki::der::Decode;
+
+use crate::KeyBytes;
 
 pub const RSA_ENCRYPTION_OID: const_oid::ObjectIdentifier =
   const_oid::ObjectIdentifier::new_unwrap("1.2.840.113549.1.1.1");
 
+/// Largest RSA modulus in bits that can be generated or used for public key
+/// operations. The `rsa` crate caps public keys at 4096 bits by default.
+pub const RSA_MAX_MODULUS_LENGTH: usize = 16384;
+
 pub const ID_SECP256R1_OID: const_oid::ObjectIdentifier =
   const_oid::ObjectIdentifier::new_unwrap("1.2.840.10045.3.1.7");
 pub const ID_SECP384R1_OID: const_oid::ObjectIdentifier =
   const_oid::ObjectIdentifier::new_unwrap("1.3.132.0.34");
 pub const ID_SECP521R1_OID: const_oid::ObjectIdentifier =
   const_oid::ObjectIdentifier::new_unwrap("1.3.132.0.35");
+pub const ID_SECP256K1_OID: const_oid::ObjectIdentifier =
+  const_oid::ObjectIdentifier::new_unwrap("1.3.132.0.10");
 
 #[derive(Serialize, Deserialize, Copy, Clone, Eq, PartialEq)]
 pub enum ShaHash {
@@ -45,22 +54,34 @@ pub enum EcNamedCurve {
   P384,
   #[serde(rename = "P-521")]
   P521,
+  #[serde(rename = "secp256k1")]
+  Secp256k1,
 }
 
 #[derive(Deserialize)]
 #[serde(rename_all = "lowercase", tag = "type", content = "data")]
 pub enum V8RawKeyData {
-  Secret(JsBuffer),
-  Private(JsBuffer),
-  Public(JsBuffer),
+  Secret(KeyBytes),
+  Private(KeyBytes),
+  Public(KeyBytes),
 }
 
 #[derive(Serialize)]
 #[serde(rename_all = "lowercase", tag = "type", content = "data")]
 pub enum RustRawKeyData {
-  Secret(ToJsBuffer),
-  Private(ToJsBuffer),
-  Public(ToJsBuffer),
+  Secret(KeyBytes),
+  Private(KeyBytes),
+  Public(KeyBytes),
+}
+
+impl From<RustRawKeyData> for V8RawKeyData {
+  fn from(data: RustRawKeyData) -> Self {
+    match data {
+      RustRawKeyData::Secret(data) => V8RawKeyData::Secret(data),
+      RustRawKeyData::Private(data) => V8RawKeyData::Private(data),
+      RustRawKeyData::Public(data) => V8RawKeyData::Public(data),
+    }
+  }
 }
 
 impl V8RawKeyData {
@@ -130,12 +151,75 @@ impl V8RawKeyData {
     }
   }
 
+  pub fn as_ec_public_key_k256(&self) -> Result<k256::EncodedPoint, AnyError> {
+    match self {
+      V8RawKeyData::Public(data) => {
+        // public_key is a serialized EncodedPoint
+        k256::EncodedPoint::from_bytes(data)
+          .map_err(|_| type_error("expected valid public EC key"))
+      }
+      V8RawKeyData::Private(data) => {
+        let signing_key = k256::SecretKey::from_pkcs8_der(data)
+          .map_err(|_| type_error("expected valid private EC key"))?;
+        Ok(signing_key.public_key().to_encoded_point(false))
+      }
+      // Should never reach here.
+      V8RawKeyData::Secret(_) => unreachable!(),
+    }
+  }
+
+  pub fn as_ec_public_key_p521(&self) -> Result<p521::EncodedPoint, AnyError> {
+    match self {
+      V8RawKeyData::Public(data) => {
+        // public_key is a serialized EncodedPoint
+        p521::EncodedPoint::from_bytes(data)
+          .map_err(|_| type_error("expected valid public EC key"))
+      }
+      V8RawKeyData::Private(data) => {
+        let signing_key = p521::SecretKey::from_pkcs8_der(data)
+          .map_err(|_| type_error("expected valid private EC key"))?;
+        Ok(signing_key.public_key().to_encoded_point(false))
+      }
+      // Should never reach here.
+      V8RawKeyData::Secret(_) => unreachable!(),
+    }
+  }
+
   pub fn as_ec_private_key(&self) -> Result<&[u8], AnyError> {
     match self {
       V8RawKeyData::Private(data) => Ok(data),
       _ => Err(type_error("expected private key")),
     }
   }
+
+  pub fn as_okp_public_key(&self) -> Result<&[u8], AnyError> {
+    match self {
+      V8RawKeyData::Public(data) => Ok(data),
+      _ => Err(type_error("expected public key")),
+    }
+  }
+
+  pub fn as_okp_private_key(&self) -> Result<&[u8], AnyError> {
+    match self {
+      V8RawKeyData::Private(data) => Ok(data),
+      _ => Err(type_error("expected private key")),
+    }
+  }
+}
+
+/// Decodes a PKCS#1 `RSAPublicKey`, allowing moduli up to
+/// `RSA_MAX_MODULUS_LENGTH` bits.
+pub fn rsa_public_key_from_pkcs1_der(
+  data: &[u8],
+) -> Result<RsaPublicKey, AnyError> {
+  let public_key = rsa::pkcs1::RsaPublicKey::from_der(data)
+    .map_err(|_| data_error("invalid RSA public key"))?;
+  RsaPublicKey::new_with_max_size(
+    BigUint::from_bytes_be(public_key.modulus.as_bytes()),
+    BigUint::from_bytes_be(public_key.public_exponent.as_bytes()),
+    RSA_MAX_MODULUS_LENGTH,
+  )
+  .map_err(|_| data_error("invalid RSA public key"))
 }
 
 pub fn data_error(msg: impl Into<Cow<'static, str>>) -> AnyError {
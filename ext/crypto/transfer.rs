@@ -0,0 +1,160 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! Moving stored keys between workers of the same process without the key
+//! material passing through JS, e.g. for `postMessage(cryptoKey)`.
+
+use deno_core::error::AnyError;
+use deno_core::op;
+use deno_core::OpState;
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+use zeroize::Zeroizing;
+
+use crate::shared::data_error;
+use crate::CryptoKeyStore;
+use crate::KeyBytes;
+use crate::KeyData;
+use crate::StoredKey;
+
+/// How long a token can be redeemed after it was issued. A message is
+/// normally received well within this, so a key that is still pending after
+/// it was most likely posted to a worker which never reads it.
+const TRANSFER_TTL: Duration = Duration::from_secs(60);
+
+struct PendingTransfer {
+  key: StoredKey,
+  issued_at: Instant,
+}
+
+/// Keys on their way to another worker, by token. Each worker has its own
+/// `CryptoKeyStore`, so this is the only state shared between them. Entries
+/// are removed when they are redeemed, when they expire and when the worker
+/// which issued them exits. The key material is wiped once the last
+/// reference to it is dropped.
+static PENDING_TRANSFERS: Lazy<Mutex<HashMap<String, PendingTransfer>>> =
+  Lazy::new(Default::default);
+
+/// The tokens issued by a worker, which are revoked when its `OpState` is
+/// dropped.
+#[derive(Default)]
+pub struct IssuedTransfers(Vec<String>);
+
+impl Drop for IssuedTransfers {
+  fn drop(&mut self) {
+    let mut pending = PENDING_TRANSFERS.lock().unwrap();
+    for token in &self.0 {
+      pending.remove(token);
+    }
+  }
+}
+
+fn remove_expired(pending: &mut HashMap<String, PendingTransfer>) {
+  pending.retain(|_, transfer| transfer.issued_at.elapsed() < TRANSFER_TTL);
+}
+
+fn issue(issued: &mut IssuedTransfers, key: StoredKey) -> String {
+  // Not derived from the seeded RNG, tokens must stay unpredictable with
+  // --seed.
+  let token = uuid::Uuid::new_v4().to_string();
+  let mut pending = PENDING_TRANSFERS.lock().unwrap();
+  remove_expired(&mut pending);
+  pending.insert(
+    token.clone(),
+    PendingTransfer {
+      key,
+      issued_at: Instant::now(),
+    },
+  );
+  issued.0.retain(|token| pending.contains_key(token));
+  issued.0.push(token.clone());
+  token
+}
+
+fn redeem(token: &str) -> Option<StoredKey> {
+  let mut pending = PENDING_TRANSFERS.lock().unwrap();
+  remove_expired(&mut pending);
+  pending.remove(token).map(|transfer| transfer.key)
+}
+
+/// Returns an opaque token that `op_crypto_key_transfer_in` exchanges for a
+/// handle to the same key in any worker of this process. Tokens are
+/// unguessable and can be redeemed only once, within `TRANSFER_TTL` and while
+/// the issuing worker is alive.
+#[op]
+pub fn op_crypto_key_transfer_out(
+  state: &mut OpState,
+  mut key: KeyData,
+) -> Result<String, AnyError> {
+  key.resolve(state)?;
+  let key = StoredKey {
+    r#type: key.r#type,
+    data: match key.data {
+      KeyBytes::Stored(data) => data,
+      KeyBytes::Inline(data) => Arc::new(Zeroizing::new(data.to_vec())),
+    },
+    extractable: key.extractable,
+  };
+  Ok(issue(state.borrow_mut::<IssuedTransfers>(), key))
+}
+
+#[op]
+pub fn op_crypto_key_transfer_in(
+  state: &mut OpState,
+  token: String,
+) -> Result<u32, AnyError> {
+  let key =
+    redeem(&token).ok_or_else(|| data_error("Invalid key transfer token"))?;
+  Ok(state.borrow_mut::<CryptoKeyStore>().insert(key))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::KeyType;
+
+  fn key() -> StoredKey {
+    StoredKey {
+      r#type: KeyType::Secret,
+      data: Arc::new(Zeroizing::new(vec![1, 2, 3])),
+      extractable: false,
+    }
+  }
+
+  #[test]
+  fn test_transfer_redeemed_once() {
+    let mut issued = IssuedTransfers::default();
+    let token = issue(&mut issued, key());
+    assert_eq!(&**redeem(&token).unwrap().data, &[1, 2, 3]);
+    assert!(redeem(&token).is_none());
+  }
+
+  #[test]
+  fn test_transfer_revoked_with_worker() {
+    let mut issued = IssuedTransfers::default();
+    let token = issue(&mut issued, key());
+    drop(issued);
+    assert!(redeem(&token).is_none());
+  }
+
+  #[test]
+  fn test_transfer_expires() {
+    let mut issued = IssuedTransfers::default();
+    let token = issue(&mut issued, key());
+    let Some(issued_at) =
+      Instant::now().checked_sub(TRANSFER_TTL + Duration::from_secs(1))
+    else {
+      return;
+    };
+    PENDING_TRANSFERS
+      .lock()
+      .unwrap()
+      .get_mut(&token)
+      .unwrap()
+      .issued_at = issued_at;
+    assert!(redeem(&token).is_none());
+  }
+}
@@ -1,18 +1,38 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
 use curve25519_dalek::montgomery::MontgomeryPoint;
+use deno_core::error::type_error;
 use deno_core::error::AnyError;
 use deno_core::op;
-use deno_core::ToJsBuffer;
-use elliptic_curve::pkcs8::PrivateKeyInfo;
+use deno_core::JsBuffer;
+use deno_core::OpState;
 use elliptic_curve::subtle::ConstantTimeEq;
 use rand::rngs::OsRng;
 use rand::RngCore;
+use rsa::pkcs8::PrivateKeyInfo;
+use serde::Deserialize;
 use spki::der::Decode;
 use spki::der::Encode;
+use std::sync::Arc;
+use zeroize::Zeroizing;
+
+use crate::fips;
+use crate::hkdf_derive;
+use crate::key::CryptoHash;
+use crate::shared::data_error;
+use crate::shared::operation_error;
+use crate::CryptoKeyStore;
+use crate::KeyData;
+use crate::KeyType;
+use crate::StoredKey;
 
 #[op(fast)]
-pub fn op_crypto_generate_x25519_keypair(pkey: &mut [u8], pubkey: &mut [u8]) {
+pub fn op_crypto_generate_x25519_keypair(
+  state: &mut OpState,
+  pkey: &mut [u8],
+  pubkey: &mut [u8],
+) -> Result<(), AnyError> {
+  fips::check_unapproved(state, "X25519")?;
   // u-coordinate of the base point.
   const X25519_BASEPOINT_BYTES: [u8; 32] = [
     9, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
@@ -25,27 +45,92 @@ pub fn op_crypto_generate_x25519_keypair(pkey: &mut [u8], pubkey: &mut [u8]) {
   //   https://eprint.iacr.org/2014/140.pdf page 4
   //   https://eprint.iacr.org/2017/212.pdf algorithm 8
   // pubkey is in LE order.
-  let pkey: [u8; 32] = pkey.try_into().expect("Expected byteLength 32");
-  pubkey.copy_from_slice(&x25519_dalek::x25519(pkey, X25519_BASEPOINT_BYTES));
+  let pkey: Zeroizing<[u8; 32]> =
+    Zeroizing::new(pkey.try_into().expect("Expected byteLength 32"));
+  pubkey.copy_from_slice(&x25519_dalek::x25519(*pkey, X25519_BASEPOINT_BYTES));
+  Ok(())
 }
 
 const MONTGOMERY_IDENTITY: MontgomeryPoint = MontgomeryPoint([0; 32]);
 
-#[op(fast)]
+#[op]
 pub fn op_crypto_derive_bits_x25519(
-  k: &[u8],
-  u: &[u8],
+  state: &mut OpState,
+  mut k: KeyData,
+  mut u: KeyData,
   secret: &mut [u8],
-) -> bool {
-  let k: [u8; 32] = k.try_into().expect("Expected byteLength 32");
-  let u: [u8; 32] = u.try_into().expect("Expected byteLength 32");
-  let sh_sec = x25519_dalek::x25519(k, u);
-  let point = MontgomeryPoint(sh_sec);
+) -> Result<bool, AnyError> {
+  fips::check_unapproved(state, "X25519")?;
+  k.resolve(state)?;
+  u.resolve(state)?;
+  let k = Zeroizing::new(
+    <[u8; 32]>::try_from(&*k.data)
+      .map_err(|_| data_error("invalid X25519 private key"))?,
+  );
+  let u = <[u8; 32]>::try_from(&*u.data)
+    .map_err(|_| data_error("invalid X25519 public key"))?;
+  let sh_sec = Zeroizing::new(x25519_dalek::x25519(*k, u));
+  let point = Zeroizing::new(MontgomeryPoint(*sh_sec));
   if point.ct_eq(&MONTGOMERY_IDENTITY).unwrap_u8() == 1 {
-    return false;
+    return Ok(false);
   }
-  secret.copy_from_slice(&sh_sec);
-  true
+  secret.copy_from_slice(&*sh_sec);
+  Ok(true)
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct X25519HkdfArg {
+  private_key: KeyData,
+  public_key: KeyData,
+  hash: CryptoHash,
+  salt: JsBuffer,
+  info: JsBuffer,
+  /// Length of the derived key in bits.
+  length: usize,
+  extractable: bool,
+}
+
+/// X25519 key agreement followed by HKDF, so that the shared secret never
+/// reaches JS. The derived key is kept in the key store and its handle is
+/// returned.
+#[op]
+pub fn op_crypto_derive_key_x25519_hkdf(
+  state: &mut OpState,
+  mut args: X25519HkdfArg,
+) -> Result<u32, AnyError> {
+  fips::check_unapproved(state, "X25519")?;
+  args.private_key.resolve(state)?;
+  args.public_key.resolve(state)?;
+  if !matches!(args.private_key.r#type, KeyType::Private)
+    || !matches!(args.public_key.r#type, KeyType::Public)
+  {
+    return Err(type_error("Expected an X25519 private and public key"));
+  }
+  if args.length == 0 || args.length % 8 != 0 {
+    return Err(type_error("Length must be a non-zero multiple of 8"));
+  }
+
+  let k = Zeroizing::new(
+    <[u8; 32]>::try_from(&*args.private_key.data)
+      .map_err(|_| data_error("invalid X25519 private key"))?,
+  );
+  let u = <[u8; 32]>::try_from(&*args.public_key.data)
+    .map_err(|_| data_error("invalid X25519 public key"))?;
+  let sh_sec = Zeroizing::new(x25519_dalek::x25519(*k, u));
+  let point = Zeroizing::new(MontgomeryPoint(*sh_sec));
+  if point.ct_eq(&MONTGOMERY_IDENTITY).unwrap_u8() == 1 {
+    return Err(operation_error("Invalid X25519 public key"));
+  }
+
+  let okm =
+    hkdf_derive(args.hash, &*sh_sec, &args.salt, &args.info, args.length / 8)?;
+  let handle = state.borrow_mut::<CryptoKeyStore>().insert(StoredKey {
+    r#type: KeyType::Secret,
+    data: Arc::new(Zeroizing::new(okm)),
+    extractable: args.extractable,
+  });
+  Ok(handle)
 }
 
 // id-X25519 OBJECT IDENTIFIER ::= { 1 3 101 110 }
@@ -98,10 +183,7 @@ pub fn op_crypto_import_pkcs8_x25519(key_data: &[u8], out: &mut [u8]) -> bool {
   true
 }
 
-#[op]
-pub fn op_crypto_export_spki_x25519(
-  pubkey: &[u8],
-) -> Result<ToJsBuffer, AnyError> {
+pub fn export_spki(pubkey: &[u8]) -> Result<Vec<u8>, AnyError> {
   let key_info = spki::SubjectPublicKeyInfo {
     algorithm: spki::AlgorithmIdentifier {
       // id-X25519
@@ -110,13 +192,16 @@ pub fn op_crypto_export_spki_x25519(
     },
     subject_public_key: pubkey,
   };
-  Ok(key_info.to_vec()?.into())
+  Ok(key_info.to_vec()?)
 }
 
-#[op]
-pub fn op_crypto_export_pkcs8_x25519(
-  pkey: &[u8],
-) -> Result<ToJsBuffer, AnyError> {
+pub fn export_pkcs8(pkey: &[u8]) -> Result<Vec<u8>, AnyError> {
+  // CurvePrivateKey ::= OCTET STRING
+  let mut private_key = Vec::with_capacity(pkey.len() + 2);
+  private_key.push(0x04);
+  private_key.push(pkey.len() as u8);
+  private_key.extend_from_slice(pkey);
+
   // This should probably use OneAsymmetricKey instead
   let pk_info = rsa::pkcs8::PrivateKeyInfo {
     public_key: None,
@@ -125,8 +210,8 @@ pub fn op_crypto_export_pkcs8_x25519(
       oid: X25519_OID,
       parameters: None,
     },
-    private_key: pkey, // OCTET STRING
+    private_key: &private_key,
   };
 
-  Ok(pk_info.to_vec()?.into())
+  Ok(pk_info.to_vec()?)
 }
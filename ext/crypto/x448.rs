@@ -0,0 +1,168 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use deno_core::error::AnyError;
+use deno_core::op;
+use deno_core::OpState;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use rsa::pkcs8::PrivateKeyInfo;
+use spki::der::Decode;
+use spki::der::Encode;
+
+use crate::fips;
+use crate::KeyData;
+
+// https://www.rfc-editor.org/rfc/rfc7748#section-5
+const X448_KEY_LEN: usize = 56;
+
+fn secret_from_bytes(k: &[u8]) -> Option<x448::Secret> {
+  let k: [u8; X448_KEY_LEN] = k.try_into().ok()?;
+  // Clamping is done by `x448::Secret::from`.
+  Some(x448::Secret::from(k))
+}
+
+#[op(fast)]
+pub fn op_crypto_generate_x448_keypair(
+  state: &mut OpState,
+  pkey: &mut [u8],
+  pubkey: &mut [u8],
+) -> Result<bool, AnyError> {
+  fips::check_unapproved(state, "X448")?;
+  let mut rng = OsRng;
+  rng.fill_bytes(pkey);
+  // https://www.rfc-editor.org/rfc/rfc7748#section-6.2
+  // pubkey = x448(a, 5)
+  let secret = match secret_from_bytes(pkey) {
+    Some(s) => s,
+    None => return Ok(false),
+  };
+  pubkey.copy_from_slice(x448::PublicKey::from(&secret).as_bytes());
+  Ok(true)
+}
+
+#[op]
+pub fn op_crypto_derive_bits_x448(
+  state: &mut OpState,
+  mut k: KeyData,
+  mut u: KeyData,
+  secret: &mut [u8],
+) -> Result<bool, AnyError> {
+  fips::check_unapproved(state, "X448")?;
+  k.resolve(state)?;
+  u.resolve(state)?;
+  let k = match secret_from_bytes(&k.data) {
+    Some(k) => k,
+    None => return Ok(false),
+  };
+  // Rejects low order points.
+  let u = match x448::PublicKey::from_bytes(&u.data) {
+    Some(u) => u,
+    None => return Ok(false),
+  };
+  // Rejects an all-zero shared secret.
+  match k.as_diffie_hellman(&u) {
+    Some(sh_sec) => {
+      secret.copy_from_slice(sh_sec.as_bytes());
+      Ok(true)
+    }
+    None => Ok(false),
+  }
+}
+
+// id-X448 OBJECT IDENTIFIER ::= { 1 3 101 111 }
+pub const X448_OID: const_oid::ObjectIdentifier =
+  const_oid::ObjectIdentifier::new_unwrap("1.3.101.111");
+
+#[op(fast)]
+pub fn op_crypto_import_spki_x448(key_data: &[u8], out: &mut [u8]) -> bool {
+  // 2-3.
+  let pk_info = match spki::SubjectPublicKeyInfo::from_der(key_data) {
+    Ok(pk_info) => pk_info,
+    Err(_) => return false,
+  };
+  // 4.
+  let alg = pk_info.algorithm.oid;
+  if alg != X448_OID {
+    return false;
+  }
+  // 5.
+  if pk_info.algorithm.parameters.is_some() {
+    return false;
+  }
+  if pk_info.subject_public_key.len() != X448_KEY_LEN {
+    return false;
+  }
+  out.copy_from_slice(pk_info.subject_public_key);
+  true
+}
+
+#[op(fast)]
+pub fn op_crypto_import_pkcs8_x448(key_data: &[u8], out: &mut [u8]) -> bool {
+  // 2-3.
+  // This should probably use OneAsymmetricKey instead
+  let pk_info = match PrivateKeyInfo::from_der(key_data) {
+    Ok(pk_info) => pk_info,
+    Err(_) => return false,
+  };
+  // 4.
+  let alg = pk_info.algorithm.oid;
+  if alg != X448_OID {
+    return false;
+  }
+  // 5.
+  if pk_info.algorithm.parameters.is_some() {
+    return false;
+  }
+  // 6.
+  // CurvePrivateKey ::= OCTET STRING
+  if pk_info.private_key.len() != X448_KEY_LEN + 2 {
+    return false;
+  }
+  out.copy_from_slice(&pk_info.private_key[2..]);
+  true
+}
+
+pub fn export_spki(pubkey: &[u8]) -> Result<Vec<u8>, AnyError> {
+  let key_info = spki::SubjectPublicKeyInfo {
+    algorithm: spki::AlgorithmIdentifier {
+      // id-X448
+      oid: X448_OID,
+      parameters: None,
+    },
+    subject_public_key: pubkey,
+  };
+  Ok(key_info.to_vec()?)
+}
+
+pub fn export_pkcs8(pkey: &[u8]) -> Result<Vec<u8>, AnyError> {
+  // CurvePrivateKey ::= OCTET STRING
+  let mut private_key = Vec::with_capacity(pkey.len() + 2);
+  private_key.push(0x04);
+  private_key.push(pkey.len() as u8);
+  private_key.extend_from_slice(pkey);
+
+  // This should probably use OneAsymmetricKey instead
+  let pk_info = rsa::pkcs8::PrivateKeyInfo {
+    public_key: None,
+    algorithm: rsa::pkcs8::AlgorithmIdentifier {
+      // id-X448
+      oid: X448_OID,
+      parameters: None,
+    },
+    private_key: &private_key,
+  };
+
+  Ok(pk_info.to_vec()?)
+}
+
+// 'x' from Section 2 of RFC 8037
+// https://www.rfc-editor.org/rfc/rfc8037#section-2
+pub fn jwk_x(pkey: &[u8]) -> Result<String, AnyError> {
+  let secret = secret_from_bytes(pkey).ok_or_else(|| {
+    crate::shared::data_error("invalid X448 private key length")
+  })?;
+  Ok(base64::encode_config(
+    x448::PublicKey::from(&secret).as_bytes(),
+    base64::URL_SAFE_NO_PAD,
+  ))
+}
@@ -0,0 +1,65 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use deno_core::error::AnyError;
+use deno_core::op;
+use deno_core::ToJsBuffer;
+use serde::Serialize;
+use x509_parser::pem;
+use x509_parser::prelude::*;
+
+use crate::shared::data_error;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct X509Info {
+  subject: String,
+  issuer: String,
+  serial_number: String,
+  /// Milliseconds since the Unix epoch.
+  not_before: i64,
+  /// Milliseconds since the Unix epoch.
+  not_after: i64,
+  extensions: Vec<X509Extension>,
+  /// DER encoded `SubjectPublicKeyInfo`, importable with the "spki" format.
+  subject_public_key_info: ToJsBuffer,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct X509Extension {
+  oid: String,
+  critical: bool,
+  /// DER encoded extension value.
+  value: ToJsBuffer,
+}
+
+/// Parses a DER or PEM encoded certificate.
+#[op]
+pub fn op_crypto_x509_parse(buf: &[u8]) -> Result<X509Info, AnyError> {
+  let pem = pem::parse_x509_pem(buf).ok().map(|(_, pem)| pem);
+  let der = pem.as_ref().map(|pem| &pem.contents[..]).unwrap_or(buf);
+  let (_, cert) = X509Certificate::from_der(der)
+    .map_err(|e| data_error(format!("invalid X.509 certificate: {e}")))?;
+
+  let validity = cert.validity();
+  let mut serial_number = cert.serial.to_str_radix(16);
+  serial_number.make_ascii_uppercase();
+
+  Ok(X509Info {
+    subject: cert.subject().to_string(),
+    issuer: cert.issuer().to_string(),
+    serial_number,
+    not_before: validity.not_before.timestamp() * 1000,
+    not_after: validity.not_after.timestamp() * 1000,
+    extensions: cert
+      .extensions()
+      .iter()
+      .map(|ext| X509Extension {
+        oid: ext.oid.to_id_string(),
+        critical: ext.critical,
+        value: ext.value.to_vec().into(),
+      })
+      .collect(),
+    subject_public_key_info: cert.public_key().raw.to_vec().into(),
+  })
+}
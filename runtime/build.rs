@@ -93,6 +93,24 @@ mod startup_snapshot {
     }
   }
 
+  impl deno_crypto::CryptoPermissions for Permissions {
+    fn check_read(
+      &mut self,
+      _p: &Path,
+      _api_name: &str,
+    ) -> Result<(), deno_core::error::AnyError> {
+      unreachable!("snapshotting!")
+    }
+
+    fn check_write(
+      &mut self,
+      _p: &Path,
+      _api_name: &str,
+    ) -> Result<(), deno_core::error::AnyError> {
+      unreachable!("snapshotting!")
+    }
+  }
+
   impl deno_web::TimersPermission for Permissions {
     fn allow_hrtime(&mut self) -> bool {
       unreachable!("snapshotting!")
@@ -328,7 +346,12 @@ mod startup_snapshot {
         None,
       ),
       deno_webstorage::deno_webstorage::init_ops_and_esm(None),
-      deno_crypto::deno_crypto::init_ops_and_esm(None),
+      deno_crypto::deno_crypto::init_ops_and_esm::<Permissions>(
+        None,
+        false,
+        Default::default(),
+        None, // Default key generation concurrency.
+      ),
       deno_broadcast_channel::deno_broadcast_channel::init_ops_and_esm(
         deno_broadcast_channel::InMemoryBroadcastChannel::default(),
         false, // No --unstable.
@@ -23,6 +23,7 @@ import * as tty from "ext:runtime/40_tty.js";
 // TODO(bartlomieju): this is funky we have two `http` imports
 import * as httpRuntime from "ext:runtime/40_http.js";
 import * as kv from "ext:deno_kv/01_db.ts";
+import * as crypto from "ext:deno_crypto/00_crypto.js";
 
 const denoNs = {
   metrics: core.metrics,
@@ -178,6 +179,8 @@ const denoNsUnstable = {
   Kv: kv.Kv,
   KvU64: kv.KvU64,
   KvListIterator: kv.KvListIterator,
+  saveCryptoKey: crypto.saveCryptoKey,
+  loadCryptoKey: crypto.loadCryptoKey,
 };
 
 export { denoNs, denoNsUnstable };
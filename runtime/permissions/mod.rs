@@ -1403,6 +1403,24 @@ impl deno_websocket::WebSocketPermissions for PermissionsContainer {
   }
 }
 
+impl deno_crypto::CryptoPermissions for PermissionsContainer {
+  fn check_read(
+    &mut self,
+    path: &Path,
+    api_name: &str,
+  ) -> Result<(), AnyError> {
+    self.0.lock().read.check(path, Some(api_name))
+  }
+
+  fn check_write(
+    &mut self,
+    path: &Path,
+    api_name: &str,
+  ) -> Result<(), AnyError> {
+    self.0.lock().write.check(path, Some(api_name))
+  }
+}
+
 impl deno_fs::FsPermissions for PermissionsContainer {
   fn check_read(
     &mut self,
@@ -334,6 +334,9 @@ pub struct WebWorkerOptions {
   pub unsafely_ignore_certificate_errors: Option<Vec<String>>,
   pub root_cert_store_provider: Option<Arc<dyn RootCertStoreProvider>>,
   pub seed: Option<u64>,
+  pub fips: bool,
+  pub crypto_backend: deno_crypto::CryptoBackend,
+  pub max_keygen_tasks: Option<usize>,
   pub fs: Arc<dyn FileSystem>,
   pub module_loader: Rc<dyn ModuleLoader>,
   pub npm_resolver: Option<Arc<dyn deno_node::NpmResolver>>,
@@ -426,7 +429,12 @@ impl WebWorker {
         options.unsafely_ignore_certificate_errors.clone(),
       ),
       deno_webstorage::deno_webstorage::init_ops_and_esm(None).disable(),
-      deno_crypto::deno_crypto::init_ops_and_esm(options.seed),
+      deno_crypto::deno_crypto::init_ops_and_esm::<PermissionsContainer>(
+        options.seed,
+        options.fips,
+        options.crypto_backend,
+        options.max_keygen_tasks,
+      ),
       deno_broadcast_channel::deno_broadcast_channel::init_ops_and_esm(
         options.broadcast_channel.clone(),
         unstable,
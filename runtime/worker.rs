@@ -167,6 +167,14 @@ pub struct WorkerOptions {
   pub unsafely_ignore_certificate_errors: Option<Vec<String>>,
   pub root_cert_store_provider: Option<Arc<dyn RootCertStoreProvider>>,
   pub seed: Option<u64>,
+  /// Restricts Web Crypto to FIPS approved algorithms. The crypto backend
+  /// should be validated with `deno_crypto::check_fips_backend` first.
+  pub fips: bool,
+  /// Library used by Web Crypto for RSA private key operations.
+  pub crypto_backend: deno_crypto::CryptoBackend,
+  /// Maximum number of RSA keys Web Crypto generates concurrently, which
+  /// defaults to the available parallelism.
+  pub max_keygen_tasks: Option<usize>,
 
   pub fs: Arc<dyn FileSystem>,
   /// Implementation of `ModuleLoader` which will be
@@ -225,6 +233,9 @@ impl Default for WorkerOptions {
       fs: Arc::new(deno_fs::RealFs),
       module_loader: Rc::new(FsModuleLoader),
       seed: None,
+      fips: false,
+      crypto_backend: Default::default(),
+      max_keygen_tasks: None,
       unsafely_ignore_certificate_errors: Default::default(),
       should_break_on_first_statement: Default::default(),
       should_wait_for_inspector_session: Default::default(),
@@ -321,7 +332,12 @@ impl MainWorker {
       deno_webstorage::deno_webstorage::init_ops_and_esm(
         options.origin_storage_dir.clone(),
       ),
-      deno_crypto::deno_crypto::init_ops_and_esm(options.seed),
+      deno_crypto::deno_crypto::init_ops_and_esm::<PermissionsContainer>(
+        options.seed,
+        options.fips,
+        options.crypto_backend,
+        options.max_keygen_tasks,
+      ),
       deno_broadcast_channel::deno_broadcast_channel::init_ops_and_esm(
         options.broadcast_channel.clone(),
         unstable,
@@ -406,6 +406,12 @@ impl InitializeParamsBuilder {
     self
   }
 
+  pub fn set_preferences(&mut self, value: serde_json::Value) -> &mut Self {
+    let options = self.initialization_options_mut();
+    options.insert("preferences".to_string(), value);
+    self
+  }
+
   pub fn set_preload_limit(&mut self, arg: usize) -> &mut Self {
     let options = self.initialization_options_mut();
     options.insert("documentPreloadLimit".to_string(), arg.into());
@@ -427,6 +433,15 @@ impl InitializeParamsBuilder {
     self
   }
 
+  pub fn set_untitled_base(&mut self, value: impl AsRef<str>) -> &mut Self {
+    let options = self.initialization_options_mut();
+    options.insert(
+      "untitledBase".to_string(),
+      value.as_ref().to_string().into(),
+    );
+    self
+  }
+
   pub fn add_test_server_suggestions(&mut self) -> &mut Self {
     self.set_suggest_imports_hosts(vec![(
       "http://localhost:4545/".to_string(),